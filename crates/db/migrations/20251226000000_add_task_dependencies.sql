@@ -0,0 +1,17 @@
+-- Blocked-by edges between tasks: `task_id` cannot start until `depends_on_task_id`
+-- is done or merged
+CREATE TABLE task_dependencies (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    depends_on_task_id  BLOB NOT NULL,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    CHECK (task_id != depends_on_task_id),
+    UNIQUE (task_id, depends_on_task_id),
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_dependencies_task_id ON task_dependencies(task_id);
+CREATE INDEX idx_task_dependencies_depends_on ON task_dependencies(depends_on_task_id);
@@ -0,0 +1,18 @@
+-- Token usage reported by the coding agent for each execution process. Counts are
+-- cumulative for the execution and overwritten as the agent reports new totals.
+CREATE TABLE execution_process_token_usage (
+    id                           BLOB PRIMARY KEY,
+    execution_process_id         BLOB NOT NULL UNIQUE,
+    model                        TEXT,
+    input_tokens                 INTEGER NOT NULL DEFAULT 0,
+    output_tokens                INTEGER NOT NULL DEFAULT 0,
+    cache_creation_input_tokens  INTEGER NOT NULL DEFAULT 0,
+    cache_read_input_tokens      INTEGER NOT NULL DEFAULT 0,
+    created_at                   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_token_usage_created_at
+    ON execution_process_token_usage(created_at);
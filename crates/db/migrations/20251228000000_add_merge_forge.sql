@@ -0,0 +1,5 @@
+-- Which code forge a PR merge lives on (NULL for direct merges)
+ALTER TABLE merges ADD COLUMN pr_forge TEXT CHECK (pr_forge IN ('github', 'gitlab'));
+
+-- Every PR recorded so far was created through GitHub
+UPDATE merges SET pr_forge = 'github' WHERE merge_type = 'pr';
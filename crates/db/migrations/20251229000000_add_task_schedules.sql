@@ -0,0 +1,30 @@
+-- Recurring task templates. Each run creates a task from the template and
+-- starts a workspace for it, exactly one of cron_expression/interval_seconds
+-- describes when.
+CREATE TABLE task_schedules (
+    id                   BLOB PRIMARY KEY,
+    project_id           BLOB NOT NULL,
+    name                 TEXT NOT NULL,
+    task_title           TEXT NOT NULL,
+    task_description     TEXT,
+    executor_profile_id  TEXT NOT NULL, -- JSON ExecutorProfileId
+    repos                TEXT NOT NULL, -- JSON array of { repo_id, target_branch }
+    cron_expression      TEXT,
+    interval_seconds     INTEGER,
+    enabled              INTEGER NOT NULL DEFAULT 1,
+    next_run_at          TEXT,
+    last_run_at          TEXT,
+    last_task_id         BLOB,
+    last_error           TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    CHECK ((cron_expression IS NULL) != (interval_seconds IS NULL)),
+    CHECK (interval_seconds IS NULL OR interval_seconds > 0),
+
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_due ON task_schedules(enabled, next_run_at);
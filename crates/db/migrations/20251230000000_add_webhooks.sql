@@ -0,0 +1,35 @@
+-- Outbound webhooks notified of task and execution lifecycle events
+CREATE TABLE webhooks (
+    id          BLOB PRIMARY KEY,
+    name        TEXT NOT NULL,
+    url         TEXT NOT NULL,
+    secret      TEXT NOT NULL,
+    events      TEXT NOT NULL DEFAULT '[]',   -- JSON array of event types; empty means all
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- One row per event sent to a webhook; doubles as the retry queue
+CREATE TABLE webhook_deliveries (
+    id                BLOB PRIMARY KEY,
+    webhook_id        BLOB NOT NULL,
+    event_type        TEXT NOT NULL,
+    payload           TEXT NOT NULL,       -- JSON body exactly as signed and sent
+    status            TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts          INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at   TEXT,
+    response_status   INTEGER,
+    last_error        TEXT,
+    delivered_at      TEXT,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at DESC);
+
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,25 @@
+-- Tool approval requests and their outcomes, kept for auditing and so
+-- pending approvals survive a restart
+CREATE TABLE approvals (
+    id                    BLOB PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    tool_name             TEXT NOT NULL,
+    tool_input            TEXT NOT NULL,   -- JSON arguments as sent by the agent
+    tool_call_id          TEXT NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'pending'
+                             CHECK (status IN ('pending', 'approved', 'denied', 'timed_out')),
+    decided_by            TEXT CHECK (decided_by IN ('user', 'policy', 'system')),
+    policy_rule           TEXT,            -- approval policy rule that decided, if any
+    reason                TEXT,
+    requested_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    timeout_at            TEXT NOT NULL,
+    decided_at            TEXT,
+    latency_ms            INTEGER,         -- time from request to decision
+
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approvals_execution_process ON approvals(execution_process_id, requested_at);
+
+CREATE INDEX idx_approvals_pending ON approvals(timeout_at)
+    WHERE status = 'pending';
@@ -0,0 +1,30 @@
+-- Best-of-N fan-out: several attempts at one task, each with a different
+-- executor profile, compared once they have all finished
+CREATE TABLE attempt_groups (
+    id                     BLOB PRIMARY KEY,
+    task_id                BLOB NOT NULL,
+    status                 TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'completed', 'promoted')),
+    promoted_workspace_id  BLOB,
+    report                 TEXT,            -- JSON comparison report, written when the last attempt finishes
+    completed_at           TEXT,
+    created_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (promoted_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+
+CREATE TABLE attempt_group_members (
+    group_id             BLOB NOT NULL,
+    workspace_id         BLOB NOT NULL UNIQUE,
+    executor_profile_id  TEXT NOT NULL,     -- JSON ExecutorProfileId
+    archived_at          TEXT,              -- set on the attempts not promoted
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    PRIMARY KEY (group_id, workspace_id),
+    FOREIGN KEY (group_id) REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
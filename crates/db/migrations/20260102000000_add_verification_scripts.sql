@@ -0,0 +1,23 @@
+-- Verification script runs after the coding agent (and cleanup script) and
+-- must pass before the task moves to review
+ALTER TABLE project_repos ADD COLUMN verification_script TEXT;
+
+CREATE TABLE verification_runs (
+    id                               BLOB PRIMARY KEY,
+    workspace_id                     BLOB NOT NULL,
+    execution_process_id             BLOB NOT NULL UNIQUE,
+    status                           TEXT NOT NULL
+                                        CHECK (status IN ('passed', 'failed')),
+    exit_code                        INTEGER,
+    output                           TEXT NOT NULL DEFAULT '',  -- tail of stdout/stderr
+    retry                            INTEGER NOT NULL DEFAULT 0, -- automatic fix attempts before this run
+    follow_up_execution_process_id   BLOB,                      -- agent follow-up started to fix a failure
+    created_at                       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (follow_up_execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_verification_runs_workspace_id ON verification_runs(workspace_id);
+CREATE INDEX idx_verification_runs_follow_up ON verification_runs(follow_up_execution_process_id);
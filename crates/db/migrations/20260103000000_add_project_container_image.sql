@@ -0,0 +1,3 @@
+-- Image the project's setup scripts, dev server and coding agents run in.
+-- NULL keeps them on the host.
+ALTER TABLE projects ADD COLUMN container_image TEXT;
@@ -0,0 +1,33 @@
+-- Searchable text from normalized log entries: user and assistant messages,
+-- tool calls and errors. One row per entry; rewritten as the entry updates.
+CREATE TABLE log_search_entries (
+    id                    INTEGER PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    entry_index           INTEGER NOT NULL,
+    kind                  TEXT NOT NULL
+                             CHECK (kind IN ('user_message', 'assistant_message', 'tool_use', 'error')),
+    content               TEXT NOT NULL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    UNIQUE (execution_process_id, entry_index),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE VIRTUAL TABLE log_search_fts USING fts5(
+    content,
+    content = 'log_search_entries',
+    content_rowid = 'id'
+);
+
+CREATE TRIGGER log_search_entries_ai AFTER INSERT ON log_search_entries BEGIN
+    INSERT INTO log_search_fts (rowid, content) VALUES (new.id, new.content);
+END;
+
+CREATE TRIGGER log_search_entries_ad AFTER DELETE ON log_search_entries BEGIN
+    INSERT INTO log_search_fts (log_search_fts, rowid, content) VALUES ('delete', old.id, old.content);
+END;
+
+CREATE TRIGGER log_search_entries_au AFTER UPDATE ON log_search_entries BEGIN
+    INSERT INTO log_search_fts (log_search_fts, rowid, content) VALUES ('delete', old.id, old.content);
+    INSERT INTO log_search_fts (rowid, content) VALUES (new.id, new.content);
+END;
@@ -0,0 +1,12 @@
+-- Attempts imported from archives exported by another instance. The archive
+-- itself is kept on disk; this table lists them.
+CREATE TABLE imported_attempts (
+    id               BLOB PRIMARY KEY,
+    task_title       TEXT NOT NULL,
+    branch           TEXT NOT NULL,
+    format_version   INTEGER NOT NULL,
+    app_version      TEXT NOT NULL,     -- vibe-kanban version that exported it
+    exported_at      TEXT NOT NULL,
+    size_bytes       INTEGER NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,14 @@
+-- Logs of finished execution processes compacted by the retention job. The
+-- process's rows in execution_process_logs are replaced by one compressed
+-- blob; coding agents also keep their final conversation so it can still be
+-- shown once the raw logs are pruned.
+CREATE TABLE execution_process_log_archives (
+    execution_id          BLOB PRIMARY KEY,
+    raw_logs              BLOB,               -- zstd-compressed JSONL; NULL once pruned
+    conversation          BLOB,               -- zstd-compressed JSON array of conversation entries
+    raw_byte_size         INTEGER NOT NULL,   -- uncompressed size of the raw logs
+    stored_byte_size      INTEGER NOT NULL,   -- compressed size of both blobs
+    compacted_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    raw_pruned_at         TEXT,
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Coding agent turn a session was forked from, in another workspace. NULL for
+-- sessions started from scratch.
+ALTER TABLE sessions ADD COLUMN forked_from_process_id BLOB
+    REFERENCES execution_processes(id) ON DELETE SET NULL;
@@ -0,0 +1,59 @@
+-- Reusable sequences of steps (agent prompts, scripts, checks) that run
+-- unattended in an attempt once the agent's first turn is done.
+CREATE TABLE playbooks (
+    id           BLOB PRIMARY KEY,
+    name         TEXT NOT NULL,
+    description  TEXT,
+    steps        TEXT NOT NULL DEFAULT '[]', -- JSON array of PlaybookStep
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Playbook every new attempt of a task runs
+CREATE TABLE task_playbooks (
+    task_id      BLOB PRIMARY KEY,
+    playbook_id  BLOB NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (playbook_id) REFERENCES playbooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_playbooks_playbook_id ON task_playbooks(playbook_id);
+
+-- One run of a playbook in a workspace. The steps are copied from the
+-- playbook when the run starts, so editing it doesn't affect running ones.
+CREATE TABLE playbook_runs (
+    id            BLOB PRIMARY KEY,
+    playbook_id   BLOB,
+    workspace_id  BLOB NOT NULL,
+    name          TEXT NOT NULL,
+    steps         TEXT NOT NULL, -- JSON array of PlaybookStep
+    status        TEXT NOT NULL DEFAULT 'running'
+                     CHECK (status IN ('running', 'completed', 'failed', 'cancelled')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (playbook_id) REFERENCES playbooks(id) ON DELETE SET NULL,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_playbook_runs_workspace_id ON playbook_runs(workspace_id);
+
+CREATE TABLE playbook_step_runs (
+    id                    BLOB PRIMARY KEY,
+    playbook_run_id       BLOB NOT NULL,
+    step_index            INTEGER NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'running'
+                             CHECK (status IN ('running', 'passed', 'failed', 'cancelled')),
+    execution_process_id  BLOB,                      -- latest process started for the step
+    retries               INTEGER NOT NULL DEFAULT 0, -- follow-ups sent after a failed check
+    output                TEXT,                      -- tail of the output of a failed script
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at          TEXT,
+
+    FOREIGN KEY (playbook_run_id) REFERENCES playbook_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_playbook_step_runs_run_id ON playbook_step_runs(playbook_run_id);
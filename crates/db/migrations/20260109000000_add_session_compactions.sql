@@ -0,0 +1,4 @@
+-- Session whose conversation outgrew the agent's context window and was
+-- summarised into this one. NULL for sessions that weren't compacted.
+ALTER TABLE sessions ADD COLUMN compacted_from_session_id BLOB
+    REFERENCES sessions(id) ON DELETE SET NULL;
@@ -0,0 +1,22 @@
+-- Projects opt in to having new PR review comments sent to the workspace's
+-- coding agent by the PR monitor
+ALTER TABLE projects ADD COLUMN auto_address_review_comments BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Review comments that were sent to a coding agent, one row per comment so
+-- each is only addressed once
+CREATE TABLE pr_review_follow_ups (
+    id                     BLOB PRIMARY KEY,
+    merge_id               BLOB NOT NULL,
+    comment_id             INTEGER NOT NULL,          -- review comment id on the forge
+    execution_process_id   BLOB,                      -- coding agent follow-up that addresses it
+    status                 TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'replied', 'failed')),
+    created_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (merge_id) REFERENCES merges(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    UNIQUE (merge_id, comment_id)
+);
+
+CREATE INDEX idx_pr_review_follow_ups_merge_id ON pr_review_follow_ups(merge_id);
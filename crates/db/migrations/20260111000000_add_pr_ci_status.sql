@@ -0,0 +1,16 @@
+-- CI result for the head commit of an open PR, polled by the PR monitor
+ALTER TABLE merges ADD COLUMN pr_ci_status TEXT CHECK (pr_ci_status IN ('pending', 'success', 'failure'));
+ALTER TABLE merges ADD COLUMN pr_ci_head_sha TEXT;
+ALTER TABLE merges ADD COLUMN pr_ci_checks TEXT;       -- JSON array of the individual checks
+ALTER TABLE merges ADD COLUMN pr_ci_checked_at TEXT;
+
+-- Follow-ups started to fix failing CI; the head commit guards against
+-- retrying the same failure and the execution process is pushed once it ends
+ALTER TABLE merges ADD COLUMN pr_ci_fix_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE merges ADD COLUMN pr_ci_fix_head_sha TEXT;
+ALTER TABLE merges ADD COLUMN pr_ci_fix_execution_process_id BLOB
+    REFERENCES execution_processes(id) ON DELETE SET NULL;
+
+-- Projects opt in to sending failing CI to the workspace's coding agent
+ALTER TABLE projects ADD COLUMN auto_fix_ci BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE projects ADD COLUMN max_ci_fix_attempts INTEGER NOT NULL DEFAULT 3;
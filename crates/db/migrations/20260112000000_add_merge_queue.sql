@@ -0,0 +1,25 @@
+-- Attempts waiting to be merged locally into their target branch. Entries of
+-- the same repo are merged one at a time, oldest first, each rebased and
+-- verified right before its merge.
+CREATE TABLE merge_queue_entries (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    status                TEXT NOT NULL DEFAULT 'queued'
+                             CHECK (status IN ('queued', 'rebasing', 'verifying', 'merged', 'failed', 'cancelled')),
+    execution_process_id  BLOB,               -- verification or setup script run after the rebase
+    error_message         TEXT,               -- conflicts or script output when the merge failed
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+-- An attempt is queued at most once per repo at a time
+CREATE UNIQUE INDEX idx_merge_queue_entries_active
+    ON merge_queue_entries(workspace_id, repo_id)
+    WHERE status IN ('queued', 'rebasing', 'verifying');
+
+CREATE INDEX idx_merge_queue_entries_repo_status ON merge_queue_entries(repo_id, status, created_at);
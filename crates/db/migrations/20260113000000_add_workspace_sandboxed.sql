@@ -0,0 +1,3 @@
+-- Set once a sandboxed process has run in the workspace. Git on the host then
+-- treats the workspace's worktrees as untrusted, including after a restart.
+ALTER TABLE workspaces ADD COLUMN sandboxed BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- Set when the workspace this one is stacked on merges: the branch to move onto
+-- and the base its PR moves to. Cleared once the restack has gone through, so
+-- a busy workspace or a failed attempt is retried by the next PR monitor poll.
+ALTER TABLE workspace_repos ADD COLUMN restack_onto TEXT;
+ALTER TABLE workspace_repos ADD COLUMN restack_pr_base TEXT;
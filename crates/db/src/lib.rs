@@ -1,8 +1,11 @@
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
 
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
+    sqlite::{
+        SqliteAutoVacuum, SqliteConnectOptions, SqliteConnection, SqliteJournalMode,
+        SqlitePoolOptions,
+    },
 };
 use utils::assets::asset_dir;
 
@@ -22,6 +25,7 @@ impl DBService {
         let options = SqliteConnectOptions::from_str(&database_url)?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Wal)
+            .auto_vacuum(SqliteAutoVacuum::Incremental)
             .busy_timeout(Duration::from_secs(5));
         let pool = SqlitePool::connect_with(options).await?;
         sqlx::migrate!("./migrations").run(&pool).await?;
@@ -59,6 +63,7 @@ impl DBService {
         let options = SqliteConnectOptions::from_str(&database_url)?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Wal)
+            .auto_vacuum(SqliteAutoVacuum::Incremental)
             .busy_timeout(Duration::from_secs(5));
 
         let pool = if let Some(hook) = after_connect {
@@ -79,4 +84,90 @@ impl DBService {
         sqlx::migrate!("./migrations").run(&pool).await?;
         Ok(pool)
     }
+
+    /// Hand pages freed by deleted rows back to the OS so the file shrinks.
+    /// Databases created before incremental auto-vacuum was enabled switch to
+    /// it with one full VACUUM, which can't run inside a migration.
+    pub async fn reclaim_space(&self) -> Result<(), Error> {
+        let mut conn = self.pool.acquire().await?;
+        let mode: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(&mut *conn)
+            .await?;
+        // 0 is NONE, 1 FULL and 2 INCREMENTAL
+        if mode == 2 {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&mut *conn)
+                .await?;
+        } else {
+            tracing::info!("Switching the database to incremental auto-vacuum");
+            sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        }
+        // Freed pages only leave the file once the WAL is checkpointed
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Position of each column of `table`, keyed by column name
+    pub async fn column_positions(&self, table: &str) -> Result<HashMap<String, i32>, Error> {
+        // Table-valued pragmas can't be checked at compile time
+        let columns: Vec<(i32, String)> =
+            sqlx::query_as("SELECT cid, name FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(columns.into_iter().map(|(cid, name)| (name, cid)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pragma(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query_scalar(&format!("PRAGMA {name}"))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_reclaim_space_shrinks_the_file(pool: SqlitePool) {
+        let db = DBService { pool: pool.clone() };
+        sqlx::query("CREATE TABLE blobs (data BLOB)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for _ in 0..64 {
+            sqlx::query("INSERT INTO blobs (data) VALUES (zeroblob(65536))")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        // A database without auto-vacuum is converted with a full VACUUM
+        sqlx::query("DELETE FROM blobs WHERE rowid % 2 = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(pragma(&pool, "freelist_count").await > 0);
+        let pages = pragma(&pool, "page_count").await;
+        db.reclaim_space().await.unwrap();
+        assert_eq!(pragma(&pool, "auto_vacuum").await, 2);
+        assert_eq!(pragma(&pool, "freelist_count").await, 0);
+        assert!(pragma(&pool, "page_count").await < pages);
+
+        // After that, freed pages are released incrementally
+        sqlx::query("DELETE FROM blobs")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(pragma(&pool, "freelist_count").await > 0);
+        db.reclaim_space().await.unwrap();
+        assert_eq!(pragma(&pool, "freelist_count").await, 0);
+    }
 }
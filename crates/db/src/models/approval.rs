@@ -0,0 +1,313 @@
+//! Audit trail for tool approval requests.
+//!
+//! Every request an agent makes is recorded with its outcome, who decided it
+//! and how long the decision took. Rows still pending when the server stops
+//! are carried over to the execution that resumes the session.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalOutcome {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// Who resolved an approval request
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalDecider {
+    User,
+    /// A rule from the approval policy
+    Policy,
+    /// Timeouts and restarts
+    System,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct Approval {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    #[ts(type = "JsonValue")]
+    pub tool_input: Json<Value>,
+    pub tool_call_id: String,
+    pub status: ApprovalOutcome,
+    pub decided_by: Option<ApprovalDecider>,
+    /// Approval policy rule that decided the request, if any
+    pub policy_rule: Option<String>,
+    pub reason: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    /// Milliseconds between the request and its decision
+    #[ts(type = "number | null")]
+    pub latency_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateApproval {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub tool_call_id: String,
+    pub requested_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApprovalDecision {
+    pub status: ApprovalOutcome,
+    pub decided_by: ApprovalDecider,
+    pub policy_rule: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl Approval {
+    pub async fn create(pool: &SqlitePool, data: &CreateApproval) -> Result<Self, sqlx::Error> {
+        let tool_input = Json(&data.tool_input);
+        sqlx::query_as!(
+            Approval,
+            r#"INSERT INTO approvals (id, execution_process_id, tool_name, tool_input, tool_call_id, requested_at, timeout_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         tool_name,
+                         tool_input as "tool_input!: Json<Value>",
+                         tool_call_id,
+                         status as "status!: ApprovalOutcome",
+                         decided_by as "decided_by: ApprovalDecider",
+                         policy_rule,
+                         reason,
+                         requested_at as "requested_at!: DateTime<Utc>",
+                         timeout_at as "timeout_at!: DateTime<Utc>",
+                         decided_at as "decided_at: DateTime<Utc>",
+                         latency_ms"#,
+            data.id,
+            data.execution_process_id,
+            data.tool_name,
+            tool_input,
+            data.tool_call_id,
+            data.requested_at,
+            data.timeout_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: Json<Value>",
+                      tool_call_id,
+                      status as "status!: ApprovalOutcome",
+                      decided_by as "decided_by: ApprovalDecider",
+                      policy_rule,
+                      reason,
+                      requested_at as "requested_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      decided_at as "decided_at: DateTime<Utc>",
+                      latency_ms
+               FROM approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Approval history for every attempt of a task, newest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id as "id!: Uuid",
+                      a.execution_process_id as "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.tool_input as "tool_input!: Json<Value>",
+                      a.tool_call_id,
+                      a.status as "status!: ApprovalOutcome",
+                      a.decided_by as "decided_by: ApprovalDecider",
+                      a.policy_rule,
+                      a.reason,
+                      a.requested_at as "requested_at!: DateTime<Utc>",
+                      a.timeout_at as "timeout_at!: DateTime<Utc>",
+                      a.decided_at as "decided_at: DateTime<Utc>",
+                      a.latency_ms
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               ORDER BY a.requested_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Approval history for one workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id as "id!: Uuid",
+                      a.execution_process_id as "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.tool_input as "tool_input!: Json<Value>",
+                      a.tool_call_id,
+                      a.status as "status!: ApprovalOutcome",
+                      a.decided_by as "decided_by: ApprovalDecider",
+                      a.policy_rule,
+                      a.reason,
+                      a.requested_at as "requested_at!: DateTime<Utc>",
+                      a.timeout_at as "timeout_at!: DateTime<Utc>",
+                      a.decided_at as "decided_at: DateTime<Utc>",
+                      a.latency_ms
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               ORDER BY a.requested_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT id as "id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: Json<Value>",
+                      tool_call_id,
+                      status as "status!: ApprovalOutcome",
+                      decided_by as "decided_by: ApprovalDecider",
+                      policy_rule,
+                      reason,
+                      requested_at as "requested_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      decided_at as "decided_at: DateTime<Utc>",
+                      latency_ms
+               FROM approvals
+               WHERE status = 'pending'
+               ORDER BY requested_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A request left pending by an earlier execution in the same session
+    /// for the same tool call, which a resumed execution should pick up
+    /// instead of asking again
+    pub async fn find_carried_over(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tool_name: &str,
+        tool_input: &Value,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let tool_input = Json(tool_input);
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id as "id!: Uuid",
+                      a.execution_process_id as "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.tool_input as "tool_input!: Json<Value>",
+                      a.tool_call_id,
+                      a.status as "status!: ApprovalOutcome",
+                      a.decided_by as "decided_by: ApprovalDecider",
+                      a.policy_rule,
+                      a.reason,
+                      a.requested_at as "requested_at!: DateTime<Utc>",
+                      a.timeout_at as "timeout_at!: DateTime<Utc>",
+                      a.decided_at as "decided_at: DateTime<Utc>",
+                      a.latency_ms
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               JOIN execution_processes cur ON cur.id = $1
+               WHERE a.status = 'pending'
+                 AND ep.session_id = cur.session_id
+                 AND a.execution_process_id != $1
+                 AND a.tool_name = $2
+                 AND a.tool_input = $3
+                 AND datetime(a.timeout_at) > datetime($4)
+               ORDER BY a.requested_at ASC
+               LIMIT 1"#,
+            execution_process_id,
+            tool_name,
+            tool_input,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Move a carried-over request to the execution that asked for it again
+    pub async fn reassign(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+        tool_call_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE approvals
+               SET execution_process_id = $2,
+                   tool_call_id = $3
+               WHERE id = $1"#,
+            id,
+            execution_process_id,
+            tool_call_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Resolve a pending request. Returns false if it was already decided.
+    pub async fn record_decision(
+        pool: &SqlitePool,
+        id: Uuid,
+        decision: &ApprovalDecision,
+        decided_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET status = $2,
+                   decided_by = $3,
+                   policy_rule = $4,
+                   reason = $5,
+                   decided_at = $6,
+                   latency_ms = MAX(0, CAST(ROUND((julianday($6) - julianday(requested_at)) * 86400000) AS INTEGER))
+               WHERE id = $1 AND status = 'pending'"#,
+            id,
+            decision.status,
+            decision.decided_by,
+            decision.policy_rule,
+            decision.reason,
+            decided_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
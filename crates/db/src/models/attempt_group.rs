@@ -0,0 +1,327 @@
+//! Best-of-N fan-out groups.
+//!
+//! A group ties together several workspaces started for one task with
+//! different executor profiles. Once every attempt has finished a comparison
+//! report is stored on the group; promoting one attempt archives the rest.
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AttemptGroupStatus {
+    /// At least one attempt is still running or waiting to start
+    Running,
+    /// Every attempt finished and the report is available
+    Completed,
+    Promoted,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub status: AttemptGroupStatus,
+    pub promoted_workspace_id: Option<Uuid>,
+    #[ts(type = "AttemptGroupReport | null")]
+    pub report: Option<Json<AttemptGroupReport>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroupMember {
+    pub group_id: Uuid,
+    pub workspace_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    /// Set when another attempt in the group was promoted
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroupWithMembers {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub group: AttemptGroup,
+    pub members: Vec<AttemptGroupMember>,
+}
+
+/// Side-by-side comparison of the attempts in a group
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptGroupReport {
+    pub generated_at: DateTime<Utc>,
+    pub attempts: Vec<AttemptComparison>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptComparison {
+    pub workspace_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+    pub branch: String,
+    /// Outcome of the latest coding agent run
+    pub agent_status: Option<ExecutionProcessStatus>,
+    #[ts(type = "number | null")]
+    pub agent_exit_code: Option<i64>,
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Why the diff stats are missing, e.g. the worktree was already removed
+    pub diff_error: Option<String>,
+    /// Latest setup and cleanup script runs
+    pub scripts: Vec<ScriptOutcome>,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    /// None when any model used has no configured pricing
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ScriptOutcome {
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    #[ts(type = "number | null")]
+    pub exit_code: Option<i64>,
+}
+
+impl AttemptGroup {
+    pub async fn create(pool: &SqlitePool, id: Uuid, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"INSERT INTO attempt_groups (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         status as "status!: AttemptGroupStatus",
+                         promoted_workspace_id as "promoted_workspace_id: Uuid",
+                         report as "report: Json<AttemptGroupReport>",
+                         completed_at as "completed_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      status as "status!: AttemptGroupStatus",
+                      promoted_workspace_id as "promoted_workspace_id: Uuid",
+                      report as "report: Json<AttemptGroupReport>",
+                      completed_at as "completed_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Groups for a task, newest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      status as "status!: AttemptGroupStatus",
+                      promoted_workspace_id as "promoted_workspace_id: Uuid",
+                      report as "report: Json<AttemptGroupReport>",
+                      completed_at as "completed_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The group a workspace was fanned out in, if any
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT g.id as "id!: Uuid",
+                      g.task_id as "task_id!: Uuid",
+                      g.status as "status!: AttemptGroupStatus",
+                      g.promoted_workspace_id as "promoted_workspace_id: Uuid",
+                      g.report as "report: Json<AttemptGroupReport>",
+                      g.completed_at as "completed_at: DateTime<Utc>",
+                      g.created_at as "created_at!: DateTime<Utc>",
+                      g.updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups g
+               JOIN attempt_group_members m ON m.group_id = g.id
+               WHERE m.workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn add_member(
+        pool: &SqlitePool,
+        group_id: Uuid,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<AttemptGroupMember, sqlx::Error> {
+        let executor_profile_id = Json(executor_profile_id);
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"INSERT INTO attempt_group_members (group_id, workspace_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING group_id as "group_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         archived_at as "archived_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            group_id,
+            workspace_id,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Members in the order they were fanned out
+    pub async fn members(
+        pool: &SqlitePool,
+        group_id: Uuid,
+    ) -> Result<Vec<AttemptGroupMember>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"SELECT group_id as "group_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      archived_at as "archived_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_group_members
+               WHERE group_id = $1
+               ORDER BY rowid ASC"#,
+            group_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Members that are still running something other than a dev server, or
+    /// whose coding agent has not started yet (e.g. waiting in the task queue)
+    pub async fn count_unfinished_members(
+        pool: &SqlitePool,
+        group_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM attempt_group_members m
+               WHERE m.group_id = $1
+                 AND m.archived_at IS NULL
+                 AND (
+                     EXISTS (
+                         SELECT 1 FROM execution_processes ep
+                         JOIN sessions s ON s.id = ep.session_id
+                         WHERE s.workspace_id = m.workspace_id
+                           AND ep.status = 'running'
+                           AND ep.run_reason != 'devserver'
+                     )
+                     OR NOT EXISTS (
+                         SELECT 1 FROM execution_processes ep
+                         JOIN sessions s ON s.id = ep.session_id
+                         WHERE s.workspace_id = m.workspace_id
+                           AND ep.run_reason = 'codingagent'
+                     )
+                 )"#,
+            group_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Store the report and mark the group completed. Returns false if the
+    /// group was no longer running, e.g. another attempt completed it first.
+    pub async fn complete(
+        pool: &SqlitePool,
+        id: Uuid,
+        report: &AttemptGroupReport,
+        completed_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let report = Json(report);
+        let result = sqlx::query!(
+            r#"UPDATE attempt_groups
+               SET status = 'completed',
+                   report = $2,
+                   completed_at = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'running'"#,
+            id,
+            report,
+            completed_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark `workspace_id` as the chosen attempt and archive the other
+    /// members. A report generated at promotion time is only stored if the
+    /// group does not have one yet.
+    pub async fn promote(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        report: Option<&AttemptGroupReport>,
+        promoted_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let report = report.map(Json);
+        sqlx::query!(
+            r#"UPDATE attempt_groups
+               SET status = 'promoted',
+                   promoted_workspace_id = $2,
+                   report = COALESCE(report, $3),
+                   completed_at = COALESCE(completed_at, $4),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            workspace_id,
+            report,
+            promoted_at
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE attempt_group_members
+               SET archived_at = $3
+               WHERE group_id = $1 AND workspace_id != $2 AND archived_at IS NULL"#,
+            id,
+            workspace_id,
+            promoted_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+//! Compacted logs of finished execution processes.
+//!
+//! The blobs are opaque here; compression and the retention policy live in
+//! the services crate.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogArchive {
+    pub execution_id: Uuid,
+    /// Compressed JSONL logs, None once pruned
+    pub raw_logs: Option<Vec<u8>>,
+    /// Compressed conversation entries of a coding agent process
+    pub conversation: Option<Vec<u8>>,
+    pub raw_byte_size: i64,
+    pub stored_byte_size: i64,
+    pub compacted_at: DateTime<Utc>,
+    pub raw_pruned_at: Option<DateTime<Utc>>,
+}
+
+/// How much space the logs of one project's execution processes take
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectLogStorage {
+    pub project_id: Uuid,
+    pub project_name: String,
+    #[ts(type = "number")]
+    pub process_count: i64,
+    /// Logs not compacted yet
+    #[ts(type = "number")]
+    pub live_bytes: i64,
+    /// Uncompressed size of the compacted logs
+    #[ts(type = "number")]
+    pub compacted_bytes: i64,
+    /// What the compacted logs actually take on disk
+    #[ts(type = "number")]
+    pub compacted_stored_bytes: i64,
+    #[ts(type = "number")]
+    pub pruned_process_count: i64,
+}
+
+impl ExecutionProcessLogArchive {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessLogArchive,
+            r#"SELECT execution_id as "execution_id!: Uuid",
+                      raw_logs,
+                      conversation,
+                      raw_byte_size,
+                      stored_byte_size,
+                      compacted_at as "compacted_at!: DateTime<Utc>",
+                      raw_pruned_at as "raw_pruned_at?: DateTime<Utc>"
+               FROM execution_process_log_archives
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store the compacted logs of a process and drop its rows from
+    /// `execution_process_logs` in one transaction. Compacting a process again
+    /// replaces its earlier archive.
+    pub async fn replace_logs(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        raw_logs: &[u8],
+        conversation: Option<&[u8]>,
+        raw_byte_size: i64,
+    ) -> Result<(), sqlx::Error> {
+        let stored_byte_size = (raw_logs.len() + conversation.map_or(0, <[u8]>::len)) as i64;
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_archives
+                   (execution_id, raw_logs, conversation, raw_byte_size, stored_byte_size)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   raw_logs = excluded.raw_logs,
+                   conversation = excluded.conversation,
+                   raw_byte_size = excluded.raw_byte_size,
+                   stored_byte_size = excluded.stored_byte_size,
+                   compacted_at = datetime('now', 'subsec'),
+                   raw_pruned_at = NULL"#,
+            execution_id,
+            raw_logs,
+            conversation,
+            raw_byte_size,
+            stored_byte_size
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Drop the raw logs of a compacted process, keeping its conversation.
+    /// Returns the number of bytes freed.
+    pub async fn prune_raw_logs(pool: &SqlitePool, execution_id: Uuid) -> Result<i64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let freed = sqlx::query_scalar!(
+            r#"SELECT length(raw_logs) as "freed!: i64"
+               FROM execution_process_log_archives
+               WHERE execution_id = $1 AND raw_logs IS NOT NULL"#,
+            execution_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(freed) = freed else {
+            return Ok(0);
+        };
+        sqlx::query!(
+            r#"UPDATE execution_process_log_archives
+               SET raw_logs = NULL,
+                   stored_byte_size = COALESCE(length(conversation), 0),
+                   raw_pruned_at = datetime('now', 'subsec')
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(freed)
+    }
+
+    /// Finished processes whose logs are still in `execution_process_logs`,
+    /// oldest first
+    pub async fn find_uncompacted(
+        pool: &SqlitePool,
+        finished_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               WHERE ep.status != 'running'
+                 AND ep.completed_at IS NOT NULL
+                 AND ep.completed_at < $1
+                 AND EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+               ORDER BY ep.completed_at ASC"#,
+            finished_before
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Compacted processes whose raw logs can be pruned, oldest first. Coding
+    /// agents whose conversation couldn't be kept are left alone, since the raw
+    /// logs are all that's left of them.
+    pub async fn find_prunable(
+        pool: &SqlitePool,
+        finished_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT a.execution_id as "id!: Uuid"
+               FROM execution_process_log_archives a
+               JOIN execution_processes ep ON ep.id = a.execution_id
+               WHERE a.raw_logs IS NOT NULL
+                 AND ep.completed_at < $1
+                 AND (a.conversation IS NOT NULL OR ep.run_reason != 'codingagent')
+               ORDER BY ep.completed_at ASC"#,
+            finished_before
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Bytes taken by all stored logs, compacted or not
+    pub async fn total_stored_bytes(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT (SELECT COALESCE(SUM(byte_size), 0) FROM execution_process_logs)
+                    + (SELECT COALESCE(SUM(stored_byte_size), 0) FROM execution_process_log_archives)
+                    as "total!: i64""#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Log storage of every project that has run a process, largest first
+    pub async fn storage_by_project(
+        pool: &SqlitePool,
+    ) -> Result<Vec<ProjectLogStorage>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectLogStorage,
+            r#"SELECT p.id as "project_id!: Uuid",
+                      p.name as "project_name!",
+                      COUNT(ep.id) as "process_count!: i64",
+                      COALESCE(SUM(l.bytes), 0) as "live_bytes!: i64",
+                      COALESCE(SUM(a.raw_byte_size), 0) as "compacted_bytes!: i64",
+                      COALESCE(SUM(a.stored_byte_size), 0) as "compacted_stored_bytes!: i64",
+                      COUNT(a.raw_pruned_at) as "pruned_process_count!: i64"
+               FROM projects p
+               JOIN tasks t ON t.project_id = p.id
+               JOIN workspaces w ON w.task_id = t.id
+               JOIN sessions s ON s.workspace_id = w.id
+               JOIN execution_processes ep ON ep.session_id = s.id
+               LEFT JOIN (
+                   SELECT execution_id, SUM(byte_size) AS bytes
+                   FROM execution_process_logs
+                   GROUP BY execution_id
+               ) l ON l.execution_id = ep.id
+               LEFT JOIN execution_process_log_archives a ON a.execution_id = ep.id
+               GROUP BY p.id, p.name
+               ORDER BY COALESCE(SUM(l.bytes), 0) + COALESCE(SUM(a.stored_byte_size), 0) DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,227 @@
+//! Token usage reported by coding agents, stored per execution process.
+//!
+//! Counts are the agent's cumulative totals for the execution; each report
+//! overwrites the previous one. Costs are derived at read time from the
+//! configured pricing table so price changes apply retroactively.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use utils::log_msg::TokenUsage;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessTokenUsage {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub model: Option<String>,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_creation_input_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_input_tokens: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Token totals for one executor/model pair within a task, project or globally
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TokenUsageBreakdown {
+    pub executor: Option<String>,
+    pub model: Option<String>,
+    #[ts(type = "number")]
+    pub execution_count: i64,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_creation_input_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_input_tokens: i64,
+}
+
+impl ExecutionProcessTokenUsage {
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessTokenUsage,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                model,
+                input_tokens as "input_tokens!: i64",
+                output_tokens as "output_tokens!: i64",
+                cache_creation_input_tokens as "cache_creation_input_tokens!: i64",
+                cache_read_input_tokens as "cache_read_input_tokens!: i64",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_token_usage
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record the latest cumulative usage for an execution process
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TokenUsage,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_creation_input_tokens = usage.cache_creation_input_tokens as i64;
+        let cache_read_input_tokens = usage.cache_read_input_tokens as i64;
+
+        sqlx::query!(
+            r#"INSERT INTO execution_process_token_usage (
+                   id, execution_process_id, model, input_tokens, output_tokens,
+                   cache_creation_input_tokens, cache_read_input_tokens
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   model = COALESCE(excluded.model, model),
+                   input_tokens = excluded.input_tokens,
+                   output_tokens = excluded.output_tokens,
+                   cache_creation_input_tokens = excluded.cache_creation_input_tokens,
+                   cache_read_input_tokens = excluded.cache_read_input_tokens,
+                   updated_at = datetime('now', 'subsec')"#,
+            id,
+            execution_process_id,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens,
+            cache_read_input_tokens
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Usage for every execution of a task, grouped by executor and model
+    pub async fn find_breakdown_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<TokenUsageBreakdown>, sqlx::Error> {
+        sqlx::query_as!(
+            TokenUsageBreakdown,
+            r#"SELECT
+                s.executor                             as "executor?: String",
+                u.model                                as "model?: String",
+                COUNT(*)                               as "execution_count!: i64",
+                COALESCE(SUM(u.input_tokens), 0)       as "input_tokens!: i64",
+                COALESCE(SUM(u.output_tokens), 0)      as "output_tokens!: i64",
+                COALESCE(SUM(u.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(u.cache_read_input_tokens), 0)     as "cache_read_input_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               GROUP BY s.executor, u.model
+               ORDER BY s.executor, u.model"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage for every execution in one workspace, grouped by executor and model
+    pub async fn find_breakdown_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<TokenUsageBreakdown>, sqlx::Error> {
+        sqlx::query_as!(
+            TokenUsageBreakdown,
+            r#"SELECT
+                s.executor                             as "executor?: String",
+                u.model                                as "model?: String",
+                COUNT(*)                               as "execution_count!: i64",
+                COALESCE(SUM(u.input_tokens), 0)       as "input_tokens!: i64",
+                COALESCE(SUM(u.output_tokens), 0)      as "output_tokens!: i64",
+                COALESCE(SUM(u.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(u.cache_read_input_tokens), 0)     as "cache_read_input_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               GROUP BY s.executor, u.model
+               ORDER BY s.executor, u.model"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage for every task in a project, optionally limited to executions recorded
+    /// at or after `since`, grouped by executor and model
+    pub async fn find_breakdown_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<TokenUsageBreakdown>, sqlx::Error> {
+        sqlx::query_as!(
+            TokenUsageBreakdown,
+            r#"SELECT
+                s.executor                             as "executor?: String",
+                u.model                                as "model?: String",
+                COUNT(*)                               as "execution_count!: i64",
+                COALESCE(SUM(u.input_tokens), 0)       as "input_tokens!: i64",
+                COALESCE(SUM(u.output_tokens), 0)      as "output_tokens!: i64",
+                COALESCE(SUM(u.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(u.cache_read_input_tokens), 0)     as "cache_read_input_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+                 AND ($2 IS NULL OR datetime(u.created_at) >= datetime($2))
+               GROUP BY s.executor, u.model
+               ORDER BY s.executor, u.model"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage across all projects, optionally limited to executions recorded at or
+    /// after `since`, grouped by executor and model
+    pub async fn find_breakdown(
+        pool: &SqlitePool,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<TokenUsageBreakdown>, sqlx::Error> {
+        sqlx::query_as!(
+            TokenUsageBreakdown,
+            r#"SELECT
+                s.executor                             as "executor?: String",
+                u.model                                as "model?: String",
+                COUNT(*)                               as "execution_count!: i64",
+                COALESCE(SUM(u.input_tokens), 0)       as "input_tokens!: i64",
+                COALESCE(SUM(u.output_tokens), 0)      as "output_tokens!: i64",
+                COALESCE(SUM(u.cache_creation_input_tokens), 0) as "cache_creation_input_tokens!: i64",
+                COALESCE(SUM(u.cache_read_input_tokens), 0)     as "cache_read_input_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE ($1 IS NULL OR datetime(u.created_at) >= datetime($1))
+               GROUP BY s.executor, u.model
+               ORDER BY s.executor, u.model"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,110 @@
+//! Attempts imported from archives exported by another instance.
+//!
+//! The archive is stored on disk and browsed read-only; these rows only
+//! list what has been imported.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ImportedAttempt {
+    pub id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+    pub format_version: u32,
+    /// Version of vibe-kanban that exported the attempt
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateImportedAttempt {
+    pub task_title: String,
+    pub branch: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub size_bytes: i64,
+}
+
+impl ImportedAttempt {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateImportedAttempt,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ImportedAttempt,
+            r#"INSERT INTO imported_attempts
+                   (id, task_title, branch, format_version, app_version, exported_at, size_bytes)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         task_title,
+                         branch,
+                         format_version as "format_version!: u32",
+                         app_version,
+                         exported_at as "exported_at!: DateTime<Utc>",
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.task_title,
+            data.branch,
+            data.format_version,
+            data.app_version,
+            data.exported_at,
+            data.size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportedAttempt,
+            r#"SELECT id as "id!: Uuid",
+                      task_title,
+                      branch,
+                      format_version as "format_version!: u32",
+                      app_version,
+                      exported_at as "exported_at!: DateTime<Utc>",
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM imported_attempts
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportedAttempt,
+            r#"SELECT id as "id!: Uuid",
+                      task_title,
+                      branch,
+                      format_version as "format_version!: u32",
+                      app_version,
+                      exported_at as "exported_at!: DateTime<Utc>",
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM imported_attempts
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM imported_attempts WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
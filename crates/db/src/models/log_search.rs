@@ -0,0 +1,168 @@
+//! Full-text index over normalized log entries.
+//!
+//! Messages, tool calls and errors from coding agent logs are copied into
+//! `log_search_entries` as the logs stream in, and an FTS5 table kept in sync
+//! by triggers makes them searchable across every task and attempt.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Longest snippet returned around a match, in tokens
+const SNIPPET_TOKENS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LogSearchKind {
+    UserMessage,
+    AssistantMessage,
+    ToolUse,
+    Error,
+}
+
+/// Searchable text of one normalized entry
+#[derive(Debug, Clone)]
+pub struct LogSearchEntry {
+    pub entry_index: i64,
+    pub kind: LogSearchKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LogSearchHit {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub workspace_branch: String,
+    pub execution_process_id: Uuid,
+    pub executor: Option<String>,
+    #[ts(type = "number")]
+    pub entry_index: i64,
+    pub kind: LogSearchKind,
+    /// Text around the match, with matched words wrapped in `**`
+    pub snippet: String,
+    /// When the execution process started
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogSearchFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub kind: Option<LogSearchKind>,
+}
+
+/// Turn free text into an FTS5 query matching entries that contain every
+/// word, so FTS5 operators and quotes in the input are taken literally
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(|term| format!("\"{term}\""))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+impl LogSearchEntry {
+    /// Insert or refresh the indexed text of entries from one execution
+    pub async fn upsert_many(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        entries: &[LogSearchEntry],
+    ) -> Result<(), sqlx::Error> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut tx = pool.begin().await?;
+        for entry in entries {
+            sqlx::query!(
+                r#"INSERT INTO log_search_entries (execution_process_id, entry_index, kind, content)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT(execution_process_id, entry_index) DO UPDATE
+                   SET kind = excluded.kind, content = excluded.content
+                   WHERE content != excluded.content OR kind != excluded.kind"#,
+                execution_process_id,
+                entry.entry_index,
+                entry.kind,
+                entry.content
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Entries matching `query`, newest execution first
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        filter: &LogSearchFilter,
+        limit: i64,
+    ) -> Result<Vec<LogSearchHit>, sqlx::Error> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        sqlx::query_as!(
+            LogSearchHit,
+            r#"SELECT t.project_id as "project_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as "task_title!",
+                      w.id as "workspace_id!: Uuid",
+                      w.branch as "workspace_branch!",
+                      ep.id as "execution_process_id!: Uuid",
+                      s.executor,
+                      e.entry_index as "entry_index!: i64",
+                      e.kind as "kind!: LogSearchKind",
+                      snippet(log_search_fts, 0, '**', '**', '…', $6) as "snippet!: String",
+                      ep.created_at as "created_at!: DateTime<Utc>"
+               FROM log_search_fts
+               JOIN log_search_entries e ON e.id = log_search_fts.rowid
+               JOIN execution_processes ep ON ep.id = e.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE log_search_fts MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+                 AND ($3 IS NULL OR t.id = $3)
+                 AND ($4 IS NULL OR e.kind = $4)
+               ORDER BY ep.created_at DESC, e.entry_index DESC
+               LIMIT $5"#,
+            query,
+            filter.project_id,
+            filter.task_id,
+            filter.kind,
+            limit,
+            SNIPPET_TOKENS
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fts_query_quotes_every_word() {
+        assert_eq!(
+            fts_query("`cargo sqlx prepare`").as_deref(),
+            Some(r#""`cargo" "sqlx" "prepare`""#)
+        );
+        assert_eq!(
+            fts_query(r#"src/main.rs NOT "fix"#).as_deref(),
+            Some(r#""src/main.rs" "NOT" "fix""#)
+        );
+    }
+
+    #[test]
+    fn fts_query_without_words() {
+        assert_eq!(fts_query("   "), None);
+        assert_eq!(fts_query(r#"" * -"#), None);
+    }
+}
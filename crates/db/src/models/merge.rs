@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -14,6 +14,59 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// Code hosting service a pull/merge request was opened on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    #[default]
+    GitHub,
+    GitLab,
+}
+
+/// Result of a CI check, or of all checks on a commit combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CiStatus {
+    Pending,
+    Success,
+    Failure,
+}
+
+impl CiStatus {
+    /// Any failure fails the commit; otherwise it is pending until every check is done
+    pub fn combine(checks: &[CiCheck]) -> Self {
+        if checks.iter().any(|check| check.status == CiStatus::Failure) {
+            CiStatus::Failure
+        } else if checks.iter().any(|check| check.status == CiStatus::Pending) {
+            CiStatus::Pending
+        } else {
+            CiStatus::Success
+        }
+    }
+}
+
+/// One CI job or commit status reported for a PR's head commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CiCheck {
+    pub name: String,
+    pub status: CiStatus,
+    pub url: Option<String>,
+    /// Forge id of the job, set when its log can be fetched
+    #[ts(type = "number | null")]
+    pub job_id: Option<i64>,
+}
+
+/// CI checks on the head commit of a PR
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PrCiState {
+    pub head_sha: String,
+    pub status: CiStatus,
+    pub checks: Vec<CiCheck>,
+    pub checked_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -39,7 +92,17 @@ pub struct PrMerge {
     pub repo_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub target_branch_name: String,
+    pub forge: ForgeKind,
     pub pr_info: PullRequestInfo,
+    /// Latest CI result for the PR head, unset while the forge reports no checks
+    pub ci: Option<PrCiState>,
+    /// Follow-ups started so far to fix failing CI on this PR
+    #[ts(type = "number")]
+    pub ci_fix_attempts: i64,
+    /// Head commit whose failing CI the last fix follow-up was started for
+    pub ci_fix_head_sha: Option<String>,
+    /// Latest fix follow-up, until its changes have been pushed
+    pub ci_fix_execution_process_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -71,6 +134,14 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    pr_forge: Option<ForgeKind>,
+    pr_ci_status: Option<CiStatus>,
+    pr_ci_head_sha: Option<String>,
+    pr_ci_checks: Option<Json<Vec<CiCheck>>>,
+    pr_ci_checked_at: Option<DateTime<Utc>>,
+    pr_ci_fix_attempts: i64,
+    pr_ci_fix_head_sha: Option<String>,
+    pr_ci_fix_execution_process_id: Option<Uuid>,
     created_at: DateTime<Utc>,
 }
 
@@ -109,6 +180,14 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: Json<Vec<CiCheck>>",
+                pr_ci_checked_at as "pr_ci_checked_at?: DateTime<Utc>",
+                pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64",
+                pr_ci_fix_head_sha,
+                pr_ci_fix_execution_process_id as "pr_ci_fix_execution_process_id?: Uuid",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -131,6 +210,7 @@ impl Merge {
         target_branch_name: &str,
         pr_number: i64,
         pr_url: &str,
+        forge: ForgeKind,
     ) -> Result<PrMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -138,8 +218,8 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, workspace_id, repo_id, merge_type, pr_number, pr_url, pr_status, created_at, target_branch_name
-            ) VALUES ($1, $2, $3, 'pr', $4, $5, 'open', $6, $7)
+                id, workspace_id, repo_id, merge_type, pr_number, pr_url, pr_status, created_at, target_branch_name, pr_forge
+            ) VALUES ($1, $2, $3, 'pr', $4, $5, 'open', $6, $7, $8)
             RETURNING
                 id as "id!: Uuid",
                 workspace_id as "workspace_id!: Uuid",
@@ -151,6 +231,14 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: Json<Vec<CiCheck>>",
+                pr_ci_checked_at as "pr_ci_checked_at?: DateTime<Utc>",
+                pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64",
+                pr_ci_fix_head_sha,
+                pr_ci_fix_execution_process_id as "pr_ci_fix_execution_process_id?: Uuid",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -160,7 +248,8 @@ impl Merge {
             pr_number,
             pr_url,
             now,
-            target_branch_name
+            target_branch_name,
+            forge
         )
         .fetch_one(pool)
         .await
@@ -182,6 +271,14 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: Json<Vec<CiCheck>>",
+                pr_ci_checked_at as "pr_ci_checked_at?: DateTime<Utc>",
+                pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64",
+                pr_ci_fix_head_sha,
+                pr_ci_fix_execution_process_id as "pr_ci_fix_execution_process_id?: Uuid",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges
@@ -223,6 +320,103 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Record that a PR now targets a different branch
+    pub async fn update_target_branch(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET target_branch_name = $1 WHERE id = $2",
+            target_branch_name,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store the latest CI result for a PR, `None` when the forge reports no checks
+    pub async fn update_ci(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        ci: Option<&PrCiState>,
+    ) -> Result<(), sqlx::Error> {
+        let status = ci.map(|ci| ci.status);
+        let head_sha = ci.map(|ci| ci.head_sha.as_str());
+        let checks = ci.map(|ci| Json(&ci.checks));
+        let checked_at = ci.map(|ci| ci.checked_at);
+
+        sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_status = $1,
+                pr_ci_head_sha = $2,
+                pr_ci_checks = $3,
+                pr_ci_checked_at = $4
+            WHERE id = $5"#,
+            status,
+            head_sha,
+            checks,
+            checked_at,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a follow-up started to fix failing CI on `head_sha`
+    pub async fn record_ci_fix(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        head_sha: &str,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merges
+            SET pr_ci_fix_attempts = pr_ci_fix_attempts + 1,
+                pr_ci_fix_head_sha = $1,
+                pr_ci_fix_execution_process_id = $2
+            WHERE id = $3"#,
+            head_sha,
+            execution_process_id,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget the fix follow-up once its changes were pushed
+    pub async fn clear_ci_fix_execution_process(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET pr_ci_fix_execution_process_id = NULL WHERE id = $1",
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_workspace_id_by_rowid(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid" FROM merges WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
@@ -242,6 +436,14 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: Json<Vec<CiCheck>>",
+                pr_ci_checked_at as "pr_ci_checked_at?: DateTime<Utc>",
+                pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64",
+                pr_ci_fix_head_sha,
+                pr_ci_fix_execution_process_id as "pr_ci_fix_execution_process_id?: Uuid",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -275,6 +477,14 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                pr_forge as "pr_forge?: ForgeKind",
+                pr_ci_status as "pr_ci_status?: CiStatus",
+                pr_ci_head_sha,
+                pr_ci_checks as "pr_ci_checks?: Json<Vec<CiCheck>>",
+                pr_ci_checked_at as "pr_ci_checked_at?: DateTime<Utc>",
+                pr_ci_fix_attempts as "pr_ci_fix_attempts!: i64",
+                pr_ci_fix_head_sha,
+                pr_ci_fix_execution_process_id as "pr_ci_fix_execution_process_id?: Uuid",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges
@@ -313,6 +523,8 @@ impl From<MergeRow> for PrMerge {
             workspace_id: row.workspace_id,
             repo_id: row.repo_id,
             target_branch_name: row.target_branch_name,
+            // Rows written before forges were tracked are all GitHub PRs
+            forge: row.pr_forge.unwrap_or_default(),
             pr_info: PullRequestInfo {
                 number: row.pr_number.expect("pr merge must have pr_number"),
                 url: row.pr_url.expect("pr merge must have pr_url"),
@@ -320,6 +532,18 @@ impl From<MergeRow> for PrMerge {
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
             },
+            ci: match (row.pr_ci_status, row.pr_ci_head_sha, row.pr_ci_checked_at) {
+                (Some(status), Some(head_sha), Some(checked_at)) => Some(PrCiState {
+                    head_sha,
+                    status,
+                    checks: row.pr_ci_checks.map(|checks| checks.0).unwrap_or_default(),
+                    checked_at,
+                }),
+                _ => None,
+            },
+            ci_fix_attempts: row.pr_ci_fix_attempts,
+            ci_fix_head_sha: row.pr_ci_fix_head_sha,
+            ci_fix_execution_process_id: row.pr_ci_fix_execution_process_id,
             created_at: row.created_at,
         }
     }
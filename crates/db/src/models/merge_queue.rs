@@ -0,0 +1,476 @@
+//! Local merge queue for attempts that are ready to merge.
+//!
+//! Merging one attempt makes the others targeting the same branch stale, so
+//! queued attempts of a repo are merged one at a time, oldest first. Each is
+//! rebased onto its target branch and verified right before its merge; a
+//! failure is recorded on the entry and the attempt goes back to the agent.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MergeQueueStatus {
+    Queued,
+    Rebasing,
+    Verifying,
+    Merged,
+    Failed,
+    Cancelled,
+}
+
+impl MergeQueueStatus {
+    /// Still waiting for its merge or being merged
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Rebasing | Self::Verifying)
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub status: MergeQueueStatus,
+    /// Verification or setup script run after the rebase
+    pub execution_process_id: Option<Uuid>,
+    /// Conflicts or script output when the merge failed
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MergeQueueEntry {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"INSERT INTO merge_queue_entries (id, workspace_id, repo_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         status as "status!: MergeQueueStatus",
+                         execution_process_id as "execution_process_id: Uuid",
+                         error_message,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent entry of an attempt for one repo, whatever its status
+    pub async fn find_latest_by_workspace_and_repo(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: MergeQueueStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      error_message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE workspace_id = $1 AND repo_id = $2
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether any repo of the attempt is waiting in the queue or being merged
+    pub async fn is_workspace_queued(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM merge_queue_entries
+               WHERE workspace_id = $1 AND status IN ('queued', 'rebasing', 'verifying')"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Number of queued entries of the same repo that are merged before this one
+    pub async fn count_ahead(pool: &SqlitePool, entry: &Self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM merge_queue_entries
+               WHERE repo_id = $1
+                 AND id != $2
+                 AND (status IN ('rebasing', 'verifying')
+                      OR (status = 'queued'
+                          AND created_at < (SELECT created_at FROM merge_queue_entries WHERE id = $2)))"#,
+            entry.repo_id,
+            entry.id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Entries being rebased or verified
+    pub async fn find_in_flight(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: MergeQueueStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      error_message,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE status IN ('rebasing', 'verifying')
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Queued entries of repos that have nothing in flight, oldest first. Only the
+    /// first entry of each repo may start.
+    pub async fn find_startable(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT q.id as "id!: Uuid",
+                      q.workspace_id as "workspace_id!: Uuid",
+                      q.repo_id as "repo_id!: Uuid",
+                      q.status as "status!: MergeQueueStatus",
+                      q.execution_process_id as "execution_process_id: Uuid",
+                      q.error_message,
+                      q.created_at as "created_at!: DateTime<Utc>",
+                      q.updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries q
+               WHERE q.status = 'queued'
+                 AND NOT EXISTS (
+                     SELECT 1 FROM merge_queue_entries other
+                     WHERE other.repo_id = q.repo_id
+                       AND other.status IN ('rebasing', 'verifying')
+                 )
+               ORDER BY q.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: MergeQueueStatus,
+        error_message: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $2,
+                   error_message = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error_message
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the script that verifies the rebased attempt
+    pub async fn set_verifying(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'verifying',
+                   execution_process_id = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Cancel an entry that has not started yet. Returns false if it already has.
+    pub async fn cancel(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'cancelled',
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'queued'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put entries whose rebase was cut short by a shutdown back in the queue
+    pub async fn requeue_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'queued',
+                   updated_at = datetime('now', 'subsec')
+               WHERE status = 'rebasing'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        repo::Repo,
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    async fn create_project(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: "project".to_string(),
+            repositories: vec![],
+        };
+        Project::create(pool, &project, project_id).await.unwrap();
+        project_id
+    }
+
+    /// Creates a task with one workspace on the given branch
+    async fn create_workspace(pool: &SqlitePool, project_id: Uuid, branch: &str) -> Uuid {
+        let task_id = Uuid::new_v4();
+        let data = CreateTask::from_title_description(project_id, branch.to_string(), None);
+        Task::create(pool, &data, task_id).await.unwrap();
+
+        let workspace_id = Uuid::new_v4();
+        let workspace = CreateWorkspace {
+            branch: format!("vk/{branch}"),
+            agent_working_dir: None,
+        };
+        Workspace::create(pool, &workspace, workspace_id, task_id)
+            .await
+            .unwrap();
+        workspace_id
+    }
+
+    /// Queues an entry; the pause keeps `created_at` distinct so the order is stable
+    async fn queue(pool: &SqlitePool, workspace_id: Uuid, repo_id: Uuid) -> MergeQueueEntry {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        MergeQueueEntry::create(pool, workspace_id, repo_id)
+            .await
+            .unwrap()
+    }
+
+    fn ids(entries: &[MergeQueueEntry]) -> Vec<Uuid> {
+        entries.iter().map(|entry| entry.id).collect()
+    }
+
+    #[sqlx::test]
+    async fn test_find_startable_waits_for_the_repo_in_flight(pool: SqlitePool) {
+        let project_id = create_project(&pool).await;
+        let frontend = Repo::find_or_create(&pool, Path::new("/repos/frontend"), "frontend")
+            .await
+            .unwrap();
+        let backend = Repo::find_or_create(&pool, Path::new("/repos/backend"), "backend")
+            .await
+            .unwrap();
+        let first = create_workspace(&pool, project_id, "first").await;
+        let second = create_workspace(&pool, project_id, "second").await;
+
+        let first_frontend = queue(&pool, first, frontend.id).await;
+        let second_frontend = queue(&pool, second, frontend.id).await;
+        let second_backend = queue(&pool, second, backend.id).await;
+
+        let startable = MergeQueueEntry::find_startable(&pool).await.unwrap();
+        assert_eq!(
+            ids(&startable),
+            vec![first_frontend.id, second_frontend.id, second_backend.id]
+        );
+        assert_eq!(
+            MergeQueueEntry::count_ahead(&pool, &second_frontend)
+                .await
+                .unwrap(),
+            1
+        );
+
+        // While an entry of the frontend repo is being merged, only the backend may start
+        MergeQueueEntry::update_status(&pool, first_frontend.id, MergeQueueStatus::Rebasing, None)
+            .await
+            .unwrap();
+        let startable = MergeQueueEntry::find_startable(&pool).await.unwrap();
+        assert_eq!(ids(&startable), vec![second_backend.id]);
+        let in_flight = MergeQueueEntry::find_in_flight(&pool).await.unwrap();
+        assert_eq!(ids(&in_flight), vec![first_frontend.id]);
+        assert_eq!(
+            MergeQueueEntry::count_ahead(&pool, &second_frontend)
+                .await
+                .unwrap(),
+            1
+        );
+
+        let session_id = Uuid::new_v4();
+        let session = CreateSession {
+            executor: Some("setup-script".to_string()),
+        };
+        Session::create(&pool, &session, session_id, first)
+            .await
+            .unwrap();
+        let process_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO execution_processes (id, session_id) VALUES ($1, $2)")
+            .bind(process_id)
+            .bind(session_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        MergeQueueEntry::set_verifying(&pool, first_frontend.id, process_id)
+            .await
+            .unwrap();
+        let verifying =
+            MergeQueueEntry::find_latest_by_workspace_and_repo(&pool, first, frontend.id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(verifying.status, MergeQueueStatus::Verifying);
+        assert_eq!(verifying.execution_process_id, Some(process_id));
+        assert!(
+            MergeQueueEntry::find_startable(&pool)
+                .await
+                .unwrap()
+                .iter()
+                .all(|entry| entry.repo_id != frontend.id)
+        );
+
+        MergeQueueEntry::update_status(&pool, first_frontend.id, MergeQueueStatus::Merged, None)
+            .await
+            .unwrap();
+        let startable = MergeQueueEntry::find_startable(&pool).await.unwrap();
+        assert_eq!(ids(&startable), vec![second_frontend.id, second_backend.id]);
+        assert_eq!(
+            MergeQueueEntry::count_ahead(&pool, &second_frontend)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(
+            !MergeQueueEntry::is_workspace_queued(&pool, first)
+                .await
+                .unwrap()
+        );
+        assert!(
+            MergeQueueEntry::is_workspace_queued(&pool, second)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_entries_of_an_attempt_are_active_once_per_repo(pool: SqlitePool) {
+        let project_id = create_project(&pool).await;
+        let repo = Repo::find_or_create(&pool, Path::new("/repos/app"), "app")
+            .await
+            .unwrap();
+        let workspace_id = create_workspace(&pool, project_id, "attempt").await;
+
+        let entry = queue(&pool, workspace_id, repo.id).await;
+        assert!(
+            MergeQueueEntry::create(&pool, workspace_id, repo.id)
+                .await
+                .is_err()
+        );
+
+        MergeQueueEntry::update_status(
+            &pool,
+            entry.id,
+            MergeQueueStatus::Failed,
+            Some("conflicts in src/main.rs"),
+        )
+        .await
+        .unwrap();
+        let failed =
+            MergeQueueEntry::find_latest_by_workspace_and_repo(&pool, workspace_id, repo.id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(failed.status, MergeQueueStatus::Failed);
+        assert_eq!(
+            failed.error_message.as_deref(),
+            Some("conflicts in src/main.rs")
+        );
+
+        // A failed attempt can be queued again
+        let requeued = queue(&pool, workspace_id, repo.id).await;
+        let latest =
+            MergeQueueEntry::find_latest_by_workspace_and_repo(&pool, workspace_id, repo.id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(latest.id, requeued.id);
+    }
+
+    #[sqlx::test]
+    async fn test_cancel_and_requeue_interrupted(pool: SqlitePool) {
+        let project_id = create_project(&pool).await;
+        let repo = Repo::find_or_create(&pool, Path::new("/repos/app"), "app")
+            .await
+            .unwrap();
+        let rebasing_workspace = create_workspace(&pool, project_id, "rebasing").await;
+        let queued_workspace = create_workspace(&pool, project_id, "queued").await;
+
+        let rebasing = queue(&pool, rebasing_workspace, repo.id).await;
+        let queued = queue(&pool, queued_workspace, repo.id).await;
+        MergeQueueEntry::update_status(&pool, rebasing.id, MergeQueueStatus::Rebasing, None)
+            .await
+            .unwrap();
+
+        // Only entries that have not started can leave the queue
+        assert!(!MergeQueueEntry::cancel(&pool, rebasing.id).await.unwrap());
+        assert!(MergeQueueEntry::cancel(&pool, queued.id).await.unwrap());
+        assert!(!MergeQueueEntry::cancel(&pool, queued.id).await.unwrap());
+        assert!(
+            !MergeQueueEntry::is_workspace_queued(&pool, queued_workspace)
+                .await
+                .unwrap()
+        );
+
+        // A rebase cut short by a shutdown starts over
+        assert_eq!(
+            MergeQueueEntry::requeue_interrupted(&pool).await.unwrap(),
+            1
+        );
+        let startable = MergeQueueEntry::find_startable(&pool).await.unwrap();
+        assert_eq!(ids(&startable), vec![rebasing.id]);
+    }
+}
@@ -1,10 +1,20 @@
+pub mod approval;
+pub mod attempt_group;
 pub mod coding_agent_turn;
 pub mod execution_process;
+pub mod execution_process_log_archive;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_process_token_usage;
 pub mod image;
+pub mod imported_attempt;
 pub mod interrupted_execution;
+pub mod log_search;
 pub mod merge;
+pub mod merge_queue;
+pub mod playbook;
+pub mod playbook_run;
+pub mod pr_review_follow_up;
 pub mod project;
 pub mod project_repo;
 pub mod repo;
@@ -12,6 +22,11 @@ pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_dependency;
 pub mod task_queue;
+pub mod task_schedule;
+pub mod verification_run;
+pub mod webhook;
+pub mod webhook_delivery;
 pub mod workspace;
 pub mod workspace_repo;
@@ -0,0 +1,204 @@
+//! Reusable multi-turn task scripts.
+//!
+//! A playbook is an ordered list of steps that can be attached to a task. Once
+//! the agent's first turn in a new attempt of that task is done, the container
+//! runs the steps one after the other without anyone sending follow-ups. Runs
+//! and the status of each of their steps live in `playbook_run`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlaybookStep {
+    /// Follow-up prompt for the coding agent
+    Prompt { prompt: String },
+    /// Shell script run in the workspace; the playbook stops if it fails
+    Script {
+        script: String,
+        /// Directory to run in, relative to the workspace (usually a repo name)
+        working_dir: Option<String>,
+    },
+    /// Script that has to pass. While it fails and retries are left, the agent
+    /// is sent `on_failure` with the script's output and the check runs again.
+    Check {
+        script: String,
+        working_dir: Option<String>,
+        on_failure: Option<String>,
+        #[serde(default)]
+        max_retries: u32,
+    },
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Playbook {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    #[ts(type = "Array<PlaybookStep>")]
+    pub steps: Json<Vec<PlaybookStep>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreatePlaybook {
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<PlaybookStep>,
+}
+
+/// Fields left out keep their current value
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdatePlaybook {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub steps: Option<Vec<PlaybookStep>>,
+}
+
+impl Playbook {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Playbook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      description,
+                      steps as "steps!: Json<Vec<PlaybookStep>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM playbooks
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Playbook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      description,
+                      steps as "steps!: Json<Vec<PlaybookStep>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM playbooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Playbook attached to a task, if any
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Playbook,
+            r#"SELECT p.id as "id!: Uuid",
+                      p.name,
+                      p.description,
+                      p.steps as "steps!: Json<Vec<PlaybookStep>>",
+                      p.created_at as "created_at!: DateTime<Utc>",
+                      p.updated_at as "updated_at!: DateTime<Utc>"
+               FROM playbooks p
+               JOIN task_playbooks tp ON tp.playbook_id = p.id
+               WHERE tp.task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePlaybook,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let steps = Json(&data.steps);
+        sqlx::query_as!(
+            Playbook,
+            r#"INSERT INTO playbooks (id, name, description, steps)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         description,
+                         steps as "steps!: Json<Vec<PlaybookStep>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.description,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replace every editable field
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreatePlaybook,
+    ) -> Result<Self, sqlx::Error> {
+        let steps = Json(&data.steps);
+        sqlx::query_as!(
+            Playbook,
+            r#"UPDATE playbooks
+               SET name = $2,
+                   description = $3,
+                   steps = $4,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         name,
+                         description,
+                         steps as "steps!: Json<Vec<PlaybookStep>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.description,
+            steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM playbooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Attach a playbook to a task, replacing the one attached before
+    pub async fn attach_to_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        playbook_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_playbooks (task_id, playbook_id)
+               VALUES ($1, $2)
+               ON CONFLICT(task_id) DO UPDATE SET playbook_id = excluded.playbook_id"#,
+            task_id,
+            playbook_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn detach_from_task(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM task_playbooks WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
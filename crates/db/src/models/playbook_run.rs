@@ -0,0 +1,300 @@
+//! Runs of playbooks in task attempts.
+//!
+//! A run copies its playbook's steps when it starts and records one step run
+//! per step it reaches, pointing at the latest process started for that step.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::playbook::{Playbook, PlaybookStep};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybookRunStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PlaybookStepRunStatus {
+    Running,
+    Passed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PlaybookRun {
+    pub id: Uuid,
+    /// None once the playbook was deleted
+    pub playbook_id: Option<Uuid>,
+    pub workspace_id: Uuid,
+    pub name: String,
+    /// Steps of the playbook when the run started
+    #[ts(type = "Array<PlaybookStep>")]
+    pub steps: Json<Vec<PlaybookStep>>,
+    pub status: PlaybookRunStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PlaybookStepRun {
+    pub id: Uuid,
+    pub playbook_run_id: Uuid,
+    #[ts(type = "number")]
+    pub step_index: i64,
+    pub status: PlaybookStepRunStatus,
+    /// Latest process started for the step
+    pub execution_process_id: Option<Uuid>,
+    /// Follow-ups sent to the agent after the step's check failed
+    #[ts(type = "number")]
+    pub retries: i64,
+    /// Last part of the output of a failed script or check
+    pub output: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PlaybookRunWithSteps {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub run: PlaybookRun,
+    /// Steps reached so far, in order
+    pub step_runs: Vec<PlaybookStepRun>,
+}
+
+impl PlaybookRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        playbook: &Playbook,
+        workspace_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PlaybookRun,
+            r#"INSERT INTO playbook_runs (id, playbook_id, workspace_id, name, steps)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         playbook_id as "playbook_id: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         name,
+                         steps as "steps!: Json<Vec<PlaybookStep>>",
+                         status as "status!: PlaybookRunStatus",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            playbook.id,
+            workspace_id,
+            playbook.name,
+            playbook.steps
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Runs in one workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlaybookRun,
+            r#"SELECT id as "id!: Uuid",
+                      playbook_id as "playbook_id: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      steps as "steps!: Json<Vec<PlaybookStep>>",
+                      status as "status!: PlaybookRunStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM playbook_runs
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_running_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlaybookRun,
+            r#"SELECT id as "id!: Uuid",
+                      playbook_id as "playbook_id: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      steps as "steps!: Json<Vec<PlaybookStep>>",
+                      status as "status!: PlaybookRunStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM playbook_runs
+               WHERE workspace_id = $1 AND status = 'running'
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Runs in one workspace with their steps, newest first
+    pub async fn find_with_steps_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<PlaybookRunWithSteps>, sqlx::Error> {
+        let mut runs = Vec::new();
+        for run in Self::find_by_workspace_id(pool, workspace_id).await? {
+            let step_runs = PlaybookStepRun::find_by_run_id(pool, run.id).await?;
+            runs.push(PlaybookRunWithSteps { run, step_runs });
+        }
+        Ok(runs)
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PlaybookRunStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE playbook_runs
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl PlaybookStepRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        playbook_run_id: Uuid,
+        step_index: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PlaybookStepRun,
+            r#"INSERT INTO playbook_step_runs (id, playbook_run_id, step_index)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         playbook_run_id as "playbook_run_id!: Uuid",
+                         step_index as "step_index!: i64",
+                         status as "status!: PlaybookStepRunStatus",
+                         execution_process_id as "execution_process_id: Uuid",
+                         retries as "retries!: i64",
+                         output,
+                         created_at as "created_at!: DateTime<Utc>",
+                         completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            playbook_run_id,
+            step_index
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_run_id(
+        pool: &SqlitePool,
+        playbook_run_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlaybookStepRun,
+            r#"SELECT id as "id!: Uuid",
+                      playbook_run_id as "playbook_run_id!: Uuid",
+                      step_index as "step_index!: i64",
+                      status as "status!: PlaybookStepRunStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      retries as "retries!: i64",
+                      output,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM playbook_step_runs
+               WHERE playbook_run_id = $1
+               ORDER BY step_index ASC"#,
+            playbook_run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The step of a run that is in progress
+    pub async fn find_running_by_run_id(
+        pool: &SqlitePool,
+        playbook_run_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PlaybookStepRun,
+            r#"SELECT id as "id!: Uuid",
+                      playbook_run_id as "playbook_run_id!: Uuid",
+                      step_index as "step_index!: i64",
+                      status as "status!: PlaybookStepRunStatus",
+                      execution_process_id as "execution_process_id: Uuid",
+                      retries as "retries!: i64",
+                      output,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM playbook_step_runs
+               WHERE playbook_run_id = $1 AND status = 'running'
+               ORDER BY step_index DESC
+               LIMIT 1"#,
+            playbook_run_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record the process just started for the step
+    pub async fn set_execution_process(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+        retries: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE playbook_step_runs
+               SET execution_process_id = $2, retries = $3
+               WHERE id = $1"#,
+            id,
+            execution_process_id,
+            retries
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PlaybookStepRunStatus,
+        output: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE playbook_step_runs
+               SET status = $2, output = $3, completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            output
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
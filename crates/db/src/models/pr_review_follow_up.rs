@@ -0,0 +1,117 @@
+//! PR review comments handed to a workspace's coding agent.
+//!
+//! The PR monitor sends new unresolved review comments to the agent in one
+//! follow-up and records each comment here, so it is only sent once. When the
+//! follow-up has finished, the branch is pushed and every comment gets a reply.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PrReviewFollowUpStatus {
+    /// Sent to the coding agent, which may still be working on it
+    Running,
+    Replied,
+    /// The follow-up or the push failed; the comment is not sent again
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PrReviewFollowUp {
+    pub id: Uuid,
+    pub merge_id: Uuid,
+    /// Review comment id on the forge
+    pub comment_id: i64,
+    pub execution_process_id: Option<Uuid>,
+    pub status: PrReviewFollowUpStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PrReviewFollowUp {
+    pub async fn create(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        comment_id: i64,
+        execution_process_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PrReviewFollowUp,
+            r#"INSERT INTO pr_review_follow_ups (id, merge_id, comment_id, execution_process_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         merge_id as "merge_id!: Uuid",
+                         comment_id as "comment_id!: i64",
+                         execution_process_id as "execution_process_id: Uuid",
+                         status as "status!: PrReviewFollowUpStatus",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            merge_id,
+            comment_id,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Every comment of a PR that was already sent to the agent
+    pub async fn find_comment_ids_by_merge_id(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT comment_id as "comment_id!: i64"
+               FROM pr_review_follow_ups
+               WHERE merge_id = $1"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Comments of a PR whose follow-up has not been wrapped up yet
+    pub async fn find_running_by_merge_id(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrReviewFollowUp,
+            r#"SELECT id as "id!: Uuid",
+                      merge_id as "merge_id!: Uuid",
+                      comment_id as "comment_id!: i64",
+                      execution_process_id as "execution_process_id: Uuid",
+                      status as "status!: PrReviewFollowUpStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_review_follow_ups
+               WHERE merge_id = $1 AND status = 'running'
+               ORDER BY created_at ASC"#,
+            merge_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PrReviewFollowUpStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_review_follow_ups
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
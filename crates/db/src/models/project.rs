@@ -24,6 +24,15 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    /// Image to run the project's workspaces in, instead of on the host
+    pub container_image: Option<String>,
+    /// Send new PR review comments to the workspace's coding agent
+    pub auto_address_review_comments: bool,
+    /// Send failing CI on the workspace's PRs to its coding agent
+    pub auto_fix_ci: bool,
+    /// Follow-ups started per PR to fix failing CI before giving up
+    #[ts(type = "number")]
+    pub max_ci_fix_attempts: i64,
     pub remote_project_id: Option<Uuid>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -43,20 +52,55 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub dev_script_working_dir: Option<String>,
     pub default_agent_working_dir: Option<String>,
+    pub container_image: Option<String>,
+    pub auto_address_review_comments: Option<bool>,
+    pub auto_fix_ci: Option<bool>,
+    #[ts(type = "number | null")]
+    pub max_ci_fix_attempts: Option<i64>,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct SearchResult {
     pub path: String,
     pub is_file: bool,
     pub match_type: SearchMatchType,
+    /// Definition found in the file, for symbol matches
+    pub symbol: Option<SymbolMatch>,
 }
 
-#[derive(Debug, Clone, Serialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub enum SearchMatchType {
     FileName,
     DirectoryName,
     FullPath,
+    Symbol,
+}
+
+/// Function, type or module defined in a repository file
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SymbolMatch {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based line the definition starts on
+    pub line: u32,
+    /// Type, trait or module the symbol is defined in, e.g. the `impl` type of a method
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Class,
+    Enum,
+    Interface,
+    Trait,
+    Type,
+    Module,
+    Constant,
+    Macro,
 }
 
 impl Project {
@@ -74,6 +118,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      container_image,
+                      auto_address_review_comments as "auto_address_review_comments!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -90,7 +138,10 @@ impl Project {
             Project,
             r#"
             SELECT p.id as "id!: Uuid", p.name, p.dev_script, p.dev_script_working_dir,
-                   p.default_agent_working_dir,
+                   p.default_agent_working_dir, p.container_image,
+                   p.auto_address_review_comments as "auto_address_review_comments!: bool",
+                   p.auto_fix_ci as "auto_fix_ci!: bool",
+                   p.max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                    p.remote_project_id as "remote_project_id: Uuid",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
@@ -116,6 +167,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      container_image,
+                      auto_address_review_comments as "auto_address_review_comments!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -135,6 +190,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      container_image,
+                      auto_address_review_comments as "auto_address_review_comments!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -157,6 +216,10 @@ impl Project {
                       dev_script,
                       dev_script_working_dir,
                       default_agent_working_dir,
+                      container_image,
+                      auto_address_review_comments as "auto_address_review_comments!: bool",
+                      auto_fix_ci as "auto_fix_ci!: bool",
+                      max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -187,6 +250,10 @@ impl Project {
                           dev_script,
                           dev_script_working_dir,
                           default_agent_working_dir,
+                          container_image,
+                          auto_address_review_comments as "auto_address_review_comments!: bool",
+                          auto_fix_ci as "auto_fix_ci!: bool",
+                          max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                           remote_project_id as "remote_project_id: Uuid",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
@@ -210,17 +277,37 @@ impl Project {
         let dev_script = payload.dev_script.clone();
         let dev_script_working_dir = payload.dev_script_working_dir.clone();
         let default_agent_working_dir = payload.default_agent_working_dir.clone();
+        let container_image = payload
+            .container_image
+            .as_deref()
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+            .map(str::to_string);
+        let auto_address_review_comments = payload
+            .auto_address_review_comments
+            .unwrap_or(existing.auto_address_review_comments);
+        let auto_fix_ci = payload.auto_fix_ci.unwrap_or(existing.auto_fix_ci);
+        let max_ci_fix_attempts = payload
+            .max_ci_fix_attempts
+            .map(|attempts| attempts.max(0))
+            .unwrap_or(existing.max_ci_fix_attempts);
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5
+               SET name = $2, dev_script = $3, dev_script_working_dir = $4, default_agent_working_dir = $5,
+                   container_image = $6, auto_address_review_comments = $7, auto_fix_ci = $8,
+                   max_ci_fix_attempts = $9
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          dev_script,
                          dev_script_working_dir,
                          default_agent_working_dir,
+                         container_image,
+                         auto_address_review_comments as "auto_address_review_comments!: bool",
+                         auto_fix_ci as "auto_fix_ci!: bool",
+                         max_ci_fix_attempts as "max_ci_fix_attempts!: i64",
                          remote_project_id as "remote_project_id: Uuid",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
@@ -229,6 +316,10 @@ impl Project {
             dev_script,
             dev_script_working_dir,
             default_agent_working_dir,
+            container_image,
+            auto_address_review_comments,
+            auto_fix_ci,
+            max_ci_fix_attempts,
         )
         .fetch_one(pool)
         .await
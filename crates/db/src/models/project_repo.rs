@@ -26,6 +26,8 @@ pub struct ProjectRepo {
     pub repo_id: Uuid,
     pub setup_script: Option<String>,
     pub cleanup_script: Option<String>,
+    /// Runs after the cleanup script; the task only moves to review if it passes
+    pub verification_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
 }
@@ -39,6 +41,7 @@ pub struct ProjectRepoWithName {
     pub repo_name: String,
     pub setup_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub verification_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
 }
@@ -54,6 +57,7 @@ pub struct CreateProjectRepo {
 pub struct UpdateProjectRepo {
     pub setup_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub verification_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
 }
@@ -70,6 +74,7 @@ impl ProjectRepo {
                       repo_id as "repo_id!: Uuid",
                       setup_script,
                       cleanup_script,
+                      verification_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool"
                FROM project_repos
@@ -91,6 +96,7 @@ impl ProjectRepo {
                       repo_id as "repo_id!: Uuid",
                       setup_script,
                       cleanup_script,
+                      verification_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool"
                FROM project_repos
@@ -113,6 +119,7 @@ impl ProjectRepo {
                       r.name as "repo_name!",
                       pr.setup_script,
                       pr.cleanup_script,
+                      pr.verification_script,
                       pr.copy_files,
                       pr.parallel_setup_script as "parallel_setup_script!: bool"
                FROM project_repos pr
@@ -159,6 +166,7 @@ impl ProjectRepo {
                       repo_id as "repo_id!: Uuid",
                       setup_script,
                       cleanup_script,
+                      verification_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool"
                FROM project_repos
@@ -234,6 +242,7 @@ impl ProjectRepo {
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
+                         verification_script,
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool""#,
             id,
@@ -255,6 +264,7 @@ impl ProjectRepo {
 
         let setup_script = payload.setup_script.clone();
         let cleanup_script = payload.cleanup_script.clone();
+        let verification_script = payload.verification_script.clone();
         let copy_files = payload.copy_files.clone();
         let parallel_setup_script = payload
             .parallel_setup_script
@@ -265,18 +275,21 @@ impl ProjectRepo {
             r#"UPDATE project_repos
                SET setup_script = $1,
                    cleanup_script = $2,
-                   copy_files = $3,
-                   parallel_setup_script = $4
-               WHERE project_id = $5 AND repo_id = $6
+                   verification_script = $3,
+                   copy_files = $4,
+                   parallel_setup_script = $5
+               WHERE project_id = $6 AND repo_id = $7
                RETURNING id as "id!: Uuid",
                          project_id as "project_id!: Uuid",
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
+                         verification_script,
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool""#,
             setup_script,
             cleanup_script,
+            verification_script,
             copy_files,
             parallel_setup_script,
             project_id,
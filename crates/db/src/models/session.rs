@@ -20,6 +20,11 @@ pub struct Session {
     pub id: Uuid,
     pub workspace_id: Uuid,
     pub executor: Option<String>,
+    /// Coding agent turn this session was forked from
+    pub forked_from_process_id: Option<Uuid>,
+    /// Session whose conversation outgrew the context window and was
+    /// summarised into this one
+    pub compacted_from_session_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -36,6 +41,8 @@ impl Session {
             r#"SELECT id AS "id!: Uuid",
                       workspace_id AS "workspace_id!: Uuid",
                       executor,
+                      forked_from_process_id AS "forked_from_process_id: Uuid",
+                      compacted_from_session_id AS "compacted_from_session_id: Uuid",
                       created_at AS "created_at!: DateTime<Utc>",
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM sessions
@@ -55,6 +62,8 @@ impl Session {
             r#"SELECT id AS "id!: Uuid",
                       workspace_id AS "workspace_id!: Uuid",
                       executor,
+                      forked_from_process_id AS "forked_from_process_id: Uuid",
+                      compacted_from_session_id AS "compacted_from_session_id: Uuid",
                       created_at AS "created_at!: DateTime<Utc>",
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM sessions
@@ -76,6 +85,8 @@ impl Session {
             r#"SELECT id AS "id!: Uuid",
                       workspace_id AS "workspace_id!: Uuid",
                       executor,
+                      forked_from_process_id AS "forked_from_process_id: Uuid",
+                      compacted_from_session_id AS "compacted_from_session_id: Uuid",
                       created_at AS "created_at!: DateTime<Utc>",
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM sessions
@@ -101,6 +112,8 @@ impl Session {
                RETURNING id AS "id!: Uuid",
                          workspace_id AS "workspace_id!: Uuid",
                          executor,
+                         forked_from_process_id AS "forked_from_process_id: Uuid",
+                         compacted_from_session_id AS "compacted_from_session_id: Uuid",
                          created_at AS "created_at!: DateTime<Utc>",
                          updated_at AS "updated_at!: DateTime<Utc>""#,
             id,
@@ -110,4 +123,77 @@ impl Session {
         .fetch_one(pool)
         .await?)
     }
+
+    /// Create a session that continues the conversation of another session's
+    /// coding agent turn
+    pub async fn create_fork(
+        pool: &SqlitePool,
+        data: &CreateSession,
+        id: Uuid,
+        workspace_id: Uuid,
+        forked_from_process_id: Uuid,
+    ) -> Result<Self, SessionError> {
+        Ok(sqlx::query_as!(
+            Session,
+            r#"INSERT INTO sessions (id, workspace_id, executor, forked_from_process_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         executor,
+                         forked_from_process_id AS "forked_from_process_id: Uuid",
+                         compacted_from_session_id AS "compacted_from_session_id: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            data.executor,
+            forked_from_process_id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Create a session that continues a session whose conversation no longer
+    /// fit in the agent's context window
+    pub async fn create_compacted(
+        pool: &SqlitePool,
+        data: &CreateSession,
+        id: Uuid,
+        workspace_id: Uuid,
+        compacted_from_session_id: Uuid,
+    ) -> Result<Self, SessionError> {
+        Ok(sqlx::query_as!(
+            Session,
+            r#"INSERT INTO sessions (id, workspace_id, executor, compacted_from_session_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         executor,
+                         forked_from_process_id AS "forked_from_process_id: Uuid",
+                         compacted_from_session_id AS "compacted_from_session_id: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            data.executor,
+            compacted_from_session_id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// How many times the sessions of a workspace were compacted
+    pub async fn count_compactions(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64"
+               FROM sessions
+               WHERE workspace_id = $1 AND compacted_from_session_id IS NOT NULL"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
 }
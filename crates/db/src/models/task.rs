@@ -5,7 +5,7 @@ use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{merge::CiStatus, project::Project, workspace::Workspace};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -43,6 +43,8 @@ pub struct TaskWithAttemptStatus {
     pub has_in_progress_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
+    /// CI result of the task's latest open PR
+    pub ci_status: Option<CiStatus>,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -67,6 +69,8 @@ pub struct TaskWithAttemptStatusAndProject {
     pub has_in_progress_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
+    /// CI result of the task's latest open PR
+    pub ci_status: Option<CiStatus>,
     pub project_name: String,
 }
 
@@ -213,7 +217,17 @@ impl Task {
       WHERE w.task_id = t.id
      ORDER BY s.created_at DESC
       LIMIT 1
-    )                               AS "executor!: String"
+    )                               AS "executor!: String",
+
+  ( SELECT m.pr_ci_status
+      FROM workspaces w
+      JOIN merges m ON m.workspace_id = w.id
+     WHERE w.task_id = t.id
+       AND m.merge_type = 'pr'
+       AND m.pr_status = 'open'
+     ORDER BY m.created_at DESC
+      LIMIT 1
+    )                               AS "ci_status: CiStatus"
 
 FROM tasks t
 WHERE t.project_id = $1
@@ -240,6 +254,7 @@ ORDER BY t.created_at DESC"#,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
+                ci_status: rec.ci_status,
             })
             .collect();
 
@@ -294,7 +309,17 @@ ORDER BY t.created_at DESC"#,
         WHERE w.task_id = t.id
        ORDER BY s.created_at DESC
         LIMIT 1
-    ), '')                         AS "executor!: String"
+    ), '')                         AS "executor!: String",
+
+  ( SELECT m.pr_ci_status
+      FROM workspaces w
+      JOIN merges m ON m.workspace_id = w.id
+     WHERE w.task_id = t.id
+       AND m.merge_type = 'pr'
+       AND m.pr_status = 'open'
+     ORDER BY m.created_at DESC
+      LIMIT 1
+    )                               AS "ci_status: CiStatus"
 
 FROM tasks t
 JOIN projects p ON p.id = t.project_id
@@ -320,6 +345,7 @@ ORDER BY p.name ASC, t.created_at DESC"#
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
+                ci_status: rec.ci_status,
                 project_name: rec.project_name,
             })
             .collect();
@@ -381,11 +407,14 @@ ORDER BY p.name ASC, t.created_at DESC"#
         .await
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'e, E>(
+        executor: E,
         data: &CreateTask,
         task_id: Uuid,
-    ) -> Result<Self, sqlx::Error> {
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let status = data.status.clone().unwrap_or_default();
         sqlx::query_as!(
             Task,
@@ -400,7 +429,7 @@ ORDER BY p.name ASC, t.created_at DESC"#
             data.parent_workspace_id,
             data.shared_task_id
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
     }
 
@@ -0,0 +1,382 @@
+//! Blocked-by relationships between tasks.
+//!
+//! A task with unsatisfied dependencies is held in the task queue until every
+//! prerequisite is either `Done` or has a merged attempt.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqliteConnection, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Dependencies must be between tasks in the same project")]
+    CrossProject,
+    #[error("Dependency already exists")]
+    AlreadyExists,
+    #[error("Dependency not found")]
+    NotFound,
+    #[error("Adding this dependency would create a cycle")]
+    CycleDetected,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+/// A prerequisite task together with whether it currently blocks its dependent
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencyStatus {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub is_merged: bool,
+    pub is_satisfied: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencies {
+    /// Tasks this task waits on
+    pub blocked_by: Vec<TaskDependencyStatus>,
+    /// Tasks waiting on this task
+    pub blocking: Vec<Task>,
+    /// True when at least one prerequisite is not yet done or merged
+    pub is_blocked: bool,
+}
+
+impl TaskDependency {
+    pub async fn find_by_task_id<'e, E>(
+        executor: E,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Prerequisites of a task along with their done/merged state
+    pub async fn find_blocked_by(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<TaskDependencyStatus>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT
+  t.id              AS "id!: Uuid",
+  t.title           AS "title!: String",
+  t.status          AS "status!: TaskStatus",
+  CASE WHEN EXISTS (
+      SELECT 1
+        FROM merges m
+        JOIN workspaces w ON m.workspace_id = w.id
+       WHERE w.task_id = t.id
+         AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+       LIMIT 1
+  ) THEN 1 ELSE 0 END AS "is_merged!: i64"
+FROM task_dependencies td
+JOIN tasks t ON t.id = td.depends_on_task_id
+WHERE td.task_id = $1
+ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|rec| {
+                let is_merged = rec.is_merged != 0;
+                TaskDependencyStatus {
+                    task_id: rec.id,
+                    title: rec.title,
+                    is_satisfied: is_merged || rec.status == TaskStatus::Done,
+                    status: rec.status,
+                    is_merged,
+                }
+            })
+            .collect())
+    }
+
+    /// Tasks that list `task_id` as one of their prerequisites
+    pub async fn find_blocking(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+               ORDER BY t.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_dependencies_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        let blocked_by = Self::find_blocked_by(pool, task_id).await?;
+        let blocking = Self::find_blocking(pool, task_id).await?;
+        let is_blocked = blocked_by.iter().any(|d| !d.is_satisfied);
+        Ok(TaskDependencies {
+            blocked_by,
+            blocking,
+            is_blocked,
+        })
+    }
+
+    /// Whether the task has any prerequisite that is neither done nor merged
+    pub async fn is_blocked(pool: &SqlitePool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let blocked = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+  SELECT 1
+    FROM task_dependencies td
+    JOIN tasks dep ON dep.id = td.depends_on_task_id
+   WHERE td.task_id = $1
+     AND dep.status != 'done'
+     AND NOT EXISTS (
+         SELECT 1
+           FROM merges m
+           JOIN workspaces w ON m.workspace_id = w.id
+          WHERE w.task_id = dep.id
+            AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+     )
+) AS "blocked!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(blocked)
+    }
+
+    /// Whether `depends_on_task_id` already (transitively) depends on `task_id`
+    async fn would_create_cycle<'e, E>(
+        executor: E,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let cycle = sqlx::query_scalar!(
+            r#"WITH RECURSIVE reachable(id) AS (
+    SELECT depends_on_task_id FROM task_dependencies WHERE task_id = $1
+    UNION
+    SELECT td.depends_on_task_id
+      FROM task_dependencies td
+      JOIN reachable r ON td.task_id = r.id
+)
+SELECT EXISTS (SELECT 1 FROM reachable WHERE id = $2) AS "cycle!: bool""#,
+            depends_on_task_id,
+            task_id
+        )
+        .fetch_one(executor)
+        .await?;
+        Ok(cycle)
+    }
+
+    async fn find_project_id<'e, E>(executor: E, task_id: Uuid) -> Result<Uuid, TaskDependencyError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_scalar!(
+            r#"SELECT project_id as "project_id!: Uuid" FROM tasks WHERE id = $1"#,
+            task_id
+        )
+        .fetch_optional(executor)
+        .await?
+        .ok_or(TaskDependencyError::TaskNotFound)
+    }
+
+    /// Add a blocked-by edge, rejecting self-edges, cross-project edges and cycles
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<Self, TaskDependencyError> {
+        let mut tx = pool.begin().await?;
+        let dependency = Self::create_tx(&mut tx, task_id, depends_on_task_id).await?;
+        tx.commit().await?;
+        Ok(dependency)
+    }
+
+    /// Transaction-compatible version of create, so the checks and the insert
+    /// see the same snapshot
+    pub async fn create_tx(
+        conn: &mut SqliteConnection,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<Self, TaskDependencyError> {
+        if task_id == depends_on_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+
+        let project_id = Self::find_project_id(&mut *conn, task_id).await?;
+        let depends_on_project_id = Self::find_project_id(&mut *conn, depends_on_task_id).await?;
+        if project_id != depends_on_project_id {
+            return Err(TaskDependencyError::CrossProject);
+        }
+
+        if Self::find_by_task_id(&mut *conn, task_id)
+            .await?
+            .iter()
+            .any(|d| d.depends_on_task_id == depends_on_task_id)
+        {
+            return Err(TaskDependencyError::AlreadyExists);
+        }
+
+        if Self::would_create_cycle(&mut *conn, task_id, depends_on_task_id).await? {
+            return Err(TaskDependencyError::CycleDetected);
+        }
+
+        let id = Uuid::new_v4();
+        let dependency = sqlx::query_as!(
+            TaskDependency,
+            r#"INSERT INTO task_dependencies (id, task_id, depends_on_task_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            depends_on_task_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(dependency)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<(), TaskDependencyError> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(TaskDependencyError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        task::CreateTask,
+    };
+
+    async fn create_task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        let data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        Task::create(pool, &data, id).await.unwrap();
+        id
+    }
+
+    async fn create_project(pool: &SqlitePool) -> Uuid {
+        let id = Uuid::new_v4();
+        let data = CreateProject {
+            name: "project".to_string(),
+            repositories: vec![],
+        };
+        Project::create(pool, &data, id).await.unwrap();
+        id
+    }
+
+    #[sqlx::test]
+    async fn test_create_rejects_cycles(pool: SqlitePool) {
+        let project_id = create_project(&pool).await;
+        let a = create_task(&pool, project_id, "a").await;
+        let b = create_task(&pool, project_id, "b").await;
+        let c = create_task(&pool, project_id, "c").await;
+
+        // c waits on b, b waits on a
+        TaskDependency::create(&pool, c, b).await.unwrap();
+        TaskDependency::create(&pool, b, a).await.unwrap();
+
+        assert!(matches!(
+            TaskDependency::create(&pool, a, c).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+        assert!(matches!(
+            TaskDependency::create(&pool, a, b).await,
+            Err(TaskDependencyError::CycleDetected)
+        ));
+        assert!(matches!(
+            TaskDependency::create(&pool, a, a).await,
+            Err(TaskDependencyError::SelfDependency)
+        ));
+        assert!(
+            TaskDependency::find_by_task_id(&pool, a)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // A diamond is not a cycle
+        TaskDependency::create(&pool, c, a).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_create_rejects_cross_project(pool: SqlitePool) {
+        let a = create_task(&pool, create_project(&pool).await, "a").await;
+        let b = create_task(&pool, create_project(&pool).await, "b").await;
+
+        assert!(matches!(
+            TaskDependency::create(&pool, a, b).await,
+            Err(TaskDependencyError::CrossProject)
+        ));
+    }
+
+    #[sqlx::test]
+    async fn test_is_blocked_until_dependency_done(pool: SqlitePool) {
+        let project_id = create_project(&pool).await;
+        let a = create_task(&pool, project_id, "a").await;
+        let b = create_task(&pool, project_id, "b").await;
+        TaskDependency::create(&pool, b, a).await.unwrap();
+
+        assert!(TaskDependency::is_blocked(&pool, b).await.unwrap());
+        assert!(!TaskDependency::is_blocked(&pool, a).await.unwrap());
+
+        Task::update_status(&pool, a, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert!(!TaskDependency::is_blocked(&pool, b).await.unwrap());
+    }
+}
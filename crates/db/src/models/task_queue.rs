@@ -188,13 +188,20 @@ impl TaskQueueEntry {
     }
 
     /// Claim the next pending entry for processing.
+    /// Entries whose task still has unfinished dependencies are skipped, as are
+    /// entries belonging to `excluded_project_ids`.
     /// Returns None if no entries are available.
     /// Uses a transaction to ensure atomicity.
-    pub async fn claim_next(pool: &SqlitePool) -> Result<Option<Self>, sqlx::Error> {
+    pub async fn claim_next(
+        pool: &SqlitePool,
+        excluded_project_ids: &[Uuid],
+    ) -> Result<Option<Self>, sqlx::Error> {
         // Find and update in one query using RETURNING
         let now = Utc::now();
         let pending = QueueEntryStatus::Pending.to_string();
         let processing = QueueEntryStatus::Processing.to_string();
+        let excluded = serde_json::to_string(excluded_project_ids)
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
 
         let result = sqlx::query_as!(
             TaskQueueEntry,
@@ -203,6 +210,28 @@ impl TaskQueueEntry {
                WHERE id = (
                    SELECT id FROM task_queue
                    WHERE status = ?
+                     AND NOT EXISTS (
+                         SELECT 1
+                           FROM workspaces qw
+                           JOIN task_dependencies td ON td.task_id = qw.task_id
+                           JOIN tasks dep ON dep.id = td.depends_on_task_id
+                          WHERE qw.id = task_queue.workspace_id
+                            AND dep.status != 'done'
+                            AND NOT EXISTS (
+                                SELECT 1
+                                  FROM merges m
+                                  JOIN workspaces w ON m.workspace_id = w.id
+                                 WHERE w.task_id = dep.id
+                                   AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+                            )
+                     )
+                     AND NOT EXISTS (
+                         SELECT 1
+                           FROM workspaces pw
+                           JOIN tasks pt ON pt.id = pw.task_id
+                          WHERE pw.id = task_queue.workspace_id
+                            AND pt.project_id IN (SELECT unhex(value, '-') FROM json_each(?))
+                     )
                    ORDER BY priority ASC, queued_at ASC
                    LIMIT 1
                )
@@ -225,6 +254,7 @@ impl TaskQueueEntry {
             now,
             now,
             pending,
+            excluded,
         )
         .fetch_optional(pool)
         .await?;
@@ -249,6 +279,21 @@ impl TaskQueueEntry {
                WHERE id = (
                    SELECT id FROM task_queue
                    WHERE status = ? AND executor_type = ?
+                     AND NOT EXISTS (
+                         SELECT 1
+                           FROM workspaces qw
+                           JOIN task_dependencies td ON td.task_id = qw.task_id
+                           JOIN tasks dep ON dep.id = td.depends_on_task_id
+                          WHERE qw.id = task_queue.workspace_id
+                            AND dep.status != 'done'
+                            AND NOT EXISTS (
+                                SELECT 1
+                                  FROM merges m
+                                  JOIN workspaces w ON m.workspace_id = w.id
+                                 WHERE w.task_id = dep.id
+                                   AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+                            )
+                     )
                    ORDER BY priority ASC, queued_at ASC
                    LIMIT 1
                )
@@ -279,6 +324,19 @@ impl TaskQueueEntry {
         Ok(result)
     }
 
+    /// Projects that currently have pending entries
+    pub async fn pending_project_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT t.project_id as "project_id!: Uuid"
+               FROM task_queue q
+               JOIN workspaces w ON w.id = q.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE q.status = 'pending'"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Update the status of a queue entry
     pub async fn update_status(
         pool: &SqlitePool,
@@ -540,3 +598,149 @@ impl TaskQueueEntry {
         Ok(count as u32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task, TaskStatus},
+        task_dependency::TaskDependency,
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    /// Creates a task with a workspace and session and queues its run
+    async fn queue_task(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        title: &str,
+        priority: i32,
+    ) -> (Uuid, Uuid) {
+        let task_id = Uuid::new_v4();
+        let data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        Task::create(pool, &data, task_id).await.unwrap();
+
+        let workspace_id = Uuid::new_v4();
+        let workspace = CreateWorkspace {
+            branch: format!("vk/{title}"),
+            agent_working_dir: None,
+        };
+        Workspace::create(pool, &workspace, workspace_id, task_id)
+            .await
+            .unwrap();
+
+        let session_id = Uuid::new_v4();
+        let session = CreateSession {
+            executor: Some("CLAUDE_CODE".to_string()),
+        };
+        Session::create(pool, &session, session_id, workspace_id)
+            .await
+            .unwrap();
+
+        let entry = TaskQueueEntry::create(
+            pool,
+            &CreateTaskQueueEntry {
+                session_id,
+                workspace_id,
+                executor_action: "{}".to_string(),
+                executor_type: "CLAUDE_CODE".to_string(),
+                prompt: None,
+                priority: Some(priority),
+            },
+        )
+        .await
+        .unwrap();
+
+        (task_id, entry.id)
+    }
+
+    #[sqlx::test]
+    async fn test_claim_next_skips_blocked_tasks(pool: SqlitePool) {
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: "project".to_string(),
+            repositories: vec![],
+        };
+        Project::create(&pool, &project, project_id).await.unwrap();
+
+        let prerequisite = Uuid::new_v4();
+        let data = CreateTask::from_title_description(project_id, "prerequisite".to_string(), None);
+        Task::create(&pool, &data, prerequisite).await.unwrap();
+
+        // The blocked entry has the higher priority but must not be claimed first
+        let (blocked_task, blocked_entry) = queue_task(&pool, project_id, "blocked", 1).await;
+        let (_, free_entry) = queue_task(&pool, project_id, "free", 2).await;
+        TaskDependency::create(&pool, blocked_task, prerequisite)
+            .await
+            .unwrap();
+
+        let claimed = TaskQueueEntry::claim_next(&pool, &[])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, free_entry);
+        assert!(
+            TaskQueueEntry::claim_next(&pool, &[])
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            TaskQueueEntry::claim_next_for_executor(&pool, "CLAUDE_CODE")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        Task::update_status(&pool, prerequisite, TaskStatus::Done)
+            .await
+            .unwrap();
+        let claimed = TaskQueueEntry::claim_next(&pool, &[])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, blocked_entry);
+    }
+
+    #[sqlx::test]
+    async fn test_claim_next_skips_excluded_projects(pool: SqlitePool) {
+        let mut project_ids = Vec::new();
+        for name in ["over budget", "within budget"] {
+            let id = Uuid::new_v4();
+            let data = CreateProject {
+                name: name.to_string(),
+                repositories: vec![],
+            };
+            Project::create(&pool, &data, id).await.unwrap();
+            project_ids.push(id);
+        }
+        let (_, excluded_entry) = queue_task(&pool, project_ids[0], "excluded", 1).await;
+        let (_, other_entry) = queue_task(&pool, project_ids[1], "other", 2).await;
+
+        let mut pending = TaskQueueEntry::pending_project_ids(&pool).await.unwrap();
+        pending.sort();
+        let mut expected = project_ids.clone();
+        expected.sort();
+        assert_eq!(pending, expected);
+
+        let excluded = [project_ids[0]];
+        let claimed = TaskQueueEntry::claim_next(&pool, &excluded)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, other_entry);
+        assert!(
+            TaskQueueEntry::claim_next(&pool, &excluded)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let claimed = TaskQueueEntry::claim_next(&pool, &[])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.id, excluded_entry);
+    }
+}
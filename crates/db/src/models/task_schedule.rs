@@ -0,0 +1,333 @@
+//! Recurring task templates.
+//!
+//! Each due run creates a task from the template and starts a workspace for it.
+//! `next_run_at` is computed by the scheduler in the services crate; a schedule
+//! without one (disabled, or a cron expression that never fires) is never due.
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Repository and base branch a scheduled run starts its workspace from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ScheduleRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Title of created tasks; `{date}` is replaced with the run's UTC date
+    pub task_title: String,
+    /// Description of created tasks; `{date}` is replaced as in the title
+    pub task_description: Option<String>,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: Json<ExecutorProfileId>,
+    #[ts(type = "Array<ScheduleRepo>")]
+    pub repos: Json<Vec<ScheduleRepo>>,
+    /// Five-field cron expression in UTC; exclusive with `interval_seconds`
+    pub cron_expression: Option<String>,
+    #[ts(type = "number | null")]
+    pub interval_seconds: Option<i64>,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Task created by the most recent successful run
+    pub last_task_id: Option<Uuid>,
+    /// Why the most recent run did not start a task, if it didn't
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub name: String,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<ScheduleRepo>,
+    pub cron_expression: Option<String>,
+    #[ts(type = "number | null")]
+    pub interval_seconds: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+/// Fields left out keep their current value. Setting either `cron_expression`
+/// or `interval_seconds` replaces the schedule's timing entirely.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub name: Option<String>,
+    pub task_title: Option<String>,
+    pub task_description: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<ScheduleRepo>>,
+    pub cron_expression: Option<String>,
+    #[ts(type = "number | null")]
+    pub interval_seconds: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+impl TaskSchedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      task_title,
+                      task_description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      cron_expression,
+                      interval_seconds,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      task_title,
+                      task_description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      cron_expression,
+                      interval_seconds,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      task_title,
+                      task_description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      cron_expression,
+                      interval_seconds,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      task_title,
+                      task_description,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      cron_expression,
+                      interval_seconds,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1
+                 AND next_run_at IS NOT NULL
+                 AND datetime(next_run_at) <= datetime($1)
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (
+                   id, project_id, name, task_title, task_description, executor_profile_id,
+                   repos, cron_expression, interval_seconds, enabled, next_run_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         task_title,
+                         task_description,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<ScheduleRepo>>",
+                         cron_expression,
+                         interval_seconds,
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.task_title,
+            data.task_description,
+            executor_profile_id,
+            repos,
+            data.cron_expression,
+            data.interval_seconds,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replace every editable field. `data.project_id` is ignored; schedules
+    /// don't move between projects.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET name = $2,
+                   task_title = $3,
+                   task_description = $4,
+                   executor_profile_id = $5,
+                   repos = $6,
+                   cron_expression = $7,
+                   interval_seconds = $8,
+                   enabled = $9,
+                   next_run_at = $10,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         task_title,
+                         task_description,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<ScheduleRepo>>",
+                         cron_expression,
+                         interval_seconds,
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.task_title,
+            data.task_description,
+            executor_profile_id,
+            repos,
+            data.cron_expression,
+            data.interval_seconds,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the outcome of a run and when the next one is due. A failed run
+    /// keeps the previous `last_task_id`.
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        ran_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+        task_id: Option<Uuid>,
+        error: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2,
+                   next_run_at = $3,
+                   last_task_id = COALESCE($4, last_task_id),
+                   last_error = $5,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            ran_at,
+            next_run_at,
+            task_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
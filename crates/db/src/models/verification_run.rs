@@ -0,0 +1,171 @@
+//! Results of project verification scripts.
+//!
+//! A verification script runs after the coding agent and decides whether the
+//! task moves to review. Each finished run is recorded with the tail of its
+//! output so a failure can be shown on the task or sent back to the agent.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationRunStatus {
+    Passed,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct VerificationRun {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub status: VerificationRunStatus,
+    #[ts(type = "number | null")]
+    pub exit_code: Option<i64>,
+    /// Last part of the script's stdout and stderr
+    pub output: String,
+    /// Number of automatic fix attempts that preceded this run
+    #[ts(type = "number")]
+    pub retry: i64,
+    /// Agent follow-up started to fix this failure, if any
+    pub follow_up_execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateVerificationRun {
+    pub workspace_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub status: VerificationRunStatus,
+    pub exit_code: Option<i64>,
+    pub output: String,
+    pub retry: i64,
+}
+
+impl VerificationRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateVerificationRun,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            VerificationRun,
+            r#"INSERT INTO verification_runs (id, workspace_id, execution_process_id, status, exit_code, output, retry)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         status as "status!: VerificationRunStatus",
+                         exit_code,
+                         output,
+                         retry as "retry!: i64",
+                         follow_up_execution_process_id as "follow_up_execution_process_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.execution_process_id,
+            data.status,
+            data.exit_code,
+            data.output,
+            data.retry
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Verification runs for one workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      status as "status!: VerificationRunStatus",
+                      exit_code,
+                      output,
+                      retry as "retry!: i64",
+                      follow_up_execution_process_id as "follow_up_execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Verification runs for every attempt of a task, newest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT v.id as "id!: Uuid",
+                      v.workspace_id as "workspace_id!: Uuid",
+                      v.execution_process_id as "execution_process_id!: Uuid",
+                      v.status as "status!: VerificationRunStatus",
+                      v.exit_code,
+                      v.output,
+                      v.retry as "retry!: i64",
+                      v.follow_up_execution_process_id as "follow_up_execution_process_id: Uuid",
+                      v.created_at as "created_at!: DateTime<Utc>"
+               FROM verification_runs v
+               JOIN workspaces w ON w.id = v.workspace_id
+               WHERE w.task_id = $1
+               ORDER BY v.created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The failed run that a coding agent follow-up was started to fix
+    pub async fn find_by_follow_up(
+        pool: &SqlitePool,
+        follow_up_execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      status as "status!: VerificationRunStatus",
+                      exit_code,
+                      output,
+                      retry as "retry!: i64",
+                      follow_up_execution_process_id as "follow_up_execution_process_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE follow_up_execution_process_id = $1"#,
+            follow_up_execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set_follow_up(
+        pool: &SqlitePool,
+        id: Uuid,
+        follow_up_execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE verification_runs
+               SET follow_up_execution_process_id = $2
+               WHERE id = $1"#,
+            id,
+            follow_up_execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Outbound webhook endpoints.
+//!
+//! Deliveries are queued in `webhook_deliveries` and sent by the dispatcher in
+//! the services crate, which signs each body with the webhook's secret.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Lifecycle events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Display, EnumString)]
+pub enum WebhookEventType {
+    #[serde(rename = "task.status_changed")]
+    #[strum(serialize = "task.status_changed")]
+    TaskStatusChanged,
+    #[serde(rename = "execution.completed")]
+    #[strum(serialize = "execution.completed")]
+    ExecutionCompleted,
+    #[serde(rename = "execution.failed")]
+    #[strum(serialize = "execution.failed")]
+    ExecutionFailed,
+    #[serde(rename = "approval.requested")]
+    #[strum(serialize = "approval.requested")]
+    ApprovalRequested,
+    #[serde(rename = "pr.merged")]
+    #[strum(serialize = "pr.merged")]
+    PrMerged,
+    /// Sent on demand to check that an endpoint is reachable
+    #[serde(rename = "ping")]
+    #[strum(serialize = "ping")]
+    Ping,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    /// Key for the `X-Vibe-Kanban-Signature` HMAC-SHA256 header. It can only
+    /// be set; responses carry a masked `secret_hint` instead.
+    #[serde(rename = "secret_hint", serialize_with = "serialize_secret_hint")]
+    #[ts(rename = "secret_hint")]
+    pub secret: String,
+    /// Events to deliver; empty subscribes to all of them
+    #[ts(type = "Array<WebhookEventType>")]
+    pub events: Json<Vec<WebhookEventType>>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Masks all but the last four characters of a secret, and all of it when
+/// the secret is too short for that to be safe
+fn serialize_secret_hint<S: Serializer>(secret: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let chars: Vec<char> = secret.chars().collect();
+    let visible = if chars.len() >= 16 { 4 } else { 0 };
+    let suffix: String = chars[chars.len() - visible..].iter().collect();
+    serializer.serialize_str(&format!("****{suffix}"))
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEventType>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub events: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+}
+
+impl Webhook {
+    /// Pings are always delivered, whatever the filter
+    pub fn subscribes_to(&self, event: WebhookEventType) -> bool {
+        event == WebhookEventType::Ping || self.events.is_empty() || self.events.contains(&event)
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE enabled = 1
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhook,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let events = Json(&data.events);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, name, url, secret, events, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         events as "events!: Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.url,
+            data.secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.clone().unwrap_or(existing.name);
+        let url = data.url.clone().unwrap_or(existing.url);
+        let secret = data.secret.clone().unwrap_or(existing.secret);
+        let events = Json(data.events.clone().unwrap_or(existing.events.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET name = $2,
+                   url = $3,
+                   secret = $4,
+                   events = $5,
+                   enabled = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         events as "events!: Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(secret: &str) -> Webhook {
+        Webhook {
+            id: Uuid::new_v4(),
+            name: "ci".to_string(),
+            url: "https://example.com/hook".to_string(),
+            secret: secret.to_string(),
+            events: Json(vec![]),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_secret_is_never_serialized() {
+        let json = serde_json::to_value(webhook("whsec_0123456789abcdef")).unwrap();
+        assert!(json.get("secret").is_none());
+        assert_eq!(json["secret_hint"], "****cdef");
+
+        let json = serde_json::to_value(webhook("short")).unwrap();
+        assert_eq!(json["secret_hint"], "****");
+    }
+}
@@ -0,0 +1,242 @@
+//! Delivery log and retry queue for outbound webhooks.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or for a retry
+    Pending,
+    Delivered,
+    /// Gave up after the maximum number of attempts
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: String,
+    /// JSON body exactly as signed and sent
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    #[ts(type = "number")]
+    pub attempts: i64,
+    /// When the next attempt is due while the delivery is pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the most recent attempt, if the endpoint answered
+    #[ts(type = "number | null")]
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        webhook_id: Uuid,
+        event_type: &str,
+        payload: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload, next_attempt_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         webhook_id as "webhook_id!: Uuid",
+                         event_type,
+                         payload,
+                         status as "status!: WebhookDeliveryStatus",
+                         attempts as "attempts!: i64",
+                         next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                         response_status,
+                         last_error,
+                         delivered_at as "delivered_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event_type,
+            payload,
+            next_attempt_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type,
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent deliveries for a webhook, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type,
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is at or before `now`, oldest first
+    pub async fn find_due(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type,
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending'
+                 AND next_attempt_at IS NOT NULL
+                 AND datetime(next_attempt_at) <= datetime($1)
+               ORDER BY next_attempt_at ASC
+               LIMIT $2"#,
+            now,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_delivered(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: i64,
+        delivered_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'delivered',
+                   attempts = attempts + 1,
+                   next_attempt_at = NULL,
+                   response_status = $2,
+                   last_error = NULL,
+                   delivered_at = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            response_status,
+            delivered_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. With a `retry_at` the delivery stays pending
+    /// until then; without one it is marked failed for good.
+    pub async fn mark_attempt_failed(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: Option<i64>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let status = if retry_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        };
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $2,
+                   attempts = attempts + 1,
+                   next_attempt_at = $3,
+                   response_status = $4,
+                   last_error = $5,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            retry_at,
+            response_status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Queue a delivery to be sent again as soon as possible, keeping its
+    /// attempt count
+    pub async fn requeue(
+        pool: &SqlitePool,
+        id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'pending',
+                   next_attempt_at = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Self::find_by_id(pool, id).await
+    }
+}
@@ -223,6 +223,36 @@ impl Workspace {
         Ok(())
     }
 
+    /// Record that a sandboxed process ran in the workspace
+    pub async fn set_sandboxed(pool: &SqlitePool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET sandboxed = TRUE WHERE id = $1 AND sandboxed = FALSE",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Workspaces with a directory that sandboxed processes have run in
+    pub async fn find_sandboxed(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       agent_working_dir,
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    workspaces
+               WHERE   sandboxed = TRUE AND container_ref IS NOT NULL"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Workspace,
@@ -261,6 +291,38 @@ impl Workspace {
         .await
     }
 
+    /// Workspaces of the parent's child tasks whose repo targets the parent's branch,
+    /// i.e. whose PRs are stacked on the parent's PR for that repo
+    pub async fn find_stacked_on(
+        pool: &SqlitePool,
+        parent_workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  w.id                AS "id!: Uuid",
+                       w.task_id           AS "task_id!: Uuid",
+                       w.container_ref,
+                       w.branch,
+                       w.agent_working_dir,
+                       w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       w.created_at        AS "created_at!: DateTime<Utc>",
+                       w.updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    workspaces w
+               JOIN    tasks t ON w.task_id = t.id
+               JOIN    workspace_repos wr ON wr.workspace_id = w.id
+               JOIN    workspaces parent ON parent.id = t.parent_workspace_id
+               WHERE   t.parent_workspace_id = $1
+                 AND   wr.repo_id = $2
+                 AND   wr.target_branch = parent.branch
+               ORDER BY w.created_at ASC"#,
+            parent_workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn container_ref_exists(
         pool: &SqlitePool,
         container_ref: &str,
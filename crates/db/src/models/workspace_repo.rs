@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::{repo::Repo, task::TaskStatus, workspace::Workspace};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -34,6 +34,25 @@ pub struct RepoWithTargetBranch {
     pub target_branch: String,
 }
 
+/// A workspace repo that still has to move off the branch of a merged parent.
+/// `target_branch` is the parent's branch until the rebase is done.
+#[derive(Debug, Clone)]
+pub struct PendingRestack {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub restack_onto: String,
+    pub restack_pr_base: String,
+}
+
+/// Another attempt of a project on the same repo, with its task's status
+#[derive(Debug, Clone)]
+pub struct AttemptOnRepo {
+    pub workspace: Workspace,
+    pub workspace_repo: WorkspaceRepo,
+    pub task_status: TaskStatus,
+}
+
 /// Repo info with copy_files configuration from project_repos.
 #[derive(Debug, Clone)]
 pub struct RepoWithCopyFiles {
@@ -194,6 +213,123 @@ impl WorkspaceRepo {
         Ok(())
     }
 
+    /// Attempts of the project's tasks on a repo other than `exclude_workspace_id`,
+    /// newest first
+    pub async fn find_other_attempts_on_repo(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        exclude_workspace_id: Uuid,
+    ) -> Result<Vec<AttemptOnRepo>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT
+                w.id as "id!: Uuid",
+                w.task_id as "task_id!: Uuid",
+                w.container_ref,
+                w.branch,
+                w.agent_working_dir,
+                w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
+                w.created_at as "created_at!: DateTime<Utc>",
+                w.updated_at as "updated_at!: DateTime<Utc>",
+                wr.id as "workspace_repo_id!: Uuid",
+                wr.target_branch,
+                wr.created_at as "workspace_repo_created_at!: DateTime<Utc>",
+                wr.updated_at as "workspace_repo_updated_at!: DateTime<Utc>",
+                t.status as "task_status!: TaskStatus"
+            FROM workspaces w
+            JOIN tasks t ON w.task_id = t.id
+            JOIN workspace_repos wr ON wr.workspace_id = w.id
+            WHERE t.project_id = $1 AND wr.repo_id = $2 AND w.id != $3
+            ORDER BY w.created_at DESC"#,
+            project_id,
+            repo_id,
+            exclude_workspace_id,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AttemptOnRepo {
+                workspace: Workspace {
+                    id: row.id,
+                    task_id: row.task_id,
+                    container_ref: row.container_ref,
+                    branch: row.branch,
+                    agent_working_dir: row.agent_working_dir,
+                    setup_completed_at: row.setup_completed_at,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                workspace_repo: WorkspaceRepo {
+                    id: row.workspace_repo_id,
+                    workspace_id: row.id,
+                    repo_id,
+                    target_branch: row.target_branch,
+                    created_at: row.workspace_repo_created_at,
+                    updated_at: row.workspace_repo_updated_at,
+                },
+                task_status: row.task_status,
+            })
+            .collect())
+    }
+
+    pub async fn mark_needs_restack(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        onto: &str,
+        pr_base: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_repos
+               SET restack_onto = $1, restack_pr_base = $2, updated_at = datetime('now')
+               WHERE workspace_id = $3 AND repo_id = $4"#,
+            onto,
+            pr_base,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_pending_restacks(
+        pool: &SqlitePool,
+    ) -> Result<Vec<PendingRestack>, sqlx::Error> {
+        sqlx::query_as!(
+            PendingRestack,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      restack_onto as "restack_onto!",
+                      restack_pr_base as "restack_pr_base!"
+               FROM workspace_repos
+               WHERE restack_onto IS NOT NULL AND restack_pr_base IS NOT NULL
+               ORDER BY updated_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn clear_restack(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_repos
+               SET restack_onto = NULL, restack_pr_base = NULL, updated_at = datetime('now')
+               WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_target_branch_for_children_of_workspace(
         pool: &SqlitePool,
         parent_workspace_id: Uuid,
@@ -123,7 +123,10 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(())
     }
 
-    async fn spawn_pr_monitor_service(&self) -> tokio::task::JoinHandle<()> {
+    async fn spawn_pr_monitor_service<C: ContainerService + Send + Sync + 'static>(
+        &self,
+        container: Arc<C>,
+    ) -> tokio::task::JoinHandle<()> {
         let db = self.db().clone();
         let analytics = self
             .analytics()
@@ -133,7 +136,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 analytics_service: analytics_service.clone(),
             });
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        PrMonitorService::spawn(db, self.config().clone(), analytics, publisher, container).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -8,11 +8,13 @@ use ts_rs::TS;
 use crate::{
     actions::{
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest, script::ScriptRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest},
     },
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -63,6 +65,25 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    pub fn script_context(&self) -> Option<&ScriptContext> {
+        match self.typ() {
+            ExecutorActionType::ScriptRequest(request) => Some(&request.context),
+            _ => None,
+        }
+    }
+
+    pub fn executor_profile_id(&self) -> Option<ExecutorProfileId> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_profile_id.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.executor_profile_id.clone())
+            }
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
 }
 
 #[async_trait]
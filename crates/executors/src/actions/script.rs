@@ -3,7 +3,6 @@ use std::{path::Path, sync::Arc};
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::get_shell_command;
 
@@ -25,6 +24,10 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    /// Runs after the coding agent; the task only moves to review if it passes
+    Verification,
+    /// Script or check step of a playbook
+    Playbook,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -52,8 +55,13 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        // The host's login shell may not exist in the project's image
+        let (shell_cmd, shell_arg) = if env.container.is_some() {
+            ("/bin/sh".to_string(), "-c")
+        } else {
+            get_shell_command()
+        };
+        let mut command = env.command(shell_cmd, &effective_dir).await?;
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{executors::ExecutorError, sandbox::SandboxConfig};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -36,6 +36,10 @@ impl CommandParts {
             .ok_or(ExecutorError::ExecutableNotFound { program })?;
         Ok((executable, args))
     }
+
+    pub fn into_parts(self) -> (String, Vec<String>) {
+        (self.program, self.args)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
@@ -58,6 +62,21 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Sandbox",
+        description = "Confine the agent and the attempt's scripts to the workspace (Linux only, requires bubblewrap)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_sandbox: Option<SandboxConfig>,
+}
+
+impl CmdOverrides {
+    /// Whether the profile runs its processes in the sandbox
+    pub fn sandboxed(&self) -> bool {
+        self.process_sandbox
+            .as_ref()
+            .is_some_and(|sandbox| sandbox.enabled)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -1,19 +1,35 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{
+    command::{CmdOverrides, CommandParts},
+    executors::ExecutorError,
+    oci::OciContainer,
+    sandbox::SandboxConfig,
+};
 
 /// Environment variables to inject into executor processes
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    /// Sandbox the process runs in, if the executor profile enables one
+    pub sandbox: Option<SandboxConfig>,
+    /// Container the process runs in, if the project has a container image.
+    /// Takes the place of the sandbox.
+    pub container: Option<OciContainer>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            sandbox: None,
+            container: None,
         }
     }
 
@@ -34,8 +50,11 @@ impl ExecutionEnv {
         self
     }
 
-    /// Return a new env with profile env from CmdOverrides merged in.
-    pub fn with_profile(self, cmd: &CmdOverrides) -> Self {
+    /// Return a new env with profile env and sandbox from CmdOverrides merged in.
+    pub fn with_profile(mut self, cmd: &CmdOverrides) -> Self {
+        if cmd.process_sandbox.is_some() {
+            self.sandbox = cmd.process_sandbox.clone();
+        }
         if let Some(ref profile_env) = cmd.env {
             self.with_overrides(profile_env)
         } else {
@@ -50,6 +69,40 @@ impl ExecutionEnv {
         }
     }
 
+    /// Program path and arguments for `command_parts`. Inside a container the
+    /// program is looked up on the image's PATH rather than the host's.
+    pub async fn resolve(
+        &self,
+        command_parts: CommandParts,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if self.container.is_some() {
+            let (program, args) = command_parts.into_parts();
+            Ok((PathBuf::from(program), args))
+        } else {
+            command_parts.into_resolved().await
+        }
+    }
+
+    /// Command for `program`, started in the container or through the sandbox
+    /// when either is configured
+    pub async fn command(
+        &self,
+        program: impl AsRef<OsStr>,
+        current_dir: &Path,
+    ) -> Result<Command, ExecutorError> {
+        if let Some(container) = &self.container {
+            return Ok(container
+                .command(Path::new(program.as_ref()), current_dir, &self.vars)
+                .await?);
+        }
+        match self.sandbox.as_ref().filter(|sandbox| sandbox.enabled) {
+            Some(sandbox) => Ok(sandbox
+                .command(Path::new(program.as_ref()), current_dir)
+                .await?),
+            None => Ok(Command::new(program)),
+        }
+    }
+
     pub fn contains_key(&self, key: &str) -> bool {
         self.vars.contains_key(key)
     }
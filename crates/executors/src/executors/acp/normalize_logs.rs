@@ -22,10 +22,10 @@ use crate::{
     },
 };
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path, sandboxed: bool) {
     // stderr normalization
     let entry_index = EntryIndexProvider::start_from(&msg_store);
-    normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+    normalize_stderr_logs(msg_store.clone(), entry_index.clone(), sandboxed);
 
     // stdout normalization (main loop)
     let worktree_path = worktree_path.to_path_buf();
@@ -10,10 +10,12 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, TokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -204,7 +206,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         );
 
         // Process stderr logs using the standard stderr processor
-        normalize_stderr_logs(msg_store, entry_index_provider);
+        normalize_stderr_logs(msg_store, entry_index_provider, self.cmd.sandboxed());
     }
 
     // MCP configuration methods
@@ -238,10 +240,11 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts).await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path, current_dir).await?;
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
@@ -250,9 +253,7 @@ impl ClaudeCode {
             .current_dir(current_dir)
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         // Remove ANTHROPIC_API_KEY if disable_api_key is enabled
         if self.disable_api_key.unwrap_or(false) {
@@ -368,7 +369,10 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::TokenUsage(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -412,6 +416,15 @@ impl ClaudeLogProcessor {
                             for patch in patches {
                                 msg_store.push_patch(patch);
                             }
+
+                            // The result message carries the cumulative usage for this run
+                            if let ClaudeJson::Result {
+                                usage: Some(usage), ..
+                            } = &claude_json
+                            {
+                                let model = processor.model_name.clone();
+                                msg_store.push_token_usage(usage.to_token_usage(model));
+                            }
                         }
                         Err(_) => {
                             // Handle non-JSON output as raw system message
@@ -1120,8 +1133,32 @@ impl ClaudeLogProcessor {
                 }
                 ClaudeStreamEvent::Unknown => {}
             },
-            ClaudeJson::Result { is_error, .. } => {
-                if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
+            ClaudeJson::Result {
+                is_error,
+                result,
+                error,
+                ..
+            } => {
+                let message = error
+                    .clone()
+                    .or_else(|| result.as_ref().and_then(|r| r.as_str()).map(str::to_string));
+                let context_limit = message.as_deref().is_some_and(|message| {
+                    NormalizedEntryError::from_message(message)
+                        == NormalizedEntryError::ContextLimitExceeded
+                });
+                if is_error.unwrap_or(false) && context_limit {
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::ContextLimitExceeded,
+                        },
+                        content: message.unwrap_or_default(),
+                        metadata: None,
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                } else if matches!(self.strategy, HistoryStrategy::AmpResume)
+                    && is_error.unwrap_or(false)
                 {
                     let entry = NormalizedEntry {
                         timestamp: None,
@@ -1504,6 +1541,8 @@ pub enum ClaudeJson {
         num_turns: Option<u32>,
         #[serde(default, alias = "sessionId")]
         session_id: Option<String>,
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
     },
     #[serde(rename = "approval_response")]
     ApprovalResponse {
@@ -1614,6 +1653,18 @@ pub struct ClaudeUsage {
     pub service_tier: Option<String>,
 }
 
+impl ClaudeUsage {
+    pub fn to_token_usage(&self, model: Option<String>) -> TokenUsage {
+        TokenUsage {
+            model,
+            input_tokens: self.input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cache_creation_input_tokens: self.cache_creation_input_tokens.unwrap_or(0),
+            cache_read_input_tokens: self.cache_read_input_tokens.unwrap_or(0),
+        }
+    }
+}
+
 /// Structured tool data for Claude tools based on real samples
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "name", content = "input")]
@@ -2018,6 +2069,7 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                process_sandbox: None,
             },
             approvals_service: None,
             disable_api_key: None,
@@ -2317,6 +2369,32 @@ mod tests {
         assert_eq!(entries_no_key.len(), 0); // No warning when field is missing
     }
 
+    #[test]
+    fn test_result_context_limit_error() {
+        let too_long = r#"{"type":"result","subtype":"success","is_error":true,"result":"Prompt is too long","session_id":"test123"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(too_long).unwrap();
+        let entries = normalize(&parsed, "");
+
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::ContextLimitExceeded,
+            },
+        ));
+        assert_eq!(entries[0].content, "Prompt is too long");
+
+        // Other errors in results are left to the assistant messages
+        let other_error = r#"{"type":"result","subtype":"success","is_error":true,"result":"API Error: 500","session_id":"test123"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(other_error).unwrap();
+        assert_eq!(normalize(&parsed, "").len(), 0);
+
+        // Merely mentioning the context window isn't running out of it
+        let mention = r#"{"type":"result","subtype":"success","is_error":true,"result":"Invalid context window setting","session_id":"test123"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(mention).unwrap();
+        assert_eq!(normalize(&parsed, "").len(), 0);
+    }
+
     #[test]
     fn test_mixed_content_with_thinking_ignores_tool_result() {
         let complex_assistant_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"I need to read the file first"},{"type":"text","text":"I'll help you with that"},{"type":"tool_result","tool_use_id":"tool_789","content":"Success","is_error":false}]}}"#;
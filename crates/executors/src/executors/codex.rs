@@ -19,7 +19,6 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use strum_macros::AsRefStr;
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -172,7 +171,7 @@ impl StandardCodingAgentExecutor for Codex {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        normalize_logs(msg_store, worktree_path);
+        normalize_logs(msg_store, worktree_path, self.cmd.sandboxed());
     }
 
     fn default_mcp_config_path(&self) -> Option<PathBuf> {
@@ -300,9 +299,10 @@ impl Codex {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.resolve(command_parts).await?;
 
-        let mut process = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut process = env.command(program_path, current_dir).await?;
         process
             .kill_on_drop(true)
             .stdin(std::process::Stdio::piped())
@@ -314,9 +314,7 @@ impl Codex {
             .env("NO_COLOR", "1")
             .env("RUST_LOG", "error");
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut process);
+        env.apply_to_command(&mut process);
 
         let mut child = process.group_spawn()?;
 
@@ -26,8 +26,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::TokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -216,6 +216,7 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    model: Option<String>,
 }
 
 enum StreamingTextKind {
@@ -234,6 +235,7 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            model: None,
         }
     }
 
@@ -357,9 +359,9 @@ fn format_todo_status(status: &StepStatus) -> String {
     .to_string()
 }
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path, sandboxed: bool) {
     let entry_index = EntryIndexProvider::start_from(&msg_store);
-    normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+    normalize_stderr_logs(msg_store.clone(), entry_index.clone(), sandboxed);
 
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
     tokio::spawn(async move {
@@ -389,6 +391,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -427,6 +430,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -656,15 +660,16 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     message,
                     codex_error_info,
                 }) => {
+                    let content = format!("Stream error: {message} {codex_error_info:?}");
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
                         NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::from_message(&content),
                             },
-                            content: format!("Stream error: {message} {codex_error_info:?}"),
+                            content,
                             metadata: None,
                         },
                     );
@@ -956,21 +961,24 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     message,
                     codex_error_info,
                 }) => {
+                    let content = format!("Error: {message} {codex_error_info:?}");
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
                         NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::from_message(&content),
                             },
-                            content: format!("Error: {message} {codex_error_info:?}"),
+                            content,
                             metadata: None,
                         },
                     );
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        msg_store
+                            .push_token_usage(token_usage_from_info(&info, state.model.clone()));
                         state.token_usage_info = Some(info);
                     }
                 }
@@ -1040,6 +1048,21 @@ fn handle_jsonrpc_response(
     );
 }
 
+/// Codex reports cached tokens as a subset of `input_tokens`, so split them out to
+/// match the cache-exclusive input count used elsewhere.
+fn token_usage_from_info(info: &TokenUsageInfo, model: Option<String>) -> TokenUsage {
+    let total = &info.total_token_usage;
+    let input_tokens: u64 = total.input_tokens.try_into().unwrap_or(0);
+    let cached_input_tokens: u64 = total.cached_input_tokens.try_into().unwrap_or(0);
+    TokenUsage {
+        model,
+        input_tokens: input_tokens.saturating_sub(cached_input_tokens),
+        output_tokens: total.output_tokens.try_into().unwrap_or(0),
+        cache_creation_input_tokens: 0,
+        cache_read_input_tokens: cached_input_tokens,
+    }
+}
+
 fn handle_model_params(
     model: String,
     reasoning_effort: Option<ReasoningEffort>,
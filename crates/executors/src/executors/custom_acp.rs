@@ -0,0 +1,213 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{msg_store::MsgStore, shell::resolve_executable_path_blocking};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+};
+
+const DEFAULT_SESSION_NAMESPACE: &str = "custom_acp_sessions";
+
+/// Any agent that speaks the Agent Client Protocol over stdio. Everything
+/// needed to launch it comes from the executor profile.
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomAcp {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    /// Command that starts the agent in ACP mode, e.g. `npx -y my-agent --acp`
+    pub command: String,
+    /// Extra arguments appended to the command
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Directory under ~/.vibe-kanban where session history is kept. Give each
+    /// agent its own namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Auto-approve agent actions
+    #[serde(default)]
+    pub auto_approve: bool,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomAcp {
+    fn build_command_builder(&self) -> CommandBuilder {
+        let builder = CommandBuilder::new(self.command.as_str()).extend_params(self.args.clone());
+        apply_overrides(builder, &self.cmd)
+    }
+
+    /// The configured namespace, limited to characters that are safe in a
+    /// directory name
+    fn session_namespace(&self) -> String {
+        let namespace: String = self
+            .session_namespace
+            .as_deref()
+            .unwrap_or(DEFAULT_SESSION_NAMESPACE)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if namespace.trim_matches('_').is_empty() {
+            DEFAULT_SESSION_NAMESPACE.to_string()
+        } else {
+            namespace
+        }
+    }
+
+    fn harness(&self) -> AcpAgentHarness {
+        let mut harness = AcpAgentHarness::with_session_namespace(self.session_namespace());
+        if let Some(model) = &self.model {
+            harness = harness.with_model(model);
+        }
+        if let Some(mode) = &self.mode {
+            harness = harness.with_mode(mode);
+        }
+        harness
+    }
+
+    fn approvals(&self) -> Option<Arc<dyn ExecutorApprovalService>> {
+        if self.auto_approve {
+            None
+        } else {
+            self.approvals.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAcp {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let command = self.build_command_builder().build_initial()?;
+        self.harness()
+            .spawn_with_command(
+                current_dir,
+                combined_prompt,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let command = self.build_command_builder().build_follow_up(&[])?;
+        self.harness()
+            .spawn_follow_up_with_command(
+                current_dir,
+                combined_prompt,
+                session_id,
+                command,
+                env,
+                &self.cmd,
+                self.approvals(),
+            )
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        super::acp::normalize_logs(msg_store, worktree_path, self.cmd.sandboxed());
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let base = self
+            .cmd
+            .base_command_override
+            .as_deref()
+            .unwrap_or(&self.command);
+        let program = shlex::split(base).and_then(|parts| parts.into_iter().next());
+        match program {
+            Some(program) if resolve_executable_path_blocking(&program).is_some() => {
+                AvailabilityInfo::InstallationFound
+            }
+            _ => AvailabilityInfo::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(json: &str) -> CustomAcp {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_from_profile() {
+        let agent = agent(
+            r#"{"command": "my-agent", "args": ["--acp"], "model": "fast", "auto_approve": true}"#,
+        );
+        assert_eq!(agent.command, "my-agent");
+        assert_eq!(agent.args, vec!["--acp".to_string()]);
+        assert_eq!(agent.model.as_deref(), Some("fast"));
+        assert!(agent.mode.is_none());
+        assert!(agent.auto_approve);
+    }
+
+    #[test]
+    fn test_session_namespace() {
+        assert_eq!(
+            agent(r#"{"command": "a"}"#).session_namespace(),
+            DEFAULT_SESSION_NAMESPACE
+        );
+        assert_eq!(
+            agent(r#"{"command": "a", "session_namespace": "acme-agent_v2"}"#).session_namespace(),
+            "acme-agent_v2"
+        );
+        assert_eq!(
+            agent(r#"{"command": "a", "session_namespace": "../etc"}"#).session_namespace(),
+            "___etc"
+        );
+        assert_eq!(
+            agent(r#"{"command": "a", "session_namespace": "/"}"#).session_namespace(),
+            DEFAULT_SESSION_NAMESPACE
+        );
+    }
+}
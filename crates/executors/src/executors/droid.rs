@@ -5,7 +5,7 @@ use command_group::AsyncCommandGroup;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -112,9 +112,10 @@ async fn spawn_droid(
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
-    let (program_path, args) = command_parts.into_resolved().await?;
+    let (program_path, args) = env.resolve(command_parts).await?;
 
-    let mut command = Command::new(program_path);
+    let env = env.clone().with_profile(cmd_overrides);
+    let mut command = env.command(program_path, current_dir).await?;
     command
         .kill_on_drop(true)
         .stdin(Stdio::piped())
@@ -123,9 +124,7 @@ async fn spawn_droid(
         .current_dir(current_dir)
         .args(args);
 
-    env.clone()
-        .with_profile(cmd_overrides)
-        .apply_to_command(&mut command);
+    env.apply_to_command(&mut command);
 
     let mut child = command.group_spawn()?;
 
@@ -176,6 +175,7 @@ impl StandardCodingAgentExecutor for Droid {
             msg_store.clone(),
             current_dir,
             EntryIndexProvider::start_from(&msg_store),
+            self.cmd.sandboxed(),
         );
     }
 
@@ -15,6 +15,7 @@ use crate::logs::{
     ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
     NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
     plain_text_processor::PlainTextLogProcessor,
+    stderr_processor::stderr_error_entry,
     utils::{
         EntryIndexProvider,
         patch::{add_normalized_entry, replace_normalized_entry},
@@ -25,8 +26,9 @@ pub fn normalize_logs(
     msg_store: Arc<MsgStore>,
     worktree_path: &Path,
     entry_index_provider: EntryIndexProvider,
+    sandboxed: bool,
 ) {
-    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone(), sandboxed);
 
     let worktree_path = worktree_path.to_path_buf();
     tokio::spawn(async move {
@@ -51,7 +53,7 @@ pub fn normalize_logs(
                         let entry = NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::from_message(&error.message),
                             },
                             content: error.message,
                             metadata: None,
@@ -656,7 +658,7 @@ pub fn normalize_logs(
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::ErrorMessage {
-                            error_type: NormalizedEntryError::Other,
+                            error_type: NormalizedEntryError::from_message(message),
                         },
                         content: message.clone(),
                         metadata: None,
@@ -668,18 +670,17 @@ pub fn normalize_logs(
     });
 }
 
-fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
+fn normalize_stderr_logs(
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+    sandboxed: bool,
+) {
     tokio::spawn(async move {
         let mut stderr = msg_store.stderr_chunked_stream();
 
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content,
-                metadata: None,
+            .normalized_entry_producer(Box::new(move |content: String| {
+                stderr_error_entry(content, sandboxed)
             }))
             .transform_lines(Box::new(|lines| {
                 lines.iter_mut().for_each(|line| {
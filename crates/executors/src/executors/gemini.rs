@@ -114,7 +114,7 @@ impl StandardCodingAgentExecutor for Gemini {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        super::acp::normalize_logs(msg_store, worktree_path);
+        super::acp::normalize_logs(msg_store, worktree_path, self.cmd.sandboxed());
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
@@ -19,9 +19,11 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        custom_acp::CustomAcp, droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
     },
     mcp_config::McpConfig,
+    oci::OciError,
+    sandbox::{SandboxConfig, SandboxError},
 };
 
 pub mod acp;
@@ -30,6 +32,7 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom_acp;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -70,6 +73,10 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+    #[error(transparent)]
+    Oci(#[from] OciError),
 }
 
 #[enum_dispatch]
@@ -100,6 +107,7 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    CustomAcp,
 }
 
 impl CodingAgent {
@@ -153,6 +161,23 @@ impl CodingAgent {
         self.default_mcp_config_path().is_some()
     }
 
+    /// Sandbox settings from the profile, which also apply to the attempt's scripts
+    pub fn sandbox(&self) -> Option<&SandboxConfig> {
+        let cmd = match self {
+            Self::ClaudeCode(agent) => &agent.cmd,
+            Self::Amp(agent) => &agent.cmd,
+            Self::Gemini(agent) => &agent.cmd,
+            Self::Codex(agent) => &agent.cmd,
+            Self::Opencode(agent) => &agent.cmd,
+            Self::CursorAgent(agent) => &agent.cmd,
+            Self::QwenCode(agent) => &agent.cmd,
+            Self::Copilot(agent) => &agent.cmd,
+            Self::Droid(agent) => &agent.cmd,
+            Self::CustomAcp(agent) => &agent.cmd,
+        };
+        cmd.process_sandbox.as_ref()
+    }
+
     pub fn capabilities(&self) -> Vec<BaseAgentCapability> {
         match self {
             Self::ClaudeCode(_)
@@ -160,7 +185,8 @@ impl CodingAgent {
             | Self::Gemini(_)
             | Self::QwenCode(_)
             | Self::Droid(_)
-            | Self::Opencode(_) => vec![BaseAgentCapability::SessionFork],
+            | Self::Opencode(_)
+            | Self::CustomAcp(_) => vec![BaseAgentCapability::SessionFork],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::SetupHelper,
@@ -124,7 +124,7 @@ impl StandardCodingAgentExecutor for Opencode {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        crate::executors::acp::normalize_logs(msg_store, worktree_path);
+        crate::executors::acp::normalize_logs(msg_store, worktree_path, self.cmd.sandboxed());
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
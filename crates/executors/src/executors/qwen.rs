@@ -105,7 +105,7 @@ impl StandardCodingAgentExecutor for QwenCode {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        crate::executors::acp::normalize_logs(msg_store, worktree_path);
+        crate::executors::acp::normalize_logs(msg_store, worktree_path, self.cmd.sandboxed());
     }
 
     // MCP configuration methods
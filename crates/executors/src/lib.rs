@@ -5,5 +5,7 @@ pub mod env;
 pub mod executors;
 pub mod logs;
 pub mod mcp_config;
+pub mod oci;
 pub mod profile;
+pub mod sandbox;
 pub mod stdout_dup;
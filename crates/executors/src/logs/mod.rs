@@ -67,9 +67,43 @@ pub struct NormalizedConversation {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum NormalizedEntryError {
     SetupRequired,
+    /// The sandbox blocked a file write or network access
+    SandboxBlocked,
+    /// The conversation no longer fits in the model's context window
+    ContextLimitExceeded,
     Other,
 }
 
+/// Provider errors for a request that no longer fits in the context window,
+/// lowercased. Kept to exact phrasings so other errors that merely mention
+/// the context window aren't mistaken for it.
+const CONTEXT_LIMIT_ERRORS: &[&str] = &[
+    // Anthropic
+    "prompt is too long",
+    // OpenAI error code and message
+    "context_length_exceeded",
+    "maximum context length is",
+    // Codex error info and message
+    "contextwindowexceeded",
+    "exceeds the context window",
+];
+
+impl NormalizedEntryError {
+    /// Error type for an agent error message, telling context-limit errors
+    /// apart from the rest
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if CONTEXT_LIMIT_ERRORS
+            .iter()
+            .any(|error| message.contains(error))
+        {
+            Self::ContextLimitExceeded
+        } else {
+            Self::Other
+        }
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -145,6 +179,12 @@ pub enum ToolStatus {
         timeout_at: DateTime<Utc>,
     },
     TimedOut,
+    /// Approved by an approval policy rule without asking a human
+    AutoApproved {
+        /// Rule that approved the call, or `None` for the policy's default
+        rule: Option<String>,
+        reason: Option<String>,
+    },
 }
 
 impl ToolStatus {
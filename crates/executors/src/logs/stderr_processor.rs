@@ -5,7 +5,7 @@
 //!
 //! Example:
 //! ```rust,ignore
-//! normalize_stderr_logs(msg_store.clone(), EntryIndexProvider::new());
+//! normalize_stderr_logs(msg_store.clone(), EntryIndexProvider::new(), false);
 //! ```
 //!
 use std::{sync::Arc, time::Duration};
@@ -17,7 +17,7 @@ use super::{
     NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
     plain_text_processor::PlainTextLogProcessor,
 };
-use crate::logs::utils::EntryIndexProvider;
+use crate::{logs::utils::EntryIndexProvider, sandbox};
 
 /// Standard stderr log normalizer that uses PlainTextLogProcessor to stream error logs.
 ///
@@ -36,19 +36,19 @@ use crate::logs::utils::EntryIndexProvider;
 /// # Arguments
 /// * `msg_store` - the message store providing a stream of stderr chunks and accepting patches.
 /// * `entry_index_provider` - provider of incremental entry indices for patch ordering.
-pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
+/// * `sandboxed` - whether the process ran in the sandbox, see [`stderr_error_entry`].
+pub fn normalize_stderr_logs(
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+    sandboxed: bool,
+) {
     tokio::spawn(async move {
         let mut stderr = msg_store.stderr_chunked_stream();
 
         // Create a processor with time-based emission for stderr
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(Box::new(move |content: String| {
+                stderr_error_entry(strip_ansi_escapes::strip_str(&content), sandboxed)
             }))
             .time_gap(Duration::from_secs(2)) // Break messages if they are 2 seconds apart
             .index_provider(entry_index_provider)
@@ -61,3 +61,25 @@ pub fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: Ent
         }
     });
 }
+
+/// Error entry for a chunk of stderr. When the process ran in the sandbox,
+/// output that looks like the sandbox blocked something is marked as such and
+/// prefixed with an explanation.
+pub fn stderr_error_entry(content: String, sandboxed: bool) -> NormalizedEntry {
+    let blocked = sandboxed
+        .then(|| sandbox::blocked_message(&content))
+        .flatten();
+    let (error_type, content) = match blocked {
+        Some(message) => (
+            NormalizedEntryError::SandboxBlocked,
+            format!("{message}\n\n{content}"),
+        ),
+        None => (NormalizedEntryError::Other, content),
+    };
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ErrorMessage { error_type },
+        content,
+        metadata: None,
+    }
+}
@@ -0,0 +1,428 @@
+//! Running workspace processes in OCI containers.
+//!
+//! When a project has a container image, its setup scripts, dev server and
+//! coding agent are started with `docker run` (or `podman run`) instead of on
+//! the host. The image is either pulled by reference or built per project
+//! from a Dockerfile in the workspace. The worktree is bind-mounted at the
+//! same path it has on the host, so paths in logs, diffs and agent sessions
+//! line up. Each container gets its own network namespace, so concurrent
+//! attempts can't see each other's ports; a dev server's port is published
+//! on the host's loopback interface.
+//!
+//! Worktrees, git operations, diffs and log storage stay on the host.
+//! Containers are labelled so ones left behind by a crash can be removed at
+//! the next startup.
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{path::expand_tilde, shell::resolve_executable_path};
+
+use crate::sandbox::git_dirs;
+
+/// Label on every container started for an execution process and every
+/// image built for a project
+const LABEL: &str = "dev.vibe-kanban.managed";
+
+/// Lines of `build` output kept when an image fails to build
+const BUILD_ERROR_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OciRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl OciRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            OciRuntime::Docker => "docker",
+            OciRuntime::Podman => "podman",
+        }
+    }
+
+    /// Names of the containers started for execution processes, stopped ones
+    /// included. Empty if the runtime isn't installed.
+    pub async fn list_containers(&self) -> Vec<String> {
+        let Some(runtime) = resolve_executable_path(self.program()).await else {
+            return Vec::new();
+        };
+        let output = Command::new(runtime)
+            .args(["ps", "-a", "--filter"])
+            .arg(format!("label={LABEL}"))
+            .args(["--format", "{{.Names}}"])
+            .stderr(Stdio::null())
+            .output()
+            .await;
+        match output {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                tracing::warn!("Failed to list {} containers: {}", self.program(), e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Build a project's image from `dockerfile`, with the file's directory as
+    /// build context, and return its tag. The tag follows the Dockerfile's
+    /// contents, so the image is only rebuilt when the Dockerfile changes.
+    pub async fn build_image(
+        &self,
+        dockerfile: &Path,
+        project_id: Uuid,
+    ) -> Result<String, OciError> {
+        let runtime = resolve_executable_path(self.program())
+            .await
+            .ok_or(OciError::RuntimeNotFound(self.program()))?;
+        let contents = tokio::fs::read(dockerfile)
+            .await
+            .map_err(|e| OciError::BuildFailed(format!("{}: {e}", dockerfile.display())))?;
+        let tag = image_tag(project_id, &contents);
+
+        let built = Command::new(&runtime)
+            .args(["image", "inspect", &tag])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .is_ok_and(|status| status.success());
+        if built {
+            return Ok(tag);
+        }
+
+        tracing::info!("Building image {} from {}", tag, dockerfile.display());
+        let output = Command::new(&runtime)
+            .args(["build", "--label"])
+            .arg(format!("{LABEL}=true"))
+            .args(["-t", &tag, "-f"])
+            .arg(dockerfile)
+            .arg(dockerfile.parent().unwrap_or(Path::new(".")))
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| OciError::BuildFailed(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let lines: Vec<&str> = stderr.lines().collect();
+            let tail = lines[lines.len().saturating_sub(BUILD_ERROR_LINES)..].join("\n");
+            return Err(OciError::BuildFailed(tail));
+        }
+        Ok(tag)
+    }
+
+    /// Force-remove a container
+    pub async fn remove_container(&self, name: &str) {
+        let Some(runtime) = resolve_executable_path(self.program()).await else {
+            return;
+        };
+        let result = Command::new(runtime)
+            .args(["rm", "-f", name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to remove container {}: {}", name, e);
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum OciError {
+    #[error("`{0}` is not installed; it is needed to run this project's workspaces in containers")]
+    RuntimeNotFound(&'static str),
+    #[error("failed to build the project's container image: {0}")]
+    BuildFailed(String),
+}
+
+/// Whether a project's container image setting names a Dockerfile, relative
+/// to the workspace, to build instead of an image to pull
+pub fn is_dockerfile(image: &str) -> bool {
+    Path::new(image)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_ascii_lowercase)
+        .is_some_and(|name| {
+            name == "dockerfile"
+                || name == "containerfile"
+                || name.starts_with("dockerfile.")
+                || name.ends_with(".dockerfile")
+        })
+}
+
+fn image_tag(project_id: Uuid, dockerfile: &[u8]) -> String {
+    let digest = Sha256::digest(dockerfile);
+    let hex: String = digest[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("vk-project-{project_id}:{hex}")
+}
+
+/// One container running a single execution process
+#[derive(Debug, Clone, PartialEq)]
+pub struct OciContainer {
+    pub runtime: OciRuntime,
+    pub image: String,
+    /// Container name, used to remove it when the process is stopped
+    pub name: String,
+    /// Host paths mounted read-write at the same path inside the container,
+    /// in addition to the working directory
+    pub mounts: Vec<PathBuf>,
+    /// Container ports published on the same port of the host's loopback
+    /// interface, e.g. the dev server's
+    pub ports: Vec<u16>,
+}
+
+impl OciContainer {
+    /// Name of the container that runs an execution process
+    pub fn name_for(execution_process_id: impl std::fmt::Display) -> String {
+        format!("vk-{execution_process_id}")
+    }
+
+    /// Execution process a container named by [`Self::name_for`] runs
+    pub fn execution_process_id(name: &str) -> Option<Uuid> {
+        name.strip_prefix("vk-")?.parse().ok()
+    }
+
+    /// A command that starts `program` in a fresh container with `current_dir`
+    /// mounted and `vars` set. Arguments added to the returned command go to
+    /// `program`.
+    pub async fn command(
+        &self,
+        program: &Path,
+        current_dir: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Command, OciError> {
+        let runtime = resolve_executable_path(self.runtime.program())
+            .await
+            .ok_or(OciError::RuntimeNotFound(self.runtime.program()))?;
+
+        // The container is the isolation boundary, so git directories are
+        // mounted whole
+        let git_dirs: Vec<PathBuf> = git_dirs(current_dir)
+            .into_iter()
+            .map(|dirs| dirs.common)
+            .collect();
+        let mut command = Command::new(runtime);
+        command
+            .args(self.run_args(current_dir, &git_dirs, vars))
+            .arg(program);
+        Ok(command)
+    }
+
+    fn run_args(
+        &self,
+        current_dir: &Path,
+        git_dirs: &[PathBuf],
+        vars: &HashMap<String, String>,
+    ) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["run", "--rm", "-i", "--init"].map(OsString::from).into();
+        args.extend(["--name".into(), self.name.clone().into()]);
+        args.extend(["--label".into(), format!("{LABEL}=true").into()]);
+
+        // Files the process writes must stay owned by the user who owns the
+        // worktree
+        match self.runtime {
+            OciRuntime::Docker => {
+                if let Some(user) = owner(current_dir) {
+                    args.extend(["--user".into(), user.into()]);
+                }
+            }
+            OciRuntime::Podman => args.extend(["--userns", "keep-id"].map(OsString::from)),
+        }
+
+        let mut mounted: Vec<&Path> = Vec::new();
+        for path in std::iter::once(current_dir)
+            .chain(git_dirs.iter().map(PathBuf::as_path))
+            .chain(self.mounts.iter().map(PathBuf::as_path))
+        {
+            if mounted.contains(&path) {
+                continue;
+            }
+            mounted.push(path);
+            let mut volume = OsString::from(path);
+            volume.push(":");
+            volume.push(path);
+            args.extend(["-v".into(), volume]);
+        }
+
+        for port in &self.ports {
+            args.extend(["-p".into(), format!("127.0.0.1:{port}:{port}").into()]);
+        }
+
+        args.extend(["-w".into(), current_dir.into()]);
+        let mut home = OsString::from("HOME=");
+        home.push(expand_tilde("~"));
+        args.extend(["-e".into(), home]);
+        // Values are read from the runtime client's environment, so they
+        // don't show up in the process list
+        let mut keys: Vec<&String> = vars.keys().collect();
+        keys.sort();
+        for key in keys {
+            args.extend(["-e".into(), key.into()]);
+        }
+
+        args.push(self.image.clone().into());
+        args
+    }
+
+    /// Force-remove the container. Killing the `run` client does not stop
+    /// the container it started.
+    pub async fn remove(&self) {
+        self.runtime.remove_container(&self.name).await;
+    }
+}
+
+/// Host paths to mount into every container, e.g. agent credentials in the
+/// home directory
+pub fn expand_mounts(paths: &[String]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| expand_tilde(path.trim()))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+#[cfg(unix)]
+fn owner(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(runtime: OciRuntime) -> OciContainer {
+        OciContainer {
+            runtime,
+            image: "ghcr.io/acme/toolchain:1".to_string(),
+            name: OciContainer::name_for("abc"),
+            mounts: vec![
+                PathBuf::from("/home/me/.claude"),
+                PathBuf::from("/home/me/repo/.git"),
+            ],
+            ports: vec![5173],
+        }
+    }
+
+    fn args(container: &OciContainer) -> Vec<String> {
+        let vars = HashMap::from([
+            ("VK_TASK_ID".to_string(), "t".to_string()),
+            ("API_KEY".to_string(), "secret".to_string()),
+        ]);
+        container
+            .run_args(
+                Path::new("/nonexistent/worktrees/ws"),
+                &[PathBuf::from("/home/me/repo/.git")],
+                &vars,
+            )
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    fn contains(args: &[String], expected: &[&str]) -> bool {
+        args.windows(expected.len())
+            .any(|window| window == expected)
+    }
+
+    #[test]
+    fn test_run_args() {
+        let args = args(&container(OciRuntime::Docker));
+        assert_eq!(&args[..2], ["run", "--rm"]);
+        assert!(contains(&args, &["--name", "vk-abc"]));
+        assert!(contains(
+            &args,
+            &["--label", "dev.vibe-kanban.managed=true"]
+        ));
+        // Containers don't share the host network; ports are published
+        assert!(!args.contains(&"--network".to_string()));
+        assert!(contains(&args, &["-p", "127.0.0.1:5173:5173"]));
+        assert!(contains(
+            &args,
+            &["-v", "/nonexistent/worktrees/ws:/nonexistent/worktrees/ws"]
+        ));
+        assert!(contains(
+            &args,
+            &["-v", "/home/me/.claude:/home/me/.claude"]
+        ));
+        assert_eq!(
+            args.iter()
+                .filter(|arg| *arg == "/home/me/repo/.git:/home/me/repo/.git")
+                .count(),
+            1
+        );
+        assert!(contains(&args, &["-w", "/nonexistent/worktrees/ws"]));
+        // Only names are passed; values come from the client's environment
+        assert!(contains(&args, &["-e", "API_KEY", "-e", "VK_TASK_ID"]));
+        assert!(!args.iter().any(|arg| arg.contains("secret")));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("ghcr.io/acme/toolchain:1")
+        );
+    }
+
+    #[test]
+    fn test_execution_process_id() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            OciContainer::execution_process_id(&OciContainer::name_for(id)),
+            Some(id)
+        );
+        assert_eq!(OciContainer::execution_process_id("vk-abc"), None);
+        assert_eq!(OciContainer::execution_process_id("postgres"), None);
+    }
+
+    #[test]
+    fn test_dockerfile_images() {
+        assert!(is_dockerfile("Dockerfile"));
+        assert!(is_dockerfile("backend/.devcontainer/Dockerfile"));
+        assert!(is_dockerfile("api/Containerfile"));
+        assert!(is_dockerfile("api/ci.Dockerfile"));
+        assert!(is_dockerfile("api/Dockerfile.dev"));
+        assert!(!is_dockerfile("ghcr.io/acme/toolchain:1"));
+        assert!(!is_dockerfile("node:22"));
+
+        let project_id = Uuid::new_v4();
+        let tag = image_tag(project_id, b"FROM node:22\n");
+        assert!(tag.starts_with(&format!("vk-project-{project_id}:")));
+        assert_eq!(tag, image_tag(project_id, b"FROM node:22\n"));
+        assert_ne!(tag, image_tag(project_id, b"FROM node:20\n"));
+    }
+
+    #[test]
+    fn test_podman_keeps_user() {
+        let args = args(&container(OciRuntime::Podman));
+        assert!(contains(&args, &["--userns", "keep-id"]));
+        assert!(!args.contains(&"--user".to_string()));
+    }
+}
@@ -0,0 +1,456 @@
+//! Optional sandbox for coding agents and scripts.
+//!
+//! On Linux the process is started through bubblewrap (`bwrap`) in fresh
+//! namespaces. It can write to its working directory, the parts of the
+//! repository's git directory that git needs for the worktrees in it and any
+//! paths the profile declares writable. System directories, the rest of the
+//! git directory (hooks, config, info), the files that tie a worktree to its
+//! git directory and declared read-only paths are mounted read-only, and the
+//! rest of the host filesystem is not visible. Network access can be denied
+//! per executor profile.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::process::Command;
+use ts_rs::TS;
+use workspace_utils::{path::expand_tilde, shell::resolve_executable_path};
+
+const BWRAP: &str = "bwrap";
+
+/// Host directories every sandboxed process can read
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/nix", "/snap",
+];
+
+/// Where /etc/resolv.conf commonly points on hosts that manage DNS under /run
+const RESOLVER_PATHS: &[&str] = &[
+    "/run/systemd/resolve",
+    "/run/NetworkManager",
+    "/run/resolvconf",
+];
+
+/// Parts of a repository's git directory that worktree commands write to.
+/// The rest, notably hooks/, config and info/, stays read-only so a sandboxed
+/// process can't plant anything that git on the host would run.
+const WRITABLE_GIT_PATHS: &[&str] = &["objects", "refs", "logs", "packed-refs"];
+
+/// Files in a worktree's own git directory that decide which repository and
+/// config git on the host uses for it
+const PINNED_WORKTREE_FILES: &[&str] = &["commondir", "gitdir", "config.worktree"];
+
+/// Git's global config, relative to the home directory. Kept read-only even
+/// when a writable path covers it.
+const GLOBAL_GIT_CONFIG: &[&str] = &[".gitconfig", ".config/git"];
+
+/// Errors seen when a process without network access tries to connect
+const NETWORK_ERRORS: &[&str] = &[
+    "Network is unreachable",
+    "ENETUNREACH",
+    "Temporary failure in name resolution",
+    "EAI_AGAIN",
+    "Could not resolve host",
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxNetwork {
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct SandboxConfig {
+    /// Run the agent and the attempt's scripts inside the sandbox
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether sandboxed processes can reach the network
+    #[serde(default)]
+    pub network: SandboxNetwork,
+    /// Extra host paths mounted read-only, e.g. a toolchain in your home directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_paths: Vec<String>,
+    /// Extra host paths the process may write to, e.g. the agent's config and cache directories
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("the sandbox is only supported on Linux")]
+    Unsupported,
+    #[error("the sandbox is enabled but bubblewrap (`bwrap`) is not installed")]
+    BubblewrapNotFound,
+}
+
+impl SandboxConfig {
+    /// A command that starts `program` inside the sandbox, allowed to write to
+    /// `current_dir`. Arguments added to the returned command go to `program`.
+    pub async fn command(
+        &self,
+        program: &Path,
+        current_dir: &Path,
+    ) -> Result<Command, SandboxError> {
+        if !cfg!(target_os = "linux") {
+            return Err(SandboxError::Unsupported);
+        }
+        let bwrap = resolve_executable_path(BWRAP)
+            .await
+            .ok_or(SandboxError::BubblewrapNotFound)?;
+
+        let mut command = Command::new(bwrap);
+        command
+            .args(self.bwrap_args(program, current_dir, &git_dirs(current_dir)))
+            .arg(program);
+        Ok(command)
+    }
+
+    fn bwrap_args(
+        &self,
+        program: &Path,
+        current_dir: &Path,
+        git_dirs: &[GitDirs],
+    ) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]
+            .map(OsString::from)
+            .into();
+        let mut mount = |flag: &str, path: &Path| {
+            args.push(flag.into());
+            args.push(path.into());
+            args.push(path.into());
+        };
+        let home = dirs::home_dir();
+
+        for path in SYSTEM_PATHS {
+            mount("--ro-bind-try", Path::new(path));
+        }
+        if self.network == SandboxNetwork::Allow {
+            for path in RESOLVER_PATHS {
+                mount("--ro-bind-try", Path::new(path));
+            }
+        }
+        // Agents installed outside the system directories, e.g. through a
+        // node version manager, need their install prefix
+        if let Some(prefix) = install_prefix(program, home.as_deref()) {
+            mount("--ro-bind-try", prefix);
+        }
+        for path in &self.read_only_paths {
+            mount("--ro-bind-try", &expand_tilde(path));
+        }
+        let writable_paths: Vec<PathBuf> = self
+            .writable_paths
+            .iter()
+            .map(|p| expand_tilde(p))
+            .collect();
+        for path in &writable_paths {
+            mount("--bind-try", path);
+        }
+        if let Some(home) = &home {
+            for config in GLOBAL_GIT_CONFIG.iter().map(|config| home.join(config)) {
+                if writable_paths.iter().any(|path| config.starts_with(path)) {
+                    mount("--ro-bind-try", &config);
+                }
+            }
+        }
+        for dirs in git_dirs {
+            mount("--ro-bind-try", &dirs.common);
+            for path in WRITABLE_GIT_PATHS {
+                mount("--bind-try", &dirs.common.join(path));
+            }
+            if let Some(worktree) = &dirs.worktree {
+                mount("--bind-try", worktree);
+            }
+        }
+        mount("--bind", current_dir);
+        // The worktrees' `.git` files sit inside the writable working directory;
+        // pointing one elsewhere would make git on the host use a config the
+        // process wrote
+        for dirs in git_dirs {
+            mount("--ro-bind-try", &dirs.dot_git);
+            if let Some(worktree) = &dirs.worktree {
+                for file in PINNED_WORKTREE_FILES {
+                    mount("--ro-bind-try", &worktree.join(file));
+                }
+            }
+        }
+
+        // Everything outside the mounts above is read-only
+        args.extend(["--remount-ro", "/", "--unshare-all"].map(OsString::from));
+        if self.network == SandboxNetwork::Allow {
+            args.push("--share-net".into());
+        }
+        // No --new-session: the sandbox has to stay in our process group so
+        // stopping the execution kills everything inside it
+        args.extend(["--die-with-parent", "--chdir"].map(OsString::from));
+        args.push(current_dir.into());
+        args.push("--".into());
+        args
+    }
+}
+
+/// Install prefix of `program`, e.g. `~/.nvm/versions/node/v22` for
+/// `~/.nvm/versions/node/v22/bin/claude`. Never `/`, the home directory or one
+/// of its parents, which would expose everything the sandbox hides.
+fn install_prefix<'a>(program: &'a Path, home: Option<&Path>) -> Option<&'a Path> {
+    let prefix = program.parent()?.parent()?;
+    if !program.is_absolute()
+        || prefix.parent().is_none()
+        || home.is_some_and(|home| home.starts_with(prefix))
+    {
+        return None;
+    }
+    Some(prefix)
+}
+
+/// Git directories of a worktree
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GitDirs {
+    /// The worktree's `.git` file
+    pub dot_git: PathBuf,
+    /// The repository's git directory, shared by all its worktrees
+    pub common: PathBuf,
+    /// `<common>/worktrees/<name>`, holding the worktree's index and HEAD.
+    /// `None` when the `.git` file doesn't point at a linked worktree.
+    pub worktree: Option<PathBuf>,
+}
+
+/// Git directories used by the worktrees in `dir` or its immediate children.
+/// A worktree's `.git` is a file pointing into the main repository, which git
+/// writes to for the index, refs and objects.
+pub(crate) fn git_dirs(dir: &Path) -> Vec<GitDirs> {
+    let children = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path());
+    std::iter::once(dir.to_path_buf())
+        .chain(children)
+        .filter_map(|path| {
+            let dot_git = path.join(".git");
+            let contents = std::fs::read_to_string(&dot_git).ok()?;
+            let git_dir = path.join(contents.strip_prefix("gitdir:")?.trim());
+            // <repo>/.git/worktrees/<name> lists <repo>/.git as its common dir
+            Some(match std::fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common) => GitDirs {
+                    dot_git,
+                    common: git_dir.join(common.trim()),
+                    worktree: Some(git_dir),
+                },
+                Err(_) => GitDirs {
+                    dot_git,
+                    common: git_dir,
+                    worktree: None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Explanation to show next to process output that looks like the sandbox
+/// blocked something
+pub fn blocked_message(output: &str) -> Option<&'static str> {
+    if output.lines().any(|line| line.starts_with("bwrap:")) {
+        Some("The sandbox could not start the process.")
+    } else if output.contains("Read-only file system") {
+        Some(
+            "The sandbox blocked a write outside the workspace. Add the path to the profile's sandbox writable paths if the agent needs it.",
+        )
+    } else if NETWORK_ERRORS.iter().any(|error| output.contains(error)) {
+        Some(
+            "A network request failed. If this profile's sandbox denies network access, allow it in the sandbox settings.",
+        )
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(config: &SandboxConfig) -> Vec<String> {
+        config
+            .bwrap_args(
+                Path::new("/opt/agent/bin/agent"),
+                Path::new("/var/tmp/vibe-kanban/worktrees/ws"),
+                &[GitDirs {
+                    dot_git: PathBuf::from("/var/tmp/vibe-kanban/worktrees/ws/.git"),
+                    common: PathBuf::from("/home/me/repo/.git"),
+                    worktree: Some(PathBuf::from("/home/me/repo/.git/worktrees/ws")),
+                }],
+            )
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    fn contains(args: &[String], expected: &[&str]) -> bool {
+        args.windows(expected.len())
+            .any(|window| window == expected)
+    }
+
+    #[test]
+    fn test_network_switch() {
+        let allowed = args(&SandboxConfig::default());
+        assert!(allowed.contains(&"--share-net".to_string()));
+        assert!(contains(
+            &allowed,
+            &[
+                "--ro-bind-try",
+                "/run/systemd/resolve",
+                "/run/systemd/resolve"
+            ]
+        ));
+
+        let denied = args(&SandboxConfig {
+            network: SandboxNetwork::Deny,
+            ..Default::default()
+        });
+        assert!(denied.contains(&"--unshare-all".to_string()));
+        assert!(!denied.contains(&"--share-net".to_string()));
+    }
+
+    #[test]
+    fn test_mounts() {
+        let args = args(&SandboxConfig {
+            read_only_paths: vec!["/srv/toolchain".to_string()],
+            writable_paths: vec!["/srv/cache".to_string()],
+            ..Default::default()
+        });
+        assert!(contains(
+            &args,
+            &["--ro-bind-try", "/opt/agent", "/opt/agent"]
+        ));
+        assert!(contains(
+            &args,
+            &["--ro-bind-try", "/srv/toolchain", "/srv/toolchain"]
+        ));
+        assert!(contains(&args, &["--bind-try", "/srv/cache", "/srv/cache"]));
+        assert!(contains(
+            &args,
+            &["--ro-bind-try", "/home/me/repo/.git", "/home/me/repo/.git"]
+        ));
+        for path in ["objects", "refs", "logs", "packed-refs", "worktrees/ws"] {
+            let path = format!("/home/me/repo/.git/{path}");
+            assert!(contains(&args, &["--bind-try", &path, &path]));
+        }
+        // Nothing git runs on the host may be writable
+        assert!(
+            !args
+                .iter()
+                .any(|arg| arg.contains("hooks") || arg.ends_with("/config"))
+        );
+        assert!(!contains(
+            &args,
+            &["--bind-try", "/home/me/repo/.git", "/home/me/repo/.git"]
+        ));
+        assert!(contains(
+            &args,
+            &[
+                "--bind",
+                "/var/tmp/vibe-kanban/worktrees/ws",
+                "/var/tmp/vibe-kanban/worktrees/ws"
+            ]
+        ));
+        // The worktree's links to its git directory are re-mounted read-only
+        // on top of the writable binds
+        let position = |expected: &[&str]| {
+            args.windows(expected.len())
+                .position(|window| window == expected)
+                .unwrap()
+        };
+        let worktree_bind = position(&[
+            "--bind",
+            "/var/tmp/vibe-kanban/worktrees/ws",
+            "/var/tmp/vibe-kanban/worktrees/ws",
+        ]);
+        let dot_git = "/var/tmp/vibe-kanban/worktrees/ws/.git";
+        assert!(position(&["--ro-bind-try", dot_git, dot_git]) > worktree_bind);
+        let git_dir_bind = position(&[
+            "--bind-try",
+            "/home/me/repo/.git/worktrees/ws",
+            "/home/me/repo/.git/worktrees/ws",
+        ]);
+        for file in ["commondir", "gitdir", "config.worktree"] {
+            let path = format!("/home/me/repo/.git/worktrees/ws/{file}");
+            assert!(position(&["--ro-bind-try", &path, &path]) > git_dir_bind);
+        }
+        assert!(contains(
+            &args,
+            &["--chdir", "/var/tmp/vibe-kanban/worktrees/ws", "--"]
+        ));
+        assert_eq!(args.last().map(String::as_str), Some("--"));
+
+        // A shell in /bin must not expose the whole filesystem
+        let args: Vec<String> = SandboxConfig::default()
+            .bwrap_args(Path::new("/bin/sh"), Path::new("/ws"), &[])
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect();
+        assert!(!contains(&args, &["--ro-bind-try", "/", "/"]));
+    }
+
+    #[test]
+    fn test_global_git_config_stays_read_only() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let gitconfig = home.join(".gitconfig").to_string_lossy().into_owned();
+
+        let writable_home = args(&SandboxConfig {
+            writable_paths: vec!["~".to_string()],
+            ..Default::default()
+        });
+        assert!(contains(
+            &writable_home,
+            &["--ro-bind-try", &gitconfig, &gitconfig]
+        ));
+        // Not mounted at all unless a writable path would expose it
+        assert!(!args(&SandboxConfig::default()).contains(&gitconfig));
+    }
+
+    #[test]
+    fn test_install_prefix() {
+        let home = Some(Path::new("/home/me"));
+        assert_eq!(
+            install_prefix(
+                Path::new("/home/me/.nvm/versions/node/v22/bin/claude"),
+                home
+            ),
+            Some(Path::new("/home/me/.nvm/versions/node/v22"))
+        );
+        assert_eq!(
+            install_prefix(Path::new("/opt/agent/bin/agent"), home),
+            Some(Path::new("/opt/agent"))
+        );
+        // Never the home directory, its parents or the root
+        assert_eq!(install_prefix(Path::new("/home/me/bin/agent"), home), None);
+        assert_eq!(install_prefix(Path::new("/home/me/agent"), home), None);
+        assert_eq!(install_prefix(Path::new("/bin/sh"), home), None);
+        assert_eq!(install_prefix(Path::new("bin/agent"), home), None);
+    }
+
+    #[test]
+    fn test_blocked_message() {
+        assert!(blocked_message("bwrap: Can't mount proc on /newroot/proc").is_some());
+        assert!(
+            blocked_message(
+                "mkdir: cannot create directory '/home/me/.cache': Read-only file system"
+            )
+            .unwrap()
+            .contains("writable paths")
+        );
+        assert!(
+            blocked_message("curl: (6) Could not resolve host: example.com")
+                .unwrap()
+                .contains("network")
+        );
+        assert!(blocked_message("error: expected `;`").is_none());
+    }
+}
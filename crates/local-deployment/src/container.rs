@@ -17,10 +17,14 @@ use db::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        playbook::{Playbook, PlaybookStep},
+        playbook_run::{PlaybookRun, PlaybookRunStatus, PlaybookStepRun, PlaybookStepRunStatus},
         project_repo::ProjectRepo,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
+        session::Session,
         task::{Task, TaskStatus},
+        verification_run::{CreateVerificationRun, VerificationRun, VerificationRunStatus},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -30,27 +34,39 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptContext,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
-    profile::ExecutorProfileId,
+    executors::{
+        BaseCodingAgent, ExecutorError, ExecutorExitResult, ExecutorExitSignal, InterruptSender,
+    },
+    logs::{
+        NormalizedEntryError, NormalizedEntryType,
+        utils::patch::extract_normalized_entry_from_patch,
+    },
+    oci::{OciContainer, expand_mounts, is_dockerfile},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::SandboxConfig,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    attempt_groups,
+    concurrency::{BudgetScope, ConcurrencyCheckResult, ConcurrencyService},
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     git::{Commit, GitCli, GitService},
     image::ImageService,
+    log_retention,
     notification::NotificationService,
+    playbook,
     queued_message::QueuedMessageService,
     share::SharePublisher,
+    verification,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -69,6 +85,7 @@ pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     interrupt_senders: Arc<RwLock<HashMap<Uuid, InterruptSender>>>,
+    oci_containers: Arc<RwLock<HashMap<Uuid, OciContainer>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
@@ -95,12 +112,14 @@ impl LocalContainerService {
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
+        let oci_containers = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
             db,
             child_store,
             interrupt_senders,
+            oci_containers,
             msg_stores,
             config,
             git,
@@ -112,7 +131,11 @@ impl LocalContainerService {
             notification_service,
         };
 
+        container.reap_orphan_containers().await;
+        container.harden_sandboxed_workspaces().await;
         container.spawn_workspace_cleanup().await;
+        container.spawn_budget_enforcement();
+        container.spawn_log_retention();
 
         container
     }
@@ -142,6 +165,58 @@ impl LocalContainerService {
         map.remove(id)
     }
 
+    /// Remove the container an execution process ran in, if any. Killing the
+    /// `docker run` client leaves the container itself running.
+    async fn remove_oci_container(&self, id: &Uuid) {
+        let container = self.oci_containers.write().await.remove(id);
+        if let Some(container) = container {
+            container.remove().await;
+        }
+    }
+
+    /// Remove containers left behind by execution processes of a previous run,
+    /// e.g. after a crash. Containers of processes this database doesn't know
+    /// belong to another instance and are left alone.
+    async fn reap_orphan_containers(&self) {
+        let runtime = self.config.read().await.containers.runtime;
+        for name in runtime.list_containers().await {
+            let Some(id) = OciContainer::execution_process_id(&name) else {
+                continue;
+            };
+            if let Ok(Some(_)) = ExecutionProcess::find_by_id(&self.db.pool, id).await {
+                tracing::info!("Removing orphaned container {}", name);
+                runtime.remove_container(&name).await;
+            }
+        }
+    }
+
+    /// Keep host git from trusting worktrees that sandboxed processes ran in
+    /// before a restart
+    async fn harden_sandboxed_workspaces(&self) {
+        match Workspace::find_sandboxed(&self.db.pool).await {
+            Ok(workspaces) => {
+                for workspace in &workspaces {
+                    self.harden_git(workspace).await;
+                }
+            }
+            Err(e) => tracing::error!("Failed to load sandboxed workspaces: {}", e),
+        }
+    }
+
+    async fn harden_git(&self, workspace: &Workspace) {
+        let Some(container_ref) = &workspace.container_ref else {
+            return;
+        };
+        let repo_paths: Vec<PathBuf> =
+            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|repo| repo.path)
+                .collect();
+        GitCli::new().mark_sandboxed(Path::new(container_ref), &repo_paths);
+    }
+
     pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace) {
         let Some(container_ref) = &workspace.container_ref else {
             return;
@@ -211,6 +286,107 @@ impl LocalContainerService {
         });
     }
 
+    /// Periodically compact and prune the logs of finished execution processes
+    pub fn spawn_log_retention(&self) {
+        let container = self.clone();
+        let mut retention_interval = tokio::time::interval(Duration::from_secs(3600));
+        tokio::spawn(async move {
+            loop {
+                retention_interval.tick().await;
+                let policy = container.config.read().await.log_retention.clone();
+                match log_retention::apply_policy(&container, &policy).await {
+                    Ok(report) if report.compacted > 0 || report.pruned > 0 => tracing::info!(
+                        "Log retention compacted {} and pruned {} execution logs, freeing {} bytes",
+                        report.compacted,
+                        report.pruned,
+                        report.freed_bytes
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Failed to apply log retention: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Periodically interrupt running coding agents once a hard budget cap is crossed
+    pub fn spawn_budget_enforcement(&self) {
+        let container = self.clone();
+        let concurrency = ConcurrencyService::new(self.db.clone());
+        let mut enforcement_interval = tokio::time::interval(Duration::from_secs(60));
+        tokio::spawn(async move {
+            loop {
+                enforcement_interval.tick().await;
+                container
+                    .enforce_hard_budget(&concurrency)
+                    .await
+                    .unwrap_or_else(|e| tracing::error!("Failed to enforce budgets: {}", e));
+            }
+        });
+    }
+
+    async fn enforce_hard_budget(
+        &self,
+        concurrency: &ConcurrencyService,
+    ) -> Result<(), ContainerError> {
+        let (concurrency_config, pricing) = {
+            let config = self.config.read().await;
+            (config.concurrency.clone(), config.pricing.clone())
+        };
+        if !concurrency_config.budget.hard_limit {
+            return Ok(());
+        }
+
+        let running = ExecutionProcess::find_running(&self.db.pool).await?;
+        let mut exceeded_by_project: HashMap<Uuid, Option<BudgetScope>> = HashMap::new();
+
+        for process in running
+            .into_iter()
+            .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent)
+        {
+            let ctx = ExecutionProcess::load_context(&self.db.pool, process.id).await?;
+            let project_id = ctx.task.project_id;
+
+            let exceeded = match exceeded_by_project.get(&project_id) {
+                Some(exceeded) => *exceeded,
+                None => {
+                    let exceeded = match concurrency
+                        .check_budget(&concurrency_config, &pricing, Some(project_id))
+                        .await?
+                    {
+                        ConcurrencyCheckResult::BudgetExceeded { scope, .. } => Some(scope),
+                        _ => None,
+                    };
+                    exceeded_by_project.insert(project_id, exceeded);
+                    exceeded
+                }
+            };
+            let Some(scope) = exceeded else {
+                continue;
+            };
+
+            tracing::info!(
+                "{} budget exceeded, interrupting execution process {}",
+                scope,
+                process.id
+            );
+            if let Some(msg_store) = self.msg_stores.read().await.get(&process.id) {
+                msg_store.push_stderr(format!("{scope} budget exceeded; stopping the agent"));
+            }
+            if let Err(e) = self
+                .stop_execution(&process, ExecutionProcessStatus::Killed)
+                .await
+            {
+                tracing::error!(
+                    "Failed to interrupt execution process {}: {}",
+                    process.id,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Record the current HEAD commit for each repository as the "after" state.
     /// Errors are silently ignored since this runs after the main execution completes
     /// and failure should not block process finalization.
@@ -389,6 +565,7 @@ impl LocalContainerService {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
             }
+            container.remove_oci_container(&exec_id).await;
 
             let (exit_code, status) = match status_result {
                 Ok(exit_status) => {
@@ -421,15 +598,24 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
+                // A failed verification script stops the chain, unlike a failed cleanup script
+                let is_verification = ctx.execution_process.executor_action().is_ok_and(|action| {
+                    action.script_context() == Some(&ScriptContext::Verification)
+                });
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
-                ) && !matches!(
-                    ctx.execution_process.status,
-                    ExecutionProcessStatus::Running
-                );
+                ) && !is_verification
+                    && !matches!(
+                        ctx.execution_process.status,
+                        ExecutionProcessStatus::Running
+                    );
 
-                if success || cleanup_done {
+                // An agent that ran out of context window carries on in a fresh session
+                let compaction_started = container.compact_if_context_exceeded(&ctx).await;
+
+                let mut playbook_started = None;
+                if (success || cleanup_done) && !compaction_started {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -453,6 +639,11 @@ impl LocalContainerService {
                         // If the process exited successfully, start the next action
                         if let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
+                            // A failed cleanup script is otherwise left to its chain to
+                            // finalize; if verification can't start, the task fails it
+                            if container.continues_after_failed_cleanup(&ctx) {
+                                container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                            }
                         }
                     } else {
                         tracing::info!(
@@ -460,12 +651,42 @@ impl LocalContainerService {
                             ctx.workspace.id
                         );
 
-                        // Manually finalize task since we're bypassing normal execution flow
-                        container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                        // Manually finalize task since we're bypassing normal execution flow,
+                        // unless the workspace's playbook carries on from here
+                        let started = container.advance_playbook(&ctx).await;
+                        playbook_started = Some(started);
+                        if !started {
+                            container.finalize_task(publisher.as_ref().ok(), &ctx).await;
+                        }
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                // Record verification results; a failure may be handed back to the agent
+                let fix_started = match container.handle_verification_result(&ctx).await {
+                    Ok(started) => started,
+                    Err(e) => {
+                        tracing::error!("Failed to handle verification result: {}", e);
+                        false
+                    }
+                };
+
+                // Carry on with the workspace's playbook once the current step is done;
+                // queued messages wait until it has finished
+                let playbook_started = match playbook_started {
+                    Some(started) => started,
+                    None => {
+                        !fix_started
+                            && !compaction_started
+                            && container.should_finalize(&ctx)
+                            && container.advance_playbook(&ctx).await
+                    }
+                };
+
+                if !fix_started
+                    && !compaction_started
+                    && !playbook_started
+                    && container.should_finalize(&ctx)
+                {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -519,6 +740,21 @@ impl LocalContainerService {
                     }
                 }
 
+                // Compare best-of-N attempts once the last one in the group is done
+                if !compaction_started && container.should_finalize(&ctx) {
+                    let pricing = config.read().await.pricing.clone();
+                    if let Err(e) = attempt_groups::complete_if_finished(
+                        &db,
+                        container.git(),
+                        &pricing,
+                        ctx.workspace.id,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to complete attempt group: {}", e);
+                    }
+                }
+
                 // Fire analytics event when CodingAgent execution has finished
                 if config.read().await.analytics_enabled
                     && matches!(
@@ -678,6 +914,25 @@ impl LocalContainerService {
         None
     }
 
+    /// Whether the coding agent's last error says it ran out of context window
+    async fn hit_context_limit(&self, exec_id: &Uuid) -> bool {
+        let msg_stores = self.msg_stores.read().await;
+        let Some(msg_store) = msg_stores.get(exec_id) else {
+            return false;
+        };
+
+        let last_error = msg_store.get_history().iter().rev().find_map(|msg| {
+            let LogMsg::JsonPatch(patch) = msg else {
+                return None;
+            };
+            match extract_normalized_entry_from_patch(patch)?.1.entry_type {
+                NormalizedEntryType::ErrorMessage { error_type } => Some(error_type),
+                _ => None,
+            }
+        });
+        last_error == Some(NormalizedEntryError::ContextLimitExceeded)
+    }
+
     /// Update the coding agent turn summary with the final assistant message
     async fn update_executor_session_summary(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
         // Check if there's a coding agent turn for this execution process
@@ -796,19 +1051,71 @@ impl LocalContainerService {
         ctx: &ExecutionContext,
         queued_data: &DraftFollowUpData,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Get executor profile from the latest CodingAgent process in this session
-        let initial_executor_profile_id =
-            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
-                .await
-                .map_err(|e| {
-                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
-                })?;
-
+        let initial_executor_profile_id = self.latest_executor_profile(ctx).await?;
         let executor_profile_id = ExecutorProfileId {
             executor: initial_executor_profile_id.executor,
             variant: queued_data.variant.clone(),
         };
 
+        self.start_follow_up(ctx, queued_data.message.clone(), executor_profile_id)
+            .await
+    }
+
+    /// Get executor profile from the latest CodingAgent process in this session
+    async fn latest_executor_profile(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<ExecutorProfileId, ContainerError> {
+        ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!("Failed to get executor profile: {e}")))
+    }
+
+    /// Scripts run in the sandbox of the coding agent profile they belong to:
+    /// the agent that runs after them, otherwise the session's latest agent.
+    /// Fails rather than running the script unsandboxed when that profile
+    /// can't be found.
+    async fn script_sandbox(
+        &self,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<Option<SandboxConfig>, ContainerError> {
+        let mut next = executor_action.next_action();
+        let mut profile_id = None;
+        while let Some(action) = next {
+            profile_id = action.executor_profile_id();
+            if profile_id.is_some() {
+                break;
+            }
+            next = action.next_action();
+        }
+        let profile_id = match profile_id {
+            Some(profile_id) => profile_id,
+            None => ExecutionProcess::latest_executor_profile_for_session(
+                &self.db.pool,
+                execution_process.session_id,
+            )
+            .await
+            .map_err(|e| {
+                ContainerError::Other(anyhow!("Failed to find the script's executor profile: {e}"))
+            })?,
+        };
+        let agent = ExecutorConfigs::get_cached()
+            .get_coding_agent(&profile_id)
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!("Executor profile {profile_id} not found"))
+            })?;
+        Ok(agent.sandbox().cloned())
+    }
+
+    /// Start a coding agent follow-up in the execution's session, followed by the
+    /// project's cleanup and verification scripts
+    async fn start_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        prompt: String,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
         // Get latest agent session ID for session continuity (from coding agent turns)
         let latest_agent_session_id = ExecutionProcess::find_latest_coding_agent_turn_session_id(
             &self.db.pool,
@@ -818,7 +1125,7 @@ impl LocalContainerService {
 
         let project_repos =
             ProjectRepo::find_by_project_id_with_names(&self.db.pool, ctx.project.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&project_repos);
 
         let working_dir = ctx
             .workspace
@@ -829,20 +1136,20 @@ impl LocalContainerService {
 
         let action_type = if let Some(agent_session_id) = latest_agent_session_id {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
+                prompt,
                 session_id: agent_session_id,
-                executor_profile_id: executor_profile_id.clone(),
-                working_dir: working_dir.clone(),
+                executor_profile_id,
+                working_dir,
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
-                executor_profile_id: executor_profile_id.clone(),
+                prompt,
+                executor_profile_id,
                 working_dir,
             })
         };
 
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+        let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
         self.start_execution(
             &ctx.workspace,
@@ -852,6 +1159,360 @@ impl LocalContainerService {
         )
         .await
     }
+
+    /// Record the result of a finished verification script. If it failed and
+    /// automatic fixes are enabled, the failure output is sent back to the coding
+    /// agent. Returns true when such a follow-up was started.
+    async fn handle_verification_result(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let process = &ctx.execution_process;
+        let Ok(action) = process.executor_action() else {
+            return Ok(false);
+        };
+        let ExecutorActionType::ScriptRequest(script) = action.typ() else {
+            return Ok(false);
+        };
+        if script.context != ScriptContext::Verification {
+            return Ok(false);
+        }
+        let status = match process.status {
+            ExecutionProcessStatus::Completed => VerificationRunStatus::Passed,
+            ExecutionProcessStatus::Failed => VerificationRunStatus::Failed,
+            // Stopped by the user
+            _ => return Ok(false),
+        };
+
+        let output = match self.get_msg_store_by_id(&process.id).await {
+            Some(store) => {
+                verification::output_tail(&store.get_history(), verification::MAX_OUTPUT_BYTES)
+            }
+            None => String::new(),
+        };
+
+        // Continue the retry count when the agent run being verified was itself
+        // an automatic fix
+        let latest_agent = ExecutionProcess::find_latest_by_session_and_run_reason(
+            &self.db.pool,
+            ctx.session.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        let retry = match latest_agent {
+            Some(agent) => VerificationRun::find_by_follow_up(&self.db.pool, agent.id)
+                .await?
+                .map_or(0, |previous| previous.retry + 1),
+            None => 0,
+        };
+
+        let run = VerificationRun::create(
+            &self.db.pool,
+            &CreateVerificationRun {
+                workspace_id: ctx.workspace.id,
+                execution_process_id: process.id,
+                status,
+                exit_code: process.exit_code,
+                output,
+                retry,
+            },
+        )
+        .await?;
+
+        if status == VerificationRunStatus::Passed {
+            return Ok(false);
+        }
+        let settings = self.config.read().await.verification.clone();
+        if !settings.auto_fix || run.retry >= i64::from(settings.max_auto_fix_attempts) {
+            return Ok(false);
+        }
+
+        let prompt =
+            verification::fix_prompt(script.working_dir.as_deref(), run.exit_code, &run.output);
+        let executor_profile_id = self.latest_executor_profile(ctx).await?;
+        let follow_up = self
+            .start_follow_up(ctx, prompt, executor_profile_id)
+            .await?;
+        VerificationRun::set_follow_up(&self.db.pool, run.id, follow_up.id).await?;
+
+        tracing::info!(
+            "Verification failed for workspace {}, started fix attempt {} of {}",
+            ctx.workspace.id,
+            run.retry + 1,
+            settings.max_auto_fix_attempts
+        );
+        Ok(true)
+    }
+
+    /// Compact the session if its coding agent ran out of context window and
+    /// automatic compaction is enabled, logging failures. Returns true when a
+    /// new session was started.
+    async fn compact_if_context_exceeded(&self, ctx: &ExecutionContext) -> bool {
+        match self.handle_context_limit(ctx).await {
+            Ok(started) => started,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to compact session {} of workspace {}: {}",
+                    ctx.session.id,
+                    ctx.workspace.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    async fn handle_context_limit(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        let process = &ctx.execution_process;
+        if process.run_reason != ExecutionProcessRunReason::CodingAgent
+            || process.status != ExecutionProcessStatus::Failed
+            || !self.hit_context_limit(&process.id).await
+        {
+            return Ok(false);
+        }
+
+        let settings = self.config.read().await.context_compaction.clone();
+        if !settings.auto_compact {
+            return Ok(false);
+        }
+        let compactions = Session::count_compactions(&self.db.pool, ctx.workspace.id).await?;
+        if compactions >= i64::from(settings.max_compactions) {
+            tracing::info!(
+                "Workspace {} ran out of context window after {} compactions, leaving it to the user",
+                ctx.workspace.id,
+                compactions
+            );
+            return Ok(false);
+        }
+
+        let executor_profile_id = self.latest_executor_profile(ctx).await?;
+        self.compact_session(&ctx.session, executor_profile_id)
+            .await?;
+        Ok(true)
+    }
+
+    /// Advance the workspace's playbook, logging failures. Returns true when a
+    /// process was started for it.
+    async fn advance_playbook(&self, ctx: &ExecutionContext) -> bool {
+        match self.handle_playbook_progress(ctx).await {
+            Ok(started) => started,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to advance playbook for workspace {}: {}",
+                    ctx.workspace.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Called when the processes started for something in the workspace are all
+    /// done. Moves the running playbook on to its next step, or starts the
+    /// task's playbook once the first turn of an attempt is done. Returns true
+    /// when another process was started for it.
+    async fn handle_playbook_progress(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db.pool;
+        let process = &ctx.execution_process;
+
+        let Some(run) = PlaybookRun::find_running_by_workspace_id(pool, ctx.workspace.id).await?
+        else {
+            return self.start_task_playbook(ctx).await;
+        };
+        let step_run = PlaybookStepRun::find_running_by_run_id(pool, run.id).await?;
+        let Some((step_run, step)) = step_run.and_then(|step_run| {
+            let step = run.steps.get(step_run.step_index as usize)?.clone();
+            Some((step_run, step))
+        }) else {
+            PlaybookRun::update_status(pool, run.id, PlaybookRunStatus::Failed).await?;
+            return Ok(false);
+        };
+
+        if process.status == ExecutionProcessStatus::Killed {
+            PlaybookStepRun::finish(pool, step_run.id, PlaybookStepRunStatus::Cancelled, None)
+                .await?;
+            PlaybookRun::update_status(pool, run.id, PlaybookRunStatus::Cancelled).await?;
+            return Ok(false);
+        }
+
+        let passed = process.status == ExecutionProcessStatus::Completed;
+        let is_playbook_script = process
+            .executor_action()
+            .is_ok_and(|action| action.script_context() == Some(&ScriptContext::Playbook));
+
+        if let PlaybookStep::Check {
+            script,
+            working_dir,
+            on_failure,
+            max_retries,
+        } = &step
+        {
+            if !is_playbook_script && passed {
+                // The agent is done fixing what the check reported; check again
+                let check = self
+                    .start_execution(
+                        &ctx.workspace,
+                        &ctx.session,
+                        &playbook::script_action(script, working_dir.as_deref()),
+                        &ExecutionProcessRunReason::CleanupScript,
+                    )
+                    .await?;
+                PlaybookStepRun::set_execution_process(
+                    pool,
+                    step_run.id,
+                    check.id,
+                    step_run.retries,
+                )
+                .await?;
+                return Ok(true);
+            }
+            if is_playbook_script
+                && !passed
+                && let Some(on_failure) = on_failure
+                && step_run.retries < i64::from(*max_retries)
+            {
+                let output = self.output_tail(process.id).await;
+                let prompt =
+                    playbook::check_fix_prompt(on_failure, script, process.exit_code, &output);
+                let executor_profile_id = self.latest_executor_profile(ctx).await?;
+                let follow_up = self
+                    .start_follow_up(ctx, prompt, executor_profile_id)
+                    .await?;
+                PlaybookStepRun::set_execution_process(
+                    pool,
+                    step_run.id,
+                    follow_up.id,
+                    step_run.retries + 1,
+                )
+                .await?;
+                return Ok(true);
+            }
+        }
+
+        if !passed {
+            let output = if is_playbook_script {
+                Some(self.output_tail(process.id).await)
+            } else {
+                None
+            };
+            PlaybookStepRun::finish(pool, step_run.id, PlaybookStepRunStatus::Failed, output)
+                .await?;
+            PlaybookRun::update_status(pool, run.id, PlaybookRunStatus::Failed).await?;
+            tracing::info!(
+                "Playbook '{}' failed at step {} in workspace {}",
+                run.name,
+                step_run.step_index + 1,
+                ctx.workspace.id
+            );
+            return Ok(false);
+        }
+
+        PlaybookStepRun::finish(pool, step_run.id, PlaybookStepRunStatus::Passed, None).await?;
+        let next = step_run.step_index as usize + 1;
+        if next < run.steps.len() {
+            self.start_playbook_step(ctx, &run, next).await?;
+            return Ok(true);
+        }
+        PlaybookRun::update_status(pool, run.id, PlaybookRunStatus::Completed).await?;
+        Ok(false)
+    }
+
+    /// Start the playbook attached to the task if this attempt hasn't run one yet
+    async fn start_task_playbook(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        let pool = &self.db.pool;
+        if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            return Ok(false);
+        }
+        let Some(playbook) = Playbook::find_by_task_id(pool, ctx.task.id).await? else {
+            return Ok(false);
+        };
+        if playbook.steps.is_empty()
+            || !PlaybookRun::find_by_workspace_id(pool, ctx.workspace.id)
+                .await?
+                .is_empty()
+        {
+            return Ok(false);
+        }
+
+        let run = PlaybookRun::create(pool, &playbook, ctx.workspace.id).await?;
+        tracing::info!(
+            "Starting playbook '{}' in workspace {}",
+            playbook.name,
+            ctx.workspace.id
+        );
+        self.start_playbook_step(ctx, &run, 0).await?;
+        Ok(true)
+    }
+
+    /// Start one step of a playbook run: a follow-up for a prompt, the script
+    /// for anything else. A step that can't start fails the run.
+    async fn start_playbook_step(
+        &self,
+        ctx: &ExecutionContext,
+        run: &PlaybookRun,
+        step_index: usize,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let step_run = PlaybookStepRun::create(pool, run.id, step_index as i64).await?;
+
+        let started = match &run.steps[step_index] {
+            PlaybookStep::Prompt { prompt } => match self.latest_executor_profile(ctx).await {
+                Ok(executor_profile_id) => {
+                    self.start_follow_up(ctx, prompt.clone(), executor_profile_id)
+                        .await
+                }
+                Err(e) => Err(e),
+            },
+            PlaybookStep::Script {
+                script,
+                working_dir,
+            }
+            | PlaybookStep::Check {
+                script,
+                working_dir,
+                ..
+            } => {
+                self.start_execution(
+                    &ctx.workspace,
+                    &ctx.session,
+                    &playbook::script_action(script, working_dir.as_deref()),
+                    &ExecutionProcessRunReason::CleanupScript,
+                )
+                .await
+            }
+        };
+
+        match started {
+            Ok(process) => {
+                PlaybookStepRun::set_execution_process(pool, step_run.id, process.id, 0).await?;
+                Ok(())
+            }
+            Err(e) => {
+                PlaybookStepRun::finish(
+                    pool,
+                    step_run.id,
+                    PlaybookStepRunStatus::Failed,
+                    Some(e.to_string()),
+                )
+                .await?;
+                PlaybookRun::update_status(pool, run.id, PlaybookRunStatus::Failed).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Tail of a finished process's output while its log store is still around
+    async fn output_tail(&self, execution_process_id: Uuid) -> String {
+        match self.get_msg_store_by_id(&execution_process_id).await {
+            Some(store) => {
+                verification::output_tail(&store.get_history(), verification::MAX_OUTPUT_BYTES)
+            }
+            None => String::new(),
+        }
+    }
 }
 
 fn failure_exit_status() -> std::process::ExitStatus {
@@ -1055,12 +1716,15 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::CustomAcp,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
                     self.notification_service.clone(),
+                    self.config.clone(),
                     execution_process.id,
+                    executor_action.executor_profile_id(),
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
@@ -1085,6 +1749,56 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+        // Dev servers stay outside the sandbox so the browser can reach them
+        if executor_action.base_executor().is_none()
+            && executor_action.script_context() != Some(&ScriptContext::DevServer)
+        {
+            env.sandbox = self
+                .script_sandbox(execution_process, executor_action)
+                .await?;
+        }
+        // Host git mustn't trust the git directories a sandboxed process can write to
+        let sandboxed = match executor_action.executor_profile_id() {
+            Some(profile_id) => ExecutorConfigs::get_cached()
+                .get_coding_agent(&profile_id)
+                .is_some_and(|agent| agent.sandbox().is_some_and(|sandbox| sandbox.enabled)),
+            None => env.sandbox.as_ref().is_some_and(|sandbox| sandbox.enabled),
+        };
+        if sandboxed {
+            Workspace::set_sandboxed(&self.db.pool, workspace.id).await?;
+            self.harden_git(workspace).await;
+        }
+        if let Some(image) = &project.container_image {
+            let containers = self.config.read().await.containers.clone();
+            let image = if is_dockerfile(image) {
+                containers
+                    .runtime
+                    .build_image(&current_dir.join(image), project.id)
+                    .await
+                    .map_err(ExecutorError::from)?
+            } else {
+                image.clone()
+            };
+            // Scripts run in a repo inside the workspace but may reach into
+            // its siblings, so the whole workspace is mounted
+            let mut mounts = vec![current_dir.clone()];
+            mounts.extend(expand_mounts(&containers.mounts));
+            // The container has its own network, so the dev server listens on
+            // a port picked here and published on the host
+            let mut ports = Vec::new();
+            if executor_action.script_context() == Some(&ScriptContext::DevServer) {
+                let port = free_port()?;
+                env.insert("PORT", port.to_string());
+                ports.push(port);
+            }
+            env.container = Some(OciContainer {
+                runtime: containers.runtime,
+                image,
+                name: OciContainer::name_for(execution_process.id),
+                mounts,
+                ports,
+            });
+        }
 
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
@@ -1104,6 +1818,13 @@ impl ContainerService for LocalContainerService {
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
+        if let Some(container) = env.container {
+            self.oci_containers
+                .write()
+                .await
+                .insert(execution_process.id, container);
+        }
+
         // Store interrupt sender for graceful shutdown
         if let Some(interrupt_sender) = spawned.interrupt_sender {
             self.add_interrupt_sender(execution_process.id, interrupt_sender)
@@ -1201,6 +1922,7 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.remove_oci_container(&execution_process.id).await;
 
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
@@ -1630,6 +2352,13 @@ impl ContainerService for LocalContainerService {
         Ok(resumed)
     }
 }
+/// A port nothing on the host listens on right now
+fn free_port() -> io::Result<u16> {
+    Ok(std::net::TcpListener::bind(("127.0.0.1", 0))?
+        .local_addr()?
+        .port())
+}
+
 fn success_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
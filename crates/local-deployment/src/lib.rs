@@ -8,9 +8,10 @@ use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
     auth::AuthContext,
+    concurrency::ConcurrencyService,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
-    events::EventService,
+    events::{EventService, TransitionColumns},
     file_search_cache::FileSearchCache,
     filesystem::FilesystemService,
     git::GitService,
@@ -22,6 +23,7 @@ use services::services::{
     repo::RepoService,
     share::{ShareConfig, SharePublisher},
     task_queue::TaskQueueService,
+    usage::UsageService,
 };
 use tokio::sync::RwLock;
 use utils::{
@@ -53,6 +55,8 @@ pub struct LocalDeployment {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     task_queue_service: TaskQueueService,
+    usage_service: UsageService,
+    concurrency_service: ConcurrencyService,
     share_publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     share_config: Option<ShareConfig>,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
@@ -106,6 +110,7 @@ impl Deployment for LocalDeployment {
         let events_msg_store = Arc::new(MsgStore::new());
         let events_entry_count = Arc::new(RwLock::new(0));
         let db_cell: Arc<std::sync::OnceLock<DBService>> = Arc::new(std::sync::OnceLock::new());
+        let transition_columns = Arc::new(std::sync::OnceLock::new());
 
         // Create DB with event hooks
         let db = {
@@ -113,8 +118,17 @@ impl Deployment for LocalDeployment {
                 events_msg_store.clone(),
                 events_entry_count.clone(),
                 db_cell.clone(),
+                transition_columns.clone(),
             );
             let db = DBService::new_with_after_connect(hook).await?;
+            match TransitionColumns::resolve(&db).await {
+                Ok(columns) => {
+                    let _ = transition_columns.set(columns);
+                }
+                Err(e) => {
+                    tracing::error!("Status change webhooks disabled, unknown schema: {}", e);
+                }
+            }
             let _ = db_cell.set(db.clone());
             db
         };
@@ -130,9 +144,11 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
+        let approvals = Approvals::new(db.clone(), msg_stores.clone());
         let queued_message_service = QueuedMessageService::new();
         let task_queue_service = TaskQueueService::new(db.clone());
+        let usage_service = UsageService::new(db.clone());
+        let concurrency_service = ConcurrencyService::new(db.clone());
 
         let share_config = ShareConfig::from_env();
 
@@ -211,6 +227,8 @@ impl Deployment for LocalDeployment {
             approvals,
             queued_message_service,
             task_queue_service,
+            usage_service,
+            concurrency_service,
             share_publisher,
             share_config: share_config.clone(),
             remote_client,
@@ -352,6 +370,14 @@ impl LocalDeployment {
         &self.task_queue_service
     }
 
+    pub fn usage_service(&self) -> &UsageService {
+        &self.usage_service
+    }
+
+    pub fn concurrency_service(&self) -> &ConcurrencyService {
+        &self.concurrency_service
+    }
+
     /// Get a cloned container service wrapped in Arc for use with queue processor
     pub fn container_arc(&self) -> Arc<LocalContainerService> {
         Arc::new(self.container.clone())
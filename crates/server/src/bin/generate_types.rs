@@ -20,6 +20,8 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::project::SymbolMatch::decl(),
+        db::models::project::SymbolKind::decl(),
         db::models::repo::Repo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
@@ -37,6 +39,51 @@ fn generate_types_content() -> String {
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::TaskDependencyStatus::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
+        db::models::task_schedule::ScheduleRepo::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        services::services::task_scheduler::ScheduleRunOutcome::decl(),
+        db::models::webhook::WebhookEventType::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::webhook_delivery::WebhookDeliveryStatus::decl(),
+        db::models::webhook_delivery::WebhookDelivery::decl(),
+        db::models::approval::ApprovalOutcome::decl(),
+        db::models::approval::ApprovalDecider::decl(),
+        db::models::approval::Approval::decl(),
+        db::models::attempt_group::AttemptGroupStatus::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptGroupMember::decl(),
+        db::models::attempt_group::AttemptGroupWithMembers::decl(),
+        db::models::attempt_group::AttemptGroupReport::decl(),
+        db::models::attempt_group::AttemptComparison::decl(),
+        db::models::attempt_group::ScriptOutcome::decl(),
+        db::models::verification_run::VerificationRunStatus::decl(),
+        db::models::verification_run::VerificationRun::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        db::models::playbook::PlaybookStep::decl(),
+        db::models::playbook::Playbook::decl(),
+        db::models::playbook::CreatePlaybook::decl(),
+        db::models::playbook::UpdatePlaybook::decl(),
+        db::models::playbook_run::PlaybookRunStatus::decl(),
+        db::models::playbook_run::PlaybookStepRunStatus::decl(),
+        db::models::playbook_run::PlaybookRun::decl(),
+        db::models::playbook_run::PlaybookStepRun::decl(),
+        db::models::playbook_run::PlaybookRunWithSteps::decl(),
+        server::routes::playbooks::SetTaskPlaybook::decl(),
+        db::models::log_search::LogSearchKind::decl(),
+        db::models::log_search::LogSearchHit::decl(),
+        db::models::imported_attempt::ImportedAttempt::decl(),
+        services::services::attempt_archive::AttemptArchiveManifest::decl(),
+        services::services::attempt_archive::ArchivedExecutionProcess::decl(),
+        db::models::execution_process_log_archive::ProjectLogStorage::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::ScratchPayload::decl(),
         db::models::scratch::ScratchType::decl(),
@@ -51,11 +98,16 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        db::models::coding_agent_turn::CodingAgentTurn::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
+        db::models::merge::ForgeKind::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge::CiStatus::decl(),
+        db::models::merge::CiCheck::decl(),
+        db::models::merge::PrCiState::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
@@ -106,9 +158,15 @@ fn generate_types_content() -> String {
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
+        server::routes::sessions::ForkSessionRequest::decl(),
+        server::routes::sessions::ForkSessionResponse::decl(),
+        server::routes::sessions::HandoffSessionRequest::decl(),
+        server::routes::sessions::HandoffSessionResponse::decl(),
+        server::routes::sessions::CompactSessionResponse::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
+        server::routes::merge_queue::MergeQueueRequest::decl(),
         server::routes::task_attempts::PushTaskAttemptRequest::decl(),
         server::routes::task_attempts::RenameBranchRequest::decl(),
         server::routes::task_attempts::RenameBranchResponse::decl(),
@@ -122,6 +180,8 @@ fn generate_types_content() -> String {
         server::routes::images::ImageMetadata::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
+        server::routes::attempt_groups::FanOutTaskAttemptsBody::decl(),
+        server::routes::attempt_groups::PromoteAttemptRequest::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
@@ -148,12 +208,24 @@ fn generate_types_content() -> String {
         services::services::config::EditorType::decl(),
         services::services::config::EditorOpenError::decl(),
         services::services::config::GitHubConfig::decl(),
+        services::services::config::GitLabConfig::decl(),
+        services::services::config::ApprovalRuleAction::decl(),
+        services::services::config::ApprovalMatcher::decl(),
+        services::services::config::ApprovalRule::decl(),
+        services::services::config::ApprovalPolicyConfig::decl(),
+        services::services::config::VerificationConfig::decl(),
+        services::services::config::ContainerConfig::decl(),
+        services::services::config::LogRetentionConfig::decl(),
+        services::services::config::ContextCompactionConfig::decl(),
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::ConcurrencyConfig::decl(),
         services::services::config::ConcurrencyLimit::decl(),
         services::services::config::QueueConfig::decl(),
+        services::services::config::BudgetConfig::decl(),
+        services::services::config::PricingConfig::decl(),
+        services::services::config::ModelPricing::decl(),
         db::models::execution_process::ConcurrencyStats::decl(),
         server::routes::config::ConcurrencyStatsResponse::decl(),
         // Task queue types
@@ -164,6 +236,13 @@ fn generate_types_content() -> String {
         services::services::task_queue::SessionQueueStatus::decl(),
         services::services::task_queue::QueueStats::decl(),
         services::services::task_queue::ExecutorQueueStats::decl(),
+        // Token usage types
+        utils::log_msg::TokenUsage::decl(),
+        db::models::execution_process_token_usage::ExecutionProcessTokenUsage::decl(),
+        db::models::execution_process_token_usage::TokenUsageBreakdown::decl(),
+        services::services::usage::ExecutionProcessUsage::decl(),
+        services::services::usage::PricedTokenUsageBreakdown::decl(),
+        services::services::usage::UsageSummary::decl(),
         server::routes::sessions::FollowUpResponse::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
@@ -180,6 +259,9 @@ fn generate_types_content() -> String {
         executors::executors::CodingAgent::decl(),
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::sandbox::SandboxConfig::decl(),
+        executors::oci::OciRuntime::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
@@ -200,6 +282,7 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom_acp::CustomAcp::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -301,6 +384,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_acp",
+            generate_json_schema::<executors::executors::custom_acp::CustomAcp>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
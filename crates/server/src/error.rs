@@ -7,21 +7,25 @@ use axum::{
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+    task_dependency::TaskDependencyError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
+    attempt_archive::AttemptArchiveError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    forge::ForgeError,
     git::GitServiceError,
     github::GitHubServiceError,
     image::ImageError,
+    log_retention::LogRetentionError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
+    task_scheduler::SchedulerError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -47,6 +51,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    Forge(#[from] ForgeError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -121,12 +127,20 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::Forge(forge_err) => match forge_err {
+                ForgeError::GitHub(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+                ForgeError::GitLab(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitLabServiceError"),
+                ForgeError::UnsupportedRemote(_) => (StatusCode::BAD_REQUEST, "ForgeError"),
+            },
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
             ApiError::Container(container_err) => match container_err {
                 ContainerError::GlobalConcurrencyLimitReached { .. }
                 | ContainerError::AgentConcurrencyLimitReached { .. } => {
                     (StatusCode::TOO_MANY_REQUESTS, "ConcurrencyLimitError")
                 }
+                ContainerError::BudgetExceeded { .. } => {
+                    (StatusCode::TOO_MANY_REQUESTS, "BudgetExceededError")
+                }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             },
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
@@ -395,3 +409,44 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<SchedulerError> for ApiError {
+    fn from(err: SchedulerError) -> Self {
+        match err {
+            SchedulerError::Database(db_err) => ApiError::Database(db_err),
+            SchedulerError::Container(container_err) => ApiError::Container(container_err),
+            SchedulerError::InvalidSchedule(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<AttemptArchiveError> for ApiError {
+    fn from(err: AttemptArchiveError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<LogRetentionError> for ApiError {
+    fn from(err: LogRetentionError) -> Self {
+        match err {
+            LogRetentionError::Database(db_err) => ApiError::Database(db_err),
+            LogRetentionError::Io(io_err) => ApiError::Io(io_err),
+            LogRetentionError::Json(json_err) => ApiError::Io(std::io::Error::other(json_err)),
+        }
+    }
+}
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            TaskDependencyError::TaskNotFound
+            | TaskDependencyError::NotFound
+            | TaskDependencyError::SelfDependency
+            | TaskDependencyError::CrossProject => ApiError::BadRequest(err.to_string()),
+            TaskDependencyError::AlreadyExists | TaskDependencyError::CycleDetected => {
+                ApiError::Conflict(err.to_string())
+            }
+        }
+    }
+}
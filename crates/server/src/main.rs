@@ -1,7 +1,10 @@
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
 use server::{DeploymentImpl, routes};
-use services::services::{container::ContainerService, queue_processor::QueueProcessor};
+use services::services::{
+    container::ContainerService, merge_queue::MergeQueueProcessor, queue_processor::QueueProcessor,
+    task_scheduler::TaskScheduler, webhooks::WebhookDispatcher,
+};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
@@ -58,6 +61,18 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
 
+    // Keep approvals pending for executions about to be resumed; must run
+    // before resuming marks them as handled
+    match deployment.approvals().restore_pending().await {
+        Ok(count) if count > 0 => {
+            tracing::info!("Restored {} pending approvals", count);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("Failed to restore pending approvals: {}", e);
+        }
+    }
+
     // Resume any interrupted executions from previous shutdown
     match deployment.container().resume_interrupted_executions().await {
         Ok(count) if count > 0 => {
@@ -77,7 +92,21 @@ async fn main() -> Result<(), VibeKanbanError> {
         deployment.config().clone(),
     );
 
-    deployment.spawn_pr_monitor_service().await;
+    deployment
+        .spawn_pr_monitor_service(deployment.container_arc())
+        .await;
+    TaskScheduler::spawn(
+        deployment.db().clone(),
+        deployment.container_arc(),
+        deployment.config().clone(),
+    );
+    MergeQueueProcessor::spawn(
+        deployment.db().clone(),
+        deployment.container_arc(),
+        deployment.config().clone(),
+        deployment.task_queue_service().clone(),
+    );
+    WebhookDispatcher::spawn(deployment.db().clone());
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
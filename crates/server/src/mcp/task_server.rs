@@ -2,11 +2,16 @@ use std::{collections::HashSet, future::Future, str::FromStr, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use db::models::{
-    project::{CreateProject, Project, UpdateProject},
+    log_search::LogSearchHit,
+    playbook::Playbook,
+    playbook_run::PlaybookRunWithSteps,
+    project::{CreateProject, Project, SearchResult, UpdateProject},
     project_repo::CreateProjectRepo,
     repo::Repo,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::TaskDependencies,
+    task_schedule::{CreateTaskSchedule, ScheduleRepo, TaskSchedule, UpdateTaskSchedule},
     workspace::{Workspace, WorkspaceContext},
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
@@ -22,6 +27,7 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use services::services::usage::UsageSummary;
 use uuid::Uuid;
 
 use crate::routes::{
@@ -417,6 +423,22 @@ pub struct UpdateProjectInput {
     pub dev_script_working_dir: Option<String>,
     #[schemars(description = "Optional default agent working directory")]
     pub default_agent_working_dir: Option<String>,
+    #[schemars(
+        description = "Optional container image to run the project's workspaces in, or a Dockerfile path relative to the workspace to build it from (requires Docker or Podman)"
+    )]
+    pub container_image: Option<String>,
+    #[schemars(
+        description = "Optional flag to send new PR review comments to the workspace's coding agent"
+    )]
+    pub auto_address_review_comments: Option<bool>,
+    #[schemars(
+        description = "Optional flag to send failing CI on the workspace's PRs to its coding agent"
+    )]
+    pub auto_fix_ci: Option<bool>,
+    #[schemars(
+        description = "Optional number of follow-ups started per PR to fix failing CI before giving up"
+    )]
+    pub max_ci_fix_attempts: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -502,6 +524,155 @@ pub struct MergeAttemptResponse {
     pub merged: bool,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TaskDependencyRequest {
+    #[schemars(description = "The ID of the task that should wait")]
+    pub task_id: Uuid,
+    #[schemars(
+        description = "The ID of the task that must be done or merged first (same project)"
+    )]
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetTaskDependenciesRequest {
+    #[schemars(description = "The ID of the task to inspect")]
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetUsageRequest {
+    #[schemars(description = "Task to report usage for. Takes precedence over project_id.")]
+    pub task_id: Option<Uuid>,
+    #[schemars(description = "Project to report usage for when task_id is not given")]
+    pub project_id: Option<Uuid>,
+    #[schemars(
+        description = "Only count executions recorded at or after this RFC 3339 timestamp (project and global reports only)"
+    )]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchLogsRequest {
+    #[schemars(
+        description = "Words to find, e.g. a command like `cargo sqlx prepare`, a file path or an error message. Every word must appear."
+    )]
+    pub query: String,
+    #[schemars(description = "Only search attempts in this project")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "Only search attempts of this task")]
+    pub task_id: Option<Uuid>,
+    #[schemars(
+        description = "Only match one kind of entry: 'user_message', 'assistant_message', 'tool_use' or 'error'"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Maximum number of matches to return (default: 20, max: 100)")]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FindSymbolRequest {
+    #[schemars(
+        description = "Name of the function, type, trait or module, e.g. `FileSearchCache`. Qualify it with the type or module it is defined in to narrow it down, e.g. `FileSearchCache::search`."
+    )]
+    pub name: String,
+    #[schemars(
+        description = "Project whose repositories to search. Defaults to the project of the active workspace session."
+    )]
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskSchedulesRequest {
+    #[schemars(description = "Only list schedules in this project")]
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateTaskScheduleRequest {
+    #[schemars(description = "The project the scheduled tasks are created in")]
+    pub project_id: Uuid,
+    #[schemars(description = "A name for the schedule")]
+    pub name: String,
+    #[schemars(description = "Title of each created task. `{date}` is replaced with the run date")]
+    pub task_title: String,
+    #[schemars(description = "Optional description of each created task")]
+    pub task_description: Option<String>,
+    #[schemars(
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'CODEX', 'GEMINI', 'CURSOR_AGENT', 'OPENCODE')"
+    )]
+    pub executor: String,
+    #[schemars(description = "Optional executor variant, if needed")]
+    pub variant: Option<String>,
+    #[schemars(description = "Base branch for each repository the workspace starts from")]
+    pub repos: Vec<McpWorkspaceRepoInput>,
+    #[schemars(
+        description = "Five-field cron expression in UTC, e.g. '0 9 * * MON'. Set this or interval_seconds"
+    )]
+    pub cron_expression: Option<String>,
+    #[schemars(description = "Run every this many seconds. Set this or cron_expression")]
+    pub interval_seconds: Option<i64>,
+    #[schemars(description = "Whether the schedule is active (defaults to true)")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UpdateTaskScheduleRequest {
+    #[schemars(description = "The ID of the schedule to update")]
+    pub schedule_id: Uuid,
+    #[schemars(description = "New name for the schedule")]
+    pub name: Option<String>,
+    #[schemars(description = "New task title template")]
+    pub task_title: Option<String>,
+    #[schemars(description = "New task description template. Empty clears it")]
+    pub task_description: Option<String>,
+    #[schemars(
+        description = "New cron expression. Setting this or interval_seconds replaces the current timing"
+    )]
+    pub cron_expression: Option<String>,
+    #[schemars(description = "New interval in seconds")]
+    pub interval_seconds: Option<i64>,
+    #[schemars(description = "Enable or pause the schedule")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TaskScheduleIdRequest {
+    #[schemars(description = "The ID of the schedule")]
+    pub schedule_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetTaskPlaybookRequest {
+    #[schemars(description = "The ID of the task")]
+    pub task_id: Uuid,
+    #[schemars(
+        description = "The playbook new attempts of the task should run. Omit to detach the current one"
+    )]
+    pub playbook_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetPlaybookRunsRequest {
+    #[schemars(description = "Optional attempt/workspace ID to inspect")]
+    pub attempt_id: Option<Uuid>,
+    #[schemars(
+        description = "Set to true to inspect the newest attempt (uses the current task context when available)"
+    )]
+    #[serde(default)]
+    pub latest: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TaskDependencyResponse {
+    #[schemars(description = "The task that waits")]
+    pub task_id: String,
+    #[schemars(description = "The prerequisite task")]
+    pub depends_on_task_id: String,
+    #[schemars(description = "Whether the dependency now exists")]
+    pub linked: bool,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct GetTasksResponse {
     #[schemars(description = "Task details for each requested task")]
@@ -1286,7 +1457,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Start working on a task by creating and launching a new workspace session. Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID."
+        description = "Start working on a task by creating and launching a new workspace session. Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID, CUSTOM_ACP."
     )]
     async fn start_workspace_session(
         &self,
@@ -1313,7 +1484,7 @@ impl TaskServer {
         let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
             Ok(exec) => exec,
             Err(_) => {
-                let options = "Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID";
+                let options = "Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID, CUSTOM_ACP";
                 return Self::err(
                     format!("Unknown executor '{executor_trimmed}'. {options}"),
                     None::<String>,
@@ -1365,7 +1536,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Start working on many tasks by creating and launching workspace sessions in bulk. Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID."
+        description = "Start working on many tasks by creating and launching workspace sessions in bulk. Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID, CUSTOM_ACP."
     )]
     async fn start_workspace_sessions(
         &self,
@@ -1405,7 +1576,7 @@ impl TaskServer {
             let base_executor = match BaseCodingAgent::from_str(&normalized_executor) {
                 Ok(exec) => exec,
                 Err(_) => {
-                    let options = "Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID";
+                    let options = "Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID, CUSTOM_ACP";
                     failed.push(BatchOperationError {
                         identifier: session.task_id.to_string(),
                         error: format!("Unknown executor '{executor_trimmed}'. {options}"),
@@ -1574,7 +1745,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Update one or many projects. Each item requires `project_id`; `name`, `dev_script`, `dev_script_working_dir`, and `default_agent_working_dir` are optional."
+        description = "Update one or many projects. Each item requires `project_id`; `name`, `dev_script`, `dev_script_working_dir`, `default_agent_working_dir`, `container_image`, `auto_address_review_comments`, `auto_fix_ci`, and `max_ci_fix_attempts` are optional."
     )]
     async fn update_projects(
         &self,
@@ -1609,6 +1780,10 @@ impl TaskServer {
                 dev_script: project_input.dev_script,
                 dev_script_working_dir: project_input.dev_script_working_dir,
                 default_agent_working_dir: project_input.default_agent_working_dir,
+                container_image: project_input.container_image,
+                auto_address_review_comments: project_input.auto_address_review_comments,
+                auto_fix_ci: project_input.auto_fix_ci,
+                max_ci_fix_attempts: project_input.max_ci_fix_attempts,
             };
 
             let url = self.url(&format!("/api/projects/{}", project_input.project_id));
@@ -1673,6 +1848,398 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Make a task wait for another task in the same project. The task's attempts stay queued until the prerequisite is done or merged."
+    )]
+    async fn add_task_dependency(
+        &self,
+        Parameters(TaskDependencyRequest {
+            task_id,
+            depends_on_task_id,
+        }): Parameters<TaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        match self
+            .send_json::<serde_json::Value>(self.client.post(&url).json(&serde_json::json!({
+                "depends_on_task_id": depends_on_task_id,
+            })))
+            .await
+        {
+            Ok(_) => TaskServer::success(&TaskDependencyResponse {
+                task_id: task_id.to_string(),
+                depends_on_task_id: depends_on_task_id.to_string(),
+                linked: true,
+            }),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(description = "Remove a dependency previously added with `add_task_dependency`.")]
+    async fn remove_task_dependency(
+        &self,
+        Parameters(TaskDependencyRequest {
+            task_id,
+            depends_on_task_id,
+        }): Parameters<TaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/tasks/{}/dependencies/{}",
+            task_id, depends_on_task_id
+        ));
+        match self
+            .send_json::<serde_json::Value>(self.client.delete(&url))
+            .await
+        {
+            Ok(_) => TaskServer::success(&TaskDependencyResponse {
+                task_id: task_id.to_string(),
+                depends_on_task_id: depends_on_task_id.to_string(),
+                linked: false,
+            }),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "List the tasks a task is blocked by (with their status) and the tasks it is blocking."
+    )]
+    async fn get_task_dependencies(
+        &self,
+        Parameters(GetTaskDependenciesRequest { task_id }): Parameters<GetTaskDependenciesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        match self
+            .send_json::<TaskDependencies>(self.client.get(&url))
+            .await
+        {
+            Ok(dependencies) => TaskServer::success(&dependencies),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Report token usage and estimated cost (USD) broken down by executor and model. Pass `task_id` for one task, `project_id` for a project, or neither for all projects."
+    )]
+    async fn get_usage(
+        &self,
+        Parameters(GetUsageRequest {
+            task_id,
+            project_id,
+            since,
+        }): Parameters<GetUsageRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut url = match (task_id, project_id) {
+            (Some(task_id), _) => self.url(&format!("/api/tasks/{}/usage", task_id)),
+            (None, Some(project_id)) => self.url(&format!("/api/projects/{}/usage", project_id)),
+            (None, None) => self.url("/api/usage"),
+        };
+        if task_id.is_none()
+            && let Some(since) = since
+        {
+            url = match reqwest::Url::parse_with_params(&url, &[("since", since)]) {
+                Ok(url) => url.to_string(),
+                Err(e) => return Self::err("Invalid usage query", Some(&e.to_string())),
+            };
+        }
+
+        match self.send_json::<UsageSummary>(self.client.get(&url)).await {
+            Ok(summary) => TaskServer::success(&summary),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Search coding agent conversations and tool calls across attempts, newest first. Each match names the task, workspace and execution process it came from, with a snippet where matched words are wrapped in `**`."
+    )]
+    async fn search_logs(
+        &self,
+        Parameters(SearchLogsRequest {
+            query,
+            project_id,
+            task_id,
+            kind,
+            limit,
+        }): Parameters<SearchLogsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut params = vec![("q", query)];
+        if let Some(project_id) = project_id {
+            params.push(("project_id", project_id.to_string()));
+        }
+        if let Some(task_id) = task_id {
+            params.push(("task_id", task_id.to_string()));
+        }
+        if let Some(kind) = kind {
+            params.push(("kind", kind));
+        }
+        if let Some(limit) = limit {
+            params.push(("limit", limit.to_string()));
+        }
+        let url = match reqwest::Url::parse_with_params(&self.url("/api/search/logs"), &params) {
+            Ok(url) => url.to_string(),
+            Err(e) => return Self::err("Invalid search query", Some(&e.to_string())),
+        };
+
+        match self
+            .send_json::<Vec<LogSearchHit>>(self.client.get(&url))
+            .await
+        {
+            Ok(hits) => TaskServer::success(&hits),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Find where a function, type, trait or module is defined across all repositories of a project. Returns up to 20 definitions with their `repo/path`, line, kind and enclosing type or module, exact name matches first. Rust, TypeScript, JavaScript, Python and Go files are indexed, from the active workspace's worktrees when searching its own project and from each repository's main checkout otherwise; the first search of a large repository takes a few seconds."
+    )]
+    async fn find_symbol(
+        &self,
+        Parameters(FindSymbolRequest { name, project_id }): Parameters<FindSymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let Some(project_id) =
+            project_id.or_else(|| self.context.as_ref().map(|ctx| ctx.project_id))
+        else {
+            return Self::err(
+                "project_id is required when no workspace context is available",
+                Some("Pass project_id explicitly or run from an active workspace session"),
+            );
+        };
+        let mut params = vec![("q", name), ("mode", "symbols".to_string())];
+        // Definitions the agent is editing are only in the workspace's worktrees
+        if let Some(ctx) = self
+            .context
+            .as_ref()
+            .filter(|ctx| ctx.project_id == project_id)
+        {
+            params.push(("workspace_id", ctx.workspace_id.to_string()));
+        }
+        let url = match reqwest::Url::parse_with_params(
+            &self.url(&format!("/api/projects/{}/search", project_id)),
+            &params,
+        ) {
+            Ok(url) => url.to_string(),
+            Err(e) => return Self::err("Invalid symbol name", Some(&e.to_string())),
+        };
+
+        match self
+            .send_json::<Vec<SearchResult>>(self.client.get(&url))
+            .await
+        {
+            Ok(definitions) => TaskServer::success(&definitions),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "List recurring task schedules, optionally for one project, with when each runs next and what its last run did."
+    )]
+    async fn list_task_schedules(
+        &self,
+        Parameters(ListTaskSchedulesRequest { project_id }): Parameters<ListTaskSchedulesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut url = self.url("/api/task-schedules");
+        if let Some(project_id) = project_id {
+            url = format!("{}?project_id={}", url, project_id);
+        }
+        match self
+            .send_json::<Vec<TaskSchedule>>(self.client.get(&url))
+            .await
+        {
+            Ok(schedules) => TaskServer::success(&schedules),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Create a recurring schedule that creates a task and starts a workspace session for it on a cron expression or fixed interval. Supported executors: CLAUDE_CODE, AMP, GEMINI, CODEX, OPENCODE, CURSOR_AGENT, QWEN_CODE, COPILOT, DROID, CUSTOM_ACP."
+    )]
+    async fn create_task_schedule(
+        &self,
+        Parameters(CreateTaskScheduleRequest {
+            project_id,
+            name,
+            task_title,
+            task_description,
+            executor,
+            variant,
+            repos,
+            cron_expression,
+            interval_seconds,
+            enabled,
+        }): Parameters<CreateTaskScheduleRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let executor_trimmed = executor.trim();
+        let normalized_executor = executor_trimmed.replace('-', "_").to_ascii_uppercase();
+        let Ok(base_executor) = BaseCodingAgent::from_str(&normalized_executor) else {
+            return Self::err(
+                format!("Unknown executor '{executor_trimmed}'."),
+                None::<String>,
+            );
+        };
+        let variant = variant
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let payload = CreateTaskSchedule {
+            project_id,
+            name,
+            task_title,
+            task_description,
+            executor_profile_id: ExecutorProfileId {
+                executor: base_executor,
+                variant,
+            },
+            repos: repos
+                .into_iter()
+                .map(|r| ScheduleRepo {
+                    repo_id: r.repo_id,
+                    target_branch: r.base_branch,
+                })
+                .collect(),
+            cron_expression,
+            interval_seconds,
+            enabled,
+        };
+
+        let url = self.url("/api/task-schedules");
+        match self
+            .send_json::<TaskSchedule>(self.client.post(&url).json(&payload))
+            .await
+        {
+            Ok(schedule) => TaskServer::success(&schedule),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(description = "Update a task schedule. Only the fields you pass are changed.")]
+    async fn update_task_schedule(
+        &self,
+        Parameters(UpdateTaskScheduleRequest {
+            schedule_id,
+            name,
+            task_title,
+            task_description,
+            cron_expression,
+            interval_seconds,
+            enabled,
+        }): Parameters<UpdateTaskScheduleRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = UpdateTaskSchedule {
+            name,
+            task_title,
+            task_description,
+            executor_profile_id: None,
+            repos: None,
+            cron_expression,
+            interval_seconds,
+            enabled,
+        };
+        let url = self.url(&format!("/api/task-schedules/{}", schedule_id));
+        match self
+            .send_json::<TaskSchedule>(self.client.put(&url).json(&payload))
+            .await
+        {
+            Ok(schedule) => TaskServer::success(&schedule),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(description = "Delete a task schedule. Tasks it already created are kept.")]
+    async fn delete_task_schedule(
+        &self,
+        Parameters(TaskScheduleIdRequest { schedule_id }): Parameters<TaskScheduleIdRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-schedules/{}", schedule_id));
+        match self
+            .send_json::<serde_json::Value>(self.client.delete(&url))
+            .await
+        {
+            Ok(_) => TaskServer::success(&serde_json::json!({
+                "schedule_id": schedule_id.to_string(),
+                "deleted": true,
+            })),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Run a task schedule now instead of waiting for its next slot. Returns whether the task was started, queued or skipped."
+    )]
+    async fn run_task_schedule(
+        &self,
+        Parameters(TaskScheduleIdRequest { schedule_id }): Parameters<TaskScheduleIdRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-schedules/{}/run", schedule_id));
+        match self
+            .send_json::<serde_json::Value>(self.client.post(&url))
+            .await
+        {
+            Ok(outcome) => TaskServer::success(&outcome),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "List playbooks: reusable lists of agent prompts, scripts and checks that run one after the other, unattended, after the first turn of a task attempt."
+    )]
+    async fn list_playbooks(&self) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/playbooks");
+        match self.send_json::<Vec<Playbook>>(self.client.get(&url)).await {
+            Ok(playbooks) => TaskServer::success(&playbooks),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Attach a playbook to a task so that new attempts run it, or detach it by omitting `playbook_id`. Use `list_playbooks` to find playbook ids."
+    )]
+    async fn set_task_playbook(
+        &self,
+        Parameters(SetTaskPlaybookRequest {
+            task_id,
+            playbook_id,
+        }): Parameters<SetTaskPlaybookRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/playbook", task_id));
+        match self
+            .send_json::<Option<Playbook>>(
+                self.client
+                    .put(&url)
+                    .json(&serde_json::json!({ "playbook_id": playbook_id })),
+            )
+            .await
+        {
+            Ok(playbook) => TaskServer::success(&serde_json::json!({
+                "task_id": task_id.to_string(),
+                "playbook": playbook,
+            })),
+            Err(err) => Ok(err),
+        }
+    }
+
+    #[tool(
+        description = "Get the playbook runs of a task attempt with the status of each step, newest run first. Provide `attempt_id` or set `latest=true` to use the newest attempt."
+    )]
+    async fn get_playbook_runs(
+        &self,
+        Parameters(GetPlaybookRunsRequest { attempt_id, latest }): Parameters<
+            GetPlaybookRunsRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let use_latest = latest.unwrap_or(attempt_id.is_none());
+        let attempt_id = match self.resolve_attempt_id(attempt_id, use_latest).await {
+            Ok(id) => id,
+            Err(err) => return Ok(err),
+        };
+
+        let url = self.url(&format!("/api/task-attempts/{}/playbook-runs", attempt_id));
+        match self
+            .send_json::<Vec<PlaybookRunWithSteps>>(self.client.get(&url))
+            .await
+        {
+            Ok(runs) => TaskServer::success(&runs),
+            Err(err) => Ok(err),
+        }
+    }
+
     #[tool(
         description = "Get detailed information (like task description) about one or many tasks/tickets. You can use `list_tasks` to find task_ids."
     )]
@@ -1791,7 +2358,7 @@ impl TaskServer {
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list_projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'create_projects', 'update_projects', 'delete_projects', 'list_tasks', 'list_tasks_by_status', 'create_tasks', 'start_workspace_session', 'start_workspace_sessions', 'get_tasks', 'get_attempt_diff', 'merge_task_attempt', 'update_tasks', 'delete_tasks', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list_projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'create_projects', 'update_projects', 'delete_projects', 'list_tasks', 'list_tasks_by_status', 'create_tasks', 'start_workspace_session', 'start_workspace_sessions', 'get_tasks', 'get_attempt_diff', 'merge_task_attempt', 'update_tasks', 'delete_tasks', 'add_task_dependency', 'remove_task_dependency', 'get_task_dependencies', 'get_usage', 'search_logs', 'find_symbol', 'list_task_schedules', 'create_task_schedule', 'update_task_schedule', 'delete_task_schedule', 'run_task_schedule', 'list_playbooks', 'set_task_playbook', 'get_playbook_runs', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
@@ -5,8 +5,9 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    attempt_group::AttemptGroup, execution_process::ExecutionProcess, playbook::Playbook,
+    project::Project, session::Session, tag::Tag, task::Task, task_schedule::TaskSchedule,
+    webhook::Webhook, workspace::Workspace,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -148,6 +149,94 @@ pub async fn load_tag_middleware(
     Ok(next.run(request).await)
 }
 
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(schedule);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_playbook_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(playbook_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let playbook = match Playbook::find_by_id(&deployment.db().pool, playbook_id).await {
+        Ok(Some(playbook)) => playbook,
+        Ok(None) => {
+            tracing::warn!("Playbook {} not found", playbook_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch playbook {}: {}", playbook_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(playbook);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_webhook_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let webhook = match Webhook::find_by_id(&deployment.db().pool, webhook_id).await {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => {
+            tracing::warn!("Webhook {} not found", webhook_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch webhook {}: {}", webhook_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(webhook);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_attempt_group_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let group = match AttemptGroup::find_by_id(&deployment.db().pool, group_id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            tracing::warn!("Attempt group {} not found", group_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attempt group {}: {}", group_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(group);
+    Ok(next.run(request).await)
+}
+
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(session_id): Path<Uuid>,
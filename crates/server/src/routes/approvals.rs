@@ -1,13 +1,18 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, State},
     http::StatusCode,
+    response::Json as ResponseJson,
     routing::post,
 };
+use db::models::{approval::Approval, task::Task, workspace::Workspace};
 use deployment::Deployment;
-use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use utils::{
+    approvals::{ApprovalResponse, ApprovalStatus},
+    response::ApiResponse,
+};
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, error::ApiError};
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
@@ -39,6 +44,24 @@ pub async fn respond_to_approval(
     }
 }
 
+/// Approval history across all attempts of a task, newest first
+pub async fn get_task_approvals(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Approval>>>, ApiError> {
+    let approvals = Approval::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
+/// Approval history for one attempt, newest first
+pub async fn get_workspace_approvals(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Approval>>>, ApiError> {
+    let approvals = Approval::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
 }
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    attempt_group::{
+        AttemptGroup, AttemptGroupReport, AttemptGroupStatus, AttemptGroupWithMembers,
+    },
+    task::Task,
+    task_queue::TaskQueueEntry,
+    workspace::Workspace,
+    workspace_repo::CreateWorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{attempt_groups, container::ContainerService};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_attempt_group_middleware,
+    routes::task_attempts::{
+        WorkspaceRepoInput, check_concurrency_limits, check_group_concurrency_limits,
+        is_start_deferred_by_dependencies,
+    },
+};
+
+/// Upper bound on attempts started by one fan-out
+const MAX_FAN_OUT: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct FanOutTaskAttemptsBody {
+    pub task_id: Uuid,
+    /// One attempt is started per profile; profiles must be distinct
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<WorkspaceRepoInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct PromoteAttemptRequest {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptGroupQuery {
+    pub task_id: Uuid,
+}
+
+async fn with_members(
+    deployment: &DeploymentImpl,
+    group: AttemptGroup,
+) -> Result<AttemptGroupWithMembers, ApiError> {
+    let members = AttemptGroup::members(&deployment.db().pool, group.id).await?;
+    Ok(AttemptGroupWithMembers { group, members })
+}
+
+/// Start one attempt per executor profile and track them as a group.
+/// Attempts that can't start right away because of dependencies or
+/// concurrency limits wait in the task queue.
+pub async fn fan_out_task_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<FanOutTaskAttemptsBody>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    let profiles = &payload.executor_profile_ids;
+    if profiles.len() < 2 || profiles.len() > MAX_FAN_OUT {
+        return Err(ApiError::BadRequest(format!(
+            "Fan-out needs between 2 and {MAX_FAN_OUT} executor profiles"
+        )));
+    }
+    if profiles.iter().collect::<HashSet<_>>().len() != profiles.len() {
+        return Err(ApiError::BadRequest(
+            "Each attempt in a fan-out needs a different executor profile".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let deferred = is_start_deferred_by_dependencies(&deployment, task.id).await?;
+    let queue_enabled = deployment.config().read().await.concurrency.queue.enabled;
+    if !deferred && !queue_enabled {
+        // Nothing would pick up attempts over the limit, so the whole group
+        // has to fit before any of it starts
+        let executors: Vec<_> = profiles.iter().map(|p| p.executor).collect();
+        check_group_concurrency_limits(&deployment, &executors, task.project_id).await?;
+    }
+
+    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
+        .repos
+        .iter()
+        .map(|r| CreateWorkspaceRepo {
+            repo_id: r.repo_id,
+            target_branch: r.target_branch.clone(),
+        })
+        .collect();
+
+    let group = AttemptGroup::create(pool, Uuid::new_v4(), task.id).await?;
+    for profile in profiles {
+        let workspace = deployment
+            .container()
+            .create_workspace_for_task(&task, &workspace_repos)
+            .await?;
+        AttemptGroup::add_member(pool, group.id, workspace.id, profile).await?;
+
+        let start_now = if deferred {
+            false
+        } else if !queue_enabled {
+            // Capacity for the whole group was checked up front
+            true
+        } else {
+            match check_concurrency_limits(&deployment, &profile.executor, task.project_id).await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::info!("Queueing fan-out attempt {}: {}", workspace.id, e);
+                    false
+                }
+            }
+        };
+
+        if !start_now {
+            deployment
+                .container()
+                .queue_workspace_start(&workspace, profile.clone())
+                .await?;
+        } else if let Err(err) = deployment
+            .container()
+            .start_workspace(&workspace, profile.clone())
+            .await
+        {
+            tracing::error!("Failed to start fan-out attempt {}: {}", workspace.id, err);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempts_fanned_out",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "attempt_group_id": group.id.to_string(),
+                "attempt_count": profiles.len(),
+                "executors": profiles.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+                "repository_count": payload.repos.len(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Fanned out {} attempts for task {} in group {}",
+        profiles.len(),
+        task.id,
+        group.id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        with_members(&deployment, group).await?,
+    )))
+}
+
+pub async fn get_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AttemptGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroupWithMembers>>>, ApiError> {
+    let groups = AttemptGroup::find_by_task_id(&deployment.db().pool, query.task_id).await?;
+    let mut result = Vec::with_capacity(groups.len());
+    for group in groups {
+        result.push(with_members(&deployment, group).await?);
+    }
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+pub async fn get_attempt_group(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        with_members(&deployment, group).await?,
+    )))
+}
+
+/// The stored report once the group has finished, otherwise a snapshot of
+/// the attempts so far
+pub async fn get_attempt_group_report(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupReport>>, ApiError> {
+    if let Some(report) = group.report {
+        return Ok(ResponseJson(ApiResponse::success(report.0)));
+    }
+    let pricing = deployment.config().read().await.pricing.clone();
+    let report =
+        attempt_groups::build_report(deployment.db(), deployment.git(), &pricing, group.id).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Keep one attempt and archive the others: their processes are stopped and
+/// worktrees removed, but their branches are left in place
+pub async fn promote_attempt(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PromoteAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    if group.status == AttemptGroupStatus::Promoted {
+        return Err(ApiError::Conflict(
+            "An attempt in this group has already been promoted".to_string(),
+        ));
+    }
+    let pool = &deployment.db().pool;
+    let members = AttemptGroup::members(pool, group.id).await?;
+    if !members
+        .iter()
+        .any(|member| member.workspace_id == payload.workspace_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Workspace is not part of this attempt group".to_string(),
+        ));
+    }
+
+    // Capture the comparison before the other worktrees go away
+    let report = match group.report {
+        Some(_) => None,
+        None => {
+            let pricing = deployment.config().read().await.pricing.clone();
+            Some(
+                attempt_groups::build_report(deployment.db(), deployment.git(), &pricing, group.id)
+                    .await?,
+            )
+        }
+    };
+    AttemptGroup::promote(
+        pool,
+        group.id,
+        payload.workspace_id,
+        report.as_ref(),
+        Utc::now(),
+    )
+    .await?;
+
+    for member in &members {
+        if member.workspace_id == payload.workspace_id || member.archived_at.is_some() {
+            continue;
+        }
+        TaskQueueEntry::cancel_for_workspace(pool, member.workspace_id).await?;
+        if let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await?
+            && let Err(e) = deployment.container().delete(&workspace).await
+        {
+            tracing::warn!(
+                "Failed to clean up archived attempt {}: {}",
+                workspace.id,
+                e
+            );
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_promoted",
+            serde_json::json!({
+                "task_id": group.task_id.to_string(),
+                "attempt_group_id": group.id.to_string(),
+                "workspace_id": payload.workspace_id.to_string(),
+                "attempt_count": members.len(),
+            }),
+        )
+        .await;
+
+    let group = AttemptGroup::find_by_id(pool, group.id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    Ok(ResponseJson(ApiResponse::success(
+        with_members(&deployment, group).await?,
+    )))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let group_router = Router::new()
+        .route("/", get(get_attempt_group))
+        .route("/report", get(get_attempt_group_report))
+        .route("/promote", post(promote_attempt))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_attempt_group_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_attempt_groups).post(fan_out_task_attempts))
+        .nest("/{group_id}", group_router);
+
+    Router::new().nest("/attempt-groups", inner)
+}
@@ -19,10 +19,13 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    ConcurrencyConfig, Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    approvals,
+    config::{
+        ConcurrencyConfig, Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -125,6 +128,10 @@ async fn update_config(
         ));
     }
 
+    if let Err(e) = approvals::policy::validate(&new_config.approval_policy) {
+        return ResponseJson(ApiResponse::error(&e));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
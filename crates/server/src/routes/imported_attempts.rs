@@ -0,0 +1,229 @@
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Multipart, Path, State},
+    http::{HeaderValue, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
+use db::models::imported_attempt::{CreateImportedAttempt, ImportedAttempt};
+use deployment::Deployment;
+use serde_json::json;
+use services::services::attempt_archive::{
+    AttemptArchiveError, AttemptArchiveManifest, imported_archive_path, imports_dir, read_image,
+    read_manifest, read_normalized_logs, read_raw_logs,
+};
+use sqlx::Error as SqlxError;
+use utils::{log_msg::LogMsg, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Archives hold every log of an attempt, so they can be much larger than an image
+const MAX_ARCHIVE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Image types served with their own content type; anything else in an
+/// imported archive is served as a download
+const IMAGE_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Reading an archive decompresses it, so it happens off the async runtime
+async fn read_archive<T: Send + 'static>(
+    archive: impl AsRef<[u8]> + Send + 'static,
+    read: impl FnOnce(&[u8]) -> Result<T, AttemptArchiveError> + Send + 'static,
+) -> Result<T, ApiError> {
+    let result = tokio::task::spawn_blocking(move || read(archive.as_ref()))
+        .await
+        .map_err(std::io::Error::other)?;
+    Ok(result?)
+}
+
+pub async fn get_imported_attempts(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ImportedAttempt>>>, ApiError> {
+    let imported = ImportedAttempt::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(imported)))
+}
+
+/// Import an archive exported from a task attempt, uploaded as the `archive` field
+pub async fn import_attempt(
+    State(deployment): State<DeploymentImpl>,
+    mut multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<ImportedAttempt>>, ApiError> {
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() != Some("archive") {
+            continue;
+        }
+        let data = field.bytes().await?;
+        let manifest = read_archive(data.clone(), read_manifest).await?;
+
+        let id = Uuid::new_v4();
+        let path = imported_archive_path(id);
+        tokio::fs::create_dir_all(imports_dir()).await?;
+        tokio::fs::write(&path, &data).await?;
+
+        let create = CreateImportedAttempt {
+            task_title: manifest.task.title,
+            branch: manifest.workspace.branch,
+            format_version: manifest.format_version,
+            app_version: manifest.app_version,
+            exported_at: manifest.exported_at,
+            size_bytes: data.len() as i64,
+        };
+        let imported = match ImportedAttempt::create(&deployment.db().pool, id, &create).await {
+            Ok(imported) => imported,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                return Err(e.into());
+            }
+        };
+
+        deployment
+            .track_if_analytics_allowed(
+                "task_attempt_imported",
+                json!({
+                    "format_version": imported.format_version,
+                    "size_bytes": imported.size_bytes,
+                }),
+            )
+            .await;
+
+        return Ok(ResponseJson(ApiResponse::success(imported)));
+    }
+
+    Err(ApiError::BadRequest(
+        "Missing archive file in upload".to_string(),
+    ))
+}
+
+async fn load_archive(deployment: &DeploymentImpl, id: Uuid) -> Result<Vec<u8>, ApiError> {
+    ImportedAttempt::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    Ok(tokio::fs::read(imported_archive_path(id)).await?)
+}
+
+/// Everything recorded about the imported attempt except logs and images
+pub async fn get_imported_attempt(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptArchiveManifest>>, ApiError> {
+    let archive = load_archive(&deployment, id).await?;
+    let manifest = read_archive(archive, read_manifest).await?;
+    Ok(ResponseJson(ApiResponse::success(manifest)))
+}
+
+/// Conversation entries of an imported coding agent process, in the shape the
+/// log viewer builds from normalized log patches
+pub async fn get_imported_process_entries(
+    Path((id, process_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    let archive = load_archive(&deployment, id).await?;
+    let entries = read_archive(archive, move |archive| {
+        read_normalized_logs(archive, process_id)
+    })
+    .await?
+    .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Stdout and stderr of an imported process, as the raw log viewer shows them
+pub async fn get_imported_process_raw_logs(
+    Path((id, process_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    let archive = load_archive(&deployment, id).await?;
+    let logs = read_archive(archive, move |archive| read_raw_logs(archive, process_id))
+        .await?
+        .unwrap_or_default();
+    let output = logs
+        .lines()
+        .filter_map(|line| match serde_json::from_str::<LogMsg>(line).ok()? {
+            LogMsg::Stdout(content) => Some(json!({"type": "STDOUT", "content": content})),
+            LogMsg::Stderr(content) => Some(json!({"type": "STDERR", "content": content})),
+            _ => None,
+        })
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(output)))
+}
+
+pub async fn serve_imported_image(
+    Path((id, image_id)): Path<(Uuid, Uuid)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let archive = load_archive(&deployment, id).await?;
+    let (image, data) = read_archive(archive, move |archive| {
+        let Some(image) = read_manifest(archive)?
+            .images
+            .into_iter()
+            .find(|image| image.id == image_id)
+        else {
+            return Ok(None);
+        };
+        let data = read_image(archive, &image.file_path)?;
+        Ok(data.map(|data| (image, data)))
+    })
+    .await?
+    .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    // The archive came from elsewhere, so its mime type is only trusted for images
+    let content_type = image
+        .mime_type
+        .and_then(|mime_type| {
+            IMAGE_MIME_TYPES
+                .iter()
+                .copied()
+                .find(|allowed| *allowed == mime_type)
+        })
+        .unwrap_or("application/octet-stream");
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            ),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+pub async fn delete_imported_attempt(
+    Path(id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ImportedAttempt::delete(&deployment.db().pool, id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+    if let Err(e) = tokio::fs::remove_file(imported_archive_path(id)).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove imported archive {}: {}", id, e);
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let imported_attempts_router = Router::new()
+        .route(
+            "/",
+            get(get_imported_attempts)
+                .post(import_attempt)
+                .layer(DefaultBodyLimit::max(MAX_ARCHIVE_BYTES)),
+        )
+        .route(
+            "/{id}",
+            get(get_imported_attempt).delete(delete_imported_attempt),
+        )
+        .route(
+            "/{id}/processes/{process_id}/entries",
+            get(get_imported_process_entries),
+        )
+        .route(
+            "/{id}/processes/{process_id}/raw-logs",
+            get(get_imported_process_raw_logs),
+        )
+        .route("/{id}/images/{image_id}", get(serve_imported_image));
+
+    Router::new().nest("/imported-attempts", imported_attempts_router)
+}
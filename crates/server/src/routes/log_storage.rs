@@ -0,0 +1,18 @@
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use db::models::execution_process_log_archive::{ExecutionProcessLogArchive, ProjectLogStorage};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Space taken by execution process logs, per project, largest first
+pub async fn get_log_storage(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectLogStorage>>>, ApiError> {
+    let storage = ExecutionProcessLogArchive::storage_by_project(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(storage)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/log-storage", get(get_log_storage))
+}
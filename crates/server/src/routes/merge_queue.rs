@@ -0,0 +1,87 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    merge_queue::{MergeQueueEntry, MergeQueueStatus},
+    repo::RepoError,
+    task::TaskStatus,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct MergeQueueRequest {
+    pub repo_id: Uuid,
+}
+
+/// Queue the attempt to be rebased, verified and merged into its target branch
+pub async fn enqueue_merge(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    if matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled) {
+        return Err(ApiError::Conflict("Task is already finished".to_string()));
+    }
+    if let Some(entry) =
+        MergeQueueEntry::find_latest_by_workspace_and_repo(pool, workspace.id, request.repo_id)
+            .await?
+        && entry.status.is_active()
+    {
+        return Err(ApiError::Conflict(
+            "Attempt is already in the merge queue".to_string(),
+        ));
+    }
+
+    let entry = MergeQueueEntry::create(pool, workspace.id, request.repo_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merge_queued",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+/// Take the attempt out of the merge queue before its merge has started
+pub async fn cancel_queued_merge(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let entry =
+        MergeQueueEntry::find_latest_by_workspace_and_repo(pool, workspace.id, request.repo_id)
+            .await?
+            .filter(|entry| entry.status == MergeQueueStatus::Queued)
+            .ok_or_else(|| {
+                ApiError::Conflict("Attempt is not waiting in the merge queue".to_string())
+            })?;
+    if !MergeQueueEntry::cancel(pool, entry.id).await? {
+        return Err(ApiError::Conflict(
+            "The merge has already started".to_string(),
+        ));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -7,6 +7,7 @@ use crate::{DeploymentImpl, middleware::request_timing};
 
 pub mod all_tasks;
 pub mod approvals;
+pub mod attempt_groups;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -16,16 +17,25 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod imported_attempts;
+pub mod log_storage;
+pub mod merge_queue;
 pub mod oauth;
 pub mod organizations;
+pub mod playbooks;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod tasks;
+pub mod usage;
+pub mod verification;
+pub mod webhooks;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
@@ -36,8 +46,11 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(tasks::router(&deployment))
+        .merge(task_schedules::router(&deployment))
+        .merge(playbooks::router(&deployment))
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
+        .merge(attempt_groups::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
@@ -48,6 +61,11 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
+        .merge(usage::router())
+        .merge(search::router())
+        .merge(imported_attempts::router())
+        .merge(log_storage::router())
+        .merge(webhooks::router(&deployment))
         .nest("/images", images::routes())
         .layer(axum::middleware::from_fn(request_timing::log_timing))
         .with_state(deployment);
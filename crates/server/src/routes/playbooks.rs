@@ -0,0 +1,169 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    playbook::{CreatePlaybook, Playbook, UpdatePlaybook},
+    playbook_run::{PlaybookRun, PlaybookRunWithSteps},
+    task::Task,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::playbook;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_playbook_middleware};
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct SetTaskPlaybook {
+    /// None detaches the task's playbook
+    pub playbook_id: Option<Uuid>,
+}
+
+fn validate_playbook(data: &CreatePlaybook) -> Result<(), ApiError> {
+    if data.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Playbook name is required".to_string(),
+        ));
+    }
+    playbook::validate_steps(&data.steps).map_err(ApiError::BadRequest)
+}
+
+pub async fn get_playbooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Playbook>>>, ApiError> {
+    let playbooks = Playbook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(playbooks)))
+}
+
+pub async fn get_playbook(
+    Extension(playbook): Extension<Playbook>,
+) -> Result<ResponseJson<ApiResponse<Playbook>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(playbook)))
+}
+
+pub async fn create_playbook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreatePlaybook>,
+) -> Result<ResponseJson<ApiResponse<Playbook>>, ApiError> {
+    validate_playbook(&payload)?;
+    let playbook = Playbook::create(&deployment.db().pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "playbook_created",
+            serde_json::json!({
+                "playbook_id": playbook.id.to_string(),
+                "step_count": playbook.steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(playbook)))
+}
+
+pub async fn update_playbook(
+    Extension(playbook): Extension<Playbook>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdatePlaybook>,
+) -> Result<ResponseJson<ApiResponse<Playbook>>, ApiError> {
+    let description = match payload.description {
+        Some(description) if description.trim().is_empty() => None,
+        Some(description) => Some(description),
+        None => playbook.description,
+    };
+    let data = CreatePlaybook {
+        name: payload.name.unwrap_or(playbook.name),
+        description,
+        steps: payload.steps.unwrap_or(playbook.steps.0),
+    };
+    validate_playbook(&data)?;
+
+    let updated = Playbook::update(&deployment.db().pool, playbook.id, &data).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_playbook(
+    Extension(playbook): Extension<Playbook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Playbook::delete(&deployment.db().pool, playbook.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Playbook that runs in new attempts of a task
+pub async fn get_task_playbook(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<Playbook>>>, ApiError> {
+    let playbook = Playbook::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(playbook)))
+}
+
+/// Attach a playbook to a task or detach it. Attempts that already started
+/// keep running the playbook they started with.
+pub async fn set_task_playbook(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskPlaybook>,
+) -> Result<ResponseJson<ApiResponse<Option<Playbook>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(playbook_id) = payload.playbook_id else {
+        Playbook::detach_from_task(pool, task.id).await?;
+        return Ok(ResponseJson(ApiResponse::success(None)));
+    };
+
+    let playbook = Playbook::find_by_id(pool, playbook_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Playbook not found".to_string()))?;
+    Playbook::attach_to_task(pool, task.id, playbook.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "playbook_attached",
+            serde_json::json!({
+                "playbook_id": playbook.id.to_string(),
+                "task_id": task.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(Some(playbook))))
+}
+
+/// Playbook runs of one attempt with the status of each step, newest first
+pub async fn get_workspace_playbook_runs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PlaybookRunWithSteps>>>, ApiError> {
+    let runs =
+        PlaybookRun::find_with_steps_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let playbook_router = Router::new()
+        .route(
+            "/",
+            get(get_playbook)
+                .put(update_playbook)
+                .delete(delete_playbook),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_playbook_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_playbooks).post(create_playbook))
+        .nest("/{playbook_id}", playbook_router);
+
+    Router::new().nest("/playbooks", inner)
+}
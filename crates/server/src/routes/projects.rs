@@ -16,12 +16,14 @@ use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo, UpdateProjectRepo},
     repo::Repo,
+    workspace::Workspace,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search_cache::SearchQuery, project::ProjectServiceError,
+    file_search_cache::{SearchMode, SearchQuery},
+    project::ProjectServiceError,
     remote_client::CreateRemoteProjectPayload,
 };
 use ts_rs::TS;
@@ -402,6 +404,13 @@ pub async fn search_project_files(
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    // Symbol searches from a workspace session look at what its agent is editing
+    let repositories = match (&search_query.mode, search_query.workspace_id) {
+        (SearchMode::Symbols, Some(workspace_id)) => {
+            worktree_repositories(&deployment, project.id, workspace_id, repositories).await
+        }
+        _ => repositories,
+    };
 
     match deployment
         .project()
@@ -420,6 +429,42 @@ pub async fn search_project_files(
     }
 }
 
+/// The repositories with their path pointing at the workspace's worktree, for the
+/// ones it has a worktree of. Left as they are if the workspace isn't in the project.
+async fn worktree_repositories(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    workspace_id: Uuid,
+    repositories: Vec<Repo>,
+) -> Vec<Repo> {
+    let pool = &deployment.db().pool;
+    let Ok(Some(workspace)) = Workspace::find_by_id(pool, workspace_id).await else {
+        return repositories;
+    };
+    let in_project = matches!(
+        workspace.parent_task(pool).await,
+        Ok(Some(task)) if task.project_id == project_id
+    );
+    let Some(container_ref) = workspace.container_ref.filter(|_| in_project) else {
+        return repositories;
+    };
+
+    repositories
+        .into_iter()
+        .map(|repo| {
+            let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+            if worktree_path.is_dir() {
+                Repo {
+                    path: worktree_path,
+                    ..repo
+                }
+            } else {
+                repo
+            }
+        })
+        .collect()
+}
+
 pub async fn get_project_repositories(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -0,0 +1,54 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::log_search::{LogSearchEntry, LogSearchFilter, LogSearchHit, LogSearchKind};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub const DEFAULT_LOG_SEARCH_LIMIT: i64 = 20;
+pub const MAX_LOG_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct LogSearchQuery {
+    /// Words that must all appear in the entry
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub kind: Option<LogSearchKind>,
+    pub limit: Option<i64>,
+}
+
+/// Search messages, tool calls and errors in coding agent logs, newest
+/// execution first
+pub async fn search_logs(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LogSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LogSearchHit>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Search query cannot be empty".to_string(),
+        ));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LOG_SEARCH_LIMIT)
+        .clamp(1, MAX_LOG_SEARCH_LIMIT);
+    let filter = LogSearchFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        kind: query.kind,
+    };
+    let hits = LogSearchEntry::search(&deployment.db().pool, &query.q, &filter, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search/logs", get(search_logs))
+}
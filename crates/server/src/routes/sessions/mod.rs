@@ -8,7 +8,7 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     project_repo::ProjectRepo,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session},
@@ -25,6 +25,7 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use services::services::{
+    concurrency::ConcurrencyCheckResult,
     config::ConcurrencyLimit,
     container::{ContainerError, ContainerService},
 };
@@ -119,6 +120,7 @@ pub enum FollowUpResponse {
 async fn check_concurrency_limits(
     deployment: &DeploymentImpl,
     executor: &BaseCodingAgent,
+    project_id: Uuid,
 ) -> Result<(), ContainerError> {
     let config = deployment.config().read().await;
     let concurrency_config = &config.concurrency;
@@ -150,6 +152,23 @@ async fn check_concurrency_limits(
         }
     }
 
+    // Check spend budgets
+    if let ConcurrencyCheckResult::BudgetExceeded {
+        scope,
+        spent_usd,
+        limit_usd,
+    } = deployment
+        .concurrency_service()
+        .check_budget(concurrency_config, &config.pricing, Some(project_id))
+        .await?
+    {
+        return Err(ContainerError::BudgetExceeded {
+            scope,
+            spent_usd,
+            limit_usd,
+        });
+    }
+
     Ok(())
 }
 
@@ -188,14 +207,15 @@ pub async fn follow_up(
     let queue_enabled = config.concurrency.queue.enabled;
     drop(config);
 
-    let concurrency_result = check_concurrency_limits(&deployment, &executor_profile_id.executor).await;
-
     // Get parent task
     let task = workspace
         .parent_task(pool)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
+    let concurrency_result =
+        check_concurrency_limits(&deployment, &executor_profile_id.executor, task.project_id).await;
+
     // Get parent project
     let project = task
         .parent_project(pool)
@@ -244,9 +264,9 @@ pub async fn follow_up(
     let prompt_for_queue = payload.prompt;
 
     let project_repos = ProjectRepo::find_by_project_id_with_names(pool, project.id).await?;
-    let cleanup_action = deployment
+    let post_agent_action = deployment
         .container()
-        .cleanup_actions_for_repos(&project_repos);
+        .post_agent_actions_for_repos(&project_repos);
 
     let working_dir = workspace
         .agent_working_dir
@@ -271,7 +291,7 @@ pub async fn follow_up(
         )
     };
 
-    let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+    let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
     // If concurrency check passed, start execution immediately
     // If it failed and queue is enabled, add to queue
@@ -304,6 +324,7 @@ pub async fn follow_up(
         }
         Err(ContainerError::GlobalConcurrencyLimitReached { .. })
         | Err(ContainerError::AgentConcurrencyLimitReached { .. })
+        | Err(ContainerError::BudgetExceeded { .. })
             if queue_enabled =>
         {
             // No capacity but queue is enabled - add to queue
@@ -357,10 +378,232 @@ pub async fn follow_up(
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct ForkSessionRequest {
+    /// Coding agent turn of this session to fork from
+    pub execution_process_id: Uuid,
+    pub prompt: String,
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ForkSessionResponse {
+    pub workspace: Workspace,
+    pub session: Session,
+    pub execution_process: ExecutionProcess,
+}
+
+/// Continue the conversation from one of this session's coding agent turns in a
+/// new workspace, leaving this one as it is
+pub async fn fork_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ForkSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<ForkSessionResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let process = ExecutionProcess::find_by_id(pool, payload.execution_process_id)
+        .await?
+        .filter(|process| process.session_id == session.id)
+        .ok_or(ApiError::BadRequest(
+            "Process does not belong to this session".to_string(),
+        ))?;
+    if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+        return Err(ApiError::BadRequest(
+            "Only coding agent turns can be forked".to_string(),
+        ));
+    }
+    if process.dropped {
+        return Err(ApiError::BadRequest(
+            "Cannot fork a turn that was replaced by a retry".to_string(),
+        ));
+    }
+    if process.status == ExecutionProcessStatus::Running {
+        return Err(ApiError::Conflict(
+            "Wait for the turn to finish before forking it".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let executor = process
+        .executor_action()
+        .ok()
+        .and_then(|action| action.executor_profile_id())
+        .ok_or(ApiError::BadRequest(
+            "Only coding agent turns can be forked".to_string(),
+        ))?
+        .executor;
+    check_concurrency_limits(&deployment, &executor, task.project_id).await?;
+
+    let (workspace, session, execution_process) = deployment
+        .container()
+        .fork_session(&process, payload.prompt, payload.variant)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_forked",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor": executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(ForkSessionResponse {
+        workspace,
+        session,
+        execution_process,
+    })))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct HandoffSessionRequest {
+    /// Coding agent that takes over the workspace
+    pub executor_profile_id: ExecutorProfileId,
+    pub prompt: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct HandoffSessionResponse {
+    pub session: Session,
+    pub execution_process: ExecutionProcess,
+}
+
+/// Continue this session's workspace with another coding agent in a new
+/// session, starting from a summary of what this one did
+pub async fn handoff_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<HandoffSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<HandoffSessionResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if payload.prompt.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Tell the next agent what to do".to_string(),
+        ));
+    }
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        pool,
+        session.workspace_id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes to finish before handing off".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let executor = payload.executor_profile_id.executor.clone();
+    check_concurrency_limits(&deployment, &executor, task.project_id).await?;
+
+    let (new_session, execution_process) = deployment
+        .container()
+        .handoff_session(&session, payload.executor_profile_id, payload.prompt)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_handed_off",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "from_executor": session.executor,
+                "to_executor": executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(HandoffSessionResponse {
+        session: new_session,
+        execution_process,
+    })))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CompactSessionResponse {
+    pub session: Session,
+    pub execution_process: ExecutionProcess,
+}
+
+/// Continue a session whose conversation outgrew the agent's context window
+/// in a new session of the same agent, seeded with a summary of it
+pub async fn compact_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CompactSessionResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        pool,
+        session.workspace_id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running processes to finish before compacting".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let executor_profile_id =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    check_concurrency_limits(&deployment, &executor_profile_id.executor, task.project_id).await?;
+
+    let (new_session, execution_process) = deployment
+        .container()
+        .compact_session(&session, executor_profile_id)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_compacted",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor": session.executor,
+                "automatic": false,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(CompactSessionResponse {
+        session: new_session,
+        execution_process,
+    })))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
+        .route("/fork", post(fork_session))
+        .route("/handoff", post(handoff_session))
+        .route("/compact", post(compact_session))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -1,5 +1,6 @@
 pub mod codex_setup;
 pub mod cursor_setup;
+pub mod export;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
@@ -26,11 +27,13 @@ use chrono::{DateTime, Utc};
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge_queue::{MergeQueueEntry, MergeQueueStatus},
     project::Project,
     project_repo::ProjectRepo,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
+    task_dependency::TaskDependency,
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
@@ -46,23 +49,28 @@ use executors::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    auto_rebase,
+    concurrency::ConcurrencyCheckResult,
     config::ConcurrencyLimit,
     container::{ContainerError, ContainerService},
     diff_stream::apply_stream_omit_policy,
+    forge::ForgeRepoInfo,
     git::{ConflictOp, DiffTarget, GitCliError, GitServiceError, format_commit_message},
     github::GitHubService,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::{
-    diff::{Diff, compute_line_change_counts},
-    response::ApiResponse,
-};
+use utils::{diff::Diff, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
+    routes::{
+        approvals, merge_queue, playbooks, task_attempts::gh_cli_setup::GhCliSetupError,
+        verification,
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -143,17 +151,6 @@ fn prefix_diff_paths(diff: &mut Diff, repo_name: &str) {
     }
 }
 
-fn diff_line_counts(diff: &Diff) -> (usize, usize) {
-    match (diff.additions, diff.deletions) {
-        (Some(add), Some(del)) => (add, del),
-        _ => {
-            let old = diff.old_content.as_deref().unwrap_or("");
-            let new = diff.new_content.as_deref().unwrap_or("");
-            compute_line_change_counts(old, new)
-        }
-    }
-}
-
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskAttemptQuery>,
@@ -191,9 +188,20 @@ pub struct RunAgentSetupRequest {
 pub struct RunAgentSetupResponse {}
 
 /// Check concurrency limits before starting a new task attempt
-async fn check_concurrency_limits(
+pub(crate) async fn check_concurrency_limits(
     deployment: &DeploymentImpl,
     executor: &executors::executors::BaseCodingAgent,
+    project_id: Uuid,
+) -> Result<(), ContainerError> {
+    check_group_concurrency_limits(deployment, std::slice::from_ref(executor), project_id).await
+}
+
+/// Check concurrency limits before starting one attempt per entry of
+/// `executors` at once
+pub(crate) async fn check_group_concurrency_limits(
+    deployment: &DeploymentImpl,
+    executors: &[executors::executors::BaseCodingAgent],
+    project_id: Uuid,
 ) -> Result<(), ContainerError> {
     let config = deployment.config().read().await;
     let concurrency_config = &config.concurrency;
@@ -203,7 +211,7 @@ async fn check_concurrency_limits(
 
     // Check global limit
     if let ConcurrencyLimit::Limited(limit) = concurrency_config.global_limit {
-        if stats.total_coding_agents >= limit {
+        if stats.total_coding_agents + executors.len() as u32 > limit {
             return Err(ContainerError::GlobalConcurrencyLimitReached {
                 current: stats.total_coding_agents,
                 limit,
@@ -211,23 +219,64 @@ async fn check_concurrency_limits(
         }
     }
 
-    // Check agent-specific limit
-    let effective_limit = concurrency_config.effective_limit_for_agent(executor);
-    if let ConcurrencyLimit::Limited(limit) = effective_limit {
-        let agent_name = executor.to_string();
-        let current = stats.by_executor.get(&agent_name).copied().unwrap_or(0);
-        if current >= *limit {
-            return Err(ContainerError::AgentConcurrencyLimitReached {
-                agent: agent_name,
-                current,
-                limit: *limit,
-            });
+    // Check agent-specific limits
+    for (i, executor) in executors.iter().enumerate() {
+        if executors[..i].contains(executor) {
+            continue;
         }
+        let effective_limit = concurrency_config.effective_limit_for_agent(executor);
+        if let ConcurrencyLimit::Limited(limit) = effective_limit {
+            let agent_name = executor.to_string();
+            let current = stats.by_executor.get(&agent_name).copied().unwrap_or(0);
+            let starting = executors.iter().filter(|e| *e == executor).count() as u32;
+            if current + starting > *limit {
+                return Err(ContainerError::AgentConcurrencyLimitReached {
+                    agent: agent_name,
+                    current,
+                    limit: *limit,
+                });
+            }
+        }
+    }
+
+    // Check spend budgets
+    if let ConcurrencyCheckResult::BudgetExceeded {
+        scope,
+        spent_usd,
+        limit_usd,
+    } = deployment
+        .concurrency_service()
+        .check_budget(concurrency_config, &config.pricing, Some(project_id))
+        .await?
+    {
+        return Err(ContainerError::BudgetExceeded {
+            scope,
+            spent_usd,
+            limit_usd,
+        });
     }
 
     Ok(())
 }
 
+/// Whether a new attempt for `task_id` has to wait in the task queue for its
+/// dependencies. Rejects the start when the queue is disabled, since nothing
+/// would ever pick the attempt up.
+pub(crate) async fn is_start_deferred_by_dependencies(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+) -> Result<bool, ApiError> {
+    if !TaskDependency::is_blocked(&deployment.db().pool, task_id).await? {
+        return Ok(false);
+    }
+    if !deployment.config().read().await.concurrency.queue.enabled {
+        return Err(ApiError::Conflict(
+            "Task is blocked by unfinished dependencies".to_string(),
+        ));
+    }
+    Ok(true)
+}
+
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
@@ -241,14 +290,19 @@ pub async fn create_task_attempt(
         ));
     }
 
-    // Check concurrency limits before creating the workspace
-    check_concurrency_limits(&deployment, &executor_profile_id.executor).await?;
-
     let pool = &deployment.db().pool;
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
+    // Blocked tasks wait in the queue, which applies concurrency limits itself
+    let deferred = is_start_deferred_by_dependencies(&deployment, payload.task_id).await?;
+    if !deferred {
+        // Check concurrency limits before creating the workspace
+        check_concurrency_limits(&deployment, &executor_profile_id.executor, task.project_id)
+            .await?;
+    }
+
     let project = task
         .parent_project(pool)
         .await?
@@ -287,7 +341,12 @@ pub async fn create_task_attempt(
         .collect();
 
     WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
-    if let Err(err) = deployment
+    if deferred {
+        deployment
+            .container()
+            .queue_workspace_start(&workspace, executor_profile_id.clone())
+            .await?;
+    } else if let Err(err) = deployment
         .container()
         .start_workspace(&workspace, executor_profile_id.clone())
         .await
@@ -401,7 +460,7 @@ pub async fn get_task_attempt_diff(
             prefix_diff_paths(diff, &repo.repo.name);
 
             if params.include_stats {
-                let (add, del) = diff_line_counts(diff);
+                let (add, del) = diff.line_counts();
                 stats.additions += add;
                 stats.deletions += del;
             }
@@ -601,6 +660,8 @@ pub async fn merge_task_attempt(
     )
     .await?;
     Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    // Dependents of this task may now be claimable from the queue
+    deployment.task_queue_service().notify_capacity_available();
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -637,19 +698,35 @@ pub async fn merge_task_attempt(
         let merged_workspace_id = workspace.id;
 
         tokio::spawn(async move {
-            if let Err(err) = auto_rebase_ready_attempts_for_project(
-                deployment_clone,
+            match auto_rebase::rebase_ready_attempts(
+                deployment_clone.db(),
+                deployment_clone.container(),
                 project_id,
-                repo_clone,
+                &repo_clone,
                 merged_workspace_id,
             )
             .await
             {
-                tracing::warn!(
-                    ?err,
-                    workspace_id = ?merged_workspace_id,
-                    "Auto rebase of ready attempts failed"
-                );
+                Ok(rebased) => {
+                    for workspace_id in rebased {
+                        deployment_clone
+                            .track_if_analytics_allowed(
+                                "task_attempt_rebased",
+                                serde_json::json!({
+                                    "workspace_id": workspace_id.to_string(),
+                                    "repo_id": repo_clone.id.to_string(),
+                                }),
+                            )
+                            .await;
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        ?err,
+                        workspace_id = ?merged_workspace_id,
+                        "Auto rebase of ready attempts failed"
+                    );
+                }
             }
         });
     }
@@ -683,180 +760,18 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
-async fn auto_rebase_ready_attempts_for_project(
-    deployment: DeploymentImpl,
-    project_id: Uuid,
-    repo: Repo,
-    merged_workspace_id: Uuid,
-) -> anyhow::Result<()> {
-    let pool = &deployment.db().pool;
-
-    let candidates = sqlx::query!(
-        r#"SELECT
-            w.id as "id!: Uuid",
-            w.task_id as "task_id!: Uuid",
-            w.container_ref,
-            w.branch,
-            w.agent_working_dir,
-            w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
-            w.created_at as "created_at!: DateTime<Utc>",
-            w.updated_at as "updated_at!: DateTime<Utc>",
-            wr.id as "workspace_repo_id!: Uuid",
-            wr.target_branch,
-            wr.created_at as "workspace_repo_created_at!: DateTime<Utc>",
-            wr.updated_at as "workspace_repo_updated_at!: DateTime<Utc>",
-            t.status as "task_status!: TaskStatus"
-        FROM workspaces w
-        JOIN tasks t ON w.task_id = t.id
-        JOIN workspace_repos wr ON wr.workspace_id = w.id
-        WHERE t.project_id = $1 AND wr.repo_id = $2 AND w.id != $3
-        ORDER BY w.created_at DESC"#,
-        project_id,
-        repo.id,
-        merged_workspace_id,
-    )
-    .fetch_all(pool)
-    .await?;
-
-    for row in candidates {
-        if matches!(row.task_status, TaskStatus::Done | TaskStatus::Cancelled) {
-            tracing::debug!(
-                workspace_id = ?row.id,
-                "Skipping auto rebase for completed/cancelled task"
-            );
-            continue;
-        }
-
-        if row.setup_completed_at.is_none() {
-            tracing::debug!(
-                workspace_id = ?row.id,
-                "Skipping auto rebase because setup is not completed"
-            );
-            continue;
-        }
-
-        let has_running = ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
-            pool,
-            row.id,
-        )
-        .await?;
-        if has_running {
-            tracing::info!(
-                workspace_id = ?row.id,
-                "Skipping auto rebase because an execution is running"
-            );
-            continue;
-        }
-
-        if !ExecutionProcess::find_running_dev_servers_by_workspace(pool, row.id)
-            .await?
-            .is_empty()
-        {
-            tracing::info!(
-                workspace_id = ?row.id,
-                "Skipping auto rebase because a dev server is running"
-            );
-            continue;
-        }
-
-        let workspace = Workspace {
-            id: row.id,
-            task_id: row.task_id,
-            container_ref: row.container_ref,
-            branch: row.branch,
-            agent_working_dir: row.agent_working_dir,
-            setup_completed_at: row.setup_completed_at,
-            created_at: row.created_at,
-            updated_at: row.updated_at,
-        };
-
-        let workspace_repo = WorkspaceRepo {
-            id: row.workspace_repo_id,
-            workspace_id: row.id,
-            repo_id: repo.id,
-            target_branch: row.target_branch,
-            created_at: row.workspace_repo_created_at,
-            updated_at: row.workspace_repo_updated_at,
-        };
-
-        let container_ref = match deployment.container().ensure_container_exists(&workspace).await {
-            Ok(path) => path,
-            Err(err) => {
-                tracing::warn!(
-                    workspace_id = ?workspace.id,
-                    ?err,
-                    "Skipping auto rebase; failed to ensure container"
-                );
-                continue;
-            }
-        };
-
-        let worktree_path = Path::new(&container_ref).join(&repo.name);
-
-        if deployment
-            .git()
-            .is_rebase_in_progress(&worktree_path)
-            .unwrap_or(false)
-        {
-            tracing::info!(
-                workspace_id = ?workspace.id,
-                "Skipping auto rebase; rebase already in progress"
-            );
-            continue;
-        }
-
-        if let Ok((uncommitted_count, _)) =
-            deployment.git().get_worktree_change_counts(&worktree_path)
-        {
-            if uncommitted_count > 0 {
-                tracing::info!(
-                    workspace_id = ?workspace.id,
-                    "Skipping auto rebase; uncommitted changes present"
-                );
-                continue;
-            }
-        }
-
-        match perform_rebase_for_attempt(
-            &deployment,
-            &workspace,
-            &repo,
-            &workspace_repo,
-            &RebaseTaskAttemptRequest {
-                repo_id: repo.id,
-                old_base_branch: None,
-                new_base_branch: None,
-            },
-        )
-        .await
-        {
-            Ok(resp) if resp.is_success() => {
-                tracing::info!(
-                    workspace_id = ?workspace.id,
-                    branch = %workspace.branch,
-                    target_branch = %workspace_repo.target_branch,
-                    "Auto-rebased attempt after merge"
-                );
-            }
-            Ok(resp) => {
-                tracing::warn!(
-                    workspace_id = ?workspace.id,
-                    branch = %workspace.branch,
-                    message = resp.message().unwrap_or("Auto rebase failed"),
-                    "Auto rebase skipped"
-                );
-            }
-            Err(err) => {
-                tracing::warn!(
-                    workspace_id = ?workspace.id,
-                    branch = %workspace.branch,
-                    ?err,
-                    "Auto rebase encountered an error"
-                );
-            }
-        }
+/// GitHub pushes rely on the gh CLI being logged in; GitLab remotes push with
+/// the user's own git credentials.
+async fn check_push_auth(deployment: &DeploymentImpl, repo_path: &Path) -> Result<(), ApiError> {
+    let gitlab_hosts = deployment.config().read().await.gitlab.hosts.clone();
+    if let Ok(ForgeRepoInfo::GitLab(_)) = deployment
+        .git()
+        .get_forge_repo_info(repo_path, &gitlab_hosts)
+    {
+        return Ok(());
     }
 
+    GitHubService::new()?.check_token().await?;
     Ok(())
 }
 
@@ -867,9 +782,6 @@ pub async fn push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -879,6 +791,8 @@ pub async fn push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    check_push_auth(&deployment, &repo.path).await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -905,9 +819,6 @@ pub async fn force_push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -917,6 +828,8 @@ pub async fn force_push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    check_push_auth(&deployment, &repo.path).await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -1034,6 +947,11 @@ pub struct BranchStatus {
     pub conflict_op: Option<ConflictOp>,
     /// List of files currently in conflicted (unmerged) state
     pub conflicted_files: Vec<String>,
+    /// Latest merge queue entry of the attempt for this repo
+    pub merge_queue_entry: Option<MergeQueueEntry>,
+    /// Entries of the same repo that are merged before the queued one
+    #[ts(type = "number | null")]
+    pub merge_queue_ahead: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -1071,6 +989,14 @@ pub async fn get_task_attempt_branch_status(
         };
 
         let repo_merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?;
+        let merge_queue_entry =
+            MergeQueueEntry::find_latest_by_workspace_and_repo(pool, workspace.id, repo.id).await?;
+        let merge_queue_ahead = match &merge_queue_entry {
+            Some(entry) if entry.status == MergeQueueStatus::Queued => {
+                Some(MergeQueueEntry::count_ahead(pool, entry).await?)
+            }
+            _ => None,
+        };
 
         let worktree_path = workspace_dir.join(&repo.name);
 
@@ -1168,6 +1094,8 @@ pub async fn get_task_attempt_branch_status(
                 is_rebase_in_progress,
                 conflict_op,
                 conflicted_files,
+                merge_queue_entry,
+                merge_queue_ahead,
             },
         });
     }
@@ -1936,7 +1864,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
+        .route("/export", get(export::export_task_attempt))
         .route("/merge", post(merge_task_attempt))
+        .route("/merge-queue", post(merge_queue::enqueue_merge))
+        .route(
+            "/merge-queue/cancel",
+            post(merge_queue::cancel_queued_merge),
+        )
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
@@ -1950,6 +1884,15 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
         .route("/repos", get(get_task_attempt_repos))
+        .route("/approvals", get(approvals::get_workspace_approvals))
+        .route(
+            "/verification-runs",
+            get(verification::get_workspace_verification_runs),
+        )
+        .route(
+            "/playbook-runs",
+            get(playbooks::get_workspace_playbook_runs),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -0,0 +1,202 @@
+//! Exporting an attempt as an archive another instance can import and browse.
+
+use std::{path::Path, time::Duration};
+
+use axum::{
+    Extension,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_repo_state::ExecutionProcessRepoState,
+    image::Image,
+    session::Session,
+    task::Task,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+};
+use deployment::Deployment;
+use futures_util::StreamExt;
+use services::services::{
+    attempt_archive::{ArchivedExecutionProcess, AttemptArchive, conversation_entries},
+    container::ContainerService,
+    git::DiffTarget,
+    log_retention,
+};
+use utils::{diff::Diff, log_msg::LogMsg};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Normalizing a finished process's logs from the database normally takes
+/// well under a second
+const NORMALIZE_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub async fn export_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+
+    // Logs of a running agent are still changing
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the attempt to finish, or stop it, before exporting".to_string(),
+        ));
+    }
+
+    let task = Task::find_by_id(pool, workspace.task_id)
+        .await?
+        .ok_or(WorkspaceError::TaskNotFound)?;
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let sessions = Session::find_by_workspace_id(pool, workspace.id).await?;
+
+    let mut processes = Vec::new();
+    for session in &sessions {
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, true).await? {
+            let repo_states =
+                ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?;
+            let agent_turn =
+                CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?;
+            processes.push(ArchivedExecutionProcess {
+                process,
+                repo_states,
+                agent_turn,
+            });
+        }
+    }
+    processes.sort_by_key(|archived| archived.process.created_at);
+
+    let diffs = match attempt_diffs(&deployment, &workspace, &repos).await {
+        Ok(diffs) => diffs,
+        Err(e) => {
+            tracing::warn!(
+                "Exporting workspace {} without its diff: {}",
+                workspace.id,
+                e
+            );
+            Vec::new()
+        }
+    };
+
+    let mut archive = AttemptArchive::new(task.clone(), workspace.clone(), repos, sessions);
+    for archived in &processes {
+        let id = archived.process.id;
+        if let Some(raw_logs) = log_retention::load_raw_logs(pool, id).await? {
+            archive.raw_logs.insert(id, raw_logs);
+        }
+
+        if archived.process.run_reason == ExecutionProcessRunReason::CodingAgent
+            && let Some(entries) = normalized_entries(&deployment, id).await
+        {
+            archive.normalized_logs.insert(id, entries);
+        }
+    }
+    archive.manifest.processes = processes;
+    archive.manifest.diffs = diffs;
+
+    let image_service = deployment.image();
+    for image in Image::find_by_task_id(pool, task.id).await? {
+        match tokio::fs::read(image_service.get_absolute_path(&image)).await {
+            Ok(data) => {
+                archive.images.insert(image.file_path.clone(), data);
+                archive.manifest.images.push(image);
+            }
+            Err(e) => tracing::warn!("Skipping missing image {} in export: {}", image.id, e),
+        }
+    }
+
+    let bytes = archive.to_tar_gz()?;
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_exported",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "processes": archive.manifest.processes.len(),
+                "size_bytes": bytes.len(),
+            }),
+        )
+        .await;
+
+    let file_name = format!("{}.tar.gz", workspace.branch.replace(['/', '\\', '"'], "-"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Conversation entries of a coding agent process, or None when its logs
+/// can't be normalized
+async fn normalized_entries(
+    deployment: &DeploymentImpl,
+    execution_process_id: Uuid,
+) -> Option<Vec<serde_json::Value>> {
+    let stream = deployment
+        .container()
+        .stream_normalized_logs(&execution_process_id)
+        .await?;
+    let patches = stream.filter_map(|msg| async move {
+        match msg {
+            Ok(LogMsg::JsonPatch(patch)) => Some(patch),
+            _ => None,
+        }
+    });
+    match tokio::time::timeout(NORMALIZE_TIMEOUT, patches.collect::<Vec<_>>()).await {
+        Ok(patches) => Some(conversation_entries(&patches)),
+        Err(_) => {
+            tracing::warn!(
+                "Timed out normalizing logs of execution {} for export",
+                execution_process_id
+            );
+            None
+        }
+    }
+}
+
+/// The attempt's changes against each repo's target branch, as shown in the
+/// diff view
+async fn attempt_diffs(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repos: &[RepoWithTargetBranch],
+) -> Result<Vec<Diff>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let workspace_root = Path::new(&container_ref);
+
+    let mut all_diffs = Vec::new();
+    for repo in repos {
+        let base_commit = deployment.git().get_base_commit(
+            &repo.repo.path,
+            &workspace.branch,
+            &repo.target_branch,
+        )?;
+        let diffs = deployment.git().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &workspace_root.join(&repo.repo.name),
+                base_commit: &base_commit,
+            },
+            None,
+        )?;
+        all_diffs.extend(diffs.into_iter().map(|mut diff| {
+            super::prefix_diff_paths(&mut diff, &repo.repo.name);
+            diff
+        }));
+    }
+    Ok(all_diffs)
+}
@@ -7,7 +7,7 @@ use axum::{
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
-    merge::{Merge, MergeStatus},
+    merge::{ForgeKind, Merge, MergeStatus},
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
@@ -23,8 +23,10 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    forge::{ForgeError, ForgeRepoInfo, forge_for_repo},
     git::{GitCliError, GitServiceError},
-    github::{CreatePrRequest, GitHubService, GitHubServiceError, UnifiedPrComment},
+    github::{CreatePrRequest, GitHubServiceError, UnifiedPrComment},
+    gitlab::GitLabServiceError,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -51,9 +53,28 @@ pub enum CreatePrError {
     GithubCliNotLoggedIn,
     GitCliNotLoggedIn,
     GitCliNotInstalled,
+    GitlabNotAuthenticated,
     TargetBranchNotFound { branch: String },
 }
 
+impl CreatePrError {
+    fn from_forge_error(err: &ForgeError) -> Option<Self> {
+        match err {
+            ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            ForgeError::GitLab(GitLabServiceError::TokenMissing)
+            | ForgeError::GitLab(GitLabServiceError::AuthFailed(_)) => {
+                Some(Self::GitlabNotAuthenticated)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct AttachPrResponse {
     pub pr_attached: bool,
@@ -79,6 +100,25 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    GitlabNotAuthenticated,
+}
+
+impl GetPrCommentsError {
+    fn from_forge_error(err: &ForgeError) -> Option<Self> {
+        match err {
+            ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            ForgeError::GitLab(GitLabServiceError::TokenMissing)
+            | ForgeError::GitLab(GitLabServiceError::AuthFailed(_)) => {
+                Some(Self::GitlabNotAuthenticated)
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -99,18 +139,35 @@ Analyze the changes in this branch and write:
 
 Use `gh pr edit` to update the PR."#;
 
+pub const DEFAULT_GITLAB_MR_DESCRIPTION_PROMPT: &str = r#"Update the GitLab merge request that was just created with a better title and description.
+The MR number is !{pr_number} and the URL is {pr_url}.
+
+Analyze the changes in this branch and write:
+1. A concise, descriptive title that summarizes the changes, postfixed with "(Vibe Kanban)"
+2. A detailed description that explains:
+   - What changes were made
+   - Why they were made (based on the task context)
+   - Any important implementation details
+   - At the end, include a note: "This MR was written using [Vibe Kanban](https://vibekanban.com)"
+
+Use `glab mr update` to update the MR."#;
+
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
+    forge: ForgeKind,
     pr_number: i64,
     pr_url: &str,
 ) -> Result<(), ApiError> {
-    // Get the custom prompt from config, or use default
+    // Get the custom prompt from config, or use the forge's default
     let config = deployment.config().read().await;
     let prompt_template = config
         .pr_auto_description_prompt
         .as_deref()
-        .unwrap_or(DEFAULT_PR_DESCRIPTION_PROMPT);
+        .unwrap_or(match forge {
+            ForgeKind::GitHub => DEFAULT_PR_DESCRIPTION_PROMPT,
+            ForgeKind::GitLab => DEFAULT_GITLAB_MR_DESCRIPTION_PROMPT,
+        });
 
     // Replace placeholders in prompt
     let prompt = prompt_template
@@ -183,6 +240,40 @@ async fn trigger_pr_description_follow_up(
     Ok(())
 }
 
+/// A PR stacked on the parent workspace's PR targets the parent's branch, which may
+/// not have been pushed yet. Pushes it and returns whether `target_branch` was it.
+async fn push_stacked_parent_branch(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_name: &str,
+    target_branch: &str,
+) -> Result<bool, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(parent_workspace_id) = workspace
+        .parent_task(pool)
+        .await?
+        .and_then(|task| task.parent_workspace_id)
+    else {
+        return Ok(false);
+    };
+    let Some(parent) = Workspace::find_by_id(pool, parent_workspace_id).await? else {
+        return Ok(false);
+    };
+    if parent.branch != target_branch {
+        return Ok(false);
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&parent)
+        .await?;
+    let worktree_path = PathBuf::from(&container_ref).join(repo_name);
+    deployment
+        .git()
+        .push_to_github(&worktree_path, &parent.branch, false)?;
+    Ok(true)
+}
+
 pub async fn create_github_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -211,18 +302,22 @@ pub async fn create_github_pr(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = PathBuf::from(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
     {
         Ok(false) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::TargetBranchNotFound {
-                    branch: target_branch.clone(),
-                },
-            )));
+            if !push_stacked_parent_branch(&deployment, &workspace, &repo.name, &target_branch)
+                .await?
+            {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    CreatePrError::TargetBranchNotFound {
+                        branch: target_branch.clone(),
+                    },
+                )));
+            }
         }
         Err(GitServiceError::GitCLI(GitCliError::AuthFailed(_))) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -286,12 +381,18 @@ pub async fn create_github_pr(
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
+    // Pick GitHub or GitLab from the repo's remote URL
+    let gitlab_config = deployment.config().read().await.gitlab.clone();
+    let repo_info = deployment
+        .git()
+        .get_forge_repo_info(&repo_path, &gitlab_config.hosts)?;
+    let forge_kind = repo_info.kind();
 
-    // Use GitHubService to create the PR
-    let github_service = GitHubService::new()?;
-    match github_service.create_pr(&repo_info, &pr_request).await {
+    let result = match forge_for_repo(repo_info, &gitlab_config) {
+        Ok(forge) => forge.create_pr(&pr_request).await,
+        Err(e) => Err(e),
+    };
+    match result {
         Ok(pr_info) => {
             // Update the workspace with PR information
             if let Err(e) = Merge::create_pr(
@@ -301,6 +402,7 @@ pub async fn create_github_pr(
                 &norm_target_branch_name,
                 pr_info.number,
                 &pr_info.url,
+                forge_kind,
             )
             .await
             {
@@ -316,6 +418,7 @@ pub async fn create_github_pr(
                     "github_pr_created",
                     serde_json::json!({
                         "workspace_id": workspace.id.to_string(),
+                        "forge": forge_kind,
                     }),
                 )
                 .await;
@@ -325,6 +428,7 @@ pub async fn create_github_pr(
                 && let Err(e) = trigger_pr_description_follow_up(
                     &deployment,
                     &workspace,
+                    forge_kind,
                     pr_info.number,
                     &pr_info.url,
                 )
@@ -340,19 +444,10 @@ pub async fn create_github_pr(
             Ok(ResponseJson(ApiResponse::success(pr_info.url)))
         }
         Err(e) => {
-            tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
-                workspace.id,
-                e
-            );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            tracing::error!("Failed to create PR for attempt {}: {}", workspace.id, e);
+            match CreatePrError::from_forge_error(&e) {
+                Some(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+                None => Err(ApiError::Forge(e)),
             }
         }
     }
@@ -390,13 +485,14 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    let gitlab_config = deployment.config().read().await.gitlab.clone();
+    let repo_info = deployment
+        .git()
+        .get_forge_repo_info(&repo.path, &gitlab_config.hosts)?;
+    let forge = forge_for_repo(repo_info, &gitlab_config)?;
 
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &workspace.branch)
-        .await?;
+    let prs = forge.list_all_prs_for_branch(&workspace.branch).await?;
 
     // Take the first PR (prefer open, but also accept merged/closed)
     if let Some(pr_info) = prs.into_iter().next() {
@@ -408,6 +504,7 @@ pub async fn attach_existing_pr(
             &workspace_repo.target_branch,
             pr_info.number,
             &pr_info.url,
+            forge.kind(),
         )
         .await?;
 
@@ -425,6 +522,7 @@ pub async fn attach_existing_pr(
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
             Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            deployment.task_queue_service().notify_capacity_available();
 
             // Try broadcast update to other users in organization
             if let Ok(publisher) = deployment.share_publisher() {
@@ -467,12 +565,7 @@ pub async fn get_pr_comments(
     let pool = &deployment.db().pool;
 
     // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-
-    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
@@ -480,23 +573,24 @@ pub async fn get_pr_comments(
     let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
 
     // Ensure there's an attached PR for this repo
-    let pr_info = match merges.into_iter().next() {
-        Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
+    let pr_merge = match merges.into_iter().next() {
+        Some(Merge::Pr(pr_merge)) => pr_merge,
         _ => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
                 GetPrCommentsError::NoPrAttached,
             )));
         }
     };
-
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
-
-    // Fetch comments from GitHub
-    match github_service
-        .get_pr_comments(&repo_info, pr_info.number)
-        .await
-    {
+    let pr_info = pr_merge.pr_info;
+
+    // Fetch comments from the forge the PR was opened on
+    let gitlab_config = deployment.config().read().await.gitlab.clone();
+    let repo_info = ForgeRepoInfo::from_pr_url(pr_merge.forge, &pr_info.url)?;
+    let result = match forge_for_repo(repo_info, &gitlab_config) {
+        Ok(forge) => forge.get_pr_comments(pr_info.number).await,
+        Err(e) => Err(e),
+    };
+    match result {
         Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
             comments,
         }))),
@@ -507,14 +601,9 @@ pub async fn get_pr_comments(
                 pr_info.number,
                 e
             );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            match GetPrCommentsError::from_forge_error(&e) {
+                Some(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+                None => Err(ApiError::Forge(e)),
             }
         }
     }
@@ -0,0 +1,210 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    project::Project,
+    project_repo::ProjectRepo,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, UpdateTaskSchedule},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::task_scheduler::{self, ScheduleRunOutcome};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_schedule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// Reject templates that could never start a task in their project
+async fn validate_schedule(
+    deployment: &DeploymentImpl,
+    data: &CreateTaskSchedule,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+
+    if data.name.trim().is_empty() || data.task_title.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Schedule name and task title are required".to_string(),
+        ));
+    }
+    if data.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    if Project::find_by_id(pool, data.project_id).await?.is_none() {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    for repo in &data.repos {
+        if ProjectRepo::find_by_project_and_repo(pool, data.project_id, repo.repo_id)
+            .await?
+            .is_none()
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Repository {} is not part of this project",
+                repo.repo_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let schedules = match query.project_id {
+        Some(project_id) => TaskSchedule::find_by_project_id(pool, project_id).await?,
+        None => TaskSchedule::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    validate_schedule(&deployment, &payload).await?;
+    let next_run_at = task_scheduler::next_run_at(
+        payload.cron_expression.as_deref(),
+        payload.interval_seconds,
+        payload.enabled.unwrap_or(true),
+        Utc::now(),
+    )?;
+
+    let schedule =
+        TaskSchedule::create(&deployment.db().pool, &payload, Uuid::new_v4(), next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+                "uses_cron": schedule.cron_expression.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    // New timing replaces the old one entirely
+    let (cron_expression, interval_seconds) =
+        if payload.cron_expression.is_some() || payload.interval_seconds.is_some() {
+            (payload.cron_expression, payload.interval_seconds)
+        } else {
+            (schedule.cron_expression, schedule.interval_seconds)
+        };
+    let task_description = match payload.task_description {
+        Some(description) if description.trim().is_empty() => None,
+        Some(description) => Some(description),
+        None => schedule.task_description,
+    };
+
+    let data = CreateTaskSchedule {
+        project_id: schedule.project_id,
+        name: payload.name.unwrap_or(schedule.name),
+        task_title: payload.task_title.unwrap_or(schedule.task_title),
+        task_description,
+        executor_profile_id: payload
+            .executor_profile_id
+            .unwrap_or(schedule.executor_profile_id.0),
+        repos: payload.repos.unwrap_or(schedule.repos.0),
+        cron_expression,
+        interval_seconds,
+        enabled: Some(payload.enabled.unwrap_or(schedule.enabled)),
+    };
+    validate_schedule(&deployment, &data).await?;
+    let next_run_at = task_scheduler::next_run_at(
+        data.cron_expression.as_deref(),
+        data.interval_seconds,
+        data.enabled.unwrap_or(true),
+        Utc::now(),
+    )?;
+
+    let updated =
+        TaskSchedule::update(&deployment.db().pool, schedule.id, &data, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Run a schedule immediately, regardless of when it is next due
+pub async fn run_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ScheduleRunOutcome>>, ApiError> {
+    let outcome = task_scheduler::run_schedule(
+        deployment.container(),
+        deployment.concurrency_service(),
+        deployment.config(),
+        &schedule,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_run",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "manual": true,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(outcome)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_task_schedule)
+                .put(update_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .route("/run", post(run_task_schedule))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_schedules).post(create_task_schedule))
+        .nest("/{schedule_id}", schedule_router);
+
+    Router::new().nest("/task-schedules", inner)
+}
@@ -4,7 +4,7 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -14,10 +14,10 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
-    project::{Project, ProjectError},
     repo::Repo,
     task::{CreateTask, Task, TaskStatus, TaskStatusWithMerge, TaskWithAttemptStatus, UpdateTask},
-    workspace::{CreateWorkspace, Workspace},
+    task_dependency::{CreateTaskDependency, TaskDependencies, TaskDependency},
+    workspace::Workspace,
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
@@ -33,8 +33,14 @@ use utils::{api::oauth::LoginStatus, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::{
+        approvals, playbooks,
+        task_attempts::{WorkspaceRepoInput, is_start_deferred_by_dependencies},
+        verification,
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -196,6 +202,9 @@ pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Tasks that must be done or merged before this one starts
+    #[ts(optional)]
+    pub depends_on_task_ids: Option<Vec<Uuid>>,
 }
 
 pub async fn create_task_and_start(
@@ -210,8 +219,15 @@ pub async fn create_task_and_start(
 
     let pool = &deployment.db().pool;
 
+    // The task and its blocked-by edges are created together so a rejected
+    // dependency doesn't leave an unblocked task behind
     let task_id = Uuid::new_v4();
-    let task = Task::create(pool, &payload.task, task_id).await?;
+    let mut tx = pool.begin().await?;
+    let task = Task::create(&mut *tx, &payload.task, task_id).await?;
+    for depends_on_task_id in payload.depends_on_task_ids.iter().flatten() {
+        TaskDependency::create_tx(&mut tx, task.id, *depends_on_task_id).await?;
+    }
+    tx.commit().await?;
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
@@ -229,33 +245,6 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    let project = Project::find_by_id(pool, task.project_id)
-        .await?
-        .ok_or(ProjectError::ProjectNotFound)?;
-
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    let agent_working_dir = project
-        .default_agent_working_dir
-        .as_ref()
-        .filter(|dir: &&String| !dir.is_empty())
-        .cloned();
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name,
-            agent_working_dir,
-        },
-        attempt_id,
-        task.id,
-    )
-    .await?;
-
     let workspace_repos: Vec<CreateWorkspaceRepo> = payload
         .repos
         .iter()
@@ -264,14 +253,25 @@ pub async fn create_task_and_start(
             target_branch: r.target_branch.clone(),
         })
         .collect();
-    WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
-
-    let is_attempt_running = deployment
+    let workspace = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
-        .await
-        .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+        .create_workspace_for_task(&task, &workspace_repos)
+        .await?;
+
+    let is_attempt_running = if is_start_deferred_by_dependencies(&deployment, task.id).await? {
+        deployment
+            .container()
+            .queue_workspace_start(&workspace, payload.executor_profile_id.clone())
+            .await?;
+        false
+    } else {
+        deployment
+            .container()
+            .start_workspace(&workspace, payload.executor_profile_id.clone())
+            .await
+            .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
+            .is_ok()
+    };
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -294,6 +294,7 @@ pub async fn create_task_and_start(
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
         executor: payload.executor_profile_id.executor.to_string(),
+        ci_status: None,
     })))
 }
 
@@ -304,6 +305,7 @@ pub async fn update_task(
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     ensure_shared_task_auth(&existing_task, &deployment).await?;
+    let was_done = existing_task.status == TaskStatus::Done;
 
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
@@ -333,6 +335,11 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    // Dependents of this task may now be claimable from the queue
+    if task.status == TaskStatus::Done && !was_done {
+        deployment.task_queue_service().notify_capacity_available();
+    }
+
     // If task has been shared, broadcast update
     if task.shared_task_id.is_some() {
         let Ok(publisher) = deployment.share_publisher() else {
@@ -344,6 +351,46 @@ pub async fn update_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies =
+        TaskDependency::find_dependencies_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let dependency =
+        TaskDependency::create(&deployment.db().pool, task.id, payload.depends_on_task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": payload.depends_on_task_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn remove_task_dependency(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskDependency::delete(&deployment.db().pool, task_id, depends_on_task_id).await?;
+    // Removing the last blocker may let a queued attempt start
+    deployment.task_queue_service().notify_capacity_available();
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 async fn ensure_shared_task_auth(
     existing_task: &Task,
     deployment: &local_deployment::LocalDeployment,
@@ -519,6 +566,19 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route("/approvals", get(approvals::get_task_approvals))
+        .route(
+            "/verification-runs",
+            get(verification::get_task_verification_runs),
+        )
+        .route(
+            "/playbook",
+            get(playbooks::get_task_playbook).put(playbooks::set_task_playbook),
+        )
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -527,6 +587,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/by-status", get(get_tasks_by_status))
         .route("/create-and-start", post(create_task_and_start))
+        .route(
+            "/{task_id}/dependencies/{depends_on_task_id}",
+            delete(remove_task_dependency),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
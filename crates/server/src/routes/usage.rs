@@ -0,0 +1,80 @@
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::usage::{ExecutionProcessUsage, UsageSummary};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Only include executions recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+pub async fn get_execution_process_usage(
+    State(deployment): State<DeploymentImpl>,
+    Path(execution_process_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionProcessUsage>>>, ApiError> {
+    let pricing = deployment.config().read().await.pricing.clone();
+    let usage = deployment
+        .usage_service()
+        .execution_process_usage(execution_process_id, &pricing)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+pub async fn get_task_usage(
+    State(deployment): State<DeploymentImpl>,
+    Path(task_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let pricing = deployment.config().read().await.pricing.clone();
+    let summary = deployment
+        .usage_service()
+        .task_summary(task_id, &pricing)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_project_usage(
+    State(deployment): State<DeploymentImpl>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let pricing = deployment.config().read().await.pricing.clone();
+    let summary = deployment
+        .usage_service()
+        .project_summary(project_id, query.since, &pricing)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<UsageSummary>>, ApiError> {
+    let pricing = deployment.config().read().await.pricing.clone();
+    let summary = deployment
+        .usage_service()
+        .global_summary(query.since, &pricing)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/usage", get(get_usage))
+        .route(
+            "/execution-processes/{id}/usage",
+            get(get_execution_process_usage),
+        )
+        .route("/tasks/{task_id}/usage", get(get_task_usage))
+        .route("/projects/{id}/usage", get(get_project_usage))
+}
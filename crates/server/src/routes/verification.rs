@@ -0,0 +1,24 @@
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{task::Task, verification_run::VerificationRun, workspace::Workspace};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Verification script results across all attempts of a task, newest first
+pub async fn get_task_verification_runs(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationRun>>>, ApiError> {
+    let runs = VerificationRun::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Verification script results for one attempt, newest first
+pub async fn get_workspace_verification_runs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationRun>>>, ApiError> {
+    let runs = VerificationRun::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
@@ -0,0 +1,175 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookEventType},
+    webhook_delivery::{WebhookDelivery, WebhookDeliveryStatus},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::webhooks;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_webhook_middleware};
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub limit: Option<i64>,
+}
+
+fn validate_webhook(name: &str, url: &str, secret: &str) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Webhook name is required".to_string()));
+    }
+    if secret.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Webhook secret is required".to_string(),
+        ));
+    }
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(ApiError::BadRequest(format!(
+            "Webhook URL must be an http(s) URL: {url}"
+        ))),
+    }
+}
+
+pub async fn get_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn get_webhook(
+    Extension(webhook): Extension<Webhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    validate_webhook(&payload.name, &payload.url, &payload.secret)?;
+    let webhook = Webhook::create(&deployment.db().pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "webhook_created",
+            serde_json::json!({
+                "webhook_id": webhook.id.to_string(),
+                "event_count": webhook.events.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn update_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    validate_webhook(
+        payload.name.as_deref().unwrap_or(&webhook.name),
+        payload.url.as_deref().unwrap_or(&webhook.url),
+        payload.secret.as_deref().unwrap_or(&webhook.secret),
+    )?;
+    let updated = Webhook::update(&deployment.db().pool, webhook.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Webhook::delete(&deployment.db().pool, webhook.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Delivery log for a webhook, newest first
+pub async fn get_webhook_deliveries(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let deliveries =
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, webhook.id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// Queue a `ping` event so the endpoint and its signature check can be tested
+pub async fn test_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let delivery = webhooks::enqueue_for_webhook(
+        &deployment.db().pool,
+        &webhook,
+        WebhookEventType::Ping,
+        &serde_json::json!({ "webhook_id": webhook.id }),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+/// Send a delivery again on the dispatcher's next pass
+pub async fn retry_webhook_delivery(
+    State(deployment): State<DeploymentImpl>,
+    Path(delivery_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(delivery) = WebhookDelivery::find_by_id(pool, delivery_id).await? else {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    };
+    if delivery.status == WebhookDeliveryStatus::Delivered {
+        return Err(ApiError::Conflict("Delivery already succeeded".to_string()));
+    }
+
+    let delivery = WebhookDelivery::requeue(pool, delivery.id, Utc::now())
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let webhook_router = Router::new()
+        .route(
+            "/",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route("/deliveries", get(get_webhook_deliveries))
+        .route("/test", post(test_webhook))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_webhook_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_webhooks).post(create_webhook))
+        .nest("/{webhook_id}", webhook_router);
+
+    Router::new().nest("/webhooks", inner).route(
+        "/webhook-deliveries/{delivery_id}/retry",
+        post(retry_webhook_delivery),
+    )
+}
@@ -1,17 +1,27 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use db::models::{
-    execution_process::ExecutionProcess,
-    task::{Task, TaskStatus},
+use db::{
+    DBService,
+    models::{
+        approval::{Approval, ApprovalDecider, ApprovalDecision, ApprovalOutcome, CreateApproval},
+        execution_process::ExecutionProcess,
+        interrupted_execution::InterruptedExecution,
+        task::{Task, TaskStatus},
+    },
 };
 use executors::{
     approvals::ToolCallMetadata,
     logs::{
         NormalizedEntry, NormalizedEntryType, ToolStatus,
-        utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
+        utils::{
+            EntryIndexProvider,
+            patch::{ConversationPatch, extract_normalized_entry_from_patch},
+        },
     },
 };
 use futures::future::{BoxFuture, FutureExt, Shared};
@@ -44,6 +54,7 @@ pub struct ToolContext {
 
 #[derive(Clone)]
 pub struct Approvals {
+    db: DBService,
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
@@ -66,8 +77,9 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(db: DBService, msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
         Self {
+            db,
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
@@ -76,8 +88,12 @@ impl Approvals {
 
     pub async fn create_with_waiter(
         &self,
-        request: ApprovalRequest,
+        mut request: ApprovalRequest,
     ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
+        if let Err(e) = self.persist_request(&mut request).await {
+            tracing::error!("Failed to record approval request {}: {}", request.id, e);
+        }
+
         let (tx, rx) = oneshot::channel();
         let waiter: ApprovalWaiter = rx
             .map(|result| result.unwrap_or(ApprovalStatus::TimedOut))
@@ -143,6 +159,7 @@ impl Approvals {
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
+            persist_decision(pool, id, &req.status, ApprovalDecider::User, None).await;
 
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
@@ -199,6 +216,7 @@ impl Approvals {
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
+        let db = self.db.clone();
 
         let now = chrono::Utc::now();
         let to_wait = (timeout_at - now)
@@ -221,6 +239,7 @@ impl Approvals {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
+                persist_decision(&db.pool, &id, &status, ApprovalDecider::System, None).await;
 
                 let store = {
                     let map = msg_stores.read().await;
@@ -252,6 +271,186 @@ impl Approvals {
         });
     }
 
+    /// Record a new request, or take over one the same session left pending
+    /// for the same tool call before a restart, keeping its id and deadline
+    async fn persist_request(&self, request: &mut ApprovalRequest) -> Result<(), ApprovalError> {
+        let pool = &self.db.pool;
+        if let Some(carried) = Approval::find_carried_over(
+            pool,
+            request.execution_process_id,
+            &request.tool_name,
+            &request.tool_input,
+            Utc::now(),
+        )
+        .await?
+            && !self.pending.contains_key(&carried.id.to_string())
+        {
+            Approval::reassign(
+                pool,
+                carried.id,
+                request.execution_process_id,
+                &request.tool_call_id,
+            )
+            .await?;
+            tracing::info!(
+                "Restored approval {} for tool '{}' in resumed execution {}",
+                carried.id,
+                request.tool_name,
+                request.execution_process_id
+            );
+            request.id = carried.id.to_string();
+            request.created_at = carried.requested_at;
+            request.timeout_at = carried.timeout_at;
+            return Ok(());
+        }
+
+        let id = Uuid::parse_str(&request.id).map_err(anyhow::Error::from)?;
+        Approval::create(
+            pool,
+            &CreateApproval {
+                id,
+                execution_process_id: request.execution_process_id,
+                tool_name: request.tool_name.clone(),
+                tool_input: request.tool_input.clone(),
+                tool_call_id: request.tool_call_id.clone(),
+                requested_at: request.created_at,
+                timeout_at: request.timeout_at,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Record a request that was decided without waiting for a human, such
+    /// as by the approval policy
+    pub async fn record_resolved(
+        &self,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        policy_rule: Option<String>,
+    ) {
+        let mut request = request.clone();
+        if let Err(e) = self.persist_request(&mut request).await {
+            tracing::error!("Failed to record approval request {}: {}", request.id, e);
+            return;
+        }
+        persist_decision(
+            &self.db.pool,
+            &request.id,
+            status,
+            ApprovalDecider::Policy,
+            policy_rule,
+        )
+        .await;
+    }
+
+    /// Called at startup. Requests left pending by executions that will be
+    /// resumed stay pending until their deadline, so the resumed agent picks
+    /// them up when it asks again; the rest are closed as timed out.
+    pub async fn restore_pending(&self) -> Result<usize, ApprovalError> {
+        let pool = &self.db.pool;
+        let now = Utc::now();
+        let mut restored = 0;
+        for approval in Approval::find_pending(pool).await? {
+            let resumable = InterruptedExecution::find_by_execution_process_id(
+                pool,
+                approval.execution_process_id,
+            )
+            .await?
+            .is_some_and(|interrupted| !interrupted.resumed);
+
+            if resumable && approval.timeout_at > now {
+                self.spawn_carried_over_expiry(approval.id, approval.timeout_at);
+                restored += 1;
+            } else {
+                Approval::record_decision(
+                    pool,
+                    approval.id,
+                    &ApprovalDecision {
+                        status: ApprovalOutcome::TimedOut,
+                        decided_by: ApprovalDecider::System,
+                        policy_rule: None,
+                        reason: Some("Server restarted before a decision was made".to_string()),
+                    },
+                    now,
+                )
+                .await?;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Time out a carried-over request nobody asked for again
+    fn spawn_carried_over_expiry(&self, id: Uuid, timeout_at: DateTime<Utc>) {
+        let db = self.db.clone();
+        let to_wait = (timeout_at - Utc::now())
+            .to_std()
+            .unwrap_or_else(|_| StdDuration::from_secs(0));
+        tokio::spawn(async move {
+            tokio::time::sleep(to_wait).await;
+            persist_decision(
+                &db.pool,
+                &id.to_string(),
+                &ApprovalStatus::TimedOut,
+                ApprovalDecider::System,
+                None,
+            )
+            .await;
+        });
+    }
+
+    /// Tool use entry for a tool call that hasn't been decided yet, with its
+    /// index in the conversation
+    pub async fn find_tool_use(
+        &self,
+        execution_process_id: &Uuid,
+        tool_call_id: &str,
+    ) -> Option<(usize, NormalizedEntry)> {
+        let store = self.msg_store_by_id(execution_process_id).await?;
+        find_matching_tool_use(store, tool_call_id)
+    }
+
+    /// Record a decision made without a human, such as by the approval
+    /// policy, on the tool use entry
+    pub async fn show_decision(
+        &self,
+        execution_process_id: &Uuid,
+        entry_index: usize,
+        entry: &NormalizedEntry,
+        status: ToolStatus,
+    ) {
+        let Some(store) = self.msg_store_by_id(execution_process_id).await else {
+            tracing::warn!(
+                "No msg_store found for execution_process_id: {}",
+                execution_process_id
+            );
+            return;
+        };
+        if let Some(updated_entry) = entry.with_tool_status(status) {
+            store.push_patch(ConversationPatch::replace(entry_index, updated_entry));
+        }
+    }
+
+    /// Add a policy decision to the conversation as its own entry, for tool
+    /// calls that have no tool use entry to show it on
+    pub async fn log_decision(&self, execution_process_id: &Uuid, message: String) {
+        let Some(store) = self.msg_store_by_id(execution_process_id).await else {
+            tracing::warn!(
+                "No msg_store found for execution_process_id: {}",
+                execution_process_id
+            );
+            return;
+        };
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: message,
+            metadata: None,
+        };
+        let index = EntryIndexProvider::start_from(&store).next();
+        store.push_patch(ConversationPatch::add_normalized_entry(index, entry));
+    }
+
     async fn msg_store_by_id(&self, execution_process_id: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores.read().await;
         map.get(execution_process_id).cloned()
@@ -270,6 +469,35 @@ pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_i
     }
 }
 
+/// Persist the outcome of a pending request. Requests already decided, for
+/// example by a racing timeout, are left as they are.
+async fn persist_decision(
+    pool: &SqlitePool,
+    id: &str,
+    status: &ApprovalStatus,
+    decided_by: ApprovalDecider,
+    policy_rule: Option<String>,
+) {
+    let (status, reason) = match status {
+        ApprovalStatus::Pending => return,
+        ApprovalStatus::Approved => (ApprovalOutcome::Approved, None),
+        ApprovalStatus::Denied { reason } => (ApprovalOutcome::Denied, reason.clone()),
+        ApprovalStatus::TimedOut => (ApprovalOutcome::TimedOut, None),
+    };
+    let Ok(id) = Uuid::parse_str(id) else {
+        return;
+    };
+    let decision = ApprovalDecision {
+        status,
+        decided_by,
+        policy_rule,
+        reason,
+    };
+    if let Err(e) = Approval::record_decision(pool, id, &decision, Utc::now()).await {
+        tracing::error!("Failed to record decision for approval {}: {}", id, e);
+    }
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
@@ -1,19 +1,37 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
-use serde_json::Value;
+use db::{
+    self, DBService,
+    models::{execution_process::ExecutionProcess, webhook::WebhookEventType},
+};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    logs::{ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus},
+    profile::ExecutorProfileId,
+};
+use serde_json::{Value, json};
+use tokio::sync::RwLock;
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{
+        Approvals,
+        policy::{self, PolicyDecision},
+    },
+    config::{ApprovalRuleAction, Config},
+    notification::NotificationService,
+    webhooks,
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
+    config: Arc<RwLock<Config>>,
     execution_process_id: Uuid,
+    executor_profile_id: Option<ExecutorProfileId>,
 }
 
 impl ExecutorApprovalBridge {
@@ -21,15 +39,89 @@ impl ExecutorApprovalBridge {
         approvals: Approvals,
         db: DBService,
         notification_service: NotificationService,
+        config: Arc<RwLock<Config>>,
         execution_process_id: Uuid,
+        executor_profile_id: Option<ExecutorProfileId>,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
+            config,
             execution_process_id,
+            executor_profile_id,
         })
     }
+
+    async fn evaluate_policy(
+        &self,
+        tool_name: &str,
+        tool_input: &Value,
+        tool_use: Option<&NormalizedEntry>,
+    ) -> PolicyDecision {
+        let approval_policy = self.config.read().await.approval_policy.clone();
+        if approval_policy.rules.is_empty()
+            && approval_policy.default_action == ApprovalRuleAction::Escalate
+        {
+            return PolicyDecision {
+                action: ApprovalRuleAction::Escalate,
+                rule: None,
+                reason: None,
+            };
+        }
+
+        // Prefer the normalized action so rules can match on commands, paths
+        // and URLs; fall back to the raw input if the entry isn't logged yet
+        let action = match tool_use.map(|entry| &entry.entry_type) {
+            Some(NormalizedEntryType::ToolUse { action_type, .. }) => action_type.clone(),
+            _ => ActionType::Tool {
+                tool_name: tool_name.to_string(),
+                arguments: Some(tool_input.clone()),
+                result: None,
+            },
+        };
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
+            .await
+            .ok();
+        let project_id = ctx.as_ref().map(|ctx| ctx.project.id);
+        // Relative paths in tool calls are relative to the agent's working dir
+        let worktree = ctx.as_ref().and_then(|ctx| {
+            let workspace_dir = PathBuf::from(ctx.workspace.container_ref.as_ref()?);
+            Some(match ctx.workspace.agent_working_dir.as_deref() {
+                Some(dir) if !dir.is_empty() => workspace_dir.join(dir),
+                _ => workspace_dir,
+            })
+        });
+
+        policy::evaluate(
+            &approval_policy,
+            project_id,
+            self.executor_profile_id.as_ref(),
+            worktree.as_deref(),
+            tool_name,
+            &action,
+        )
+    }
+
+    async fn emit_approval_webhook(&self, request: &ApprovalRequest) {
+        let mut data = json!({
+            "approval_id": request.id,
+            "execution_process_id": request.execution_process_id,
+            "tool_name": request.tool_name,
+            "tool_input": request.tool_input,
+            "created_at": request.created_at,
+            "timeout_at": request.timeout_at,
+        });
+        if let Ok(ctx) =
+            ExecutionProcess::load_context(&self.db.pool, self.execution_process_id).await
+        {
+            data["workspace_id"] = json!(ctx.workspace.id);
+            data["task_id"] = json!(ctx.task.id);
+            data["task_title"] = json!(ctx.task.title);
+            data["project_id"] = json!(ctx.project.id);
+        }
+        webhooks::emit(&self.db, WebhookEventType::ApprovalRequested, data);
+    }
 }
 
 #[async_trait]
@@ -40,7 +132,13 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+        let tool_use = self
+            .approvals
+            .find_tool_use(&self.execution_process_id, tool_call_id)
+            .await;
+        let decision = self
+            .evaluate_policy(tool_name, &tool_input, tool_use.as_ref().map(|(_, e)| e))
+            .await;
 
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
@@ -51,12 +149,62 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
-        let (_, waiter) = self
+        let resolved = match decision.action {
+            ApprovalRuleAction::Escalate => None,
+            ApprovalRuleAction::Approve => Some((
+                ApprovalStatus::Approved,
+                ToolStatus::AutoApproved {
+                    rule: decision.rule.clone(),
+                    reason: decision.reason.clone(),
+                },
+            )),
+            ApprovalRuleAction::Deny => {
+                let reason = Some(decision.describe());
+                Some((
+                    ApprovalStatus::Denied {
+                        reason: reason.clone(),
+                    },
+                    ToolStatus::Denied { reason },
+                ))
+            }
+        };
+        tracing::info!(
+            "Tool '{}' in execution {}: {}",
+            tool_name,
+            self.execution_process_id,
+            decision.describe()
+        );
+        if tool_use.is_none() {
+            self.approvals
+                .log_decision(
+                    &self.execution_process_id,
+                    format!("Tool '{tool_name}': {}", decision.describe()),
+                )
+                .await;
+        }
+
+        if let Some((status, tool_status)) = resolved {
+            if let Some((idx, entry)) = &tool_use {
+                self.approvals
+                    .show_decision(&self.execution_process_id, *idx, entry, tool_status)
+                    .await;
+            }
+            self.approvals
+                .record_resolved(&request, &status, decision.rule.clone())
+                .await;
+            return Ok(status);
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
+        let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
+        self.emit_approval_webhook(&request).await;
+
         // Play notification sound when approval is needed
         self.notification_service
             .notify(
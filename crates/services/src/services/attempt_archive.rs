@@ -0,0 +1,401 @@
+//! Self-contained archives of a task attempt.
+//!
+//! An archive is a gzipped tarball with a JSON manifest describing the task,
+//! the workspace and every execution process with its action and repo states,
+//! next to each process's raw and normalized logs and the task's images. It is
+//! written when an attempt is exported and read back, read-only, by the
+//! instance that imports it.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn, execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState, image::Image, session::Session,
+    task::Task, workspace::Workspace, workspace_repo::RepoWithTargetBranch,
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use json_patch::Patch;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tar::{Archive, Builder, Header};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::{assets::asset_dir, diff::Diff, version::APP_VERSION};
+use uuid::Uuid;
+
+/// Bumped when the layout changes in a way older readers can't handle
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+
+/// Largest entries read back from an archive. Entries are decompressed into
+/// memory, so a crafted archive must not be able to make them arbitrarily big.
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_LOG_BYTES: u64 = 256 * 1024 * 1024;
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum AttemptArchiveError {
+    #[error("Failed to read archive: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid archive contents: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("archive format version {0} was written by a newer vibe-kanban; update to import it")]
+    UnsupportedVersion(u32),
+    #[error("not an attempt archive: {0} is missing")]
+    MissingEntry(&'static str),
+    #[error("{0} in the archive is larger than {1} bytes")]
+    EntryTooLarge(String, u64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptArchiveManifest {
+    pub format_version: u32,
+    /// Version of vibe-kanban that wrote the archive
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub task: Task,
+    pub workspace: Workspace,
+    pub repos: Vec<RepoWithTargetBranch>,
+    pub sessions: Vec<Session>,
+    /// Every process of the attempt, including dropped ones, oldest first
+    pub processes: Vec<ArchivedExecutionProcess>,
+    pub images: Vec<Image>,
+    /// Changes against each repo's target branch when the attempt was exported,
+    /// with paths prefixed by the repo name
+    pub diffs: Vec<Diff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ArchivedExecutionProcess {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub process: ExecutionProcess,
+    pub repo_states: Vec<ExecutionProcessRepoState>,
+    pub agent_turn: Option<CodingAgentTurn>,
+}
+
+/// Only the version, read before the rest of the manifest whose shape depends on it
+#[derive(Deserialize)]
+struct ManifestVersion {
+    format_version: u32,
+}
+
+/// Everything that goes into an archive
+#[derive(Debug)]
+pub struct AttemptArchive {
+    pub manifest: AttemptArchiveManifest,
+    /// JSONL `LogMsg`s as stored in `execution_process_logs`, per process
+    pub raw_logs: HashMap<Uuid, String>,
+    /// Conversation entries of coding agent processes, per process
+    pub normalized_logs: HashMap<Uuid, Vec<Value>>,
+    /// Image contents keyed by `Image::file_path`
+    pub images: HashMap<String, Vec<u8>>,
+}
+
+impl AttemptArchive {
+    pub fn new(
+        task: Task,
+        workspace: Workspace,
+        repos: Vec<RepoWithTargetBranch>,
+        sessions: Vec<Session>,
+    ) -> Self {
+        Self {
+            manifest: AttemptArchiveManifest {
+                format_version: ARCHIVE_FORMAT_VERSION,
+                app_version: APP_VERSION.to_string(),
+                exported_at: Utc::now(),
+                task,
+                workspace,
+                repos,
+                sessions,
+                processes: Vec::new(),
+                images: Vec::new(),
+                diffs: Vec::new(),
+            },
+            raw_logs: HashMap::new(),
+            normalized_logs: HashMap::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Write the archive as a tar.gz
+    pub fn to_tar_gz(&self) -> Result<Vec<u8>, AttemptArchiveError> {
+        let mut buffer = Vec::new();
+        {
+            let encoder = GzEncoder::new(&mut buffer, Compression::default());
+            let mut archive = Builder::new(encoder);
+
+            append(
+                &mut archive,
+                MANIFEST_PATH,
+                &serde_json::to_vec_pretty(&self.manifest)?,
+            )?;
+            for (id, logs) in &self.raw_logs {
+                append(&mut archive, &raw_logs_path(*id), logs.as_bytes())?;
+            }
+            for (id, entries) in &self.normalized_logs {
+                append(
+                    &mut archive,
+                    &normalized_logs_path(*id),
+                    &serde_json::to_vec(entries)?,
+                )?;
+            }
+            for (file_path, data) in &self.images {
+                append(&mut archive, &image_path(file_path), data)?;
+            }
+
+            archive.into_inner()?.finish()?;
+        }
+        Ok(buffer)
+    }
+}
+
+fn append<W: Write>(archive: &mut Builder<W>, path: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    archive.append_data(&mut header, path, data)
+}
+
+fn raw_logs_path(execution_process_id: Uuid) -> String {
+    format!("logs/{execution_process_id}.jsonl")
+}
+
+fn normalized_logs_path(execution_process_id: Uuid) -> String {
+    format!("normalized/{execution_process_id}.json")
+}
+
+fn image_path(file_path: &str) -> String {
+    format!("images/{file_path}")
+}
+
+/// Read the manifest of an archive, rejecting archives written in a newer format
+pub fn read_manifest(archive: &[u8]) -> Result<AttemptArchiveManifest, AttemptArchiveError> {
+    let data = read_entry(archive, MANIFEST_PATH, MAX_MANIFEST_BYTES)?
+        .ok_or(AttemptArchiveError::MissingEntry(MANIFEST_PATH))?;
+    let version: ManifestVersion = serde_json::from_slice(&data)?;
+    if version.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(AttemptArchiveError::UnsupportedVersion(
+            version.format_version,
+        ));
+    }
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub fn read_raw_logs(
+    archive: &[u8],
+    execution_process_id: Uuid,
+) -> Result<Option<String>, AttemptArchiveError> {
+    read_entry(archive, &raw_logs_path(execution_process_id), MAX_LOG_BYTES)?
+        .map(|data| String::from_utf8(data).map_err(std::io::Error::other))
+        .transpose()
+        .map_err(Into::into)
+}
+
+pub fn read_normalized_logs(
+    archive: &[u8],
+    execution_process_id: Uuid,
+) -> Result<Option<Vec<Value>>, AttemptArchiveError> {
+    let path = normalized_logs_path(execution_process_id);
+    read_entry(archive, &path, MAX_LOG_BYTES)?
+        .map(|data| serde_json::from_slice(&data))
+        .transpose()
+        .map_err(Into::into)
+}
+
+pub fn read_image(archive: &[u8], file_path: &str) -> Result<Option<Vec<u8>>, AttemptArchiveError> {
+    read_entry(archive, &image_path(file_path), MAX_IMAGE_BYTES)
+}
+
+/// Contents of the entry at `path`, failing if it is larger than `limit` bytes
+fn read_entry(
+    archive: &[u8],
+    path: &str,
+    limit: u64,
+) -> Result<Option<Vec<u8>>, AttemptArchiveError> {
+    let too_large = || AttemptArchiveError::EntryTooLarge(path.to_string(), limit);
+    let mut archive = Archive::new(GzDecoder::new(archive));
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.path()?.as_ref() == Path::new(path) {
+            if entry.header().size()? > limit {
+                return Err(too_large());
+            }
+            // The header is only a claim, so the read is capped as well
+            let mut data = Vec::new();
+            entry.take(limit + 1).read_to_end(&mut data)?;
+            if data.len() as u64 > limit {
+                return Err(too_large());
+            }
+            return Ok(Some(data));
+        }
+    }
+    Ok(None)
+}
+
+/// Where imported archives are kept
+pub fn imports_dir() -> PathBuf {
+    asset_dir().join("imported_attempts")
+}
+
+pub fn imported_archive_path(id: Uuid) -> PathBuf {
+    imports_dir().join(format!("{id}.tar.gz"))
+}
+
+/// Final conversation entries after applying a process's normalized log
+/// patches in order, as the log viewer would
+pub fn conversation_entries<'a>(patches: impl IntoIterator<Item = &'a Patch>) -> Vec<Value> {
+    let mut entries = BTreeMap::new();
+    for patch in patches {
+        let Ok(Value::Array(ops)) = serde_json::to_value(patch) else {
+            continue;
+        };
+        for op in ops {
+            let Some(index) = op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            match op.get("op").and_then(Value::as_str) {
+                Some("add" | "replace") => {
+                    if let Some(value) = op.get("value") {
+                        entries.insert(index, value.clone());
+                    }
+                }
+                Some("remove") => {
+                    entries.remove(&index);
+                }
+                _ => {}
+            }
+        }
+    }
+    entries.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task::TaskStatus;
+    use serde_json::json;
+
+    use super::*;
+
+    fn archive() -> AttemptArchive {
+        let now = Utc::now();
+        let task = Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Fix flaky test".to_string(),
+            description: None,
+            status: TaskStatus::InReview,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let workspace = Workspace {
+            id: Uuid::new_v4(),
+            task_id: task.id,
+            container_ref: None,
+            branch: "vk/1234-fix-flaky-test".to_string(),
+            agent_working_dir: None,
+            setup_completed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        AttemptArchive::new(task, workspace, Vec::new(), Vec::new())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut archive = archive();
+        let process_id = Uuid::new_v4();
+        archive
+            .raw_logs
+            .insert(process_id, "{\"Stdout\":\"hello\"}\n".to_string());
+        archive.normalized_logs.insert(
+            process_id,
+            vec![json!({"type": "STDOUT", "content": "hello"})],
+        );
+        archive
+            .images
+            .insert("abc.png".to_string(), vec![0x89, b'P', b'N', b'G']);
+
+        let bytes = archive.to_tar_gz().unwrap();
+        let manifest = read_manifest(&bytes).unwrap();
+        assert_eq!(manifest.format_version, ARCHIVE_FORMAT_VERSION);
+        assert_eq!(manifest.workspace.branch, "vk/1234-fix-flaky-test");
+        assert_eq!(
+            read_raw_logs(&bytes, process_id).unwrap().as_deref(),
+            Some("{\"Stdout\":\"hello\"}\n")
+        );
+        assert_eq!(
+            read_normalized_logs(&bytes, process_id)
+                .unwrap()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(read_image(&bytes, "abc.png").unwrap().unwrap().len(), 4);
+        assert!(read_raw_logs(&bytes, Uuid::new_v4()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_oversized_entry() {
+        let mut archive = archive();
+        archive
+            .images
+            .insert("abc.png".to_string(), vec![0x89, b'P', b'N', b'G']);
+        let bytes = archive.to_tar_gz().unwrap();
+        assert!(matches!(
+            read_entry(&bytes, &image_path("abc.png"), 3),
+            Err(AttemptArchiveError::EntryTooLarge(_, 3))
+        ));
+        assert_eq!(
+            read_entry(&bytes, &image_path("abc.png"), 4)
+                .unwrap()
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_format() {
+        let mut archive = archive();
+        archive.manifest.format_version = ARCHIVE_FORMAT_VERSION + 1;
+        let bytes = archive.to_tar_gz().unwrap();
+        assert!(matches!(
+            read_manifest(&bytes),
+            Err(AttemptArchiveError::UnsupportedVersion(v)) if v == ARCHIVE_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            read_manifest(b"not an archive"),
+            Err(AttemptArchiveError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_conversation_entries() {
+        let patches: Vec<Patch> = serde_json::from_value(json!([
+            [{"op": "add", "path": "/entries/0", "value": {"type": "STDOUT", "content": "a"}}],
+            [{"op": "add", "path": "/entries/1", "value": {"type": "STDOUT", "content": "b"}}],
+            [{"op": "replace", "path": "/entries/0", "value": {"type": "STDOUT", "content": "a2"}}],
+            [{"op": "remove", "path": "/entries/1"}]
+        ]))
+        .unwrap();
+        assert_eq!(
+            conversation_entries(&patches),
+            vec![json!({"type": "STDOUT", "content": "a2"})]
+        );
+    }
+}
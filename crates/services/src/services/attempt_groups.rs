@@ -0,0 +1,190 @@
+//! Comparison reports for best-of-N fan-out groups.
+//!
+//! When the last attempt in a group finishes, each attempt's diff stats, agent
+//! and script exit codes and token usage are collected into an
+//! [`AttemptGroupReport`] stored on the group.
+
+use std::path::Path;
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        attempt_group::{
+            AttemptComparison, AttemptGroup, AttemptGroupMember, AttemptGroupReport,
+            AttemptGroupStatus, ScriptOutcome,
+        },
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+        execution_process_token_usage::ExecutionProcessTokenUsage,
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    config::PricingConfig,
+    git::{DiffTarget, GitService},
+    usage::UsageSummary,
+};
+
+/// Build a report from the current state of every attempt in the group
+pub async fn build_report(
+    db: &DBService,
+    git: &GitService,
+    pricing: &PricingConfig,
+    group_id: Uuid,
+) -> Result<AttemptGroupReport, sqlx::Error> {
+    let members = AttemptGroup::members(&db.pool, group_id).await?;
+    let mut attempts = Vec::with_capacity(members.len());
+    for member in &members {
+        if let Some(comparison) = compare_attempt(db, git, pricing, member).await? {
+            attempts.push(comparison);
+        }
+    }
+    Ok(AttemptGroupReport {
+        generated_at: Utc::now(),
+        attempts,
+    })
+}
+
+/// Called when an attempt finishes. If it belongs to a running group and was
+/// the last one still working, the report is generated and the group marked
+/// completed. Returns the completed group.
+pub async fn complete_if_finished(
+    db: &DBService,
+    git: &GitService,
+    pricing: &PricingConfig,
+    workspace_id: Uuid,
+) -> Result<Option<AttemptGroup>, sqlx::Error> {
+    let Some(group) = AttemptGroup::find_by_workspace_id(&db.pool, workspace_id).await? else {
+        return Ok(None);
+    };
+    if group.status != AttemptGroupStatus::Running
+        || AttemptGroup::count_unfinished_members(&db.pool, group.id).await? > 0
+    {
+        return Ok(None);
+    }
+
+    let report = build_report(db, git, pricing, group.id).await?;
+    if !AttemptGroup::complete(&db.pool, group.id, &report, Utc::now()).await? {
+        return Ok(None);
+    }
+    tracing::info!(
+        "Attempt group {} for task {} completed with {} attempts",
+        group.id,
+        group.task_id,
+        report.attempts.len()
+    );
+    AttemptGroup::find_by_id(&db.pool, group.id).await
+}
+
+async fn compare_attempt(
+    db: &DBService,
+    git: &GitService,
+    pricing: &PricingConfig,
+    member: &AttemptGroupMember,
+) -> Result<Option<AttemptComparison>, sqlx::Error> {
+    let pool = &db.pool;
+    let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+        return Ok(None);
+    };
+
+    let agent = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?;
+
+    let mut scripts = Vec::new();
+    for run_reason in [
+        ExecutionProcessRunReason::SetupScript,
+        ExecutionProcessRunReason::CleanupScript,
+    ] {
+        if let Some(process) = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &run_reason,
+        )
+        .await?
+        {
+            scripts.push(ScriptOutcome {
+                run_reason,
+                status: process.status,
+                exit_code: process.exit_code,
+            });
+        }
+    }
+
+    let usage = UsageSummary::from_breakdown(
+        ExecutionProcessTokenUsage::find_breakdown_for_workspace(pool, workspace.id).await?,
+        pricing,
+    );
+    let cost_usd = usage
+        .unpriced_models
+        .is_empty()
+        .then_some(usage.total_cost_usd);
+
+    let (files_changed, additions, deletions, diff_error) =
+        match diff_stats(db, git, &workspace).await {
+            Ok((files, add, del)) => (files, add, del, None),
+            Err(e) => (0, 0, 0, Some(e)),
+        };
+
+    Ok(Some(AttemptComparison {
+        workspace_id: workspace.id,
+        executor_profile_id: member.executor_profile_id.0.clone(),
+        branch: workspace.branch,
+        agent_status: agent.as_ref().map(|process| process.status.clone()),
+        agent_exit_code: agent.and_then(|process| process.exit_code),
+        files_changed,
+        additions,
+        deletions,
+        diff_error,
+        scripts,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cost_usd,
+    }))
+}
+
+/// Files changed, additions and deletions across the workspace's repos,
+/// relative to where each branch forked from its target branch
+async fn diff_stats(
+    db: &DBService,
+    git: &GitService,
+    workspace: &Workspace,
+) -> Result<(usize, usize, usize), String> {
+    let Some(container_ref) = &workspace.container_ref else {
+        return Err("Workspace files have been cleaned up".to_string());
+    };
+    let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(&db.pool, workspace.id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (mut files_changed, mut additions, mut deletions) = (0, 0, 0);
+    for repo in repos {
+        let worktree_path = Path::new(container_ref).join(&repo.repo.name);
+        let base_commit = git
+            .get_base_commit(&repo.repo.path, &workspace.branch, &repo.target_branch)
+            .map_err(|e| format!("{}: {e}", repo.repo.name))?;
+        let diffs = git
+            .get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                },
+                None,
+            )
+            .map_err(|e| format!("{}: {e}", repo.repo.name))?;
+
+        files_changed += diffs.len();
+        for diff in &diffs {
+            let (add, del) = diff.line_counts();
+            additions += add;
+            deletions += del;
+        }
+    }
+    Ok((files_changed, additions, deletions))
+}
@@ -0,0 +1,169 @@
+//! Rebases the other ready attempts of a project after one of them merged, so
+//! they stay on top of the target branch. Used after manual merges and merges
+//! from the merge queue when `git_auto_rebase_ready_attempts` is on.
+
+use std::path::Path;
+
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess, merge_queue::MergeQueueEntry, repo::Repo,
+        task::TaskStatus, workspace_repo::WorkspaceRepo,
+    },
+};
+use uuid::Uuid;
+
+use super::{container::ContainerService, git::GitServiceError};
+
+/// Rebase the idle attempts of the project on `repo` onto their target branch.
+/// Attempts that are busy, dirty, mid-rebase or in the merge queue are left
+/// alone, and one that can't be rebased is logged and skipped. Returns the
+/// workspaces that were rebased.
+pub async fn rebase_ready_attempts<C>(
+    db: &DBService,
+    container: &C,
+    project_id: Uuid,
+    repo: &Repo,
+    merged_workspace_id: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &db.pool;
+    let git = container.git();
+    let mut rebased = Vec::new();
+
+    let candidates =
+        WorkspaceRepo::find_other_attempts_on_repo(pool, project_id, repo.id, merged_workspace_id)
+            .await?;
+    for candidate in candidates {
+        let workspace = candidate.workspace;
+        let workspace_repo = candidate.workspace_repo;
+        if matches!(
+            candidate.task_status,
+            TaskStatus::Done | TaskStatus::Cancelled
+        ) {
+            tracing::debug!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase for completed/cancelled task"
+            );
+            continue;
+        }
+
+        if workspace.setup_completed_at.is_none() {
+            tracing::debug!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase because setup is not completed"
+            );
+            continue;
+        }
+
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            tracing::info!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase because an execution is running"
+            );
+            continue;
+        }
+
+        if !ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id)
+            .await?
+            .is_empty()
+        {
+            tracing::info!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase because a dev server is running"
+            );
+            continue;
+        }
+
+        // The merge queue rebases its attempts right before their merge
+        if MergeQueueEntry::is_workspace_queued(pool, workspace.id).await? {
+            tracing::debug!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase because the attempt is in the merge queue"
+            );
+            continue;
+        }
+
+        let container_ref = match container.ensure_container_exists(&workspace).await {
+            Ok(path) => path,
+            Err(err) => {
+                tracing::warn!(
+                    workspace_id = ?workspace.id,
+                    ?err,
+                    "Skipping auto rebase; failed to ensure container"
+                );
+                continue;
+            }
+        };
+
+        let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+        if git.is_rebase_in_progress(&worktree_path).unwrap_or(false) {
+            tracing::info!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase; rebase already in progress"
+            );
+            continue;
+        }
+
+        if let Ok((uncommitted_count, _)) = git.get_worktree_change_counts(&worktree_path)
+            && uncommitted_count > 0
+        {
+            tracing::info!(
+                workspace_id = ?workspace.id,
+                "Skipping auto rebase; uncommitted changes present"
+            );
+            continue;
+        }
+
+        let target_branch = &workspace_repo.target_branch;
+        let result = match git.check_branch_exists(&repo.path, target_branch) {
+            Ok(true) => git.rebase_branch(
+                &repo.path,
+                &worktree_path,
+                target_branch,
+                target_branch,
+                &workspace.branch,
+            ),
+            Ok(false) => Err(GitServiceError::BranchNotFound(target_branch.clone())),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(_) => {
+                tracing::info!(
+                    workspace_id = ?workspace.id,
+                    branch = %workspace.branch,
+                    target_branch = %target_branch,
+                    "Auto-rebased attempt after merge"
+                );
+                rebased.push(workspace.id);
+            }
+            Err(
+                err @ (GitServiceError::MergeConflicts(_)
+                | GitServiceError::RebaseInProgress
+                | GitServiceError::BranchNotFound(_)),
+            ) => {
+                tracing::warn!(
+                    workspace_id = ?workspace.id,
+                    branch = %workspace.branch,
+                    message = %err,
+                    "Auto rebase skipped"
+                );
+            }
+            Err(err) => {
+                tracing::warn!(
+                    workspace_id = ?workspace.id,
+                    branch = %workspace.branch,
+                    ?err,
+                    "Auto rebase encountered an error"
+                );
+            }
+        }
+    }
+
+    Ok(rebased)
+}
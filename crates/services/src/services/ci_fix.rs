@@ -0,0 +1,101 @@
+//! Prompts for failing CI on a PR that is sent to a coding agent.
+//!
+//! Once every check on the PR's head commit has finished and at least one
+//! failed, the failed jobs are listed in one follow-up together with the end
+//! of their logs, which is where the error usually is.
+
+use db::models::merge::{CiCheck, CiStatus};
+
+/// Logs are fetched for this many failed jobs; the rest are only listed
+pub const MAX_JOB_LOGS: usize = 3;
+
+const MAX_LOG_LINES: usize = 80;
+
+/// Long lines such as minified output would otherwise blow up the prompt
+const MAX_LOG_LINE_CHARS: usize = 400;
+
+/// Last lines of a job log without ANSI colors
+pub fn log_excerpt(log: &str) -> String {
+    let lines: Vec<String> = log
+        .lines()
+        // Progress output redraws the line after a carriage return
+        .map(|line| line.rsplit('\r').next().unwrap_or_default())
+        .map(|line| strip_ansi_escapes::strip_str(line).trim_end().to_string())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.char_indices().nth(MAX_LOG_LINE_CHARS) {
+            Some((end, _)) => format!("{}…", &line[..end]),
+            None => line,
+        })
+        .collect();
+    lines[lines.len().saturating_sub(MAX_LOG_LINES)..].join("\n")
+}
+
+/// Follow-up prompt listing the failed checks, each with its log excerpt if there is one
+pub fn follow_up_prompt(pr_url: &str, failures: &[(CiCheck, Option<String>)]) -> String {
+    let mut prompt = format!(
+        "CI failed on the pull request {pr_url}. Find out why the checks below fail and fix the code. If a failure is unrelated to the changes, such as a flaky test or an infrastructure problem, explain that instead of changing the code. Your changes are pushed to the pull request once you are done.\n"
+    );
+    for (check, log) in failures
+        .iter()
+        .filter(|(check, _)| check.status == CiStatus::Failure)
+    {
+        prompt.push_str(&format!("\n## {}\n\n", check.name));
+        if let Some(url) = &check.url {
+            prompt.push_str(&format!("Details: {url}\n\n"));
+        }
+        match log.as_deref().filter(|log| !log.trim().is_empty()) {
+            Some(log) => prompt.push_str(&format!("```\n{log}\n```\n")),
+            None => prompt.push_str("No log is available for this check.\n"),
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, status: CiStatus) -> CiCheck {
+        CiCheck {
+            name: name.to_string(),
+            status,
+            url: Some(format!("https://ci.example.com/{name}")),
+            job_id: Some(1),
+        }
+    }
+
+    #[test]
+    fn excerpt_keeps_the_end_of_the_log_without_colors() {
+        let log: String = (1..=100)
+            .map(|i| format!("\x1b[31mline {i}\x1b[0m\n"))
+            .chain(["section_start:1:build\r\x1b[0Kerror: it broke\n".to_string()])
+            .collect();
+
+        let excerpt = log_excerpt(&log);
+
+        assert_eq!(excerpt.lines().count(), MAX_LOG_LINES);
+        assert!(excerpt.starts_with("line 22\n"));
+        assert!(excerpt.ends_with("line 100\nerror: it broke"));
+    }
+
+    #[test]
+    fn prompt_lists_only_failed_checks() {
+        let failures = vec![
+            (
+                check("test", CiStatus::Failure),
+                Some("assertion failed".to_string()),
+            ),
+            (check("lint", CiStatus::Success), None),
+            (check("deploy", CiStatus::Failure), None),
+        ];
+
+        let prompt = follow_up_prompt("https://example.com/pull/1", &failures);
+
+        assert!(prompt.contains(
+            "## test\n\nDetails: https://ci.example.com/test\n\n```\nassertion failed\n```"
+        ));
+        assert!(!prompt.contains("## lint"));
+        assert!(prompt.contains("## deploy"));
+        assert!(prompt.contains("No log is available for this check."));
+    }
+}
@@ -1,12 +1,20 @@
 //! Concurrency limiting service for task execution.
 //!
-//! This service enforces global and per-agent concurrency limits on coding agent executions.
+//! This service enforces global and per-agent concurrency limits on coding agent executions,
+//! as well as the per-project daily and global monthly spend budgets.
 
+use std::fmt;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use db::{DBService, models::execution_process::{ConcurrencyStats, ExecutionProcess}};
 use executors::executors::BaseCodingAgent;
 use thiserror::Error;
+use uuid::Uuid;
 
-use super::config::{ConcurrencyConfig, ConcurrencyLimit};
+use super::{
+    config::{ConcurrencyConfig, ConcurrencyLimit, PricingConfig},
+    usage::UsageService,
+};
 
 #[derive(Debug, Error)]
 pub enum ConcurrencyError {
@@ -20,10 +28,35 @@ pub enum ConcurrencyError {
         limit: u32,
     },
 
+    #[error("{scope} budget exceeded: ${spent_usd:.2} of ${limit_usd:.2} spent")]
+    BudgetExceeded {
+        scope: BudgetScope,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+
     #[error(transparent)]
     Database(#[from] sqlx::Error),
 }
 
+/// Which spend cap was hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    ProjectDaily { project_id: Uuid },
+    GlobalMonthly,
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetScope::ProjectDaily { project_id } => {
+                write!(f, "Daily budget for project {project_id}")
+            }
+            BudgetScope::GlobalMonthly => write!(f, "Monthly global"),
+        }
+    }
+}
+
 /// Result of checking concurrency limits
 #[derive(Debug)]
 pub enum ConcurrencyCheckResult {
@@ -37,6 +70,12 @@ pub enum ConcurrencyCheckResult {
         current: u32,
         limit: u32,
     },
+    /// A spend budget has been used up
+    BudgetExceeded {
+        scope: BudgetScope,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
 }
 
 impl ConcurrencyCheckResult {
@@ -59,6 +98,15 @@ impl ConcurrencyCheckResult {
                 current,
                 limit,
             }),
+            ConcurrencyCheckResult::BudgetExceeded {
+                scope,
+                spent_usd,
+                limit_usd,
+            } => Err(ConcurrencyError::BudgetExceeded {
+                scope,
+                spent_usd,
+                limit_usd,
+            }),
         }
     }
 }
@@ -67,11 +115,26 @@ impl ConcurrencyCheckResult {
 #[derive(Clone)]
 pub struct ConcurrencyService {
     db: DBService,
+    usage: UsageService,
+}
+
+/// Midnight UTC of the current day
+fn start_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+/// Midnight UTC on the first day of the current month
+fn start_of_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap()
+        .and_utc()
 }
 
 impl ConcurrencyService {
     pub fn new(db: DBService) -> Self {
-        Self { db }
+        let usage = UsageService::new(db.clone());
+        Self { db, usage }
     }
 
     /// Check if starting a new coding agent execution is allowed based on concurrency limits.
@@ -112,6 +175,50 @@ impl ConcurrencyService {
         Ok(ConcurrencyCheckResult::Allowed)
     }
 
+    /// Check the spend budgets in `config.budget`.
+    ///
+    /// The global monthly cap is always checked; the per-project daily cap only
+    /// when a `project_id` is given.
+    pub async fn check_budget(
+        &self,
+        config: &ConcurrencyConfig,
+        pricing: &PricingConfig,
+        project_id: Option<Uuid>,
+    ) -> Result<ConcurrencyCheckResult, sqlx::Error> {
+        let budget = &config.budget;
+        let now = Utc::now();
+
+        if let Some(limit_usd) = budget.global_monthly_limit_usd {
+            let summary = self
+                .usage
+                .global_summary(Some(start_of_month(now)), pricing)
+                .await?;
+            if summary.total_cost_usd >= limit_usd {
+                return Ok(ConcurrencyCheckResult::BudgetExceeded {
+                    scope: BudgetScope::GlobalMonthly,
+                    spent_usd: summary.total_cost_usd,
+                    limit_usd,
+                });
+            }
+        }
+
+        if let (Some(limit_usd), Some(project_id)) = (budget.project_daily_limit_usd, project_id) {
+            let summary = self
+                .usage
+                .project_summary(project_id, Some(start_of_day(now)), pricing)
+                .await?;
+            if summary.total_cost_usd >= limit_usd {
+                return Ok(ConcurrencyCheckResult::BudgetExceeded {
+                    scope: BudgetScope::ProjectDaily { project_id },
+                    spent_usd: summary.total_cost_usd,
+                    limit_usd,
+                });
+            }
+        }
+
+        Ok(ConcurrencyCheckResult::Allowed)
+    }
+
     /// Get current concurrency statistics
     pub async fn get_stats(&self) -> Result<ConcurrencyStats, sqlx::Error> {
         ExecutionProcess::get_concurrency_stats(&self.db.pool).await
@@ -175,4 +282,30 @@ mod tests {
             Err(ConcurrencyError::AgentLimitReached { .. })
         ));
     }
+
+    #[test]
+    fn test_budget_exceeded_is_not_allowed() {
+        let exceeded = ConcurrencyCheckResult::BudgetExceeded {
+            scope: BudgetScope::GlobalMonthly,
+            spent_usd: 100.5,
+            limit_usd: 100.0,
+        };
+        assert!(!exceeded.is_allowed());
+        assert!(matches!(
+            exceeded.into_result(),
+            Err(ConcurrencyError::BudgetExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_budget_periods_start_at_utc_midnight() {
+        let now = DateTime::parse_from_rfc3339("2025-03-17T15:42:10Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(start_of_day(now).to_rfc3339(), "2025-03-17T00:00:00+00:00");
+        assert_eq!(
+            start_of_month(now).to_rfc3339(),
+            "2025-03-01T00:00:00+00:00"
+        );
+    }
 }
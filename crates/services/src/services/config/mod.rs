@@ -29,6 +29,18 @@ pub type ShowcaseState = versions::v10::ShowcaseState;
 pub type ConcurrencyConfig = versions::v10::ConcurrencyConfig;
 pub type ConcurrencyLimit = versions::v10::ConcurrencyLimit;
 pub type QueueConfig = versions::v10::QueueConfig;
+pub type BudgetConfig = versions::v10::BudgetConfig;
+pub type PricingConfig = versions::v10::PricingConfig;
+pub type ModelPricing = versions::v10::ModelPricing;
+pub type GitLabConfig = versions::v10::GitLabConfig;
+pub type ApprovalPolicyConfig = versions::v10::ApprovalPolicyConfig;
+pub type ApprovalRule = versions::v10::ApprovalRule;
+pub type ApprovalMatcher = versions::v10::ApprovalMatcher;
+pub type ApprovalRuleAction = versions::v10::ApprovalRuleAction;
+pub type VerificationConfig = versions::v10::VerificationConfig;
+pub type ContainerConfig = versions::v10::ContainerConfig;
+pub type LogRetentionConfig = versions::v10::LogRetentionConfig;
+pub type ContextCompactionConfig = versions::v10::ContextCompactionConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{executors::BaseCodingAgent, oci::OciRuntime, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use uuid::Uuid;
 pub use v9::{
     EditorConfig, EditorType, GitHubConfig, NotificationConfig, ShowcaseState, SoundFile,
     ThemeMode, UiLanguage,
@@ -117,6 +118,23 @@ impl Default for QueueConfig {
     }
 }
 
+/// Spend caps in USD, priced with the `pricing` table. Days and months are UTC.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+pub struct BudgetConfig {
+    /// Maximum spend per project per day (null = unlimited)
+    #[serde(default)]
+    pub project_daily_limit_usd: Option<f64>,
+
+    /// Maximum spend across all projects per month (null = unlimited)
+    #[serde(default)]
+    pub global_monthly_limit_usd: Option<f64>,
+
+    /// Whether crossing a cap also interrupts running coding agents,
+    /// rather than only holding back new executions
+    #[serde(default)]
+    pub hard_limit: bool,
+}
+
 /// Concurrency settings for task execution
 #[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
 pub struct ConcurrencyConfig {
@@ -133,6 +151,10 @@ pub struct ConcurrencyConfig {
     /// Queue behavior configuration
     #[serde(default)]
     pub queue: QueueConfig,
+
+    /// Token spend caps
+    #[serde(default)]
+    pub budget: BudgetConfig,
 }
 
 impl ConcurrencyConfig {
@@ -143,6 +165,277 @@ impl ConcurrencyConfig {
     }
 }
 
+/// Price of one model in USD per million tokens
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self {
+            input_per_mtok: input,
+            output_per_mtok: output,
+            cache_write_per_mtok: cache_write,
+            cache_read_per_mtok: cache_read,
+        }
+    }
+
+    /// Cost in USD for the given token counts
+    pub fn cost_usd(
+        &self,
+        input_tokens: u64,
+        output_tokens: u64,
+        cache_creation_input_tokens: u64,
+        cache_read_input_tokens: u64,
+    ) -> f64 {
+        (input_tokens as f64 * self.input_per_mtok
+            + output_tokens as f64 * self.output_per_mtok
+            + cache_creation_input_tokens as f64 * self.cache_write_per_mtok
+            + cache_read_input_tokens as f64 * self.cache_read_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Per-model pricing used to turn recorded token usage into cost
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct PricingConfig {
+    /// Model name (or model name prefix) -> pricing. The longest matching prefix wins,
+    /// so "claude-sonnet-4" also prices "claude-sonnet-4-20250514".
+    #[serde(default = "default_model_pricing")]
+    #[ts(type = "Record<string, ModelPricing>")]
+    pub models: HashMap<String, ModelPricing>,
+}
+
+fn default_model_pricing() -> HashMap<String, ModelPricing> {
+    HashMap::from([
+        (
+            "claude-opus-4".to_string(),
+            ModelPricing::new(15.0, 75.0, 18.75, 1.5),
+        ),
+        (
+            "claude-opus-4-5".to_string(),
+            ModelPricing::new(5.0, 25.0, 6.25, 0.5),
+        ),
+        (
+            "claude-sonnet-4".to_string(),
+            ModelPricing::new(3.0, 15.0, 3.75, 0.3),
+        ),
+        (
+            "claude-haiku-4".to_string(),
+            ModelPricing::new(1.0, 5.0, 1.25, 0.1),
+        ),
+        (
+            "gpt-5".to_string(),
+            ModelPricing::new(1.25, 10.0, 0.0, 0.125),
+        ),
+    ])
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            models: default_model_pricing(),
+        }
+    }
+}
+
+impl PricingConfig {
+    /// Pricing for a model, matching exactly first and then by longest prefix
+    pub fn pricing_for_model(&self, model: &str) -> Option<&ModelPricing> {
+        self.models.get(model).or_else(|| {
+            self.models
+                .iter()
+                .filter(|(name, _)| model.starts_with(name.as_str()))
+                .max_by_key(|(name, _)| name.len())
+                .map(|(_, pricing)| pricing)
+        })
+    }
+}
+
+/// GitLab merge request settings
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+pub struct GitLabConfig {
+    /// Personal access token with the `api` scope. Falls back to the
+    /// GITLAB_TOKEN environment variable when unset.
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// Extra hostnames to treat as GitLab, for self-hosted instances.
+    /// gitlab.com and hosts starting with "gitlab." are always recognised.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+impl GitLabConfig {
+    pub fn resolved_token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .filter(|token| !token.is_empty())
+            .or_else(|| std::env::var("GITLAB_TOKEN").ok())
+    }
+}
+
+/// What an approval policy rule does with a matching tool call
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleAction {
+    Approve,
+    Deny,
+    /// Ask a human, as if no policy were configured
+    #[default]
+    Escalate,
+}
+
+/// Which tool calls an approval policy rule applies to
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalMatcher {
+    /// Shell commands matching a regular expression
+    CommandRun { pattern: String },
+    /// File edits whose path matches a glob (`*` stays within a directory,
+    /// `**` crosses directories)
+    FileEdit { glob: String },
+    /// File reads whose path matches a glob
+    FileRead { glob: String },
+    /// Web fetches to a domain or any of its subdomains
+    WebFetch { domain: String },
+    /// Tool calls by the name the agent reports, e.g. an MCP tool
+    Tool { name: String },
+    /// Every tool call
+    Any,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ApprovalRule {
+    /// Shown in the conversation log whenever the rule decides a tool call
+    pub name: String,
+    /// Only apply the rule to this project
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// Only apply the rule to this executor profile. A profile without a
+    /// variant matches every variant of its executor.
+    #[serde(default)]
+    pub executor_profile: Option<ExecutorProfileId>,
+    pub matcher: ApprovalMatcher,
+    pub action: ApprovalRuleAction,
+    /// Explanation recorded with the decision and, for denials, passed back
+    /// to the agent
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Rules checked before a tool call is sent to a human for approval. The
+/// first matching rule decides; calls no rule matches get `default_action`.
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+pub struct ApprovalPolicyConfig {
+    #[serde(default)]
+    pub rules: Vec<ApprovalRule>,
+    #[serde(default)]
+    pub default_action: ApprovalRuleAction,
+}
+
+/// What happens when a project's verification script fails
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct VerificationConfig {
+    /// Send the failure output back to the coding agent as a follow-up
+    #[serde(default)]
+    pub auto_fix: bool,
+
+    /// Follow-ups sent for one attempt before leaving the failure to the user
+    #[serde(default = "default_max_auto_fix_attempts")]
+    pub max_auto_fix_attempts: u32,
+}
+
+fn default_max_auto_fix_attempts() -> u32 {
+    2
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            auto_fix: false,
+            max_auto_fix_attempts: default_max_auto_fix_attempts(),
+        }
+    }
+}
+
+/// What happens when a coding agent runs out of context window
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ContextCompactionConfig {
+    /// Continue in a new session, seeded with a summary of the conversation
+    /// and the changes so far
+    #[serde(default)]
+    pub auto_compact: bool,
+
+    /// Compactions of one attempt before leaving the failure to the user
+    #[serde(default = "default_max_compactions")]
+    pub max_compactions: u32,
+}
+
+fn default_max_compactions() -> u32 {
+    3
+}
+
+impl Default for ContextCompactionConfig {
+    fn default() -> Self {
+        Self {
+            auto_compact: false,
+            max_compactions: default_max_compactions(),
+        }
+    }
+}
+
+/// How workspaces of projects with a container image are run
+#[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
+pub struct ContainerConfig {
+    /// Container engine used to run project images
+    #[serde(default)]
+    pub runtime: OciRuntime,
+
+    /// Host paths mounted into every container at the same path, e.g. agent
+    /// credentials like `~/.claude`. Paths that don't exist are skipped.
+    #[serde(default)]
+    pub mounts: Vec<String>,
+}
+
+/// When the logs of finished execution processes are compacted or dropped
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct LogRetentionConfig {
+    /// Compress the logs of processes that finished this many days ago
+    #[serde(default = "default_compact_logs_after_days")]
+    pub compact_after_days: Option<u32>,
+
+    /// Drop the raw logs of processes that finished this many days ago. The
+    /// conversation of coding agents is kept.
+    #[serde(default)]
+    pub prune_raw_after_days: Option<u32>,
+
+    /// Once logs take more than this many megabytes, compact and then prune
+    /// the oldest finished processes until they fit
+    #[serde(default)]
+    pub max_storage_mb: Option<u32>,
+}
+
+fn default_compact_logs_after_days() -> Option<u32> {
+    Some(7)
+}
+
+impl Default for LogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            compact_after_days: default_compact_logs_after_days(),
+            prune_raw_after_days: None,
+            max_storage_mb: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -174,6 +467,27 @@ pub struct Config {
     /// Concurrency settings for task execution
     #[serde(default)]
     pub concurrency: ConcurrencyConfig,
+    /// Model pricing for token cost accounting
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    /// GitLab merge request settings
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+    /// Rules that approve, deny or escalate agent tool calls
+    #[serde(default)]
+    pub approval_policy: ApprovalPolicyConfig,
+    /// Automatic follow-ups when a verification script fails
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    /// Settings for projects that run their workspaces in containers
+    #[serde(default)]
+    pub containers: ContainerConfig,
+    /// Compaction and pruning of execution process logs
+    #[serde(default)]
+    pub log_retention: LogRetentionConfig,
+    /// Fresh sessions for agents that run out of context window
+    #[serde(default)]
+    pub context_compaction: ContextCompactionConfig,
 }
 
 impl Config {
@@ -199,6 +513,13 @@ impl Config {
             pr_auto_description_enabled: old_config.pr_auto_description_enabled,
             pr_auto_description_prompt: old_config.pr_auto_description_prompt,
             concurrency: ConcurrencyConfig::default(),
+            pricing: PricingConfig::default(),
+            gitlab: GitLabConfig::default(),
+            approval_policy: ApprovalPolicyConfig::default(),
+            verification: VerificationConfig::default(),
+            containers: ContainerConfig::default(),
+            log_retention: LogRetentionConfig::default(),
+            context_compaction: ContextCompactionConfig::default(),
         }
     }
 
@@ -252,6 +573,13 @@ impl Default for Config {
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
             concurrency: ConcurrencyConfig::default(),
+            pricing: PricingConfig::default(),
+            gitlab: GitLabConfig::default(),
+            approval_policy: ApprovalPolicyConfig::default(),
+            verification: VerificationConfig::default(),
+            containers: ContainerConfig::default(),
+            log_retention: LogRetentionConfig::default(),
+            context_compaction: ContextCompactionConfig::default(),
         }
     }
 }
@@ -297,6 +625,7 @@ mod tests {
             global_limit: ConcurrencyLimit::Limited(5),
             agent_limits: HashMap::new(),
             queue: QueueConfig::default(),
+            budget: BudgetConfig::default(),
         };
 
         // Without agent-specific limit, use global
@@ -315,4 +644,28 @@ mod tests {
         let effective = config.effective_limit_for_agent(&BaseCodingAgent::Cursor);
         assert_eq!(effective, &ConcurrencyLimit::Limited(5));
     }
+
+    #[test]
+    fn test_pricing_prefers_longest_prefix() {
+        let pricing = PricingConfig::default();
+
+        let opus = pricing
+            .pricing_for_model("claude-opus-4-1-20250805")
+            .unwrap();
+        assert_eq!(opus.input_per_mtok, 15.0);
+
+        let opus_4_5 = pricing
+            .pricing_for_model("claude-opus-4-5-20251101")
+            .unwrap();
+        assert_eq!(opus_4_5.input_per_mtok, 5.0);
+
+        assert!(pricing.pricing_for_model("unknown-model").is_none());
+    }
+
+    #[test]
+    fn test_model_pricing_cost() {
+        let pricing = ModelPricing::new(3.0, 15.0, 3.75, 0.3);
+        let cost = pricing.cost_usd(1_000_000, 100_000, 0, 2_000_000);
+        assert!((cost - (3.0 + 1.5 + 0.6)).abs() < 1e-9);
+    }
 }
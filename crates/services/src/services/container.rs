@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -18,26 +19,31 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_process_token_usage::ExecutionProcessTokenUsage,
         project::{Project, UpdateProject},
         project_repo::{ProjectRepo, ProjectRepoWithName},
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_queue::{CreateTaskQueueEntry, TaskQueueEntry},
+        verification_run::VerificationRun,
+        workspace::{AttemptResumeContext, CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
+    executors::{BaseAgentCapability, ExecutorError, StandardCodingAgentExecutor},
     logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{StreamExt, future};
+use serde_json::Value;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -49,7 +55,12 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    attempt_archive::conversation_entries,
+    concurrency::BudgetScope,
+    git::{DiffTarget, GitService, GitServiceError},
+    handoff::{self, ConversationDigest},
+    log_retention,
+    log_search::LogSearchBuffer,
     notification::NotificationService,
     share::SharePublisher,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
@@ -57,6 +68,28 @@ use crate::services::{
 };
 pub type ContainerRef = String;
 
+/// Normalizing a finished process's stored logs normally takes well under a
+/// second
+const NORMALIZE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// First prompt for an agent that can't resume a forked session: the earlier
+/// turns of the conversation, then the new prompt
+fn forked_conversation_prompt(turns: &[CodingAgentTurn], prompt: &str) -> String {
+    let mut forked = String::from(
+        "This continues an earlier conversation about this task. The working tree is as that conversation left it.\n",
+    );
+    for turn in turns {
+        if let Some(earlier_prompt) = &turn.prompt {
+            forked.push_str(&format!("\n<user>\n{earlier_prompt}\n</user>\n"));
+        }
+        if let Some(summary) = &turn.summary {
+            forked.push_str(&format!("\n<assistant>\n{summary}\n</assistant>\n"));
+        }
+    }
+    forked.push_str(&format!("\nContinue from there:\n\n{prompt}"));
+    forked
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -85,6 +118,12 @@ pub enum ContainerError {
         current: u32,
         limit: u32,
     },
+    #[error("{scope} budget exceeded: ${spent_usd:.2} of ${limit_usd:.2} spent")]
+    BudgetExceeded {
+        scope: BudgetScope,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -157,6 +196,11 @@ pub trait ContainerService {
             return false;
         }
 
+        // A failed cleanup script still hands over to the rest of its chain
+        if self.continues_after_failed_cleanup(ctx) {
+            return false;
+        }
+
         // Always finalize failed or killed executions, regardless of next action
         if matches!(
             ctx.execution_process.status,
@@ -169,26 +213,70 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
-    /// Finalize task execution by updating status to InReview and sending notifications
+    /// Whether a failed cleanup script goes on to its next action, so that verification
+    /// chained after it still runs. Verification scripts themselves stop the chain.
+    fn continues_after_failed_cleanup(&self, ctx: &ExecutionContext) -> bool {
+        matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CleanupScript
+        ) && matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed)
+            && ctx.execution_process.executor_action().is_ok_and(|action| {
+                action.script_context() != Some(&ScriptContext::Verification)
+                    && action.next_action().is_some()
+            })
+    }
+
+    /// Whether the execution ends with the workspace failing verification: either a
+    /// verification script failed or never got to run after a failed cleanup script, or
+    /// the agent follow-up sent to fix a failure finished without the verification
+    /// running again (e.g. it made no changes)
+    async fn verification_failed(&self, ctx: &ExecutionContext) -> bool {
+        let process = &ctx.execution_process;
+        match (&process.run_reason, &process.status) {
+            (_, ExecutionProcessStatus::Killed) => false,
+            (ExecutionProcessRunReason::CleanupScript, ExecutionProcessStatus::Failed) => {
+                process.executor_action().is_ok_and(|action| {
+                    std::iter::successors(Some(action), |action| action.next_action())
+                        .any(|action| action.script_context() == Some(&ScriptContext::Verification))
+                })
+            }
+            (ExecutionProcessRunReason::CodingAgent, _) => {
+                match VerificationRun::find_by_follow_up(&self.db().pool, process.id).await {
+                    Ok(run) => run.is_some(),
+                    Err(e) => {
+                        tracing::error!("Failed to look up verification run: {e}");
+                        false
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Finalize task execution by updating status to InReview and sending notifications.
+    /// Tasks that fail verification stay in progress.
     async fn finalize_task(
         &self,
         share_publisher: Option<&SharePublisher>,
         ctx: &ExecutionContext,
     ) {
-        match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
-            Ok(_) => {
-                if let Some(publisher) = share_publisher
-                    && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
-                {
-                    tracing::warn!(
-                        ?err,
-                        "Failed to propagate shared task update for {}",
-                        ctx.task.id
-                    );
+        let verification_failed = self.verification_failed(ctx).await;
+        if !verification_failed {
+            match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
+                Ok(_) => {
+                    if let Some(publisher) = share_publisher
+                        && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
+                    {
+                        tracing::warn!(
+                            ?err,
+                            "Failed to propagate shared task update for {}",
+                            ctx.task.id
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to update task status to InReview: {e}");
                 }
-            }
-            Err(e) => {
-                tracing::error!("Failed to update task status to InReview: {e}");
             }
         }
 
@@ -197,6 +285,16 @@ pub trait ContainerService {
             return;
         }
 
+        if verification_failed {
+            let title = format!("Verification Failed: {}", ctx.task.title);
+            let message = format!(
+                "❌ '{}' did not pass verification\nBranch: {:?}\nExecutor: {:?}",
+                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            );
+            self.notification_service().notify(&title, &message).await;
+            return;
+        }
+
         let title = format!("Task Complete: {}", ctx.task.title);
         let message = match ctx.execution_process.status {
             ExecutionProcessStatus::Completed => format!(
@@ -429,6 +527,10 @@ pub trait ContainerService {
                                 } else {
                                     project.default_agent_working_dir.clone()
                                 },
+                                container_image: project.container_image.clone(),
+                                auto_address_review_comments: None,
+                                auto_fix_ci: None,
+                                max_ci_fix_attempts: None,
                             },
                         )
                         .await?;
@@ -477,6 +579,46 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    fn verification_actions_for_repos(
+        &self,
+        repos: &[ProjectRepoWithName],
+    ) -> Option<ExecutorAction> {
+        repos
+            .iter()
+            .rev()
+            .filter_map(|repo| {
+                repo.verification_script
+                    .as_ref()
+                    .map(|script| (repo, script))
+            })
+            .fold(None, |next, (repo, script)| {
+                Some(ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script: script.clone(),
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::Verification,
+                        working_dir: Some(repo.repo_name.clone()),
+                    }),
+                    next.map(Box::new),
+                ))
+            })
+    }
+
+    /// Scripts that run after a coding agent turn: cleanup scripts, then
+    /// verification scripts
+    fn post_agent_actions_for_repos(
+        &self,
+        repos: &[ProjectRepoWithName],
+    ) -> Option<ExecutorAction> {
+        match (
+            self.cleanup_actions_for_repos(repos),
+            self.verification_actions_for_repos(repos),
+        ) {
+            (Some(cleanup), Some(verification)) => Some(cleanup.append_action(verification)),
+            (cleanup, verification) => cleanup.or(verification),
+        }
+    }
+
     fn setup_actions_for_repos(&self, repos: &[ProjectRepoWithName]) -> Option<ExecutorAction> {
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -636,6 +778,44 @@ pub trait ContainerService {
         }
     }
 
+    /// Create a workspace for `task` on a fresh branch, using the project's
+    /// default agent working directory
+    async fn create_workspace_for_task(
+        &self,
+        task: &Task,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let project = task
+            .parent_project(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+
+        let agent_working_dir = project
+            .default_agent_working_dir
+            .as_ref()
+            .filter(|dir: &&String| !dir.is_empty())
+            .cloned();
+
+        let workspace = Workspace::create(
+            &self.db().pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(&self.db().pool, workspace.id, repos).await?;
+
+        Ok(workspace)
+    }
+
     async fn stream_raw_logs(
         &self,
         id: &Uuid,
@@ -654,21 +834,12 @@ pub trait ContainerService {
                     .boxed(),
             );
         } else {
-            // Fallback: load from DB and create direct stream
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
-
-            let messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+            // Fallback: load from DB, decompressing compacted logs, and create direct stream
+            let messages = match log_retention::load_log_messages(&self.db().pool, *id).await {
+                Ok(Some(messages)) => messages,
+                Ok(None) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to load logs for execution {}: {}", id, e);
                     return None;
                 }
             };
@@ -703,37 +874,37 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
-            // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
-
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+            // Conversations kept by log compaction don't need normalizing again
+            match log_retention::load_conversation(&self.db().pool, *id).await {
+                Ok(Some(entries)) => {
+                    let patches = log_retention::conversation_patches(entries);
+                    return Some(
+                        futures::stream::iter(
+                            patches
+                                .into_iter()
+                                .map(LogMsg::JsonPatch)
+                                .chain(std::iter::once(LogMsg::Finished))
+                                .map(Ok::<_, std::io::Error>),
+                        )
+                        .boxed(),
+                    );
+                }
+                Ok(None) => {}
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to load conversation for execution {}: {}", id, e);
                     return None;
                 }
-            };
+            }
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
+            // Fallback: load from DB and normalize
+            let raw_messages = match log_retention::load_log_messages(&self.db().pool, *id).await {
+                Ok(Some(messages)) => messages,
+                Ok(None) => return None, // No logs exist
+                Err(e) => {
+                    tracing::error!("Failed to load logs for execution {}: {}", id, e);
+                    return None;
                 }
-            }
-            temp_store.push_finished();
+            };
 
             let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
                 Ok(Some(process)) => process,
@@ -776,47 +947,99 @@ pub trait ContainerService {
                 );
             }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+            Some(
+                self.normalize_log_messages(&process, &workspace, raw_messages)?
+                    .chain(futures::stream::once(async {
+                        Ok::<_, std::io::Error>(LogMsg::Finished)
+                    }))
+                    .boxed(),
+            )
+        }
+    }
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
+    /// Normalize stored log messages of a coding agent process. The stream
+    /// ends once the normalizer has gone through all of them.
+    fn normalize_log_messages(
+        &self,
+        process: &ExecutionProcess,
+        workspace: &Workspace,
+        raw_messages: Vec<LogMsg>,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
+            }
+        }
+        temp_store.push_finished();
+
+        let current_dir = self.workspace_to_current_dir(workspace);
+
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
+
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id);
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
                     process.executor_action()
                 );
                 return None;
-            };
+            }
+        }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .boxed(),
+        )
+    }
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+    /// Final conversation entries of a finished coding agent process,
+    /// normalized from its stored logs without recreating its worktree
+    async fn stored_conversation(&self, process: &ExecutionProcess) -> Option<Vec<Value>> {
+        let pool = &self.db().pool;
+        let messages = log_retention::load_log_messages(pool, process.id)
+            .await
+            .ok()??;
+        let (workspace, _session) = process.parent_workspace_and_session(pool).await.ok()??;
+        let patches = self
+            .normalize_log_messages(process, &workspace, messages)?
+            .filter_map(|msg| async move {
+                match msg {
+                    Ok(LogMsg::JsonPatch(patch)) => Some(patch),
+                    _ => None,
                 }
+            });
+        match tokio::time::timeout(NORMALIZE_TIMEOUT, patches.collect::<Vec<_>>()).await {
+            Ok(patches) => Some(conversation_entries(&patches)),
+            Err(_) => {
+                tracing::warn!("Timed out normalizing logs of execution {}", process.id);
+                None
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
         }
     }
 
@@ -835,6 +1058,7 @@ pub trait ContainerService {
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
                 let mut buffered_lines: Vec<String> = Vec::with_capacity(32);
+                let mut search_buffer = LogSearchBuffer::default();
 
                 fn is_foreign_key_violation(err: &SqlxError) -> bool {
                     matches!(err, SqlxError::Database(db_err)
@@ -845,15 +1069,24 @@ pub trait ContainerService {
                     pool: &sqlx::SqlitePool,
                     execution_id: Uuid,
                     lines: &mut Vec<String>,
+                    search_buffer: &mut LogSearchBuffer,
                 ) -> Result<(), SqlxError> {
-                    if lines.is_empty() {
-                        return Ok(());
+                    if !lines.is_empty() {
+                        let result =
+                            ExecutionProcessLogs::append_log_lines(pool, execution_id, lines).await;
+                        lines.clear();
+                        result?;
                     }
 
-                    let result =
-                        ExecutionProcessLogs::append_log_lines(pool, execution_id, lines).await;
-                    lines.clear();
-                    result
+                    // The index is best effort; a failure must not stop the logs
+                    if let Err(e) = search_buffer.flush(pool, execution_id).await {
+                        tracing::warn!(
+                            "Failed to update the log search index for execution {}: {}",
+                            execution_id,
+                            e
+                        );
+                    }
+                    Ok(())
                 }
 
                 let mut stop_streaming = false;
@@ -868,9 +1101,13 @@ pub trait ContainerService {
 
                                     // Flush in small batches to reduce lock contention
                                     if buffered_lines.len() >= 32 {
-                                        if let Err(e) =
-                                            flush_logs(&db.pool, execution_id, &mut buffered_lines)
-                                                .await
+                                        if let Err(e) = flush_logs(
+                                            &db.pool,
+                                            execution_id,
+                                            &mut buffered_lines,
+                                            &mut search_buffer,
+                                        )
+                                        .await
                                         {
                                             if is_foreign_key_violation(&e) {
                                                 tracing::warn!(
@@ -901,8 +1138,13 @@ pub trait ContainerService {
                         }
                         LogMsg::SessionId(agent_session_id) => {
                             // Flush pending logs before other operations
-                            if let Err(e) =
-                                flush_logs(&db.pool, execution_id, &mut buffered_lines).await
+                            if let Err(e) = flush_logs(
+                                &db.pool,
+                                execution_id,
+                                &mut buffered_lines,
+                                &mut search_buffer,
+                            )
+                            .await
                             {
                                 if is_foreign_key_violation(&e) {
                                     tracing::warn!(
@@ -936,9 +1178,26 @@ pub trait ContainerService {
                                 );
                             }
                         }
-                        LogMsg::Finished => {
+                        LogMsg::TokenUsage(usage) => {
                             if let Err(e) =
-                                flush_logs(&db.pool, execution_id, &mut buffered_lines).await
+                                ExecutionProcessTokenUsage::upsert(&db.pool, execution_id, usage)
+                                    .await
+                            {
+                                tracing::error!(
+                                    "Failed to record token usage for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
+                        LogMsg::Finished => {
+                            if let Err(e) = flush_logs(
+                                &db.pool,
+                                execution_id,
+                                &mut buffered_lines,
+                                &mut search_buffer,
+                            )
+                            .await
                             {
                                 if is_foreign_key_violation(&e) {
                                     tracing::warn!(
@@ -957,13 +1216,20 @@ pub trait ContainerService {
                             }
                             break;
                         }
-                        LogMsg::JsonPatch(_) => continue,
+                        LogMsg::JsonPatch(patch) => search_buffer.push_patch(patch),
                     }
                 }
 
                 // Final flush for any remaining buffered lines
                 if !stop_streaming {
-                    if let Err(e) = flush_logs(&db.pool, execution_id, &mut buffered_lines).await {
+                    if let Err(e) = flush_logs(
+                        &db.pool,
+                        execution_id,
+                        &mut buffered_lines,
+                        &mut search_buffer,
+                    )
+                    .await
+                    {
                         if is_foreign_key_violation(&e) {
                             tracing::warn!(
                                 "Execution {} was deleted; stopping log stream",
@@ -1025,6 +1291,21 @@ pub trait ContainerService {
 
         let prompt = task.to_prompt();
 
+        let coding_action =
+            self.initial_coding_action(&workspace, &project_repos, prompt, &executor_profile_id);
+
+        self.start_setup_then_coding_agent(&workspace, &session, &project_repos, coding_action)
+            .await
+    }
+
+    /// Run the project's setup scripts in a new workspace, then `coding_action`
+    async fn start_setup_then_coding_agent(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        project_repos: &[ProjectRepoWithName],
+        coding_action: ExecutorAction,
+    ) -> Result<ExecutionProcess, ContainerError> {
         let repos_with_setup: Vec<_> = project_repos
             .iter()
             .filter(|pr| pr.setup_script.is_some())
@@ -1032,7 +1313,57 @@ pub trait ContainerService {
 
         let all_parallel = repos_with_setup.iter().all(|pr| pr.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
+        let execution_process = if all_parallel {
+            // All parallel: start the coding agent, then each setup independently.
+            // The agent goes first so the setup scripts run in its sandbox.
+            let execution_process = self
+                .start_execution(
+                    workspace,
+                    session,
+                    &coding_action,
+                    &ExecutionProcessRunReason::CodingAgent,
+                )
+                .await?;
+            for repo in &repos_with_setup {
+                if let Some(action) = Self::setup_action_for_repo(repo)
+                    && let Err(e) = self
+                        .start_execution(
+                            workspace,
+                            session,
+                            &action,
+                            &ExecutionProcessRunReason::SetupScript,
+                        )
+                        .await
+                {
+                    tracing::warn!(?e, "Failed to start setup script in parallel mode");
+                }
+            }
+            execution_process
+        } else {
+            // Any sequential: chain ALL setups → coding agent via next_action
+            let main_action = Self::build_sequential_setup_chain(&repos_with_setup, coding_action);
+            self.start_execution(
+                workspace,
+                session,
+                &main_action,
+                &ExecutionProcessRunReason::SetupScript,
+            )
+            .await?
+        };
+
+        Ok(execution_process)
+    }
+
+    /// Coding agent request for a fresh workspace, followed by the project's cleanup and
+    /// verification scripts
+    fn initial_coding_action(
+        &self,
+        workspace: &Workspace,
+        project_repos: &[ProjectRepoWithName],
+        prompt: String,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> ExecutorAction {
+        let post_agent_action = self.post_agent_actions_for_repos(project_repos);
 
         let working_dir = workspace
             .agent_working_dir
@@ -1040,51 +1371,471 @@ pub trait ContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
-        let coding_action = ExecutorAction::new(
+        ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
             }),
-            cleanup_action.map(Box::new),
+            post_agent_action.map(Box::new),
+        )
+    }
+
+    /// Create a session for a workspace whose task is blocked by unfinished dependencies
+    /// and hold its initial run in the task queue. The queue processor starts it once
+    /// every prerequisite is done or merged. Setup scripts always run sequentially here.
+    async fn queue_workspace_start(
+        &self,
+        workspace: &Workspace,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<TaskQueueEntry, ContainerError> {
+        let task = workspace
+            .parent_task(&self.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(&self.db().pool, task.project_id).await?;
+
+        let session = Session::create(
+            &self.db().pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+
+        let prompt = task.to_prompt();
+
+        let repos_with_setup: Vec<_> = project_repos
+            .iter()
+            .filter(|pr| pr.setup_script.is_some())
+            .collect();
+
+        let coding_action = self.initial_coding_action(
+            workspace,
+            &project_repos,
+            prompt.clone(),
+            &executor_profile_id,
         );
+        let main_action = Self::build_sequential_setup_chain(&repos_with_setup, coding_action);
 
-        let execution_process = if all_parallel {
-            // All parallel: start each setup independently, then start coding agent
-            for repo in &repos_with_setup {
-                if let Some(action) = Self::setup_action_for_repo(repo)
-                    && let Err(e) = self
-                        .start_execution(
-                            &workspace,
-                            &session,
-                            &action,
-                            &ExecutionProcessRunReason::SetupScript,
-                        )
-                        .await
+        let executor_action = serde_json::to_string(&main_action)
+            .map_err(|e| anyhow!("Failed to serialize executor action: {e}"))?;
+
+        let entry = TaskQueueEntry::create(
+            &self.db().pool,
+            &CreateTaskQueueEntry {
+                session_id: session.id,
+                workspace_id: workspace.id,
+                executor_action,
+                executor_type: executor_profile_id.executor.to_string(),
+                prompt: Some(prompt),
+                priority: None,
+            },
+        )
+        .await?;
+
+        tracing::info!(
+            "Queued initial run for task {} as entry {}",
+            task.id,
+            entry.id
+        );
+
+        Ok(entry)
+    }
+
+    /// Fork the conversation of a coding agent turn into a new workspace. Its
+    /// worktrees start at the commits the turn left behind and the agent resumes
+    /// its session as it was at the end of the turn, so the source workspace and
+    /// session are left as they are. Agents that can't fork a session start over
+    /// with the earlier prompts and replies in their prompt instead.
+    async fn fork_session(
+        &self,
+        process: &ExecutionProcess,
+        prompt: String,
+        variant: Option<String>,
+    ) -> Result<(Workspace, Session, ExecutionProcess), ContainerError> {
+        let pool = &self.db().pool;
+        let ctx = ExecutionProcess::load_context(pool, process.id).await?;
+
+        let source_profile = process
+            .executor_action()?
+            .executor_profile_id()
+            .ok_or_else(|| anyhow!("Execution process {} is not a coding agent", process.id))?;
+        let executor_profile_id = ExecutorProfileId {
+            executor: source_profile.executor,
+            variant,
+        };
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &ctx.task.title)
+            .await;
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir: ctx.workspace.agent_working_dir.clone(),
+            },
+            workspace_id,
+            ctx.task.id,
+        )
+        .await?;
+        let repos: Vec<CreateWorkspaceRepo> =
+            WorkspaceRepo::find_by_workspace_id(pool, ctx.workspace.id)
+                .await?
+                .into_iter()
+                .map(|repo| CreateWorkspaceRepo {
+                    repo_id: repo.repo_id,
+                    target_branch: repo.target_branch,
+                })
+                .collect();
+        WorkspaceRepo::create_many(pool, workspace.id, &repos).await?;
+
+        // Check out each repo at the commit the turn ended on
+        let container_ref = self.create(&workspace).await?;
+        let workspace_dir = PathBuf::from(container_ref);
+        let repo_states =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?;
+        for repo in &ctx.repos {
+            let Some(commit) = repo_states
+                .iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| {
+                    state
+                        .after_head_commit
+                        .clone()
+                        .or_else(|| state.before_head_commit.clone())
+                })
+            else {
+                continue;
+            };
+            self.git()
+                .reset_worktree_to_commit(&workspace_dir.join(&repo.name), &commit, true)?;
+        }
+
+        let workspace = Workspace::find_by_id(pool, workspace.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let session = Session::create_fork(
+            pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+            process.id,
+        )
+        .await?;
+
+        let can_fork_agent_session = ExecutorConfigs::get_cached()
+            .get_coding_agent(&executor_profile_id)
+            .is_some_and(|agent| {
+                agent
+                    .capabilities()
+                    .contains(&BaseAgentCapability::SessionFork)
+            });
+        let agent_session_id = CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+            .await?
+            .and_then(|turn| turn.agent_session_id)
+            .filter(|_| can_fork_agent_session);
+
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, ctx.project.id).await?;
+        let coding_action = match agent_session_id {
+            Some(session_id) => ExecutorAction::new(
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id,
+                    executor_profile_id,
+                    working_dir: workspace
+                        .agent_working_dir
+                        .as_ref()
+                        .filter(|dir| !dir.is_empty())
+                        .cloned(),
+                }),
+                self.post_agent_actions_for_repos(&project_repos)
+                    .map(Box::new),
+            ),
+            None => {
+                let mut turns = Vec::new();
+                for earlier in
+                    ExecutionProcess::find_by_session_id(pool, process.session_id, false).await?
                 {
-                    tracing::warn!(?e, "Failed to start setup script in parallel mode");
+                    if earlier.run_reason != ExecutionProcessRunReason::CodingAgent
+                        || earlier.created_at > process.created_at
+                    {
+                        continue;
+                    }
+                    if let Some(turn) =
+                        CodingAgentTurn::find_by_execution_process_id(pool, earlier.id).await?
+                    {
+                        turns.push(turn);
+                    }
                 }
+                self.initial_coding_action(
+                    &workspace,
+                    &project_repos,
+                    forked_conversation_prompt(&turns, &prompt),
+                    &executor_profile_id,
+                )
             }
-            self.start_execution(
+        };
+
+        let execution_process = self
+            .start_setup_then_coding_agent(&workspace, &session, &project_repos, coding_action)
+            .await?;
+
+        Ok((workspace, session, execution_process))
+    }
+
+    /// Conversation digest and resume context of a session, for the first
+    /// prompt of a new session that carries on with its work
+    async fn continuation_context(
+        &self,
+        session: &Session,
+        workspace: &Workspace,
+        workspace_dir: &Path,
+    ) -> Result<(ConversationDigest, AttemptResumeContext), ContainerError> {
+        let pool = &self.db().pool;
+        let mut turns = Vec::new();
+        let mut digest = ConversationDigest::default();
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+                continue;
+            }
+            if let Some(turn) =
+                CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?
+            {
+                if turn.prompt.is_some() {
+                    digest.last_request = turn.prompt.clone();
+                }
+                turns.push(turn);
+            }
+            let conversation = match log_retention::load_conversation(pool, process.id).await {
+                Ok(Some(conversation)) => Some(conversation),
+                _ => self.stored_conversation(&process).await,
+            };
+            if let Some(conversation) = conversation {
+                digest.add_entries(&handoff::normalized_entries(&conversation));
+            }
+        }
+
+        let mut diffs = Vec::new();
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+        let prefix_paths = repos.len() > 1;
+        for repo in &repos {
+            let repo_diffs = self
+                .git()
+                .get_base_commit(&repo.repo.path, &workspace.branch, &repo.target_branch)
+                .and_then(|base_commit| {
+                    self.git().get_diffs(
+                        DiffTarget::Worktree {
+                            worktree_path: &workspace_dir.join(&repo.repo.name),
+                            base_commit: &base_commit,
+                        },
+                        None,
+                    )
+                });
+            match repo_diffs {
+                Ok(repo_diffs) => diffs.extend(repo_diffs.into_iter().map(|mut diff| {
+                    if prefix_paths {
+                        let prefix = |path: String| format!("{}/{path}", repo.repo.name);
+                        diff.old_path = diff.old_path.map(prefix);
+                        diff.new_path = diff.new_path.map(prefix);
+                    }
+                    diff
+                })),
+                Err(e) => tracing::warn!(
+                    "Failed to diff repo {} of workspace {}: {}",
+                    repo.repo.name,
+                    workspace.id,
+                    e
+                ),
+            }
+        }
+
+        Ok((digest, handoff::resume_context(&turns, &diffs)))
+    }
+
+    /// Continue a workspace with another coding agent. It starts a new session
+    /// in the same workspace, and as it can't resume the previous agent's
+    /// session, its first prompt carries over what that session did.
+    async fn handoff_session(
+        &self,
+        session: &Session,
+        executor_profile_id: ExecutorProfileId,
+        prompt: String,
+    ) -> Result<(Session, ExecutionProcess), ContainerError> {
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, session.workspace_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let workspace_dir = PathBuf::from(self.ensure_container_exists(&workspace).await?);
+        let (digest, context) = self
+            .continuation_context(session, &workspace, &workspace_dir)
+            .await?;
+
+        let previous_executor = session.executor.as_deref().unwrap_or("unknown");
+        let handoff_prompt =
+            handoff::handoff_prompt(&task, previous_executor, &digest, &context, &prompt);
+
+        let new_session = Session::create(
+            pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+        let action = self.initial_coding_action(
+            &workspace,
+            &project_repos,
+            handoff_prompt,
+            &executor_profile_id,
+        );
+        let execution_process = self
+            .start_execution(
                 &workspace,
-                &session,
-                &coding_action,
+                &new_session,
+                &action,
                 &ExecutionProcessRunReason::CodingAgent,
             )
+            .await?;
+
+        tracing::info!(
+            "Handed workspace {} over from {} to {}",
+            workspace.id,
+            previous_executor,
+            executor_profile_id.executor
+        );
+        Ok((new_session, execution_process))
+    }
+
+    /// Replace a session whose conversation no longer fits in the agent's
+    /// context window with a new session of the same agent, seeded with a
+    /// summary of the conversation and the changes so far
+    async fn compact_session(
+        &self,
+        session: &Session,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<(Session, ExecutionProcess), ContainerError> {
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, session.workspace_id)
             .await?
-        } else {
-            // Any sequential: chain ALL setups → coding agent via next_action
-            let main_action = Self::build_sequential_setup_chain(&repos_with_setup, coding_action);
-            self.start_execution(
+            .ok_or(SqlxError::RowNotFound)?;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let workspace_dir = PathBuf::from(self.ensure_container_exists(&workspace).await?);
+        let (digest, context) = self
+            .continuation_context(session, &workspace, &workspace_dir)
+            .await?;
+        let compaction_prompt = handoff::compaction_prompt(&task, &digest, &context);
+
+        let new_session = Session::create_compacted(
+            pool,
+            &CreateSession {
+                executor: Some(executor_profile_id.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+            session.id,
+        )
+        .await?;
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+        let action = self.initial_coding_action(
+            &workspace,
+            &project_repos,
+            compaction_prompt,
+            &executor_profile_id,
+        );
+        let execution_process = self
+            .start_execution(
                 &workspace,
-                &session,
-                &main_action,
-                &ExecutionProcessRunReason::SetupScript,
+                &new_session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
             )
+            .await?;
+
+        tracing::info!(
+            "Compacted session {} of workspace {} into session {}",
+            session.id,
+            workspace.id,
+            new_session.id
+        );
+        Ok((new_session, execution_process))
+    }
+
+    /// Send a prompt to the workspace's latest session without user interaction,
+    /// followed by the project's cleanup and verification scripts
+    async fn start_unattended_follow_up(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id)
             .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                .await
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
+                })?;
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+
+        let action = match latest_agent_session_id {
+            Some(agent_session_id) => ExecutorAction::new(
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id: agent_session_id,
+                    executor_profile_id,
+                    working_dir: workspace
+                        .agent_working_dir
+                        .as_ref()
+                        .filter(|dir| !dir.is_empty())
+                        .cloned(),
+                }),
+                self.post_agent_actions_for_repos(&project_repos)
+                    .map(Box::new),
+            ),
+            None => {
+                self.initial_coding_action(workspace, &project_repos, prompt, &executor_profile_id)
+            }
         };
 
-        Ok(execution_process)
+        self.ensure_container_exists(workspace).await?;
+        self.start_execution(
+            workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
     }
 
     async fn start_execution(
@@ -1275,20 +2026,22 @@ pub trait ContainerService {
         };
 
         // Determine the run reason of the next action
-        let next_run_reason = match (action.typ(), next_action.typ()) {
-            (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
-                ExecutionProcessRunReason::SetupScript
+        // Verification and playbook scripts are recorded as cleanup scripts and told
+        // apart by their script context
+        let next_run_reason = match next_action.typ() {
+            ExecutorActionType::ScriptRequest(script) => match script.context {
+                ScriptContext::SetupScript | ScriptContext::ToolInstallScript => {
+                    ExecutionProcessRunReason::SetupScript
+                }
+                ScriptContext::CleanupScript
+                | ScriptContext::Verification
+                | ScriptContext::Playbook => ExecutionProcessRunReason::CleanupScript,
+                ScriptContext::DevServer => ExecutionProcessRunReason::DevServer,
+            },
+            ExecutorActionType::CodingAgentFollowUpRequest(_)
+            | ExecutorActionType::CodingAgentInitialRequest(_) => {
+                ExecutionProcessRunReason::CodingAgent
             }
-            (
-                ExecutorActionType::CodingAgentInitialRequest(_)
-                | ExecutorActionType::CodingAgentFollowUpRequest(_),
-                ExecutorActionType::ScriptRequest(_),
-            ) => ExecutionProcessRunReason::CleanupScript,
-            (
-                _,
-                ExecutorActionType::CodingAgentFollowUpRequest(_)
-                | ExecutorActionType::CodingAgentInitialRequest(_),
-            ) => ExecutionProcessRunReason::CodingAgent,
         };
 
         self.start_execution(&ctx.workspace, &ctx.session, next_action, &next_run_reason)
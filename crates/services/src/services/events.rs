@@ -1,10 +1,14 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
 
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch, task::Task,
-        workspace::Workspace,
+        execution_process::ExecutionProcess, merge::Merge, project::Project, scratch::Scratch,
+        task::Task, webhook::WebhookEventType, workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -13,6 +17,8 @@ use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
+use super::webhooks;
+
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -82,6 +88,7 @@ impl EventService {
         msg_store: Arc<MsgStore>,
         entry_count: Arc<RwLock<usize>>,
         db_service: Arc<std::sync::OnceLock<DBService>>,
+        transition_columns: Arc<OnceLock<TransitionColumns>>,
     ) -> impl for<'a> Fn(
         &'a mut sqlx::sqlite::SqliteConnection,
     ) -> std::pin::Pin<
@@ -93,12 +100,28 @@ impl EventService {
             let msg_store_for_hook = msg_store.clone();
             let entry_count_for_hook = entry_count.clone();
             let db_cell_for_hook = db_service.clone();
+            let columns_for_hook = transition_columns.clone();
             Box::pin(async move {
                 let mut handle = conn.lock_handle().await?;
                 let runtime_handle = tokio::runtime::Handle::current();
                 handle.set_preupdate_hook({
                     let msg_store_for_preupdate = msg_store_for_hook.clone();
+                    let runtime_for_preupdate = runtime_handle.clone();
+                    let db_cell_for_preupdate = db_cell_for_hook.clone();
                     move |preupdate: sqlx::sqlite::PreupdateHookResult<'_>| {
+                        if preupdate.operation == SqliteOperation::Update {
+                            if let Some(columns) = columns_for_hook.get()
+                                && let Some(transition) =
+                                    StatusTransition::from_preupdate(&preupdate, columns)
+                                && let Some(db) = db_cell_for_preupdate.get()
+                            {
+                                let db = db.clone();
+                                runtime_for_preupdate.spawn(async move {
+                                    transition.enqueue_webhooks(&db).await;
+                                });
+                            }
+                            return;
+                        }
                         if preupdate.operation != SqliteOperation::Delete {
                             return;
                         }
@@ -177,6 +200,26 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::Merges, _) => {
+                                    // PR status and CI results are shown on the owning task
+                                    if let Ok(Some(workspace_id)) =
+                                        Merge::find_workspace_id_by_rowid(&db.pool, rowid).await
+                                        && let Ok(Some(workspace)) =
+                                            Workspace::find_by_id(&db.pool, workspace_id).await
+                                        && let Err(err) = EventService::push_task_update_for_task(
+                                            &db.pool,
+                                            msg_store_for_hook.clone(),
+                                            workspace.task_id,
+                                        )
+                                        .await
+                                    {
+                                        tracing::error!(
+                                            "Failed to push task update after merge change: {:?}",
+                                            err
+                                        );
+                                    }
+                                    return;
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -451,3 +494,188 @@ impl EventService {
         &self.msg_store
     }
 }
+
+/// Positions of the columns the preupdate hook reads to detect status changes.
+/// The hook only sees values by index, so they are looked up by name once
+/// migrations have run rather than assumed from the table layout.
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionColumns {
+    task_id: i32,
+    task_project_id: i32,
+    task_title: i32,
+    task_status: i32,
+    process_id: i32,
+    process_run_reason: i32,
+    process_status: i32,
+    process_exit_code: i32,
+}
+
+impl TransitionColumns {
+    pub async fn resolve(db: &DBService) -> Result<Self, SqlxError> {
+        let tasks = db.column_positions("tasks").await?;
+        let processes = db.column_positions("execution_processes").await?;
+        let position = |columns: &HashMap<String, i32>, name: &str| {
+            columns
+                .get(name)
+                .copied()
+                .ok_or_else(|| SqlxError::ColumnNotFound(name.to_string()))
+        };
+
+        Ok(Self {
+            task_id: position(&tasks, "id")?,
+            task_project_id: position(&tasks, "project_id")?,
+            task_title: position(&tasks, "title")?,
+            task_status: position(&tasks, "status")?,
+            process_id: position(&processes, "id")?,
+            process_run_reason: position(&processes, "run_reason")?,
+            process_status: position(&processes, "status")?,
+            process_exit_code: position(&processes, "exit_code")?,
+        })
+    }
+}
+
+/// A status change worth a webhook, captured in the preupdate hook where both
+/// the old and new row are visible
+enum StatusTransition {
+    Task {
+        task_id: Uuid,
+        project_id: Uuid,
+        title: String,
+        previous_status: String,
+        status: String,
+    },
+    ExecutionProcess {
+        process_id: Uuid,
+        run_reason: String,
+        status: String,
+        exit_code: Option<i64>,
+    },
+}
+
+impl StatusTransition {
+    fn from_preupdate(
+        preupdate: &sqlx::sqlite::PreupdateHookResult<'_>,
+        columns: &TransitionColumns,
+    ) -> Option<Self> {
+        let is_task = match preupdate.table {
+            "tasks" => true,
+            "execution_processes" => false,
+            _ => return None,
+        };
+
+        let old_text = |column: i32| {
+            preupdate
+                .get_old_column_value(column)
+                .ok()
+                .and_then(|value| <String as Decode<Sqlite>>::decode(value).ok())
+        };
+        let new_text = |column: i32| {
+            preupdate
+                .get_new_column_value(column)
+                .ok()
+                .and_then(|value| <String as Decode<Sqlite>>::decode(value).ok())
+        };
+        let old_uuid = |column: i32| {
+            preupdate
+                .get_old_column_value(column)
+                .ok()
+                .and_then(|value| <Uuid as Decode<Sqlite>>::decode(value).ok())
+        };
+
+        let status_column = if is_task {
+            columns.task_status
+        } else {
+            columns.process_status
+        };
+        let previous_status = old_text(status_column)?;
+        let status = new_text(status_column)?;
+        if previous_status == status {
+            return None;
+        }
+
+        if is_task {
+            return Some(Self::Task {
+                task_id: old_uuid(columns.task_id)?,
+                project_id: old_uuid(columns.task_project_id)?,
+                title: new_text(columns.task_title).unwrap_or_default(),
+                previous_status,
+                status,
+            });
+        }
+
+        if previous_status != "running" || !matches!(status.as_str(), "completed" | "failed") {
+            return None;
+        }
+        let exit_code = preupdate
+            .get_new_column_value(columns.process_exit_code)
+            .ok()
+            .and_then(|value| <Option<i64> as Decode<Sqlite>>::decode(value).ok())
+            .flatten();
+        Some(Self::ExecutionProcess {
+            process_id: old_uuid(columns.process_id)?,
+            run_reason: old_text(columns.process_run_reason)?,
+            status,
+            exit_code,
+        })
+    }
+
+    async fn enqueue_webhooks(self, db: &DBService) {
+        let (event, data) = match self {
+            Self::Task {
+                task_id,
+                project_id,
+                title,
+                previous_status,
+                status,
+            } => (
+                WebhookEventType::TaskStatusChanged,
+                json!({
+                    "task_id": task_id,
+                    "project_id": project_id,
+                    "title": title,
+                    "previous_status": previous_status,
+                    "status": status,
+                }),
+            ),
+            Self::ExecutionProcess {
+                process_id,
+                run_reason,
+                status,
+                exit_code,
+            } => {
+                let event = if status == "completed" {
+                    WebhookEventType::ExecutionCompleted
+                } else {
+                    WebhookEventType::ExecutionFailed
+                };
+                let mut data = json!({
+                    "execution_process_id": process_id,
+                    "run_reason": run_reason,
+                    "status": status,
+                    "exit_code": exit_code,
+                });
+                match ExecutionProcess::load_context(&db.pool, process_id).await {
+                    Ok(ctx) => {
+                        data["session_id"] = json!(ctx.session.id);
+                        data["workspace_id"] = json!(ctx.workspace.id);
+                        data["task_id"] = json!(ctx.task.id);
+                        data["task_title"] = json!(ctx.task.title);
+                        data["project_id"] = json!(ctx.project.id);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to load context for execution webhook {}: {}",
+                            process_id,
+                            e
+                        );
+                    }
+                }
+                (event, data)
+            }
+        };
+
+        if let Err(e) = webhooks::enqueue_event(&db.pool, event, data).await {
+            tracing::error!("Failed to queue webhook event {}: {}", event, e);
+        }
+    }
+}
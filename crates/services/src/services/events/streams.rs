@@ -231,6 +231,7 @@ impl EventService {
                                                         .has_in_progress_attempt,
                                                     last_attempt_failed: task.last_attempt_failed,
                                                     executor: task.executor.clone(),
+                                                    ci_status: task.ci_status,
                                                     project_name: project.name,
                                                 };
                                                 let new_patch = json!([
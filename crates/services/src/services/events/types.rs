@@ -32,6 +32,8 @@ pub enum HookTables {
     Scratch,
     #[strum(to_string = "projects")]
     Projects,
+    #[strum(to_string = "merges")]
+    Merges,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -94,7 +94,7 @@ impl FileRanker {
     /// Calculate relevance score for a search result
     fn calculate_score(&self, result: &SearchResult, stats: &FileStats) -> i64 {
         let base_score = match result.match_type {
-            SearchMatchType::FileName => BASE_MATCH_SCORE_FILENAME,
+            SearchMatchType::FileName | SearchMatchType::Symbol => BASE_MATCH_SCORE_FILENAME,
             SearchMatchType::DirectoryName => BASE_MATCH_SCORE_DIRNAME,
             SearchMatchType::FullPath => BASE_MATCH_SCORE_FULLPATH,
         };
@@ -20,12 +20,17 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 use ts_rs::TS;
+use uuid::Uuid;
 
 use super::{
     file_ranker::{FileRanker, FileStats},
     git::GitService,
+    symbol_index::{SymbolIndex, SymbolQuery},
 };
 
+/// Symbol searches return more results than file searches since names repeat across files
+pub const MAX_SYMBOL_RESULTS: usize = 20;
+
 /// Search mode for different use cases
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "lowercase")]
@@ -34,6 +39,7 @@ pub enum SearchMode {
     #[default]
     TaskForm, // Default: exclude ignored files (clean results)
     Settings, // Include ignored files (for project config like .env)
+    Symbols,  // Functions, types and modules defined in non-ignored files
 }
 
 /// Search query parameters for typed Axum extraction
@@ -42,6 +48,9 @@ pub struct SearchQuery {
     pub q: String,
     #[serde(default)]
     pub mode: SearchMode,
+    /// Search this workspace's worktrees instead of the main checkouts (symbols only)
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
 }
 
 /// FST-indexed file search result
@@ -96,6 +105,7 @@ pub struct FileSearchCache {
     cache: Cache<PathBuf, CachedRepo>,
     git_service: GitService,
     file_ranker: FileRanker,
+    symbol_index: SymbolIndex,
     build_queue: mpsc::UnboundedSender<PathBuf>,
     watchers: DashMap<PathBuf, RecommendedWatcher>,
 }
@@ -113,16 +123,19 @@ impl FileSearchCache {
         let cache_for_worker = cache.clone();
         let git_service = GitService::new();
         let file_ranker = FileRanker::new();
+        let symbol_index = SymbolIndex::new();
 
         // Spawn background worker
         let worker_git_service = git_service.clone();
         let worker_file_ranker = file_ranker.clone();
+        let worker_symbol_index = symbol_index.clone();
         tokio::spawn(async move {
             Self::background_worker(
                 build_receiver,
                 cache_for_worker,
                 worker_git_service,
                 worker_file_ranker,
+                worker_symbol_index,
             )
             .await;
         });
@@ -131,6 +144,7 @@ impl FileSearchCache {
             cache,
             git_service,
             file_ranker,
+            symbol_index,
             build_queue: build_sender,
             watchers: DashMap::new(),
         }
@@ -162,6 +176,18 @@ impl FileSearchCache {
         Err(CacheError::Miss)
     }
 
+    /// Search definitions in repository, indexing its symbols on first use
+    pub async fn search_symbols(
+        &self,
+        repo_path: &Path,
+        query: &SymbolQuery,
+    ) -> Result<Vec<SearchResult>, CacheError> {
+        self.symbol_index
+            .search(repo_path, query, MAX_SYMBOL_RESULTS)
+            .await
+            .map_err(CacheError::BuildError)
+    }
+
     /// Pre-warm cache for given repositories
     pub async fn warm_repos(&self, repo_paths: Vec<PathBuf>) -> Result<(), String> {
         for repo_path in repo_paths {
@@ -216,6 +242,9 @@ impl FileSearchCache {
             .await
             .map_err(|e| format!("Failed to warm cache: {e}"))?;
 
+        // Index symbols in the background, the symbol index watches files itself
+        self.symbol_index.warm(repo_paths.clone());
+
         // Setup watchers for active projects
         for repo_path in &repo_paths {
             if let Err(e) = self.setup_watcher(repo_path).await {
@@ -242,7 +271,7 @@ impl FileSearchCache {
             if indexed_file.path_lowercase.contains(&query_lower) {
                 // Apply mode-based filtering
                 match mode {
-                    SearchMode::TaskForm => {
+                    SearchMode::TaskForm | SearchMode::Symbols => {
                         // Exclude ignored files for task forms
                         if indexed_file.is_ignored {
                             continue;
@@ -258,6 +287,7 @@ impl FileSearchCache {
                     path: indexed_file.path.clone(),
                     is_file: indexed_file.is_file,
                     match_type: indexed_file.match_type.clone(),
+                    symbol: None,
                 });
             }
         }
@@ -431,12 +461,14 @@ impl FileSearchCache {
         cache: Cache<PathBuf, CachedRepo>,
         git_service: GitService,
         file_ranker: FileRanker,
+        symbol_index: SymbolIndex,
     ) {
         while let Some(repo_path) = build_receiver.recv().await {
             let cache_builder = FileSearchCache {
                 cache: cache.clone(),
                 git_service: git_service.clone(),
                 file_ranker: file_ranker.clone(),
+                symbol_index: symbol_index.clone(),
                 build_queue: mpsc::unbounded_channel().0, // Dummy sender
                 watchers: DashMap::new(),
             };
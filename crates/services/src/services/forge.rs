@@ -0,0 +1,308 @@
+//! Code forge abstraction so PR flows work for GitHub pull requests and GitLab
+//! merge requests alike.
+//!
+//! The forge for a repository is picked from its remote URL; PRs that were
+//! already recorded carry their `ForgeKind` so they can be resolved without
+//! looking at the repository again.
+
+use async_trait::async_trait;
+use db::models::merge::{ForgeKind, PrCiState, PullRequestInfo};
+use thiserror::Error;
+
+use crate::services::{
+    config::GitLabConfig,
+    github::{
+        CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, UnifiedPrComment,
+    },
+    gitlab::{GitLabRepoInfo, GitLabService, GitLabServiceError},
+};
+
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(transparent)]
+    GitLab(#[from] GitLabServiceError),
+    #[error("Remote is not a known GitHub or GitLab repository: {0}")]
+    UnsupportedRemote(String),
+}
+
+/// Repository coordinates on a specific forge
+#[derive(Debug, Clone)]
+pub enum ForgeRepoInfo {
+    GitHub(GitHubRepoInfo),
+    GitLab(GitLabRepoInfo),
+}
+
+fn is_gitlab_host(host: &str, gitlab_hosts: &[String]) -> bool {
+    let host = host.to_ascii_lowercase();
+    host == "gitlab.com"
+        || host.starts_with("gitlab.")
+        || gitlab_hosts
+            .iter()
+            .any(|extra| extra.trim().eq_ignore_ascii_case(&host))
+}
+
+impl ForgeRepoInfo {
+    /// Pick the forge for a git remote URL. `gitlab_hosts` lists additional
+    /// self-hosted GitLab instances.
+    pub fn from_remote_url(remote_url: &str, gitlab_hosts: &[String]) -> Result<Self, ForgeError> {
+        if let Ok(info) = GitLabRepoInfo::from_remote_url(remote_url)
+            && is_gitlab_host(&info.host, gitlab_hosts)
+        {
+            return Ok(ForgeRepoInfo::GitLab(info));
+        }
+
+        GitHubRepoInfo::from_remote_url(remote_url)
+            .map(ForgeRepoInfo::GitHub)
+            .map_err(|_| ForgeError::UnsupportedRemote(remote_url.to_string()))
+    }
+
+    /// Resolve the repository of an already recorded PR from its URL
+    pub fn from_pr_url(kind: ForgeKind, pr_url: &str) -> Result<Self, ForgeError> {
+        match kind {
+            ForgeKind::GitHub => Ok(ForgeRepoInfo::GitHub(GitHubRepoInfo::from_remote_url(
+                pr_url,
+            )?)),
+            ForgeKind::GitLab => Ok(ForgeRepoInfo::GitLab(GitLabRepoInfo::from_remote_url(
+                pr_url,
+            )?)),
+        }
+    }
+
+    pub fn kind(&self) -> ForgeKind {
+        match self {
+            ForgeRepoInfo::GitHub(_) => ForgeKind::GitHub,
+            ForgeRepoInfo::GitLab(_) => ForgeKind::GitLab,
+        }
+    }
+}
+
+/// Pull/merge request operations for one repository
+#[async_trait]
+pub trait Forge: Send + Sync {
+    fn kind(&self) -> ForgeKind;
+
+    /// Verify that the forge's CLI or API credentials work
+    async fn check_auth(&self) -> Result<(), ForgeError>;
+
+    async fn create_pr(&self, request: &CreatePrRequest) -> Result<PullRequestInfo, ForgeError>;
+
+    async fn update_pr_status(&self, pr_number: i64) -> Result<PullRequestInfo, ForgeError>;
+
+    /// All PRs whose head is `branch_name`, open ones first
+    async fn list_all_prs_for_branch(
+        &self,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError>;
+
+    async fn get_pr_comments(&self, pr_number: i64) -> Result<Vec<UnifiedPrComment>, ForgeError>;
+
+    /// Inline review comments in threads that are not resolved, oldest first
+    async fn get_unresolved_review_comments(
+        &self,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError>;
+
+    /// Reply in the thread of an inline review comment
+    async fn reply_to_review_comment(
+        &self,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), ForgeError>;
+
+    /// CI checks on the PR's head commit, `None` if there are none
+    async fn get_ci_status(&self, pr_number: i64) -> Result<Option<PrCiState>, ForgeError>;
+
+    /// Log output of a failed CI job, see `CiCheck::job_id`
+    async fn get_ci_job_log(&self, job_id: i64) -> Result<String, ForgeError>;
+
+    /// Point the PR at a different base branch, e.g. once the PR it was stacked on merged
+    async fn update_pr_base(&self, pr_number: i64, base_branch: &str) -> Result<(), ForgeError>;
+}
+
+struct GitHubForge {
+    service: GitHubService,
+    repo_info: GitHubRepoInfo,
+}
+
+#[async_trait]
+impl Forge for GitHubForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitHub
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        Ok(self.service.check_token().await?)
+    }
+
+    async fn create_pr(&self, request: &CreatePrRequest) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self.service.create_pr(&self.repo_info, request).await?)
+    }
+
+    async fn update_pr_status(&self, pr_number: i64) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self
+            .service
+            .update_pr_status(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn list_all_prs_for_branch(
+        &self,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        Ok(self
+            .service
+            .list_all_prs_for_branch(&self.repo_info, branch_name)
+            .await?)
+    }
+
+    async fn get_pr_comments(&self, pr_number: i64) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(self
+            .service
+            .get_pr_comments(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn get_unresolved_review_comments(
+        &self,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(self
+            .service
+            .get_unresolved_review_comments(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        Ok(self
+            .service
+            .reply_to_review_comment(&self.repo_info, pr_number, comment_id, body)
+            .await?)
+    }
+
+    async fn get_ci_status(&self, pr_number: i64) -> Result<Option<PrCiState>, ForgeError> {
+        Ok(self
+            .service
+            .get_ci_status(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn get_ci_job_log(&self, job_id: i64) -> Result<String, ForgeError> {
+        Ok(self.service.get_ci_job_log(&self.repo_info, job_id).await?)
+    }
+
+    async fn update_pr_base(&self, pr_number: i64, base_branch: &str) -> Result<(), ForgeError> {
+        Ok(self
+            .service
+            .update_pr_base(&self.repo_info, pr_number, base_branch)
+            .await?)
+    }
+}
+
+struct GitLabForge {
+    service: GitLabService,
+    repo_info: GitLabRepoInfo,
+}
+
+#[async_trait]
+impl Forge for GitLabForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitLab
+    }
+
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        Ok(self.service.check_token(&self.repo_info).await?)
+    }
+
+    async fn create_pr(&self, request: &CreatePrRequest) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self.service.create_pr(&self.repo_info, request).await?)
+    }
+
+    async fn update_pr_status(&self, pr_number: i64) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self
+            .service
+            .update_pr_status(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn list_all_prs_for_branch(
+        &self,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        Ok(self
+            .service
+            .list_all_prs_for_branch(&self.repo_info, branch_name)
+            .await?)
+    }
+
+    async fn get_pr_comments(&self, pr_number: i64) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(self
+            .service
+            .get_pr_comments(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn get_unresolved_review_comments(
+        &self,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(self
+            .service
+            .get_unresolved_review_comments(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        Ok(self
+            .service
+            .reply_to_review_comment(&self.repo_info, pr_number, comment_id, body)
+            .await?)
+    }
+
+    async fn get_ci_status(&self, pr_number: i64) -> Result<Option<PrCiState>, ForgeError> {
+        Ok(self
+            .service
+            .get_ci_status(&self.repo_info, pr_number)
+            .await?)
+    }
+
+    async fn get_ci_job_log(&self, job_id: i64) -> Result<String, ForgeError> {
+        Ok(self.service.get_ci_job_log(&self.repo_info, job_id).await?)
+    }
+
+    async fn update_pr_base(&self, pr_number: i64, base_branch: &str) -> Result<(), ForgeError> {
+        Ok(self
+            .service
+            .update_pr_base(&self.repo_info, pr_number, base_branch)
+            .await?)
+    }
+}
+
+/// Build the forge client for a repository
+pub fn forge_for_repo(
+    repo_info: ForgeRepoInfo,
+    gitlab: &GitLabConfig,
+) -> Result<Box<dyn Forge>, ForgeError> {
+    match repo_info {
+        ForgeRepoInfo::GitHub(repo_info) => Ok(Box::new(GitHubForge {
+            service: GitHubService::new()?,
+            repo_info,
+        })),
+        ForgeRepoInfo::GitLab(repo_info) => Ok(Box::new(GitLabForge {
+            service: GitLabService::new(gitlab.resolved_token())?,
+            repo_info,
+        })),
+    }
+}
@@ -16,10 +16,12 @@
 //! `git` CLI, while keeping libgit2 for read‑only graph queries and credentialed
 //! network operations when useful.
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
     io::Write as _,
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::{LazyLock, RwLock},
 };
 
 use thiserror::Error;
@@ -39,11 +41,31 @@ pub enum GitCliError {
     PushRejected(String),
     #[error("rebase in progress in this worktree")]
     RebaseInProgress,
+    #[error("refusing to run git in a worktree a sandboxed process may have tampered with: {0}")]
+    UntrustedWorktree(String),
 }
 
 #[derive(Clone, Default)]
 pub struct GitCli;
 
+/// Workspaces sandboxed processes have run in, with the git directories of
+/// the repositories their worktrees belong to. A sandboxed process can write
+/// to its worktrees, so git there only runs against a verified git directory,
+/// with hooks and fsmonitor disabled and never with command-running config
+/// from a file the process could have written.
+static SANDBOXED_WORKSPACES: LazyLock<RwLock<HashMap<PathBuf, Vec<PathBuf>>>> =
+    LazyLock::new(Default::default);
+
+/// Config keys whose values git runs as commands
+const COMMAND_CONFIG_KEYS: &str = r"^(core\.(sshcommand|gitproxy|askpass|editor|pager|hookspath|fsmonitor)|credential\..*|diff\.external|sequence\.editor|gpg\.(.*\.)?program|(diff|merge|filter)\..*\.(command|textconv|driver|clean|smudge|process))$";
+
+/// A worktree in a sandboxed workspace whose `.git` file checked out
+struct SandboxedWorktree {
+    root: PathBuf,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
 /// Parsed change type from `git diff --name-status` output
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ChangeType {
@@ -81,6 +103,29 @@ impl GitCli {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Harden git commands run in `workspace_dir` and the worktrees in it once a
+    /// sandboxed process has run there. `repo_paths` are the repositories the
+    /// worktrees belong to.
+    pub fn mark_sandboxed(&self, workspace_dir: &Path, repo_paths: &[PathBuf]) {
+        let common_dirs = repo_paths
+            .iter()
+            .filter_map(|repo_path| {
+                let common_dir = self.git(repo_path, ["rev-parse", "--git-common-dir"]);
+                match common_dir {
+                    Ok(common_dir) => Some(canonical(&repo_path.join(common_dir.trim()))),
+                    Err(e) => {
+                        tracing::warn!("Failed to find git directory of {:?}: {}", repo_path, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        if let Ok(mut dirs) = SANDBOXED_WORKSPACES.write() {
+            dirs.insert(canonical(workspace_dir), common_dirs);
+        }
+    }
+
     /// Run `git -C <repo> worktree add <path> <branch>` (optionally creating the branch with -b)
     pub fn worktree_add(
         &self,
@@ -644,6 +689,118 @@ impl GitCli {
 
 // Private methods
 impl GitCli {
+    /// The sandboxed workspace `repo_path` is in, with its repositories' git
+    /// directories
+    fn sandboxed_workspace(repo_path: &Path) -> Option<(PathBuf, Vec<PathBuf>)> {
+        let dirs = SANDBOXED_WORKSPACES.read().ok()?;
+        if dirs.is_empty() {
+            return None;
+        }
+        let repo_path = canonical(repo_path);
+        dirs.iter()
+            .find(|(dir, _)| repo_path.starts_with(dir))
+            .map(|(dir, common_dirs)| (dir.clone(), common_dirs.clone()))
+    }
+
+    /// Check that the worktree containing `repo_path` is still a linked
+    /// worktree of one of the workspace's repositories, rather than pointing
+    /// git at a directory the sandboxed process set up
+    fn sandboxed_worktree(
+        repo_path: &Path,
+        workspace_dir: &Path,
+        common_dirs: &[PathBuf],
+    ) -> Result<SandboxedWorktree, GitCliError> {
+        let untrusted =
+            |reason: &str| GitCliError::UntrustedWorktree(format!("{repo_path:?} {reason}"));
+        let repo_path = canonical(repo_path);
+        let root = repo_path
+            .ancestors()
+            .take_while(|dir| dir.starts_with(workspace_dir))
+            .find(|dir| dir.join(".git").symlink_metadata().is_ok())
+            .ok_or_else(|| untrusted("is not in a worktree"))?;
+        let dot_git = root.join(".git");
+        if !dot_git.symlink_metadata().is_ok_and(|meta| meta.is_file()) {
+            return Err(untrusted("has a .git that is not a file"));
+        }
+        let git_dir = std::fs::read_to_string(&dot_git)
+            .ok()
+            .and_then(|contents| {
+                let git_dir = contents.strip_prefix("gitdir:")?.trim();
+                Some(canonical(&root.join(git_dir)))
+            })
+            .ok_or_else(|| untrusted("has an unreadable .git file"))?;
+        // <common>/worktrees/<name>
+        let common_dir = git_dir
+            .parent()
+            .filter(|parent| parent.file_name() == Some(OsStr::new("worktrees")))
+            .and_then(Path::parent)
+            .filter(|common_dir| common_dirs.iter().any(|dir| dir == common_dir))
+            .ok_or_else(|| untrusted("does not point at a worktree of the workspace's repos"))?
+            .to_path_buf();
+        let listed_common_dir = std::fs::read_to_string(git_dir.join("commondir"))
+            .map(|common| canonical(&git_dir.join(common.trim())))
+            .ok();
+        if listed_common_dir.as_ref() != Some(&common_dir) {
+            return Err(untrusted(
+                "has a git directory pointing at another repository",
+            ));
+        }
+        Ok(SandboxedWorktree {
+            root: root.to_path_buf(),
+            git_dir,
+            common_dir,
+        })
+    }
+
+    /// Fail if config the sandboxed process could have written, e.g. through
+    /// the worktree's own config or an include, sets a command git would run
+    fn reject_untrusted_config(
+        git: &Path,
+        worktree: &SandboxedWorktree,
+        workspace_dir: &Path,
+    ) -> Result<(), GitCliError> {
+        let out = Command::new(git)
+            .current_dir(&worktree.root)
+            .arg("--git-dir")
+            .arg(&worktree.git_dir)
+            .arg("--work-tree")
+            .arg(&worktree.root)
+            .args(["config", "--null", "--show-origin", "--get-regexp"])
+            .arg(COMMAND_CONFIG_KEYS)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        // Exit code 1 means no key matched
+        if !out.status.success() && out.status.code() != Some(1) {
+            return Err(GitCliError::CommandFailed(
+                String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            ));
+        }
+        let repo_config = worktree.common_dir.join("config");
+        let output = String::from_utf8_lossy(&out.stdout);
+        let fields: Vec<&str> = output.split('\0').collect();
+        for entry in fields.chunks_exact(2) {
+            let (origin, key_value) = (entry[0], entry[1]);
+            let trusted = match origin.strip_prefix("file:") {
+                Some(file) => {
+                    let file = canonical(&worktree.root.join(file));
+                    file == repo_config
+                        || !(file.starts_with(workspace_dir)
+                            || file.starts_with(&worktree.common_dir))
+                }
+                None => origin == "command line:",
+            };
+            if !trusted {
+                let key = key_value.lines().next().unwrap_or_default();
+                return Err(GitCliError::UntrustedWorktree(format!(
+                    "{:?} sets {key} in {origin}",
+                    worktree.root
+                )));
+            }
+        }
+        Ok(())
+    }
+
     fn classify_cli_error(&self, msg: String) -> GitCliError {
         let lower = msg.to_ascii_lowercase();
         if lower.contains("authentication failed")
@@ -704,6 +861,20 @@ impl GitCli {
         let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
         let mut cmd = Command::new(&git);
         cmd.arg("-C").arg(repo_path);
+        if let Some((workspace_dir, common_dirs)) = Self::sandboxed_workspace(repo_path) {
+            let worktree = Self::sandboxed_worktree(repo_path, &workspace_dir, &common_dirs)?;
+            Self::reject_untrusted_config(&git, &worktree, &workspace_dir)?;
+            cmd.arg("--git-dir")
+                .arg(&worktree.git_dir)
+                .arg("--work-tree")
+                .arg(&worktree.root)
+                .args([
+                    "-c",
+                    "core.hooksPath=/dev/null",
+                    "-c",
+                    "core.fsmonitor=false",
+                ]);
+        }
 
         if let Some(envs) = envs {
             for (k, v) in envs {
@@ -850,6 +1021,12 @@ impl GitCli {
             .collect()
     }
 }
+
+/// `path` with symlinks resolved, or as given if it doesn't exist
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// Parsed entry from `git status --porcelain`
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StatusEntry {
@@ -1,8 +1,8 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 use backon::{ExponentialBuilder, Retryable};
 use chrono::{DateTime, Utc};
-use db::models::merge::PullRequestInfo;
+use db::models::merge::{PrCiState, PullRequestInfo};
 use regex::Regex;
 use serde::Serialize;
 use thiserror::Error;
@@ -44,7 +44,7 @@ pub enum UnifiedPrComment {
 }
 
 impl UnifiedPrComment {
-    fn created_at(&self) -> DateTime<Utc> {
+    pub fn created_at(&self) -> DateTime<Utc> {
         match self {
             UnifiedPrComment::General { created_at, .. } => *created_at,
             UnifiedPrComment::Review { created_at, .. } => *created_at,
@@ -371,6 +371,177 @@ impl GitHubService {
         Ok(unified)
     }
 
+    /// Inline review comments in threads that nobody has resolved, oldest first
+    pub async fn get_unresolved_review_comments(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHubServiceError> {
+        let (review_result, unresolved_result) = tokio::join!(
+            self.fetch_review_comments(repo_info, pr_number),
+            self.fetch_unresolved_review_comment_ids(repo_info, pr_number)
+        );
+        let unresolved: HashSet<i64> = unresolved_result?.into_iter().collect();
+
+        let mut comments: Vec<UnifiedPrComment> = review_result?
+            .into_iter()
+            .filter(|c| unresolved.contains(&c.id))
+            .map(|c| UnifiedPrComment::Review {
+                id: c.id,
+                author: c.user.login,
+                author_association: c.author_association,
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+                path: c.path,
+                line: c.line,
+                diff_hunk: c.diff_hunk,
+            })
+            .collect();
+        comments.sort_by_key(|c| c.created_at());
+
+        Ok(comments)
+    }
+
+    /// Reply in the thread of an inline review comment
+    pub async fn reply_to_review_comment(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let body = body.to_string();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || {
+            cli.reply_to_review_comment(&owner, &repo, pr_number, comment_id, &body)
+        })
+        .await
+        .map_err(|err| {
+            GitHubServiceError::PullRequest(format!(
+                "Failed to execute GitHub CLI for replying to review comment {comment_id}: {err}"
+            ))
+        })?
+        .map_err(GitHubServiceError::from)
+    }
+
+    /// CI checks on the head commit of a PR, `None` if it has none
+    pub async fn get_ci_status(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Option<PrCiState>, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let ci = task::spawn_blocking(move || cli.get_pr_checks(&owner, &repo, pr_number))
+                .await
+                .map_err(|err| {
+                    GitHubServiceError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for fetching PR #{pr_number} checks: {err}"
+                    ))
+                })?;
+            ci.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    /// Log output of the failed steps of a GitHub Actions job
+    pub async fn get_ci_job_log(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || cli.get_failed_job_log(&owner, &repo, job_id))
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching the log of job {job_id}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+
+    /// Point a PR at a different base branch
+    pub async fn update_pr_base(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GitHubServiceError> {
+        let owner = repo_info.owner.clone();
+        let repo = repo_info.repo_name.clone();
+        let base_branch = base_branch.to_string();
+        let cli = self.gh_cli.clone();
+        task::spawn_blocking(move || cli.update_pr_base(&owner, &repo, pr_number, &base_branch))
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for retargeting PR #{pr_number}: {err}"
+                ))
+            })?
+            .map_err(GitHubServiceError::from)
+    }
+
+    async fn fetch_unresolved_review_comment_ids(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GitHubServiceError> {
+        (|| async {
+            let owner = repo_info.owner.clone();
+            let repo = repo_info.repo_name.clone();
+            let cli = self.gh_cli.clone();
+            let ids = task::spawn_blocking(move || {
+                cli.get_unresolved_review_comment_ids(&owner, &repo, pr_number)
+            })
+            .await
+            .map_err(|err| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for fetching PR #{pr_number} review threads: {err}"
+                ))
+            })?;
+            ids.map_err(GitHubServiceError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHubServiceError| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
     async fn fetch_general_comments(
         &self,
         repo_info: &GitHubRepoInfo,
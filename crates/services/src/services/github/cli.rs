@@ -10,7 +10,7 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{CiCheck, CiStatus, MergeStatus, PrCiState, PullRequestInfo};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
@@ -58,6 +58,25 @@ pub struct PrReviewComment {
     pub author_association: String,
 }
 
+/// Review threads of a PR with the ids of their comments. Threads beyond the
+/// first 100, or comments beyond the first 100 of a thread, are not returned.
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          isResolved
+          comments(first: 100) {
+            nodes { databaseId }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
 /// High-level errors originating from the GitHub CLI.
 #[derive(Debug, Error)]
 pub enum GhCliError {
@@ -241,10 +260,109 @@ impl GhCli {
     ) -> Result<Vec<PrReviewComment>, GhCliError> {
         let raw = self.run([
             "api",
-            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments"),
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments?per_page=100"),
         ])?;
         Self::parse_pr_review_comments(&raw)
     }
+
+    /// Ids of the inline review comments in threads that are not resolved.
+    pub fn get_unresolved_review_comment_ids(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, GhCliError> {
+        let raw = self.run([
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={REVIEW_THREADS_QUERY}"),
+            "-f",
+            &format!("owner={owner}"),
+            "-f",
+            &format!("repo={repo}"),
+            "-F",
+            &format!("number={pr_number}"),
+        ])?;
+        Self::parse_unresolved_review_comment_ids(&raw)
+    }
+
+    /// Reply in the thread of an inline review comment.
+    pub fn reply_to_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "api",
+            "--method",
+            "POST",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/comments/{comment_id}/replies"),
+            "-f",
+            &format!("body={body}"),
+        ])?;
+        Ok(())
+    }
+
+    /// Fetch the check runs and commit statuses on the head commit of a pull request.
+    pub fn get_pr_checks(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Option<PrCiState>, GhCliError> {
+        let raw = self.run([
+            "pr",
+            "view",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--json",
+            "headRefOid,statusCheckRollup",
+        ])?;
+        Self::parse_pr_checks(&raw)
+    }
+
+    /// Fetch the log of the failed steps of a GitHub Actions job.
+    pub fn get_failed_job_log(
+        &self,
+        owner: &str,
+        repo: &str,
+        job_id: i64,
+    ) -> Result<String, GhCliError> {
+        self.run([
+            "run",
+            "view",
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--job",
+            &job_id.to_string(),
+            "--log-failed",
+        ])
+    }
+
+    /// Change the base branch of a pull request.
+    pub fn update_pr_base(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+        base_branch: &str,
+    ) -> Result<(), GhCliError> {
+        self.run([
+            "pr",
+            "edit",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
+            "--base",
+            base_branch,
+        ])?;
+        Ok(())
+    }
 }
 
 impl GhCli {
@@ -357,6 +475,118 @@ impl GhCli {
         })
     }
 
+    fn parse_unresolved_review_comment_ids(raw: &str) -> Result<Vec<i64>, GhCliError> {
+        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review threads response: {err}; raw: {raw}"
+            ))
+        })?;
+        let threads = value
+            .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Review threads response missing 'reviewThreads' nodes: {value:#?}"
+                ))
+            })?;
+
+        Ok(threads
+            .iter()
+            .filter(|thread| !thread["isResolved"].as_bool().unwrap_or(false))
+            .filter_map(|thread| thread.pointer("/comments/nodes").and_then(Value::as_array))
+            .flatten()
+            .filter_map(|comment| comment["databaseId"].as_i64())
+            .collect())
+    }
+
+    fn parse_pr_checks(raw: &str) -> Result<Option<PrCiState>, GhCliError> {
+        let value: Value = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh pr view --json statusCheckRollup response: {err}; raw: {raw}"
+            ))
+        })?;
+        let head_sha = value
+            .get("headRefOid")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput(format!(
+                    "gh pr view response missing 'headRefOid': {value:#?}"
+                ))
+            })?
+            .to_string();
+        let checks: Vec<CiCheck> = value
+            .get("statusCheckRollup")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Self::extract_check).collect())
+            .unwrap_or_default();
+        if checks.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(PrCiState {
+            head_sha,
+            status: CiStatus::combine(&checks),
+            checks,
+            checked_at: Utc::now(),
+        }))
+    }
+
+    /// Map a check run or a commit status context of the status check rollup
+    fn extract_check(value: &Value) -> Option<CiCheck> {
+        let field = |name: &str| value.get(name).and_then(Value::as_str).unwrap_or_default();
+        match field("__typename") {
+            "CheckRun" => {
+                let url = value
+                    .get("detailsUrl")
+                    .and_then(Value::as_str)
+                    .filter(|url| !url.is_empty())
+                    .map(str::to_string);
+                // Actions jobs link to `.../actions/runs/<run id>/job/<job id>`
+                let job_id = url.as_deref().and_then(|url| {
+                    url.split_once("/job/")?
+                        .1
+                        .split(|c: char| !c.is_ascii_digit())
+                        .next()?
+                        .parse()
+                        .ok()
+                });
+                let status = if !field("status").eq_ignore_ascii_case("COMPLETED") {
+                    CiStatus::Pending
+                } else {
+                    match field("conclusion").to_ascii_uppercase().as_str() {
+                        "SUCCESS" | "NEUTRAL" | "SKIPPED" => CiStatus::Success,
+                        _ => CiStatus::Failure,
+                    }
+                };
+                let name = match field("workflowName") {
+                    "" => field("name").to_string(),
+                    workflow => format!("{workflow} / {}", field("name")),
+                };
+                Some(CiCheck {
+                    name,
+                    status,
+                    url,
+                    job_id,
+                })
+            }
+            "StatusContext" => Some(CiCheck {
+                name: field("context").to_string(),
+                status: match field("state").to_ascii_uppercase().as_str() {
+                    "SUCCESS" => CiStatus::Success,
+                    "FAILURE" | "ERROR" => CiStatus::Failure,
+                    _ => CiStatus::Pending,
+                },
+                url: value
+                    .get("targetUrl")
+                    .and_then(Value::as_str)
+                    .filter(|url| !url.is_empty())
+                    .map(str::to_string),
+                job_id: None,
+            }),
+            _ => None,
+        }
+    }
+
     fn extract_pr_info(value: &Value) -> Option<PullRequestInfo> {
         let number = value.get("number")?.as_i64()?;
         let url = value.get("url")?.as_str()?.to_string();
@@ -0,0 +1,641 @@
+//! GitLab merge requests over the REST API (v4).
+//!
+//! Works against gitlab.com as well as self-hosted instances; the instance is
+//! derived from the repository's remote URL.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::merge::{CiCheck, CiStatus, MergeStatus, PrCiState, PullRequestInfo};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use tracing::info;
+use url::Url;
+
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+/// Notes are fetched page by page; stop after this many to bound very long threads
+const MAX_NOTE_PAGES: u32 = 50;
+const NOTES_PER_PAGE: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum GitLabServiceError {
+    #[error("Repository error: {0}")]
+    Repository(String),
+    #[error("Merge request error: {0}")]
+    MergeRequest(String),
+    #[error(
+        "GitLab token is not configured. Add a personal access token with the 'api' scope in settings or set GITLAB_TOKEN"
+    )]
+    TokenMissing,
+    #[error("GitLab authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("Insufficient permissions: {0}")]
+    InsufficientPermissions(String),
+    #[error("GitLab project not found or no access: {0}")]
+    ProjectNotFoundOrNoAccess(String),
+    #[error("GitLab API error ({status}): {message}")]
+    Api { status: u16, message: String },
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl GitLabServiceError {
+    pub fn should_retry(&self) -> bool {
+        match self {
+            GitLabServiceError::Http(_) => true,
+            GitLabServiceError::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabRepoInfo {
+    /// Web root of the instance, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    pub host: String,
+    /// Full project path including any subgroups, e.g. `group/subgroup/project`
+    pub project_path: String,
+}
+
+impl GitLabRepoInfo {
+    /// Parse an SSH, HTTPS or merge request URL. See tests for examples.
+    pub fn from_remote_url(remote_url: &str) -> Result<Self, GitLabServiceError> {
+        let remote_url = remote_url.trim();
+        let invalid =
+            || GitLabServiceError::Repository(format!("Invalid GitLab URL format: {remote_url}"));
+
+        let (base_url, host, path) = if remote_url.contains("://") {
+            let url = Url::parse(remote_url).map_err(|_| invalid())?;
+            let host = url.host_str().ok_or_else(invalid)?.to_string();
+            let base_url = match url.scheme() {
+                "http" | "https" => match url.port() {
+                    Some(port) => format!("{}://{host}:{port}", url.scheme()),
+                    None => format!("{}://{host}", url.scheme()),
+                },
+                // SSH remotes: the web UI and API live on the same host over HTTPS
+                _ => format!("https://{host}"),
+            };
+            (base_url, host, url.path().to_string())
+        } else {
+            // scp-like syntax: [user@]host:group/project.git
+            let (authority, path) = remote_url.split_once(':').ok_or_else(invalid)?;
+            let host = authority
+                .rsplit('@')
+                .next()
+                .unwrap_or(authority)
+                .to_string();
+            (format!("https://{host}"), host, path.to_string())
+        };
+
+        // Drop web UI suffixes such as `/-/merge_requests/12`
+        let path = path.split("/-/").next().unwrap_or_default();
+        let path = path.trim_matches('/');
+        let project_path = path.strip_suffix(".git").unwrap_or(path).to_string();
+
+        if host.is_empty() || !project_path.contains('/') {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            base_url,
+            host,
+            project_path,
+        })
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api/v4{path}", self.base_url)
+    }
+
+    fn project_api_url(&self, path: &str) -> String {
+        let project_id: String =
+            url::form_urlencoded::byte_serialize(self.project_path.as_bytes()).collect();
+        self.api_url(&format!("/projects/{project_id}{path}"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabMergeRequest {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+    /// Head commit of the source branch
+    #[serde(default)]
+    sha: Option<String>,
+    /// Only included when a single merge request is fetched
+    #[serde(default)]
+    head_pipeline: Option<GitLabPipeline>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabPipeline {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabJob {
+    id: i64,
+    name: String,
+    status: String,
+    web_url: Option<String>,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+impl From<GitLabJob> for CiCheck {
+    fn from(job: GitLabJob) -> Self {
+        let status = match job.status.as_str() {
+            "success" | "skipped" | "manual" => CiStatus::Success,
+            // Jobs allowed to fail only warn in the pipeline
+            "failed" if job.allow_failure => CiStatus::Success,
+            "failed" | "canceled" => CiStatus::Failure,
+            _ => CiStatus::Pending,
+        };
+        CiCheck {
+            name: job.name,
+            status,
+            url: job.web_url,
+            job_id: Some(job.id),
+        }
+    }
+}
+
+impl From<GitLabMergeRequest> for PullRequestInfo {
+    fn from(mr: GitLabMergeRequest) -> Self {
+        let status = match mr.state.as_str() {
+            // Locked MRs are open with the discussion frozen
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CreateMergeRequestBody<'a> {
+    source_branch: &'a str,
+    target_branch: &'a str,
+    title: String,
+    description: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNote {
+    id: i64,
+    body: String,
+    author: GitLabUser,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    system: bool,
+    position: Option<GitLabNotePosition>,
+    #[serde(default)]
+    resolvable: bool,
+    #[serde(default)]
+    resolved: bool,
+}
+
+/// Thread of notes; diff notes with their replies form one discussion
+#[derive(Debug, Deserialize)]
+struct GitLabDiscussion {
+    id: String,
+    notes: Vec<GitLabNote>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateNoteBody<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateMergeRequestBody<'a> {
+    target_branch: &'a str,
+}
+
+impl GitLabNote {
+    fn into_unified(self, mr_url: &str) -> UnifiedPrComment {
+        let url = format!("{mr_url}#note_{}", self.id);
+        match self.position {
+            Some(position) => UnifiedPrComment::Review {
+                id: self.id,
+                author: self.author.username,
+                author_association: String::new(),
+                body: self.body,
+                created_at: self.created_at,
+                url,
+                path: position.new_path.or(position.old_path).unwrap_or_default(),
+                line: position.new_line.or(position.old_line),
+                diff_hunk: String::new(),
+            },
+            None => UnifiedPrComment::General {
+                id: self.id.to_string(),
+                author: self.author.username,
+                author_association: String::new(),
+                body: self.body,
+                created_at: self.created_at,
+                url,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabService {
+    client: Client,
+    token: String,
+}
+
+impl GitLabService {
+    /// Create a new GitLab service authenticating with a personal access token
+    pub fn new(token: Option<String>) -> Result<Self, GitLabServiceError> {
+        let token = token
+            .filter(|token| !token.is_empty())
+            .ok_or(GitLabServiceError::TokenMissing)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("vibe-kanban")
+            .build()?;
+        Ok(Self { client, token })
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, GitLabServiceError> {
+        Ok(self.send_raw(request).await?.json::<T>().await?)
+    }
+
+    async fn send_raw(&self, request: RequestBuilder) -> Result<Response, GitLabServiceError> {
+        let response = request.header("PRIVATE-TOKEN", &self.token).send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let message = response.text().await.unwrap_or_default();
+        Err(match status {
+            StatusCode::UNAUTHORIZED => GitLabServiceError::AuthFailed(message),
+            StatusCode::FORBIDDEN => GitLabServiceError::InsufficientPermissions(message),
+            StatusCode::NOT_FOUND => GitLabServiceError::ProjectNotFoundOrNoAccess(message),
+            _ => GitLabServiceError::Api {
+                status: status.as_u16(),
+                message,
+            },
+        })
+    }
+
+    fn retry_policy() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter()
+    }
+
+    fn notify_retry(err: &GitLabServiceError, dur: Duration) {
+        tracing::warn!(
+            "GitLab API call failed, retrying after {:.2}s: {}",
+            dur.as_secs_f64(),
+            err
+        );
+    }
+
+    pub async fn check_token(&self, repo_info: &GitLabRepoInfo) -> Result<(), GitLabServiceError> {
+        let _: serde_json::Value = self
+            .send(self.client.get(repo_info.api_url("/user")))
+            .await?;
+        Ok(())
+    }
+
+    /// Create a merge request on GitLab
+    pub async fn create_pr(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreateMergeRequestBody {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref(),
+        };
+
+        let mr: GitLabMergeRequest = (|| async {
+            self.send(
+                self.client
+                    .post(repo_info.project_api_url("/merge_requests"))
+                    .json(&body),
+            )
+            .await
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(Self::notify_retry)
+        .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid, request.head_branch, repo_info.project_path
+        );
+
+        Ok(mr.into())
+    }
+
+    /// Get the current status of a merge request
+    pub async fn update_pr_status(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        let mr: GitLabMergeRequest = (|| async {
+            self.send(
+                self.client
+                    .get(repo_info.project_api_url(&format!("/merge_requests/{mr_iid}"))),
+            )
+            .await
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(Self::notify_retry)
+        .await?;
+
+        Ok(mr.into())
+    }
+
+    /// List all merge requests for a source branch (including closed/merged), open ones first
+    pub async fn list_all_prs_for_branch(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitLabServiceError> {
+        let mrs: Vec<GitLabMergeRequest> = (|| async {
+            self.send(
+                self.client
+                    .get(repo_info.project_api_url("/merge_requests"))
+                    .query(&[
+                        ("source_branch", branch_name),
+                        ("state", "all"),
+                        ("order_by", "created_at"),
+                        ("sort", "desc"),
+                    ]),
+            )
+            .await
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(Self::notify_retry)
+        .await?;
+
+        let mut prs: Vec<PullRequestInfo> = mrs.into_iter().map(Into::into).collect();
+        prs.sort_by_key(|pr| !matches!(pr.status, MergeStatus::Open));
+        Ok(prs)
+    }
+
+    /// Fetch all user comments (general and diff notes) for a merge request
+    pub async fn get_pr_comments(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitLabServiceError> {
+        let mr_url = self.update_pr_status(repo_info, mr_iid).await?.url;
+
+        let mut comments = Vec::new();
+        for page in 1..=MAX_NOTE_PAGES {
+            let notes: Vec<GitLabNote> = (|| async {
+                self.send(
+                    self.client
+                        .get(repo_info.project_api_url(&format!("/merge_requests/{mr_iid}/notes")))
+                        .query(&[
+                            ("sort", "asc".to_string()),
+                            ("order_by", "created_at".to_string()),
+                            ("per_page", NOTES_PER_PAGE.to_string()),
+                            ("page", page.to_string()),
+                        ]),
+                )
+                .await
+            })
+            .retry(Self::retry_policy())
+            .when(|e: &GitLabServiceError| e.should_retry())
+            .notify(Self::notify_retry)
+            .await?;
+
+            let is_last_page = notes.len() < NOTES_PER_PAGE;
+            comments.extend(
+                notes
+                    .into_iter()
+                    .filter(|note| !note.system)
+                    .map(|note| note.into_unified(&mr_url)),
+            );
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// Diff notes in discussions that are not resolved yet, oldest first
+    pub async fn get_unresolved_review_comments(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitLabServiceError> {
+        let mr_url = self.update_pr_status(repo_info, mr_iid).await?.url;
+
+        let mut comments: Vec<UnifiedPrComment> = self
+            .get_discussions(repo_info, mr_iid)
+            .await?
+            .into_iter()
+            .filter(|discussion| {
+                discussion
+                    .notes
+                    .iter()
+                    .any(|note| note.resolvable && !note.resolved)
+            })
+            .flat_map(|discussion| discussion.notes)
+            .filter(|note| !note.system && note.position.is_some())
+            .map(|note| note.into_unified(&mr_url))
+            .collect();
+        comments.sort_by_key(|comment| comment.created_at());
+
+        Ok(comments)
+    }
+
+    /// Reply in the discussion of a diff note
+    pub async fn reply_to_review_comment(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+        note_id: i64,
+        body: &str,
+    ) -> Result<(), GitLabServiceError> {
+        let discussion = self
+            .get_discussions(repo_info, mr_iid)
+            .await?
+            .into_iter()
+            .find(|discussion| discussion.notes.iter().any(|note| note.id == note_id))
+            .ok_or_else(|| {
+                GitLabServiceError::MergeRequest(format!(
+                    "No discussion of !{mr_iid} contains note {note_id}"
+                ))
+            })?;
+
+        let _: serde_json::Value = self
+            .send(
+                self.client
+                    .post(repo_info.project_api_url(&format!(
+                        "/merge_requests/{mr_iid}/discussions/{}/notes",
+                        discussion.id
+                    )))
+                    .json(&CreateNoteBody { body }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Jobs of the head pipeline of a merge request, `None` if it has no pipeline
+    pub async fn get_ci_status(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Option<PrCiState>, GitLabServiceError> {
+        let mr: GitLabMergeRequest = (|| async {
+            self.send(
+                self.client
+                    .get(repo_info.project_api_url(&format!("/merge_requests/{mr_iid}"))),
+            )
+            .await
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(Self::notify_retry)
+        .await?;
+        let (Some(head_sha), Some(pipeline)) = (mr.sha, mr.head_pipeline) else {
+            return Ok(None);
+        };
+
+        let jobs: Vec<GitLabJob> = (|| async {
+            self.send(
+                self.client
+                    .get(repo_info.project_api_url(&format!("/pipelines/{}/jobs", pipeline.id)))
+                    .query(&[("per_page", NOTES_PER_PAGE.to_string())]),
+            )
+            .await
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &GitLabServiceError| e.should_retry())
+        .notify(Self::notify_retry)
+        .await?;
+        let checks: Vec<CiCheck> = jobs.into_iter().map(Into::into).collect();
+        if checks.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(PrCiState {
+            head_sha,
+            status: CiStatus::combine(&checks),
+            checks,
+            checked_at: Utc::now(),
+        }))
+    }
+
+    /// Full log output of a CI job
+    pub async fn get_ci_job_log(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        job_id: i64,
+    ) -> Result<String, GitLabServiceError> {
+        let response = self
+            .send_raw(
+                self.client
+                    .get(repo_info.project_api_url(&format!("/jobs/{job_id}/trace"))),
+            )
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// Point a merge request at a different target branch
+    pub async fn update_pr_base(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+        target_branch: &str,
+    ) -> Result<(), GitLabServiceError> {
+        let _: GitLabMergeRequest = self
+            .send(
+                self.client
+                    .put(repo_info.project_api_url(&format!("/merge_requests/{mr_iid}")))
+                    .json(&UpdateMergeRequestBody { target_branch }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_discussions(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Vec<GitLabDiscussion>, GitLabServiceError> {
+        let mut discussions = Vec::new();
+        for page in 1..=MAX_NOTE_PAGES {
+            let batch: Vec<GitLabDiscussion> = (|| async {
+                self.send(
+                    self.client
+                        .get(
+                            repo_info
+                                .project_api_url(&format!("/merge_requests/{mr_iid}/discussions")),
+                        )
+                        .query(&[
+                            ("per_page", NOTES_PER_PAGE.to_string()),
+                            ("page", page.to_string()),
+                        ]),
+                )
+                .await
+            })
+            .retry(Self::retry_policy())
+            .when(|e: &GitLabServiceError| e.should_retry())
+            .notify(Self::notify_retry)
+            .await?;
+
+            let is_last_page = batch.len() < NOTES_PER_PAGE;
+            discussions.extend(batch);
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(discussions)
+    }
+}
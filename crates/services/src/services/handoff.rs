@@ -0,0 +1,427 @@
+//! Carrying a workspace's work over into a fresh agent session.
+//!
+//! A native session id from one agent can't be resumed by another, and a
+//! session that outgrew the context window can't be resumed at all. In both
+//! cases a fresh session starts whose first prompt tells the agent what
+//! happened so far: the previous agent's last summary, the files it touched
+//! and its todo list, taken from the normalized conversation, followed by the
+//! turns and the changes in the workspace.
+
+use db::models::{coding_agent_turn::CodingAgentTurn, task::Task, workspace::AttemptResumeContext};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType, TodoItem};
+use serde_json::Value;
+use utils::diff::{Diff, create_unified_diff};
+
+use crate::services::git::GitService;
+
+/// Earlier turns beyond this are cut from the start of the history
+const MAX_HISTORY_CHARS: usize = 12_000;
+/// Changes beyond this are cut, the new agent can look at the worktree
+const MAX_DIFF_CHARS: usize = 30_000;
+/// Heading that ends the prompts built here, followed by the user's request
+const INSTRUCTIONS_HEADING: &str = "## Your instructions\n\n";
+
+/// What the previous agent's conversation says about where it left off
+#[derive(Debug, Default, Clone)]
+pub struct ConversationDigest {
+    /// Last thing the agent told the user
+    pub summary: Option<String>,
+    /// Files the agent edited, in the order it first touched them
+    pub files_touched: Vec<String>,
+    /// The agent's latest todo list
+    pub todos: Vec<TodoItem>,
+    /// Prompt of the last turn, which may not be done yet
+    pub last_request: Option<String>,
+}
+
+impl ConversationDigest {
+    /// Fold in the entries of one turn; later turns win
+    pub fn add_entries(&mut self, entries: &[NormalizedEntry]) {
+        for entry in entries {
+            match &entry.entry_type {
+                NormalizedEntryType::AssistantMessage if !entry.content.trim().is_empty() => {
+                    self.summary = Some(entry.content.trim().to_string());
+                }
+                NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+                    ActionType::FileEdit { path, .. } => {
+                        if !self.files_touched.contains(path) {
+                            self.files_touched.push(path.clone());
+                        }
+                    }
+                    ActionType::TodoManagement { todos, .. } if !todos.is_empty() => {
+                        self.todos = todos.clone();
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Normalized entries among a conversation's stored patch values
+pub fn normalized_entries(values: &[Value]) -> Vec<NormalizedEntry> {
+    values
+        .iter()
+        .filter(|value| value.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY"))
+        .filter_map(|value| serde_json::from_value(value.get("content")?.clone()).ok())
+        .collect()
+}
+
+/// Resume context of a workspace: its coding agent turns and its changes
+/// against the target branches
+pub fn resume_context(turns: &[CodingAgentTurn], diffs: &[Diff]) -> AttemptResumeContext {
+    let mut execution_history = String::new();
+    for (index, turn) in turns.iter().enumerate() {
+        execution_history.push_str(&format!("### Turn {}\n", index + 1));
+        if let Some(prompt) = &turn.prompt {
+            execution_history.push_str(&format!("User:\n{}\n", user_request(prompt)));
+        }
+        if let Some(summary) = &turn.summary {
+            execution_history.push_str(&format!("Agent:\n{}\n", summary.trim()));
+        }
+        execution_history.push('\n');
+    }
+
+    let mut cumulative_diffs = String::new();
+    for diff in diffs {
+        let path = GitService::diff_path(diff);
+        if diff.content_omitted {
+            cumulative_diffs.push_str(&format!("{path}: changed, too large to show\n"));
+            continue;
+        }
+        cumulative_diffs.push_str(&create_unified_diff(
+            &path,
+            diff.old_content.as_deref().unwrap_or(""),
+            diff.new_content.as_deref().unwrap_or(""),
+        ));
+    }
+
+    AttemptResumeContext {
+        execution_history: keep_end(execution_history.trim_end(), MAX_HISTORY_CHARS),
+        cumulative_diffs: keep_start(cumulative_diffs.trim_end(), MAX_DIFF_CHARS),
+    }
+}
+
+/// First prompt for the agent taking over
+pub fn handoff_prompt(
+    task: &Task,
+    previous_executor: &str,
+    digest: &ConversationDigest,
+    context: &AttemptResumeContext,
+    prompt: &str,
+) -> String {
+    continuation_prompt(
+        &format!(
+            "You are taking over this task from another coding agent ({previous_executor}). Its session can't be resumed, so this is what happened so far."
+        ),
+        task,
+        digest,
+        context,
+        prompt.trim(),
+    )
+}
+
+/// First prompt of the session replacing one that ran out of context window.
+/// It repeats the last request of that session, which may not be done yet.
+pub fn compaction_prompt(
+    task: &Task,
+    digest: &ConversationDigest,
+    context: &AttemptResumeContext,
+) -> String {
+    let instructions = match &digest.last_request {
+        Some(request) => format!(
+            "Continue where your previous session left off. Its last request was:\n\n{}",
+            user_request(request)
+        ),
+        None => "Continue where your previous session left off.".to_string(),
+    };
+    continuation_prompt(
+        "Your previous session on this task ran out of context window, so it was replaced by this summary of what happened so far.",
+        task,
+        digest,
+        context,
+        &instructions,
+    )
+}
+
+fn continuation_prompt(
+    intro: &str,
+    task: &Task,
+    digest: &ConversationDigest,
+    context: &AttemptResumeContext,
+    instructions: &str,
+) -> String {
+    let mut prompt = format!(
+        "{intro} The working tree already contains the changes made so far.\n\n## Task\n\n{}\n",
+        task.title.trim()
+    );
+    if let Some(description) = task
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty())
+    {
+        prompt.push_str(&format!("\n{}\n", description.trim()));
+    }
+    if let Some(summary) = &digest.summary {
+        prompt.push_str(&format!(
+            "\n## Where the previous session left off\n\n{summary}\n"
+        ));
+    }
+    if !digest.files_touched.is_empty() {
+        prompt.push_str("\n## Files touched\n\n");
+        for file in &digest.files_touched {
+            prompt.push_str(&format!("- {file}\n"));
+        }
+    }
+    if !digest.todos.is_empty() {
+        prompt.push_str("\n## Todo list\n\n");
+        for todo in &digest.todos {
+            let done = if todo.status.eq_ignore_ascii_case("completed") {
+                "x"
+            } else {
+                " "
+            };
+            prompt.push_str(&format!("- [{done}] {} ({})\n", todo.content, todo.status));
+        }
+    }
+    if !context.execution_history.is_empty() {
+        prompt.push_str(&format!(
+            "\n## Conversation so far\n\n{}\n",
+            context.execution_history
+        ));
+    }
+    if !context.cumulative_diffs.is_empty() {
+        prompt.push_str(&format!(
+            "\n## Changes so far\n\n```diff\n{}\n```\n",
+            context.cumulative_diffs
+        ));
+    }
+    prompt.push_str(&format!("\n{INSTRUCTIONS_HEADING}{instructions}"));
+    prompt
+}
+
+/// What the user asked for in a turn's prompt. Prompts built here carry the
+/// previous session's summary, of which only the request at the end is kept.
+fn user_request(prompt: &str) -> &str {
+    prompt
+        .rsplit_once(INSTRUCTIONS_HEADING)
+        .map_or(prompt, |(_, request)| request)
+        .trim()
+}
+
+fn keep_end(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().skip(count - max_chars).collect();
+    format!("[earlier turns omitted]\n{kept}")
+}
+
+fn keep_start(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{kept}\n[remaining changes omitted]")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::task::TaskStatus;
+    use executors::logs::ToolStatus;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status: ToolStatus::Success,
+            },
+            "",
+        )
+    }
+
+    fn todo(content: &str, status: &str) -> TodoItem {
+        TodoItem {
+            content: content.to_string(),
+            status: status.to_string(),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn digest_keeps_latest_summary_and_todos() {
+        let mut digest = ConversationDigest::default();
+        digest.add_entries(&[
+            entry(NormalizedEntryType::AssistantMessage, "Planning"),
+            tool(ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                changes: vec![],
+            }),
+            tool(ActionType::TodoManagement {
+                todos: vec![todo("Write parser", "in_progress")],
+                operation: "write".to_string(),
+            }),
+        ]);
+        digest.add_entries(&[
+            tool(ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                changes: vec![],
+            }),
+            tool(ActionType::FileEdit {
+                path: "src/parser.rs".to_string(),
+                changes: vec![],
+            }),
+            tool(ActionType::TodoManagement {
+                todos: vec![
+                    todo("Write parser", "completed"),
+                    todo("Add tests", "pending"),
+                ],
+                operation: "write".to_string(),
+            }),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Parser done, tests left\n",
+            ),
+        ]);
+
+        assert_eq!(digest.summary.as_deref(), Some("Parser done, tests left"));
+        assert_eq!(digest.files_touched, vec!["src/lib.rs", "src/parser.rs"]);
+        assert_eq!(digest.todos.len(), 2);
+        assert_eq!(digest.todos[0].status, "completed");
+    }
+
+    #[test]
+    fn normalized_entries_skip_other_patches() {
+        let values = vec![
+            json!({"type": "STDOUT", "content": "hello"}),
+            json!({
+                "type": "NORMALIZED_ENTRY",
+                "content": {
+                    "timestamp": null,
+                    "entry_type": {"type": "assistant_message"},
+                    "content": "Done",
+                    "metadata": null,
+                },
+            }),
+        ];
+        let entries = normalized_entries(&values);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "Done");
+    }
+
+    #[test]
+    fn handoff_prompt_lists_context_before_instructions() {
+        let now = Utc::now();
+        let task = Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Add a CSV parser".to_string(),
+            description: None,
+            status: TaskStatus::InProgress,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let digest = ConversationDigest {
+            summary: Some("Parser done, tests left".to_string()),
+            files_touched: vec!["src/parser.rs".to_string()],
+            todos: vec![todo("Add tests", "pending")],
+            last_request: None,
+        };
+        let context = AttemptResumeContext {
+            execution_history: "### Turn 1\nUser:\nPlan it\n".to_string(),
+            cumulative_diffs: String::new(),
+        };
+
+        let prompt = handoff_prompt(&task, "CODEX", &digest, &context, " Write the tests ");
+        assert!(
+            prompt.starts_with("You are taking over this task from another coding agent (CODEX).")
+        );
+        assert!(prompt.contains("## Files touched\n\n- src/parser.rs\n"));
+        assert!(prompt.contains("- [ ] Add tests (pending)\n"));
+        assert!(!prompt.contains("## Changes so far"));
+        assert!(prompt.ends_with("## Your instructions\n\nWrite the tests"));
+    }
+
+    #[test]
+    fn compaction_prompt_repeats_request_without_nesting() {
+        let now = Utc::now();
+        let task = Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Add a CSV parser".to_string(),
+            description: None,
+            status: TaskStatus::InProgress,
+            parent_workspace_id: None,
+            shared_task_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let context = AttemptResumeContext {
+            execution_history: String::new(),
+            cumulative_diffs: String::new(),
+        };
+        let earlier = compaction_prompt(
+            &task,
+            &ConversationDigest {
+                last_request: Some("Handle quoted fields".to_string()),
+                ..Default::default()
+            },
+            &context,
+        );
+
+        let prompt = compaction_prompt(
+            &task,
+            &ConversationDigest {
+                last_request: Some(earlier),
+                ..Default::default()
+            },
+            &context,
+        );
+        assert!(prompt.starts_with("Your previous session on this task ran out of context window"));
+        assert!(prompt.ends_with("Its last request was:\n\nHandle quoted fields"));
+        assert_eq!(prompt.matches("## Task").count(), 1);
+    }
+
+    #[test]
+    fn resume_context_keeps_latest_turns() {
+        let now = Utc::now();
+        let turns: Vec<CodingAgentTurn> = (0..2000)
+            .map(|i| CodingAgentTurn {
+                id: Uuid::new_v4(),
+                execution_process_id: Uuid::new_v4(),
+                agent_session_id: None,
+                prompt: Some(format!("prompt {i}")),
+                summary: None,
+                created_at: now,
+                updated_at: now,
+            })
+            .collect();
+        let context = resume_context(&turns, &[]);
+        assert!(
+            context
+                .execution_history
+                .starts_with("[earlier turns omitted]")
+        );
+        assert!(context.execution_history.ends_with("prompt 1999"));
+        assert!(context.cumulative_diffs.is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+//! Retention of execution process logs.
+//!
+//! Logs are appended to `execution_process_logs` as JSONL while a process
+//! runs. Once it has been finished for a while they are compacted into one
+//! zstd-compressed blob, together with the final conversation of coding
+//! agents, and later the raw logs can be pruned while the conversation is
+//! kept. Readers go through [`load_log_messages`] and [`load_conversation`],
+//! which look in both places.
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_log_archive::ExecutionProcessLogArchive,
+    execution_process_logs::ExecutionProcessLogs,
+};
+use json_patch::Patch;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::{config::LogRetentionConfig, container::ContainerService};
+
+/// Logs are compacted once, in the background, so spend some CPU on size
+const COMPRESSION_LEVEL: i32 = 9;
+
+#[derive(Debug, Error)]
+pub enum LogRetentionError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// What one pass of the retention policy did
+#[derive(Debug, Default)]
+pub struct LogRetentionReport {
+    pub compacted: usize,
+    pub pruned: usize,
+    pub freed_bytes: i64,
+}
+
+pub fn compress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    zstd::encode_all(data, COMPRESSION_LEVEL)
+}
+
+pub fn decompress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    zstd::decode_all(data)
+}
+
+/// JSONL logs of a process, or None if it has none or they were pruned
+pub async fn load_raw_logs(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+) -> Result<Option<String>, LogRetentionError> {
+    let records = ExecutionProcessLogs::find_by_execution_id(pool, execution_id).await?;
+    if !records.is_empty() {
+        return Ok(Some(join_records(&records)));
+    }
+
+    let Some(raw_logs) = ExecutionProcessLogArchive::find_by_execution_id(pool, execution_id)
+        .await?
+        .and_then(|archive| archive.raw_logs)
+    else {
+        return Ok(None);
+    };
+    let logs = String::from_utf8(decompress(&raw_logs)?).map_err(std::io::Error::other)?;
+    Ok(Some(logs))
+}
+
+/// Stored log messages of a process, compacted or not
+pub async fn load_log_messages(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+) -> Result<Option<Vec<LogMsg>>, LogRetentionError> {
+    let Some(logs) = load_raw_logs(pool, execution_id).await? else {
+        return Ok(None);
+    };
+    let messages = logs
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<LogMsg>, _>>()?;
+    Ok(Some(messages))
+}
+
+/// Conversation entries kept when a coding agent's logs were compacted
+pub async fn load_conversation(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+) -> Result<Option<Vec<Value>>, LogRetentionError> {
+    let Some(conversation) = ExecutionProcessLogArchive::find_by_execution_id(pool, execution_id)
+        .await?
+        .and_then(|archive| archive.conversation)
+    else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&decompress(&conversation)?)?))
+}
+
+/// Patches that rebuild a kept conversation, as the normalizer would have
+/// streamed them
+pub fn conversation_patches(entries: Vec<Value>) -> Vec<Patch> {
+    entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            serde_json::from_value(json!([
+                {"op": "add", "path": format!("/entries/{index}"), "value": entry}
+            ]))
+            .ok()
+        })
+        .collect()
+}
+
+fn join_records(records: &[ExecutionProcessLogs]) -> String {
+    let mut logs = String::new();
+    for record in records {
+        logs.push_str(&record.logs);
+        if !logs.ends_with('\n') {
+            logs.push('\n');
+        }
+    }
+    logs
+}
+
+/// Replace the stored logs of a finished process with a compressed blob.
+/// Returns the bytes freed, or None if the process has no uncompacted logs.
+pub async fn compact_logs(
+    pool: &SqlitePool,
+    execution_id: Uuid,
+    conversation: Option<Vec<Value>>,
+) -> Result<Option<i64>, LogRetentionError> {
+    let records = ExecutionProcessLogs::find_by_execution_id(pool, execution_id).await?;
+    if records.is_empty() {
+        return Ok(None);
+    }
+    let raw_byte_size: i64 = records.iter().map(|record| record.byte_size).sum();
+    let logs = join_records(&records);
+
+    let (raw_logs, conversation) = tokio::task::spawn_blocking(
+        move || -> Result<(Vec<u8>, Option<Vec<u8>>), LogRetentionError> {
+            let raw_logs = compress(logs.as_bytes())?;
+            let conversation = match conversation {
+                Some(entries) => Some(compress(&serde_json::to_vec(&entries)?)?),
+                None => None,
+            };
+            Ok((raw_logs, conversation))
+        },
+    )
+    .await
+    .map_err(std::io::Error::other)??;
+
+    let stored_byte_size = (raw_logs.len() + conversation.as_ref().map_or(0, Vec::len)) as i64;
+    ExecutionProcessLogArchive::replace_logs(
+        pool,
+        execution_id,
+        &raw_logs,
+        conversation.as_deref(),
+        raw_byte_size,
+    )
+    .await?;
+    Ok(Some(raw_byte_size - stored_byte_size))
+}
+
+/// Compact one process, keeping the conversation if it's a coding agent
+async fn compact_process<C>(container: &C, execution_id: Uuid) -> Result<i64, LogRetentionError>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &container.db().pool;
+    let conversation = match ExecutionProcess::find_by_id(pool, execution_id).await? {
+        Some(process) if process.run_reason == ExecutionProcessRunReason::CodingAgent => {
+            container.stored_conversation(&process).await
+        }
+        _ => None,
+    };
+    if conversation.is_none() {
+        tracing::debug!(
+            "Compacting logs of execution {} without a conversation",
+            execution_id
+        );
+    }
+    Ok(compact_logs(pool, execution_id, conversation)
+        .await?
+        .unwrap_or(0))
+}
+
+fn days_ago(days: u32) -> DateTime<Utc> {
+    Utc::now() - Duration::days(i64::from(days))
+}
+
+/// Apply the retention policy to the logs of finished processes. A process
+/// that fails to compact is logged and skipped.
+pub async fn apply_policy<C>(
+    container: &C,
+    policy: &LogRetentionConfig,
+) -> Result<LogRetentionReport, LogRetentionError>
+where
+    C: ContainerService + Sync,
+{
+    let pool = &container.db().pool;
+    let mut report = LogRetentionReport::default();
+
+    let compact = |execution_id: Uuid| async move {
+        match compact_process(container, execution_id).await {
+            Ok(freed) => Some(freed),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to compact logs of execution {}: {}",
+                    execution_id,
+                    e
+                );
+                None
+            }
+        }
+    };
+
+    if let Some(days) = policy.compact_after_days {
+        for execution_id in
+            ExecutionProcessLogArchive::find_uncompacted(pool, days_ago(days)).await?
+        {
+            if let Some(freed) = compact(execution_id).await {
+                report.compacted += 1;
+                report.freed_bytes += freed;
+            }
+        }
+    }
+
+    if let Some(days) = policy.prune_raw_after_days {
+        let cutoff = days_ago(days);
+        // Only compacted logs are pruned, so their conversation is kept first
+        for execution_id in ExecutionProcessLogArchive::find_uncompacted(pool, cutoff).await? {
+            if let Some(freed) = compact(execution_id).await {
+                report.compacted += 1;
+                report.freed_bytes += freed;
+            }
+        }
+        for execution_id in ExecutionProcessLogArchive::find_prunable(pool, cutoff).await? {
+            report.freed_bytes +=
+                ExecutionProcessLogArchive::prune_raw_logs(pool, execution_id).await?;
+            report.pruned += 1;
+        }
+    }
+
+    if let Some(max_mb) = policy.max_storage_mb {
+        let limit = i64::from(max_mb) * 1024 * 1024;
+        let mut used = ExecutionProcessLogArchive::total_stored_bytes(pool).await?;
+        if used > limit {
+            for execution_id in
+                ExecutionProcessLogArchive::find_uncompacted(pool, Utc::now()).await?
+            {
+                if used <= limit {
+                    break;
+                }
+                if let Some(freed) = compact(execution_id).await {
+                    report.compacted += 1;
+                    report.freed_bytes += freed;
+                    used -= freed;
+                }
+            }
+            for execution_id in ExecutionProcessLogArchive::find_prunable(pool, Utc::now()).await? {
+                if used <= limit {
+                    break;
+                }
+                let freed = ExecutionProcessLogArchive::prune_raw_logs(pool, execution_id).await?;
+                report.pruned += 1;
+                report.freed_bytes += freed;
+                used -= freed;
+            }
+        }
+        if used > limit {
+            tracing::warn!(
+                "Execution logs still take {} bytes, over the {} MB limit; what's left belongs to running processes or coding agents whose conversation couldn't be kept",
+                used,
+                max_mb
+            );
+        }
+    }
+
+    // Deleting rows only frees pages inside the database file
+    if (report.compacted > 0 || report.pruned > 0)
+        && let Err(e) = container.db().reclaim_space().await
+    {
+        tracing::warn!(
+            "Failed to reclaim database space after compacting logs: {}",
+            e
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::attempt_archive::conversation_entries;
+
+    #[test]
+    fn test_compress_round_trip() {
+        let logs = "{\"Stdout\":\"hello\"}\n".repeat(1000);
+        let compressed = compress(logs.as_bytes()).unwrap();
+        assert!(compressed.len() < logs.len() / 10);
+        assert_eq!(decompress(&compressed).unwrap(), logs.as_bytes());
+    }
+
+    #[test]
+    fn test_conversation_patches_rebuild_entries() {
+        let entries = vec![
+            json!({"type": "STDOUT", "content": "a"}),
+            json!({"type": "STDERR", "content": "b"}),
+        ];
+        let patches = conversation_patches(entries.clone());
+        assert_eq!(patches.len(), 2);
+        assert_eq!(conversation_entries(&patches), entries);
+    }
+}
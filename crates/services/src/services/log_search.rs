@@ -0,0 +1,156 @@
+//! Feeding normalized log entries into the full-text search index.
+//!
+//! Entries are rewritten many times while an agent streams a message, so
+//! the latest text of each entry is buffered and written in batches
+//! alongside the raw logs.
+
+use std::collections::HashMap;
+
+use db::models::log_search::{LogSearchEntry, LogSearchKind};
+use executors::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryType,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use json_patch::Patch;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Latest searchable text of the entries seen since the last flush
+#[derive(Debug, Default)]
+pub struct LogSearchBuffer {
+    pending: HashMap<usize, LogSearchEntry>,
+}
+
+impl LogSearchBuffer {
+    pub fn push_patch(&mut self, patch: &Patch) {
+        let Some((index, entry)) = extract_normalized_entry_from_patch(patch) else {
+            return;
+        };
+        if let Some((kind, content)) = searchable_text(&entry) {
+            self.pending.insert(
+                index,
+                LogSearchEntry {
+                    entry_index: index as i64,
+                    kind,
+                    content,
+                },
+            );
+        }
+    }
+
+    pub async fn flush(
+        &mut self,
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let entries: Vec<LogSearchEntry> = self.pending.drain().map(|(_, entry)| entry).collect();
+        LogSearchEntry::upsert_many(pool, execution_process_id, &entries).await
+    }
+}
+
+/// What gets indexed for an entry. Tool calls include the command, path or
+/// query they acted on, which the summary line often shortens.
+pub fn searchable_text(entry: &NormalizedEntry) -> Option<(LogSearchKind, String)> {
+    let (kind, detail) = match &entry.entry_type {
+        NormalizedEntryType::UserMessage | NormalizedEntryType::UserFeedback { .. } => {
+            (LogSearchKind::UserMessage, None)
+        }
+        NormalizedEntryType::AssistantMessage => (LogSearchKind::AssistantMessage, None),
+        NormalizedEntryType::ErrorMessage { .. } => (LogSearchKind::Error, None),
+        NormalizedEntryType::ToolUse { action_type, .. } => {
+            let detail = match action_type {
+                ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => {
+                    Some(path.as_str())
+                }
+                ActionType::CommandRun { command, .. } => Some(command.as_str()),
+                ActionType::Search { query } => Some(query.as_str()),
+                ActionType::WebFetch { url } => Some(url.as_str()),
+                ActionType::Tool { tool_name, .. } => Some(tool_name.as_str()),
+                ActionType::TaskCreate { description } => Some(description.as_str()),
+                ActionType::PlanPresentation { plan } => Some(plan.as_str()),
+                ActionType::TodoManagement { .. } | ActionType::Other { .. } => None,
+            };
+            (LogSearchKind::ToolUse, detail)
+        }
+        NormalizedEntryType::SystemMessage
+        | NormalizedEntryType::Thinking
+        | NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. } => return None,
+    };
+
+    let content = entry.content.trim();
+    let text = match detail.map(str::trim) {
+        Some(detail) if !detail.is_empty() && !content.contains(detail) => {
+            if content.is_empty() {
+                detail.to_string()
+            } else {
+                format!("{content}\n{detail}")
+            }
+        }
+        _ => content.to_string(),
+    };
+    (!text.is_empty()).then_some((kind, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType, content: &str) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type,
+                status: ToolStatus::Created,
+            },
+            content,
+        )
+    }
+
+    #[test]
+    fn command_is_added_to_shortened_summary() {
+        let (kind, text) = searchable_text(&tool(
+            ActionType::CommandRun {
+                command: "cargo sqlx prepare --workspace".to_string(),
+                result: None,
+            },
+            "Run sqlx prepare",
+        ))
+        .unwrap();
+        assert_eq!(kind, LogSearchKind::ToolUse);
+        assert_eq!(text, "Run sqlx prepare\ncargo sqlx prepare --workspace");
+
+        let (_, text) = searchable_text(&tool(
+            ActionType::FileRead {
+                path: "src/main.rs".to_string(),
+            },
+            "`src/main.rs`",
+        ))
+        .unwrap();
+        assert_eq!(text, "`src/main.rs`");
+    }
+
+    #[test]
+    fn messages_and_errors_are_indexed() {
+        let (kind, text) =
+            searchable_text(&entry(NormalizedEntryType::AssistantMessage, " Done. ")).unwrap();
+        assert_eq!(kind, LogSearchKind::AssistantMessage);
+        assert_eq!(text, "Done.");
+        assert!(searchable_text(&entry(NormalizedEntryType::Thinking, "hmm")).is_none());
+        assert!(searchable_text(&entry(NormalizedEntryType::AssistantMessage, "  ")).is_none());
+    }
+}
@@ -0,0 +1,563 @@
+//! Works through the local merge queue.
+//!
+//! Queued attempts of a repo are merged one at a time, oldest first. Each one
+//! is rebased onto its target branch, then the repo's verification script (or
+//! its setup script when it has none) runs on the rebased branch, and the
+//! attempt is merged with `GitService::merge_changes` once the script passes.
+//! If the target branch moved on while the script ran, e.g. after a manual
+//! merge, the entry goes back to the front of the queue to be rebased and
+//! verified again. Conflicts and script failures are recorded on the entry and the task goes
+//! back to in progress. A conflicting rebase is left in progress so it can be
+//! resolved like any other, and a failed verification script may be handed to
+//! the coding agent like after any other run. The task is done once every repo
+//! of the attempt has been merged, and the other ready attempts are then
+//! rebased like after a manual merge.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        merge::Merge,
+        merge_queue::{MergeQueueEntry, MergeQueueStatus},
+        project::Project,
+        project_repo::ProjectRepo,
+        repo::Repo,
+        session::{CreateSession, Session},
+        task::{Task, TaskStatus},
+        workspace::Workspace,
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use thiserror::Error;
+use tokio::{sync::RwLock, task::JoinHandle, time::interval};
+use uuid::Uuid;
+
+use super::{
+    auto_rebase,
+    config::Config,
+    container::{ContainerError, ContainerService},
+    git::{GitService, GitServiceError, format_commit_message},
+    task_queue::TaskQueueService,
+    verification,
+};
+
+#[derive(Debug, Error)]
+pub enum MergeQueueError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error("{0}")]
+    MissingRecord(&'static str),
+}
+
+/// The git side of an entry: the attempt's branch in one repo and the branch it
+/// is merged into. Each method is one step of the queue.
+pub struct QueuedBranch<'a> {
+    pub git: &'a GitService,
+    pub repo_path: &'a Path,
+    pub worktree_path: &'a Path,
+    pub branch: &'a str,
+    pub target_branch: &'a str,
+}
+
+impl QueuedBranch<'_> {
+    /// Rebase the attempt onto the current target branch. A conflicting rebase is
+    /// left in progress and fails the entry.
+    pub fn rebase(&self) -> Result<(), GitServiceError> {
+        self.git.rebase_branch(
+            self.repo_path,
+            self.worktree_path,
+            self.target_branch,
+            self.target_branch,
+            self.branch,
+        )?;
+        Ok(())
+    }
+
+    /// Whether the target branch moved on since the rebase. The entry then goes
+    /// back to the queue instead of merging what was verified.
+    pub fn target_moved(&self) -> Result<bool, GitServiceError> {
+        let (_, behind) =
+            self.git
+                .get_branch_status(self.repo_path, self.branch, self.target_branch)?;
+        Ok(behind > 0)
+    }
+
+    /// Squash-merge the attempt into the target branch; returns the merge commit
+    pub fn merge(&self, commit_message: &str) -> Result<String, GitServiceError> {
+        self.git.merge_changes(
+            self.repo_path,
+            self.worktree_path,
+            self.branch,
+            self.target_branch,
+            commit_message,
+        )
+    }
+}
+
+/// Whether the target branch has everything on the attempt's branch, i.e. it was
+/// merged or never had changes of its own
+pub fn is_branch_merged(
+    git: &GitService,
+    repo_path: &Path,
+    branch: &str,
+    target_branch: &str,
+) -> Result<bool, GitServiceError> {
+    let (ahead, _) = git.get_branch_status(repo_path, branch, target_branch)?;
+    Ok(ahead == 0)
+}
+
+/// Records an entry needs to be rebased, verified and merged
+struct QueuedAttempt {
+    workspace: Workspace,
+    task: Task,
+    project: Project,
+    repo: Repo,
+    workspace_repo: WorkspaceRepo,
+    worktree_path: PathBuf,
+}
+
+impl QueuedAttempt {
+    fn branch<'a>(&'a self, git: &'a GitService) -> QueuedBranch<'a> {
+        QueuedBranch {
+            git,
+            repo_path: &self.repo.path,
+            worktree_path: &self.worktree_path,
+            branch: &self.workspace.branch,
+            target_branch: &self.workspace_repo.target_branch,
+        }
+    }
+}
+
+/// Background service that merges queued attempts
+pub struct MergeQueueProcessor<C> {
+    db: DBService,
+    container: Arc<C>,
+    config: Arc<RwLock<Config>>,
+    task_queue: TaskQueueService,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> MergeQueueProcessor<C> {
+    pub fn spawn(
+        db: DBService,
+        container: Arc<C>,
+        config: Arc<RwLock<Config>>,
+        task_queue: TaskQueueService,
+    ) -> JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            config,
+            task_queue,
+            poll_interval: Duration::from_secs(5),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        tracing::info!(
+            "Starting merge queue processor with interval {:?}",
+            self.poll_interval
+        );
+
+        match MergeQueueEntry::requeue_interrupted(&self.db.pool).await {
+            Ok(count) if count > 0 => {
+                tracing::info!("Requeued {} interrupted merge queue entries", count);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to requeue interrupted merges: {}", e),
+        }
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.process_queue().await {
+                tracing::error!("Error processing the merge queue: {}", e);
+            }
+        }
+    }
+
+    async fn process_queue(&self) -> Result<(), MergeQueueError> {
+        let pool = &self.db.pool;
+
+        for entry in MergeQueueEntry::find_in_flight(pool).await? {
+            if entry.status != MergeQueueStatus::Verifying {
+                continue;
+            }
+            if let Err(e) = self.finish_verification(&entry).await {
+                self.fail(&entry, &e.to_string()).await?;
+            }
+        }
+
+        // The oldest entry of each repo goes next; attempts that are busy are passed
+        // over until they are idle
+        let mut started_repos = HashSet::new();
+        for entry in MergeQueueEntry::find_startable(pool).await? {
+            if started_repos.contains(&entry.repo_id)
+                || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                    pool,
+                    entry.workspace_id,
+                )
+                .await?
+            {
+                continue;
+            }
+            started_repos.insert(entry.repo_id);
+            if let Err(e) = self.start_entry(&entry).await {
+                self.fail(&entry, &e.to_string()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn load(&self, entry: &MergeQueueEntry) -> Result<QueuedAttempt, MergeQueueError> {
+        let pool = &self.db.pool;
+        let workspace = Workspace::find_by_id(pool, entry.workspace_id)
+            .await?
+            .ok_or(MergeQueueError::MissingRecord("Workspace not found"))?;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(MergeQueueError::MissingRecord("Task not found"))?;
+        let project = Project::find_by_id(pool, task.project_id)
+            .await?
+            .ok_or(MergeQueueError::MissingRecord("Project not found"))?;
+        let repo = Repo::find_by_id(pool, entry.repo_id)
+            .await?
+            .ok_or(MergeQueueError::MissingRecord("Repository not found"))?;
+        let workspace_repo =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo.id)
+                .await?
+                .ok_or(MergeQueueError::MissingRecord(
+                    "Repository is not part of the attempt",
+                ))?;
+        let container_ref = self.container.ensure_container_exists(&workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+        Ok(QueuedAttempt {
+            workspace,
+            task,
+            project,
+            repo,
+            workspace_repo,
+            worktree_path,
+        })
+    }
+
+    /// Rebase the attempt onto its target branch, then verify or merge it
+    async fn start_entry(&self, entry: &MergeQueueEntry) -> Result<(), MergeQueueError> {
+        let pool = &self.db.pool;
+        let attempt = self.load(entry).await?;
+        // A task finished by another attempt drops this one; an attempt that merged
+        // some of its repos already keeps the rest queued
+        let done_elsewhere = attempt.task.status == TaskStatus::Done
+            && Merge::find_by_workspace_id(pool, attempt.workspace.id)
+                .await?
+                .is_empty();
+        if attempt.task.status == TaskStatus::Cancelled || done_elsewhere {
+            MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Cancelled, None)
+                .await?;
+            return Ok(());
+        }
+
+        MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Rebasing, None).await?;
+        attempt.branch(self.container.git()).rebase()?;
+
+        match self.start_verification(&attempt).await? {
+            Some(process) => {
+                MergeQueueEntry::set_verifying(pool, entry.id, process.id).await?;
+                tracing::info!(
+                    "Rebased workspace {} onto '{}', verifying before the merge",
+                    attempt.workspace.id,
+                    attempt.workspace_repo.target_branch
+                );
+                Ok(())
+            }
+            None => self.merge(entry, &attempt).await,
+        }
+    }
+
+    /// Run the repo's verification script, or its setup script when it has none, on
+    /// the rebased branch. Returns None when the repo has neither.
+    async fn start_verification(
+        &self,
+        attempt: &QueuedAttempt,
+    ) -> Result<Option<ExecutionProcess>, MergeQueueError> {
+        let pool = &self.db.pool;
+        let Some(project_repo) =
+            ProjectRepo::find_by_project_and_repo(pool, attempt.project.id, attempt.repo.id)
+                .await?
+        else {
+            return Ok(None);
+        };
+        let (script, context) = match (project_repo.verification_script, project_repo.setup_script)
+        {
+            (Some(script), _) => (script, ScriptContext::Verification),
+            (None, Some(script)) => (script, ScriptContext::SetupScript),
+            (None, None) => return Ok(None),
+        };
+
+        let session = match Session::find_latest_by_workspace_id(pool, attempt.workspace.id).await?
+        {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: Some("setup-script".to_string()),
+                    },
+                    Uuid::new_v4(),
+                    attempt.workspace.id,
+                )
+                .await?
+            }
+        };
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script,
+                language: ScriptRequestLanguage::Bash,
+                context,
+                working_dir: Some(attempt.repo.name.clone()),
+            }),
+            None,
+        );
+
+        // Recorded as a one-off script so that finishing it leaves the task status alone
+        let process = self
+            .container
+            .start_execution(
+                &attempt.workspace,
+                &session,
+                &action,
+                &ExecutionProcessRunReason::SetupScript,
+            )
+            .await?;
+        Ok(Some(process))
+    }
+
+    /// Merge a verified entry once its script has passed
+    async fn finish_verification(&self, entry: &MergeQueueEntry) -> Result<(), MergeQueueError> {
+        let pool = &self.db.pool;
+        let process = match entry.execution_process_id {
+            Some(id) => ExecutionProcess::find_by_id(pool, id).await?,
+            None => None,
+        }
+        .ok_or(MergeQueueError::MissingRecord(
+            "The verification script's process no longer exists",
+        ))?;
+
+        match process.status {
+            ExecutionProcessStatus::Running => Ok(()),
+            ExecutionProcessStatus::Completed => {
+                let attempt = self.load(entry).await?;
+                // The script only verified the branch on top of the target as it was
+                // when rebased
+                if attempt.branch(self.container.git()).target_moved()? {
+                    tracing::info!(
+                        "'{}' moved while workspace {} was being verified, rebasing again",
+                        attempt.workspace_repo.target_branch,
+                        attempt.workspace.id
+                    );
+                    MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Queued, None)
+                        .await?;
+                    return Ok(());
+                }
+                self.merge(entry, &attempt).await
+            }
+            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed => {
+                let output = match self.container.get_msg_store_by_id(&process.id).await {
+                    Some(store) => verification::output_tail(
+                        &store.get_history(),
+                        verification::MAX_OUTPUT_BYTES,
+                    ),
+                    None => String::new(),
+                };
+                let status = match process.exit_code {
+                    Some(code) => format!("The script failed with exit code {code}"),
+                    None => "The script was stopped".to_string(),
+                };
+                let message = match output.trim() {
+                    "" => status,
+                    output => format!("{status}:\n{output}"),
+                };
+                self.fail(entry, &message).await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn merge(
+        &self,
+        entry: &MergeQueueEntry,
+        attempt: &QueuedAttempt,
+    ) -> Result<(), MergeQueueError> {
+        let pool = &self.db.pool;
+        let QueuedAttempt {
+            workspace,
+            task,
+            project,
+            repo,
+            workspace_repo,
+            ..
+        } = attempt;
+
+        let config = self.config.read().await;
+        let template = config.commit_message_template.clone();
+        let auto_rebase_enabled = config.git_auto_rebase_ready_attempts;
+        drop(config);
+        let mut commit_message =
+            format_commit_message(&template, &task.title, &task.id, &project.name);
+        if let Some(description) = &task.description
+            && !description.trim().is_empty()
+        {
+            commit_message.push_str("\n\n");
+            commit_message.push_str(description);
+        }
+
+        let merge_commit_id = attempt
+            .branch(self.container.git())
+            .merge(&commit_message)?;
+        Merge::create_direct(
+            pool,
+            workspace.id,
+            repo.id,
+            &workspace_repo.target_branch,
+            &merge_commit_id,
+        )
+        .await?;
+        MergeQueueEntry::update_status(pool, entry.id, MergeQueueStatus::Merged, None).await?;
+        if self.is_fully_merged(workspace).await? {
+            self.update_task_status(task.id, TaskStatus::Done).await?;
+            // Dependents of this task may now be claimable from the queue
+            self.task_queue.notify_capacity_available();
+        }
+
+        for dev_server in
+            ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id).await?
+        {
+            if let Err(e) = self
+                .container
+                .stop_execution(&dev_server, ExecutionProcessStatus::Killed)
+                .await
+            {
+                tracing::error!(
+                    "Failed to stop dev server {} for merged workspace {}: {}",
+                    dev_server.id,
+                    workspace.id,
+                    e
+                );
+            }
+        }
+
+        if auto_rebase_enabled {
+            let db = self.db.clone();
+            let container = self.container.clone();
+            let project_id = project.id;
+            let repo = repo.clone();
+            let merged_workspace_id = workspace.id;
+            tokio::spawn(async move {
+                if let Err(err) = auto_rebase::rebase_ready_attempts(
+                    &db,
+                    container.as_ref(),
+                    project_id,
+                    &repo,
+                    merged_workspace_id,
+                )
+                .await
+                {
+                    tracing::warn!(
+                        ?err,
+                        workspace_id = ?merged_workspace_id,
+                        "Auto rebase of ready attempts failed"
+                    );
+                }
+            });
+        }
+
+        tracing::info!(
+            "Merged workspace {} into '{}' from the merge queue",
+            workspace.id,
+            workspace_repo.target_branch
+        );
+        Ok(())
+    }
+
+    /// Whether every repo of the attempt is merged and none is left in the queue.
+    /// A repo whose branch has nothing on top of its target counts as merged.
+    async fn is_fully_merged(&self, workspace: &Workspace) -> Result<bool, MergeQueueError> {
+        let pool = &self.db.pool;
+        if MergeQueueEntry::is_workspace_queued(pool, workspace.id).await? {
+            return Ok(false);
+        }
+        for repo in
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?
+        {
+            if !is_branch_merged(
+                self.container.git(),
+                &repo.repo.path,
+                &workspace.branch,
+                &repo.target_branch,
+            )? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Record why the entry could not be merged and hand the attempt back
+    async fn fail(&self, entry: &MergeQueueEntry, message: &str) -> Result<(), MergeQueueError> {
+        tracing::warn!(
+            "Merge queue entry {} for workspace {} failed: {}",
+            entry.id,
+            entry.workspace_id,
+            message
+        );
+        MergeQueueEntry::update_status(
+            &self.db.pool,
+            entry.id,
+            MergeQueueStatus::Failed,
+            Some(message),
+        )
+        .await?;
+        if let Some(workspace) = Workspace::find_by_id(&self.db.pool, entry.workspace_id).await? {
+            self.update_task_status(workspace.task_id, TaskStatus::InProgress)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_task_status(
+        &self,
+        task_id: Uuid,
+        status: TaskStatus,
+    ) -> Result<(), MergeQueueError> {
+        Task::update_status(&self.db.pool, task_id, status).await?;
+        if let Some(publisher) = self.container.share_publisher()
+            && let Err(err) = publisher.update_shared_task_by_id(task_id).await
+        {
+            tracing::warn!(
+                ?err,
+                "Failed to propagate shared task update for {}",
+                task_id
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 pub mod analytics;
 pub mod approvals;
+pub mod attempt_archive;
+pub mod attempt_groups;
 pub mod auth;
+pub mod auto_rebase;
+pub mod ci_fix;
 pub mod concurrency;
 pub mod config;
 pub mod container;
@@ -10,18 +14,31 @@ pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod forge;
 pub mod git;
 pub mod github;
+pub mod gitlab;
+pub mod handoff;
 pub mod image;
+pub mod log_retention;
+pub mod log_search;
+pub mod merge_queue;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod playbook;
 pub mod pr_monitor;
 pub mod project;
 pub mod queue_processor;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod review_follow_up;
 pub mod share;
+pub mod symbol_index;
 pub mod task_queue;
+pub mod task_scheduler;
+pub mod usage;
+pub mod verification;
+pub mod webhooks;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -0,0 +1,130 @@
+//! Helpers for running playbooks.
+//!
+//! The container advances a workspace's playbook run each time the processes
+//! started for the current step are done; these build what it starts and
+//! check playbooks before they are saved.
+
+use db::models::playbook::PlaybookStep;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+
+/// Script process for a script or check step
+pub fn script_action(script: &str, working_dir: Option<&str>) -> ExecutorAction {
+    ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: script.to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::Playbook,
+            working_dir: working_dir
+                .filter(|dir| !dir.trim().is_empty())
+                .map(str::to_string),
+        }),
+        None,
+    )
+}
+
+/// Follow-up sent to the agent after a check failed: the step's own
+/// instructions, then what the check reported
+pub fn check_fix_prompt(
+    on_failure: &str,
+    script: &str,
+    exit_code: Option<i64>,
+    output: &str,
+) -> String {
+    let status = match exit_code {
+        Some(code) => format!("failed with exit code {code}"),
+        None => "failed".to_string(),
+    };
+    let output = output.trim();
+    let mut prompt = format!(
+        "{}\n\nThe check `{}` {status}",
+        on_failure.trim(),
+        script.trim()
+    );
+    if output.is_empty() {
+        prompt.push_str(" and produced no output.");
+    } else {
+        prompt.push_str(&format!(".\n\nOutput:\n```\n{output}\n```"));
+    }
+    prompt
+}
+
+/// Reject steps that could never run
+pub fn validate_steps(steps: &[PlaybookStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("A playbook needs at least one step".to_string());
+    }
+    for (index, step) in steps.iter().enumerate() {
+        let empty = match step {
+            PlaybookStep::Prompt { prompt } => prompt.trim().is_empty(),
+            PlaybookStep::Script { script, .. } => script.trim().is_empty(),
+            PlaybookStep::Check {
+                script, on_failure, ..
+            } => {
+                script.trim().is_empty()
+                    || on_failure
+                        .as_ref()
+                        .is_some_and(|prompt| prompt.trim().is_empty())
+            }
+        };
+        if empty {
+            return Err(format!("Step {} is empty", index + 1));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_fix_prompt_includes_instructions_and_output() {
+        let prompt = check_fix_prompt(
+            "Make the linter happy.",
+            "npm run lint",
+            Some(1),
+            "src/a.ts: unused variable\n",
+        );
+        assert!(prompt.starts_with(
+            "Make the linter happy.\n\nThe check `npm run lint` failed with exit code 1."
+        ));
+        assert!(prompt.ends_with("```\nsrc/a.ts: unused variable\n```"));
+    }
+
+    #[test]
+    fn check_fix_prompt_without_output() {
+        assert_eq!(
+            check_fix_prompt("Fix it.", "make check", None, " \n"),
+            "Fix it.\n\nThe check `make check` failed and produced no output."
+        );
+    }
+
+    #[test]
+    fn validate_steps_rejects_empty_steps() {
+        assert!(validate_steps(&[]).is_err());
+        assert_eq!(
+            validate_steps(&[
+                PlaybookStep::Prompt {
+                    prompt: "Add tests".to_string()
+                },
+                PlaybookStep::Check {
+                    script: "cargo test".to_string(),
+                    working_dir: None,
+                    on_failure: Some("  ".to_string()),
+                    max_retries: 2,
+                },
+            ]),
+            Err("Step 2 is empty".to_string())
+        );
+        assert!(
+            validate_steps(&[PlaybookStep::Script {
+                script: "cargo fmt".to_string(),
+                working_dir: Some("api".to_string()),
+            }])
+            .is_ok()
+        );
+    }
+}
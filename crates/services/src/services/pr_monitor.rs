@@ -1,54 +1,85 @@
-use std::time::Duration;
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        coding_agent_turn::CodingAgentTurn,
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        execution_process_repo_state::ExecutionProcessRepoState,
+        merge::{CiStatus, Merge, MergeStatus, PrMerge},
+        pr_review_follow_up::{PrReviewFollowUp, PrReviewFollowUpStatus},
+        project::Project,
+        repo::Repo,
+        session::Session,
         task::{Task, TaskStatus},
+        webhook::WebhookEventType,
         workspace::{Workspace, WorkspaceError},
+        workspace_repo::{PendingRestack, WorkspaceRepo},
     },
 };
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
-use tracing::{debug, error, info};
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    ci_fix,
+    concurrency::ConcurrencyService,
+    config::Config,
+    container::{ContainerError, ContainerService},
+    forge::{Forge, ForgeError, ForgeRepoInfo, forge_for_repo},
+    git::GitServiceError,
+    github::UnifiedPrComment,
+    review_follow_up,
     share::SharePublisher,
+    webhooks,
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error(transparent)]
-    GitHubServiceError(#[from] GitHubServiceError),
+    Forge(#[from] ForgeError),
     #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
-pub struct PrMonitorService {
+/// Service to monitor GitHub PRs and GitLab MRs, update task status when they are merged
+/// and hand new review comments to the workspace's coding agent
+pub struct PrMonitorService<C> {
     db: DBService,
+    config: Arc<RwLock<Config>>,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     publisher: Option<SharePublisher>,
+    container: Arc<C>,
+    concurrency: ConcurrencyService,
 }
 
-impl PrMonitorService {
+impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        config: Arc<RwLock<Config>>,
         analytics: Option<AnalyticsContext>,
         publisher: Option<SharePublisher>,
+        container: Arc<C>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
+            concurrency: ConcurrencyService::new(db.clone()),
             db,
+            config,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             publisher,
+            container,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -71,17 +102,17 @@ impl PrMonitorService {
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
+    /// Check all open PRs for updates with the provided GitHub token, then restack
+    /// the workspaces whose parent has merged
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
         if open_prs.is_empty() {
             debug!("No open PRs to check");
-            return Ok(());
+        } else {
+            info!("Checking {} open PRs", open_prs.len());
         }
 
-        info!("Checking {} open PRs", open_prs.len());
-
         for pr_merge in open_prs {
             if let Err(e) = self.check_pr_status(&pr_merge).await {
                 error!(
@@ -90,18 +121,17 @@ impl PrMonitorService {
                 );
             }
         }
-        Ok(())
+
+        self.restack_pending().await
     }
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+        let repo_info = ForgeRepoInfo::from_pr_url(pr_merge.forge, &pr_merge.pr_info.url)?;
+        let gitlab_config = self.config.read().await.gitlab.clone();
+        let forge = forge_for_repo(repo_info, &gitlab_config)?;
 
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
-            .await?;
+        let pr_status = forge.update_pr_status(pr_merge.pr_info.number).await?;
 
         debug!(
             "PR #{} status: {:?} (was open)",
@@ -110,12 +140,12 @@ impl PrMonitorService {
 
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the forge
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
                 pr_status.status.clone(),
-                pr_status.merge_commit_sha,
+                pr_status.merge_commit_sha.clone(),
             )
             .await?;
 
@@ -130,6 +160,21 @@ impl PrMonitorService {
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
 
+                webhooks::emit(
+                    &self.db,
+                    WebhookEventType::PrMerged,
+                    json!({
+                        "task_id": workspace.task_id,
+                        "workspace_id": workspace.id,
+                        "repo_id": pr_merge.repo_id,
+                        "forge": pr_merge.forge,
+                        "pr_number": pr_merge.pr_info.number,
+                        "pr_url": pr_merge.pr_info.url,
+                        "target_branch": pr_merge.target_branch_name,
+                        "merge_commit_sha": pr_status.merge_commit_sha,
+                    }),
+                );
+
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
                     && let Ok(Some(task)) = Task::find_by_id(&self.db.pool, workspace.task_id).await
@@ -154,9 +199,517 @@ impl PrMonitorService {
                         workspace.task_id
                     );
                 }
+
+                if let Err(e) = self.mark_children_for_restack(pr_merge, &workspace).await {
+                    error!(
+                        "Error marking the workspaces stacked on PR #{} for restacking: {}",
+                        pr_merge.pr_info.number, e
+                    );
+                }
+            }
+        } else {
+            if let Err(e) = self.track_ci(pr_merge, forge.as_ref()).await {
+                error!(
+                    "Error checking CI of PR #{}: {}",
+                    pr_merge.pr_info.number, e
+                );
             }
+            self.address_review_comments(pr_merge, forge.as_ref())
+                .await?;
         }
 
         Ok(())
     }
+
+    /// Reply to the review comments of a finished follow-up, then send new unresolved
+    /// review comments to the workspace's coding agent if the project opted in. Nothing
+    /// happens while the workspace is busy; a later check picks the comments up.
+    async fn address_review_comments(
+        &self,
+        pr_merge: &PrMerge,
+        forge: &dyn Forge,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            pool,
+            pr_merge.workspace_id,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(());
+        };
+
+        // At most one follow-up per PR is in flight, so these all belong to it
+        let in_flight = PrReviewFollowUp::find_running_by_merge_id(pool, pr_merge.id).await?;
+        if !in_flight.is_empty() {
+            self.reply_to_addressed_comments(pr_merge, &workspace, forge, &in_flight)
+                .await?;
+        }
+
+        let Some(task) = workspace.parent_task(pool).await? else {
+            return Ok(());
+        };
+        let Some(project) = Project::find_by_id(pool, task.project_id).await? else {
+            return Ok(());
+        };
+        if !project.auto_address_review_comments {
+            return Ok(());
+        }
+
+        let addressed: HashSet<i64> =
+            PrReviewFollowUp::find_comment_ids_by_merge_id(pool, pr_merge.id)
+                .await?
+                .into_iter()
+                .collect();
+        let comments: Vec<(i64, UnifiedPrComment)> = forge
+            .get_unresolved_review_comments(pr_merge.pr_info.number)
+            .await?
+            .into_iter()
+            .filter(review_follow_up::is_reviewer_comment)
+            .filter_map(|comment| match &comment {
+                UnifiedPrComment::Review { id, .. } if !addressed.contains(id) => {
+                    Some((*id, comment))
+                }
+                _ => None,
+            })
+            .collect();
+        if comments.is_empty() {
+            return Ok(());
+        }
+
+        if !self
+            .can_start_follow_up(&workspace, task.project_id)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let (comment_ids, comments): (Vec<i64>, Vec<UnifiedPrComment>) =
+            comments.into_iter().unzip();
+        let prompt = review_follow_up::follow_up_prompt(&pr_merge.pr_info.url, &comments);
+        let execution_process = self
+            .container
+            .start_unattended_follow_up(&workspace, prompt)
+            .await?;
+        for comment_id in &comment_ids {
+            PrReviewFollowUp::create(pool, pr_merge.id, *comment_id, execution_process.id).await?;
+        }
+
+        info!(
+            "Sent {} review comments on PR #{} to the coding agent of workspace {}",
+            comment_ids.len(),
+            pr_merge.pr_info.number,
+            workspace.id
+        );
+        Ok(())
+    }
+
+    /// Push the changes of a finished review follow-up and reply to each of its comments
+    async fn reply_to_addressed_comments(
+        &self,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+        forge: &dyn Forge,
+        follow_ups: &[PrReviewFollowUp],
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let process = match follow_ups[0].execution_process_id {
+            Some(id) => ExecutionProcess::find_by_id(pool, id).await?,
+            None => None,
+        };
+        let Some(process) =
+            process.filter(|process| matches!(process.status, ExecutionProcessStatus::Completed))
+        else {
+            warn!(
+                "Review follow-up on PR #{} did not complete, leaving its comments unanswered",
+                pr_merge.pr_info.number
+            );
+            for follow_up in follow_ups {
+                PrReviewFollowUp::update_status(pool, follow_up.id, PrReviewFollowUpStatus::Failed)
+                    .await?;
+            }
+            return Ok(());
+        };
+
+        let commit = match self
+            .push_follow_up_changes(pr_merge, workspace, &process)
+            .await
+        {
+            Ok(commit) => commit,
+            Err(e) => {
+                for follow_up in follow_ups {
+                    PrReviewFollowUp::update_status(
+                        pool,
+                        follow_up.id,
+                        PrReviewFollowUpStatus::Failed,
+                    )
+                    .await?;
+                }
+                return Err(e);
+            }
+        };
+
+        let summary = CodingAgentTurn::find_by_execution_process_id(pool, process.id)
+            .await?
+            .and_then(|turn| turn.summary);
+        let body = review_follow_up::reply_body(summary.as_deref(), commit.as_deref());
+        for follow_up in follow_ups {
+            let status = match forge
+                .reply_to_review_comment(pr_merge.pr_info.number, follow_up.comment_id, &body)
+                .await
+            {
+                Ok(()) => PrReviewFollowUpStatus::Replied,
+                Err(e) => {
+                    error!(
+                        "Failed to reply to review comment {} on PR #{}: {}",
+                        follow_up.comment_id, pr_merge.pr_info.number, e
+                    );
+                    PrReviewFollowUpStatus::Failed
+                }
+            };
+            PrReviewFollowUp::update_status(pool, follow_up.id, status).await?;
+        }
+
+        info!(
+            "Replied to {} addressed review comments on PR #{}",
+            follow_ups.len(),
+            pr_merge.pr_info.number
+        );
+        Ok(())
+    }
+
+    /// Store the CI result of the PR head and push a finished CI fix. Failing CI is sent
+    /// to the workspace's coding agent once every check is done, if the project opted in
+    /// and the PR has fix attempts left.
+    async fn track_ci(&self, pr_merge: &PrMerge, forge: &dyn Forge) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let ci = forge.get_ci_status(pr_merge.pr_info.number).await?;
+        let changed = match (&pr_merge.ci, &ci) {
+            (Some(old), Some(new)) => {
+                old.head_sha != new.head_sha || old.status != new.status || old.checks != new.checks
+            }
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            Merge::update_ci(pool, pr_merge.id, ci.as_ref()).await?;
+        }
+
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            pool,
+            pr_merge.workspace_id,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+        let Some(workspace) = Workspace::find_by_id(pool, pr_merge.workspace_id).await? else {
+            return Ok(());
+        };
+
+        if let Some(process_id) = pr_merge.ci_fix_execution_process_id {
+            // The fix is pushed once; a failed push is not retried
+            Merge::clear_ci_fix_execution_process(pool, pr_merge.id).await?;
+            match ExecutionProcess::find_by_id(pool, process_id).await? {
+                Some(process) if matches!(process.status, ExecutionProcessStatus::Completed) => {
+                    if let Some(commit) = self
+                        .push_follow_up_changes(pr_merge, &workspace, &process)
+                        .await?
+                    {
+                        info!(
+                            "Pushed CI fix {} to PR #{}",
+                            commit, pr_merge.pr_info.number
+                        );
+                    }
+                    // The pushed commit is checked on the next poll
+                    return Ok(());
+                }
+                _ => warn!(
+                    "CI fix follow-up on PR #{} did not complete",
+                    pr_merge.pr_info.number
+                ),
+            }
+        }
+
+        // Wait for the remaining checks so one follow-up covers every failure
+        let Some(ci) = ci.filter(|ci| {
+            ci.status == CiStatus::Failure
+                && !ci
+                    .checks
+                    .iter()
+                    .any(|check| check.status == CiStatus::Pending)
+        }) else {
+            return Ok(());
+        };
+        if pr_merge.ci_fix_head_sha.as_deref() == Some(ci.head_sha.as_str()) {
+            return Ok(());
+        }
+
+        let Some(task) = workspace.parent_task(pool).await? else {
+            return Ok(());
+        };
+        let Some(project) = Project::find_by_id(pool, task.project_id).await? else {
+            return Ok(());
+        };
+        if !project.auto_fix_ci {
+            return Ok(());
+        }
+        if pr_merge.ci_fix_attempts >= project.max_ci_fix_attempts {
+            debug!(
+                "PR #{} used up its {} CI fix attempts",
+                pr_merge.pr_info.number, project.max_ci_fix_attempts
+            );
+            return Ok(());
+        }
+
+        // CI ran on a commit the workspace has already moved past
+        let worktree_path = self.worktree_path(pr_merge, &workspace).await?;
+        if self.container.git().get_head_info(&worktree_path)?.oid != ci.head_sha {
+            return Ok(());
+        }
+        if !self
+            .can_start_follow_up(&workspace, task.project_id)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let mut failures = Vec::new();
+        let mut logs_fetched = 0;
+        for check in ci
+            .checks
+            .iter()
+            .filter(|check| check.status == CiStatus::Failure)
+        {
+            let log = match check.job_id {
+                Some(job_id) if logs_fetched < ci_fix::MAX_JOB_LOGS => {
+                    logs_fetched += 1;
+                    match forge.get_ci_job_log(job_id).await {
+                        Ok(log) => Some(ci_fix::log_excerpt(&log)),
+                        Err(e) => {
+                            warn!("Failed to fetch the log of CI job {}: {}", job_id, e);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+            failures.push((check.clone(), log));
+        }
+
+        let prompt = ci_fix::follow_up_prompt(&pr_merge.pr_info.url, &failures);
+        let execution_process = self
+            .container
+            .start_unattended_follow_up(&workspace, prompt)
+            .await?;
+        Merge::record_ci_fix(pool, pr_merge.id, &ci.head_sha, execution_process.id).await?;
+
+        info!(
+            "Sent failing CI on PR #{} to the coding agent of workspace {} (attempt {} of {})",
+            pr_merge.pr_info.number,
+            workspace.id,
+            pr_merge.ci_fix_attempts + 1,
+            project.max_ci_fix_attempts
+        );
+        Ok(())
+    }
+
+    /// Mark the workspaces stacked on a merged PR to move onto the branch it merged
+    /// into. The restack itself runs at the end of each poll until it goes through.
+    async fn mark_children_for_restack(
+        &self,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let children = Workspace::find_stacked_on(pool, workspace.id, pr_merge.repo_id).await?;
+        if children.is_empty() {
+            return Ok(());
+        }
+        let Some(parent_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, pr_merge.repo_id)
+                .await?
+        else {
+            return Ok(());
+        };
+
+        for child in children {
+            WorkspaceRepo::mark_needs_restack(
+                pool,
+                child.id,
+                pr_merge.repo_id,
+                &parent_repo.target_branch,
+                &pr_merge.target_branch_name,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Restack every workspace still marked for it. One that is busy or fails keeps
+    /// its mark and is retried by the next poll.
+    async fn restack_pending(&self) -> Result<(), PrMonitorError> {
+        for pending in WorkspaceRepo::find_pending_restacks(&self.db.pool).await? {
+            if let Err(e) = self.restack_child(&pending).await {
+                warn!(
+                    "Failed to restack workspace {} onto '{}', retrying on the next check: {}",
+                    pending.workspace_id, pending.restack_onto, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebase a child workspace's own commits from its old target branch onto the
+    /// new one, then retarget its open PR and push the rebased branch. A rebase that
+    /// hits conflicts is left in progress, so the child's branch status reports it
+    /// like any other rebase conflict and nothing is pushed. The target branch moves
+    /// as soon as the rebase is done, so a retry after a failed push doesn't rebase
+    /// again.
+    async fn restack_child(&self, pending: &PendingRestack) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let child = Workspace::find_by_id(pool, pending.workspace_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, child.id)
+            .await?
+        {
+            debug!(
+                "Workspace {} is busy, restacking it onto '{}' later",
+                child.id, pending.restack_onto
+            );
+            return Ok(());
+        }
+        let repo = Repo::find_by_id(pool, pending.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let container_ref = self.container.ensure_container_exists(&child).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let git = self.container.git();
+        if pending.target_branch != pending.restack_onto {
+            match git.rebase_branch(
+                &repo.path,
+                &worktree_path,
+                &pending.restack_onto,
+                &pending.target_branch,
+                &child.branch,
+            ) {
+                Ok(_) => {}
+                Err(GitServiceError::MergeConflicts(msg)) => {
+                    info!("Restacking workspace {} needs attention: {}", child.id, msg);
+                }
+                Err(e) => return Err(e.into()),
+            }
+            WorkspaceRepo::update_target_branch(pool, child.id, repo.id, &pending.restack_onto)
+                .await?;
+        }
+        let conflicted = git.is_rebase_in_progress(&worktree_path)?;
+
+        let open_pr = Merge::find_by_workspace_and_repo_id(pool, child.id, repo.id)
+            .await?
+            .into_iter()
+            .find_map(|merge| match merge {
+                Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => Some(pr),
+                _ => None,
+            });
+        if let Some(pr) = open_pr {
+            let repo_info = ForgeRepoInfo::from_pr_url(pr.forge, &pr.pr_info.url)?;
+            let gitlab_config = self.config.read().await.gitlab.clone();
+            let forge = forge_for_repo(repo_info, &gitlab_config)?;
+            forge
+                .update_pr_base(pr.pr_info.number, &pending.restack_pr_base)
+                .await?;
+            Merge::update_target_branch(pool, pr.id, &pending.restack_pr_base).await?;
+            if !conflicted {
+                git.push_to_github(&worktree_path, &child.branch, true)?;
+            }
+            info!(
+                "Retargeted PR #{} of workspace {} to '{}'",
+                pr.pr_info.number, child.id, pending.restack_pr_base
+            );
+        }
+        WorkspaceRepo::clear_restack(pool, child.id, repo.id).await?;
+        Ok(())
+    }
+
+    /// Whether an unattended follow-up may start now. It waits for capacity like any
+    /// other agent run and needs a session whose coding agent can continue.
+    async fn can_start_follow_up(
+        &self,
+        workspace: &Workspace,
+        project_id: Uuid,
+    ) -> Result<bool, PrMonitorError> {
+        let pool = &self.db.pool;
+        let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
+            return Ok(false);
+        };
+        let Ok(executor_profile_id) =
+            ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await
+        else {
+            debug!(
+                "Workspace {} has no coding agent to follow up",
+                workspace.id
+            );
+            return Ok(false);
+        };
+
+        let config = self.config.read().await;
+        let mut check = self
+            .concurrency
+            .check_can_start_execution(&config.concurrency, &executor_profile_id.executor)
+            .await?;
+        if check.is_allowed() {
+            check = self
+                .concurrency
+                .check_budget(&config.concurrency, &config.pricing, Some(project_id))
+                .await?;
+        }
+        if let Err(e) = check.into_result() {
+            debug!("Not following up in workspace {} yet: {}", workspace.id, e);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    async fn worktree_path(
+        &self,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+    ) -> Result<PathBuf, PrMonitorError> {
+        let repo = Repo::find_by_id(&self.db.pool, pr_merge.repo_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let container_ref = self.container.ensure_container_exists(workspace).await?;
+        Ok(PathBuf::from(container_ref).join(&repo.name))
+    }
+
+    /// Push the PR branch if a finished follow-up committed to it. Returns the short
+    /// hash of the pushed head.
+    async fn push_follow_up_changes(
+        &self,
+        pr_merge: &PrMerge,
+        workspace: &Workspace,
+        process: &ExecutionProcess,
+    ) -> Result<Option<String>, PrMonitorError> {
+        let worktree_path = self.worktree_path(pr_merge, workspace).await?;
+        let git = self.container.git();
+
+        let head = git.get_head_info(&worktree_path)?.oid;
+        let before =
+            ExecutionProcessRepoState::find_by_execution_process_id(&self.db.pool, process.id)
+                .await?
+                .into_iter()
+                .find(|state| state.repo_id == pr_merge.repo_id)
+                .and_then(|state| state.before_head_commit);
+        if before.as_deref() == Some(head.as_str()) {
+            return Ok(None);
+        }
+
+        git.push_to_github(&worktree_path, &workspace.branch, false)?;
+        Ok(Some(head.chars().take(7).collect()))
+    }
 }
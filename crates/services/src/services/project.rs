@@ -17,9 +17,10 @@ use uuid::Uuid;
 
 use super::{
     file_ranker::FileRanker,
-    file_search_cache::{CacheError, FileSearchCache, SearchMode, SearchQuery},
+    file_search_cache::{CacheError, FileSearchCache, MAX_SYMBOL_RESULTS, SearchMode, SearchQuery},
     repo::{RepoError, RepoService},
     share::ShareError,
+    symbol_index::SymbolQuery,
 };
 
 #[derive(Debug, Error)]
@@ -132,6 +133,10 @@ impl ProjectService {
                     dev_script: None,
                     dev_script_working_dir: None,
                     default_agent_working_dir: Some(repo.name),
+                    container_image: None,
+                    auto_address_review_comments: None,
+                    auto_fix_ci: None,
+                    max_ci_fix_attempts: None,
                 },
             )
             .await?;
@@ -300,6 +305,11 @@ impl ProjectService {
         if query_str.is_empty() || repositories.is_empty() {
             return Ok(vec![]);
         }
+        if let SearchMode::Symbols = query.mode {
+            return Ok(self
+                .search_symbols(cache, repositories, &SymbolQuery::parse(query_str))
+                .await);
+        }
 
         // Search in parallel and prefix paths with repo name
         let search_futures: Vec<_> = repositories
@@ -330,6 +340,7 @@ impl ProjectService {
                     path: format!("{}/{}", repo_name, r.path),
                     is_file: r.is_file,
                     match_type: r.match_type.clone(),
+                    symbol: r.symbol,
                 })
             })
             .collect();
@@ -339,6 +350,7 @@ impl ProjectService {
                 SearchMatchType::FileName => 0,
                 SearchMatchType::DirectoryName => 1,
                 SearchMatchType::FullPath => 2,
+                SearchMatchType::Symbol => 3,
             };
             priority(&a.match_type)
                 .cmp(&priority(&b.match_type))
@@ -349,6 +361,43 @@ impl ProjectService {
         Ok(all_results)
     }
 
+    /// Definitions matching the query across all repositories, prefixed with the repo name
+    async fn search_symbols(
+        &self,
+        cache: &FileSearchCache,
+        repositories: &[Repo],
+        query: &SymbolQuery,
+    ) -> Vec<SearchResult> {
+        let search_futures = repositories.iter().map(|repo| async move {
+            let results = cache
+                .search_symbols(&repo.path, query)
+                .await
+                .unwrap_or_else(|e| {
+                    let message = match e {
+                        CacheError::BuildError(message) => message,
+                        CacheError::Miss => "not indexed".to_string(),
+                    };
+                    tracing::warn!("Symbol search failed for repo {}: {}", repo.name, message);
+                    vec![]
+                });
+            results.into_iter().map(|r| SearchResult {
+                path: format!("{}/{}", repo.name, r.path),
+                ..r
+            })
+        });
+
+        let mut all_results: Vec<SearchResult> = futures::future::join_all(search_futures)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // Stable, so equally good matches keep each repo's recency order
+        all_results.sort_by_key(|result| query.rank(result));
+        all_results.truncate(MAX_SYMBOL_RESULTS);
+        all_results
+    }
+
     async fn search_single_repo(
         &self,
         cache: &FileSearchCache,
@@ -402,7 +451,7 @@ impl ProjectService {
                     })
                     .build()
             }
-            SearchMode::TaskForm => WalkBuilder::new(repo_path)
+            SearchMode::TaskForm | SearchMode::Symbols => WalkBuilder::new(repo_path)
                 .git_ignore(true)
                 .git_global(true)
                 .git_exclude(true)
@@ -438,6 +487,7 @@ impl ProjectService {
                     path: relative_path.to_string_lossy().to_string(),
                     is_file: path.is_file(),
                     match_type: SearchMatchType::FileName,
+                    symbol: None,
                 });
             } else if relative_path_str.contains(&query_lower) {
                 let match_type = if path
@@ -456,6 +506,7 @@ impl ProjectService {
                     path: relative_path.to_string_lossy().to_string(),
                     is_file: path.is_file(),
                     match_type,
+                    symbol: None,
                 });
             }
         }
@@ -473,6 +524,7 @@ impl ProjectService {
                         SearchMatchType::FileName => 0,
                         SearchMatchType::DirectoryName => 1,
                         SearchMatchType::FullPath => 2,
+                        SearchMatchType::Symbol => 3,
                     };
 
                     priority(&a.match_type)
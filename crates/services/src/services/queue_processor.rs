@@ -12,11 +12,12 @@ use db::{
         workspace::Workspace,
     },
 };
-use executors::actions::ExecutorAction;
+use executors::actions::{ExecutorAction, ExecutorActionType};
 use tokio::{sync::RwLock, task::JoinHandle};
 
 use super::{
-    config::{ConcurrencyLimit, Config},
+    concurrency::{ConcurrencyCheckResult, ConcurrencyService},
+    config::{ConcurrencyConfig, ConcurrencyLimit, Config, PricingConfig},
     container::{ContainerError, ContainerService},
     task_queue::{TaskQueueError, TaskQueueService},
 };
@@ -25,6 +26,7 @@ use super::{
 pub struct QueueProcessor {
     db: DBService,
     task_queue: TaskQueueService,
+    concurrency: ConcurrencyService,
     config: Arc<RwLock<Config>>,
     shutdown: tokio::sync::watch::Receiver<bool>,
 }
@@ -41,6 +43,7 @@ impl QueueProcessor {
         let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
 
         let processor = QueueProcessor {
+            concurrency: ConcurrencyService::new(db.clone()),
             db,
             task_queue,
             config,
@@ -130,10 +133,26 @@ impl QueueProcessor {
             }
         }
 
+        // Nothing may start once the monthly budget is spent
+        if let ConcurrencyCheckResult::BudgetExceeded { scope, .. } = self
+            .concurrency
+            .check_budget(concurrency_config, &config.pricing, None)
+            .await?
+        {
+            tracing::debug!("Queue processor: {} budget exceeded", scope);
+            return Ok(false);
+        }
+
+        // Entries of projects over their daily budget stay queued without
+        // holding up the rest of the queue
+        let over_budget = self
+            .over_budget_projects(concurrency_config, &config.pricing)
+            .await?;
+
         drop(config); // Release lock before claiming
 
         // Try to claim an entry that respects per-agent limits
-        let entry = self.task_queue.claim_next().await?;
+        let entry = self.task_queue.claim_next(&over_budget).await?;
         let entry = match entry {
             Some(e) => e,
             None => return Ok(false),
@@ -195,6 +214,35 @@ impl QueueProcessor {
         }
     }
 
+    /// Projects with pending entries that have spent their daily budget
+    async fn over_budget_projects(
+        &self,
+        concurrency_config: &ConcurrencyConfig,
+        pricing: &PricingConfig,
+    ) -> Result<Vec<uuid::Uuid>, QueueProcessorError> {
+        if concurrency_config.budget.project_daily_limit_usd.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut over_budget = Vec::new();
+        for project_id in
+            db::models::task_queue::TaskQueueEntry::pending_project_ids(&self.db.pool).await?
+        {
+            if let ConcurrencyCheckResult::BudgetExceeded { scope, .. } = self
+                .concurrency
+                .check_budget(concurrency_config, pricing, Some(project_id))
+                .await?
+            {
+                tracing::debug!(
+                    "Queue processor: {} budget exceeded, holding its entries",
+                    scope
+                );
+                over_budget.push(project_id);
+            }
+        }
+        Ok(over_budget)
+    }
+
     /// Process a single queue entry by starting its execution
     async fn process_entry<C: ContainerService + Send + Sync + 'static>(
         &self,
@@ -217,14 +265,15 @@ impl QueueProcessor {
         // Ensure container exists
         container.ensure_container_exists(&workspace).await?;
 
+        // Initial runs held back by task dependencies start with the setup chain
+        let run_reason = match action.typ() {
+            ExecutorActionType::ScriptRequest(_) => ExecutionProcessRunReason::SetupScript,
+            _ => ExecutionProcessRunReason::CodingAgent,
+        };
+
         // Start execution
         let _execution_process = container
-            .start_execution(
-                &workspace,
-                &session,
-                &action,
-                &ExecutionProcessRunReason::CodingAgent,
-            )
+            .start_execution(&workspace, &session, &action, &run_reason)
             .await?;
 
         Ok(())
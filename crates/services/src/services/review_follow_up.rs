@@ -0,0 +1,133 @@
+//! Prompts and replies for PR review comments that are sent to a coding agent.
+//!
+//! New unresolved review comments are grouped by file into one follow-up
+//! prompt. Once the agent is done, each comment gets a reply carrying a hidden
+//! marker, so those replies are never taken for new review comments.
+
+use std::collections::BTreeMap;
+
+use crate::services::github::UnifiedPrComment;
+
+/// Invisible in rendered comments; marks the replies posted for the agent
+pub const REPLY_MARKER: &str = "<!-- vibe-kanban:review-follow-up -->";
+
+/// The comment is anchored at the end of its diff hunk, so earlier lines are
+/// rarely needed
+const MAX_HUNK_LINES: usize = 20;
+
+/// Review comments left by people, as opposed to replies posted for the agent
+pub fn is_reviewer_comment(comment: &UnifiedPrComment) -> bool {
+    match comment {
+        UnifiedPrComment::Review { body, .. } => !body.contains(REPLY_MARKER),
+        UnifiedPrComment::General { .. } => false,
+    }
+}
+
+fn hunk_tail(diff_hunk: &str) -> String {
+    let lines: Vec<&str> = diff_hunk.lines().collect();
+    lines[lines.len().saturating_sub(MAX_HUNK_LINES)..].join("\n")
+}
+
+/// Follow-up prompt with the review comments grouped by file
+pub fn follow_up_prompt(pr_url: &str, comments: &[UnifiedPrComment]) -> String {
+    let mut by_file: BTreeMap<&str, Vec<&UnifiedPrComment>> = BTreeMap::new();
+    for comment in comments {
+        if let UnifiedPrComment::Review { path, .. } = comment {
+            by_file.entry(path.as_str()).or_default().push(comment);
+        }
+    }
+
+    let mut prompt = format!(
+        "Reviewers left new comments on the pull request {pr_url}. Address each of them, or explain why no change is needed. Your changes are pushed to the pull request once you are done.\n"
+    );
+    for (path, comments) in by_file {
+        prompt.push_str(&format!("\n## `{path}`\n"));
+        for comment in comments {
+            let UnifiedPrComment::Review {
+                author,
+                body,
+                line,
+                diff_hunk,
+                ..
+            } = comment
+            else {
+                continue;
+            };
+            match line {
+                Some(line) => prompt.push_str(&format!("\n### Line {line}, from @{author}\n\n")),
+                None => prompt.push_str(&format!("\n### From @{author}\n\n")),
+            }
+            if !diff_hunk.trim().is_empty() {
+                prompt.push_str(&format!("```diff\n{}\n```\n\n", hunk_tail(diff_hunk)));
+            }
+            prompt.push_str(body.trim());
+            prompt.push('\n');
+        }
+    }
+    prompt
+}
+
+/// Reply to a review comment once the agent's follow-up has been pushed.
+/// `commit` is set when the follow-up changed the branch.
+pub fn reply_body(summary: Option<&str>, commit: Option<&str>) -> String {
+    let mut body = match commit {
+        Some(commit) => format!("Addressed in {commit}."),
+        None => "Looked into this without changing the code.".to_string(),
+    };
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        body.push_str("\n\n");
+        body.push_str(summary);
+    }
+    body.push_str("\n\n");
+    body.push_str(REPLY_MARKER);
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn review(id: i64, path: &str, line: Option<i64>, body: &str) -> UnifiedPrComment {
+        UnifiedPrComment::Review {
+            id,
+            author: "alice".to_string(),
+            author_association: String::new(),
+            body: body.to_string(),
+            created_at: Utc::now(),
+            url: String::new(),
+            path: path.to_string(),
+            line,
+            diff_hunk: "@@ -1,2 +1,2 @@\n-old\n+new".to_string(),
+        }
+    }
+
+    #[test]
+    fn prompt_groups_comments_by_file() {
+        let comments = vec![
+            review(1, "src/b.rs", Some(3), "Rename this"),
+            review(2, "src/a.rs", None, "Add a test"),
+            review(3, "src/b.rs", Some(9), "Handle the error"),
+        ];
+
+        let prompt = follow_up_prompt("https://example.com/pull/1", &comments);
+
+        let a = prompt.find("## `src/a.rs`").unwrap();
+        let b = prompt.find("## `src/b.rs`").unwrap();
+        assert!(a < b);
+        assert_eq!(prompt.matches("## `src/b.rs`").count(), 1);
+        assert!(prompt.contains("### Line 3, from @alice"));
+        assert!(prompt.contains("### From @alice"));
+        assert!(prompt.find("Rename this").unwrap() < prompt.find("Handle the error").unwrap());
+        assert!(prompt.contains("```diff\n@@ -1,2 +1,2 @@\n-old\n+new\n```"));
+    }
+
+    #[test]
+    fn replies_are_not_reviewer_comments() {
+        let reply = reply_body(Some("Renamed it."), Some("abc1234"));
+        assert!(reply.starts_with("Addressed in abc1234.\n\nRenamed it."));
+        assert!(is_reviewer_comment(&review(1, "a.rs", None, "Rename this")));
+        assert!(!is_reviewer_comment(&review(2, "a.rs", None, &reply)));
+    }
+}
@@ -0,0 +1,737 @@
+//! Index of the functions, types and modules defined in a repository.
+//!
+//! Source files of the supported languages are parsed with tree-sitter the first
+//! time a directory is searched, either a repo's main checkout or a workspace's
+//! worktree of it. A filesystem watcher then re-parses files as they change, so
+//! lookups like "where is X defined" stay current while they are edited. The
+//! watcher stops when the directory is evicted from the index.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, RwLock, Weak},
+    time::{Duration, Instant},
+};
+
+use db::models::project::{SearchMatchType, SearchResult, SymbolKind, SymbolMatch};
+use futures::StreamExt;
+use ignore::WalkBuilder;
+use moka::future::Cache;
+use notify::event::{EventKind, ModifyKind};
+use tokio_util::sync::CancellationToken;
+use tree_sitter::{Node, Parser};
+
+use super::{
+    file_ranker::FileRanker,
+    filesystem_watcher::{self, ALWAYS_SKIP_DIRS},
+};
+
+/// Larger files are usually generated or vendored
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Languages whose definitions are indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolLanguage {
+    Rust,
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Python,
+    Go,
+}
+
+/// What a syntax node defines
+enum Definition {
+    /// A named symbol; `owner` replaces the enclosing scope, e.g. for a Go method's receiver
+    Symbol {
+        kind: SymbolKind,
+        name: String,
+        owner: Option<String>,
+    },
+    /// A scope that is not a symbol itself, like a Rust `impl` block
+    Scope(String),
+}
+
+/// Type or module the definitions inside it belong to
+struct Scope {
+    name: String,
+    is_type: bool,
+}
+
+impl SymbolLanguage {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "py" | "pyi" => Some(Self::Python),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    fn definition(self, node: Node, source: &[u8], in_type: bool) -> Option<Definition> {
+        let named = |kind| {
+            Some(Definition::Symbol {
+                kind,
+                name: field_text(node, "name", source)?,
+                owner: None,
+            })
+        };
+        let function = if in_type {
+            SymbolKind::Method
+        } else {
+            SymbolKind::Function
+        };
+
+        match self {
+            Self::Rust => match node.kind() {
+                "function_item" | "function_signature_item" => named(function),
+                "struct_item" | "union_item" => named(SymbolKind::Struct),
+                "enum_item" => named(SymbolKind::Enum),
+                "trait_item" => named(SymbolKind::Trait),
+                "type_item" => named(SymbolKind::Type),
+                "mod_item" => named(SymbolKind::Module),
+                "const_item" | "static_item" => named(SymbolKind::Constant),
+                "macro_definition" => named(SymbolKind::Macro),
+                "impl_item" => {
+                    // `impl<T> Foo<T>` and `impl path::Foo` are both scoped to `Foo`
+                    let mut ty = node.child_by_field_name("type")?;
+                    if let Some(inner) = ty
+                        .child_by_field_name("type")
+                        .or_else(|| ty.child_by_field_name("name"))
+                    {
+                        ty = inner;
+                    }
+                    Some(Definition::Scope(node_text(ty, source)?))
+                }
+                _ => None,
+            },
+            Self::TypeScript | Self::Tsx | Self::JavaScript => match node.kind() {
+                "function_declaration" | "generator_function_declaration" => {
+                    named(SymbolKind::Function)
+                }
+                "class_declaration" | "abstract_class_declaration" => named(SymbolKind::Class),
+                "method_definition" | "method_signature" | "abstract_method_signature" => {
+                    named(SymbolKind::Method)
+                }
+                "interface_declaration" => named(SymbolKind::Interface),
+                "type_alias_declaration" => named(SymbolKind::Type),
+                "enum_declaration" => named(SymbolKind::Enum),
+                "internal_module" => named(SymbolKind::Module),
+                // `const Foo = () => ...`, the usual way to define components and hooks
+                "variable_declarator" => {
+                    let name = node.child_by_field_name("name")?;
+                    let value = node.child_by_field_name("value")?;
+                    if name.kind() != "identifier"
+                        || !matches!(
+                            value.kind(),
+                            "arrow_function"
+                                | "function"
+                                | "function_expression"
+                                | "generator_function"
+                        )
+                    {
+                        return None;
+                    }
+                    Some(Definition::Symbol {
+                        kind: SymbolKind::Function,
+                        name: node_text(name, source)?,
+                        owner: None,
+                    })
+                }
+                _ => None,
+            },
+            Self::Python => match node.kind() {
+                "function_definition" => named(function),
+                "class_definition" => named(SymbolKind::Class),
+                _ => None,
+            },
+            Self::Go => match node.kind() {
+                "function_declaration" => named(SymbolKind::Function),
+                "method_declaration" => Some(Definition::Symbol {
+                    kind: SymbolKind::Method,
+                    name: field_text(node, "name", source)?,
+                    owner: node
+                        .child_by_field_name("receiver")
+                        .and_then(|receiver| first_descendant(receiver, "type_identifier"))
+                        .and_then(|ty| node_text(ty, source)),
+                }),
+                "type_spec" => named(match node.child_by_field_name("type").map(|ty| ty.kind()) {
+                    Some("struct_type") => SymbolKind::Struct,
+                    Some("interface_type") => SymbolKind::Interface,
+                    _ => SymbolKind::Type,
+                }),
+                "type_alias" => named(SymbolKind::Type),
+                "const_spec" => named(SymbolKind::Constant),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn node_text(node: Node, source: &[u8]) -> Option<String> {
+    node.utf8_text(source).ok().map(str::to_string)
+}
+
+fn field_text(node: Node, field: &str, source: &[u8]) -> Option<String> {
+    node_text(node.child_by_field_name(field)?, source)
+}
+
+fn first_descendant<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find_map(|child| first_descendant(child, kind))
+}
+
+/// Definitions in a source file, in the order they appear.
+///
+/// Definitions nested in function bodies are skipped; the ones inside classes,
+/// traits, `impl` blocks and modules are kept with that scope as their container.
+pub fn extract_symbols(language: SymbolLanguage, source: &str) -> Vec<SymbolMatch> {
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&language.grammar()) {
+        tracing::error!("Failed to load the {:?} grammar: {}", language, e);
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let source = source.as_bytes();
+    let mut symbols = Vec::new();
+    // Walked with an explicit stack since generated code can nest deeply
+    let mut stack: Vec<(Node, Option<Rc<Scope>>)> = vec![(tree.root_node(), None)];
+    while let Some((node, scope)) = stack.pop() {
+        let in_type = scope.as_ref().is_some_and(|scope| scope.is_type);
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match language.definition(child, source, in_type) {
+                Some(Definition::Symbol { kind, name, owner }) => {
+                    let inner_scope = match kind {
+                        SymbolKind::Class | SymbolKind::Trait | SymbolKind::Interface => Some(true),
+                        SymbolKind::Module => Some(false),
+                        _ => None,
+                    };
+                    if let Some(is_type) = inner_scope {
+                        stack.push((
+                            child,
+                            Some(Rc::new(Scope {
+                                name: name.clone(),
+                                is_type,
+                            })),
+                        ));
+                    }
+                    symbols.push(SymbolMatch {
+                        name,
+                        kind,
+                        line: child.start_position().row as u32 + 1,
+                        container: owner.or_else(|| scope.as_ref().map(|s| s.name.clone())),
+                    });
+                }
+                Some(Definition::Scope(name)) => {
+                    stack.push((
+                        child,
+                        Some(Rc::new(Scope {
+                            name,
+                            is_type: true,
+                        })),
+                    ));
+                }
+                None => stack.push((child, scope.clone())),
+            }
+        }
+    }
+
+    symbols.sort_by_key(|symbol| symbol.line);
+    symbols
+}
+
+/// Symbol name to look for, optionally qualified with its container like `Foo::bar` or `Foo.bar`
+#[derive(Debug, Clone)]
+pub struct SymbolQuery {
+    name: String,
+    name_lower: String,
+    container_lower: Option<String>,
+}
+
+impl SymbolQuery {
+    pub fn parse(query: &str) -> Self {
+        let query = query.trim();
+        let (container, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+            Some((container, name)) if !container.is_empty() => (Some(container), name),
+            _ => (None, query),
+        };
+        // Only the innermost container is recorded, e.g. `Foo` for `a::Foo::bar`
+        let container = container.map(|c| {
+            c.rsplit(['.', ':'])
+                .find(|part| !part.is_empty())
+                .unwrap_or(c)
+        });
+        Self {
+            name: name.to_string(),
+            name_lower: name.to_lowercase(),
+            container_lower: container.map(str::to_lowercase),
+        }
+    }
+
+    /// How well a symbol matches, lower is better; `None` if it doesn't match
+    fn match_rank(&self, symbol: &SymbolMatch) -> Option<u8> {
+        if let Some(container) = &self.container_lower
+            && !symbol
+                .container
+                .as_deref()
+                .is_some_and(|c| c.to_lowercase().contains(container.as_str()))
+        {
+            return None;
+        }
+        if symbol.name == self.name {
+            return Some(0);
+        }
+        let name = symbol.name.to_lowercase();
+        if name == self.name_lower {
+            Some(1)
+        } else if name.starts_with(&self.name_lower) {
+            Some(2)
+        } else if name.contains(&self.name_lower) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    /// Rank of a symbol search result, for merging results of several repos
+    pub fn rank(&self, result: &SearchResult) -> u8 {
+        result
+            .symbol
+            .as_ref()
+            .and_then(|symbol| self.match_rank(symbol))
+            .unwrap_or(u8::MAX)
+    }
+}
+
+/// Definitions of one repo by file path relative to the repo root
+struct RepoSymbols {
+    files: RwLock<HashMap<String, Vec<SymbolMatch>>>,
+    /// Stops the repo's watcher once it leaves the index
+    stop_watching: CancellationToken,
+}
+
+/// Symbol index of the repos searched recently
+#[derive(Clone)]
+pub struct SymbolIndex {
+    repos: Cache<PathBuf, Arc<RepoSymbols>>,
+    file_ranker: FileRanker,
+}
+
+impl SymbolIndex {
+    pub fn new() -> Self {
+        Self {
+            repos: Cache::builder()
+                .max_capacity(50)
+                .time_to_idle(Duration::from_secs(3600))
+                .eviction_listener(|_, repo: Arc<RepoSymbols>, _| repo.stop_watching.cancel())
+                .build(),
+            file_ranker: FileRanker::new(),
+        }
+    }
+
+    /// Definitions matching the query, best matches first and then by how recently
+    /// their file changed. The repo is indexed on its first search.
+    pub async fn search(
+        &self,
+        repo_path: &Path,
+        query: &SymbolQuery,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        if query.name.is_empty() {
+            return Ok(Vec::new());
+        }
+        let repo = self.index(repo_path).await?;
+
+        let mut results: Vec<SearchResult> = {
+            let files = repo.files.read().unwrap();
+            files
+                .iter()
+                .flat_map(|(path, symbols)| {
+                    symbols
+                        .iter()
+                        .filter(|symbol| query.match_rank(symbol).is_some())
+                        .map(move |symbol| SearchResult {
+                            path: path.clone(),
+                            is_file: true,
+                            match_type: SearchMatchType::Symbol,
+                            symbol: Some(symbol.clone()),
+                        })
+                })
+                .collect()
+        };
+
+        // Sorts are stable, so ties keep the git recency order and then path order
+        results.sort_by(|a, b| {
+            a.path.cmp(&b.path).then_with(|| {
+                let line = |r: &SearchResult| r.symbol.as_ref().map(|s| s.line);
+                line(a).cmp(&line(b))
+            })
+        });
+        if let Ok(stats) = self.file_ranker.get_stats(repo_path).await {
+            self.file_ranker.rerank(&mut results, &stats);
+        }
+        results.sort_by_key(|result| query.rank(result));
+
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Index repositories in the background so their first search is fast
+    pub fn warm(&self, repo_paths: Vec<PathBuf>) {
+        let index = self.clone();
+        tokio::spawn(async move {
+            for repo_path in repo_paths {
+                if let Err(e) = index.index(&repo_path).await {
+                    tracing::warn!("Failed to index symbols of {:?}: {}", repo_path, e);
+                }
+            }
+        });
+    }
+
+    async fn index(&self, repo_path: &Path) -> Result<Arc<RepoSymbols>, String> {
+        self.repos
+            .try_get_with(
+                repo_path.to_path_buf(),
+                Self::build(repo_path.to_path_buf()),
+            )
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn build(repo_path: PathBuf) -> Result<Arc<RepoSymbols>, String> {
+        if !repo_path.exists() {
+            return Err(format!("Repository not found: {}", repo_path.display()));
+        }
+
+        let root = repo_path.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let files = index_files(&root, &root);
+            tracing::info!(
+                "Indexed symbols of {} files in {:?} in {:?}",
+                files.len(),
+                root,
+                started.elapsed()
+            );
+            files
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+
+        let stop_watching = CancellationToken::new();
+        let repo = Arc::new(RepoSymbols {
+            files: RwLock::new(files),
+            stop_watching: stop_watching.clone(),
+        });
+        Self::watch(repo_path, Arc::downgrade(&repo), stop_watching).await;
+        Ok(repo)
+    }
+
+    /// Re-parse changed files of the repo until it is evicted from the index
+    async fn watch(repo_path: PathBuf, repo: Weak<RepoSymbols>, stop: CancellationToken) {
+        let watched_path = repo_path.clone();
+        let watcher =
+            tokio::task::spawn_blocking(move || filesystem_watcher::async_watcher(watched_path))
+                .await;
+        let (debouncer, mut events, root) = match watcher {
+            Ok(Ok(parts)) => parts,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to watch {:?} for symbol changes: {}", repo_path, e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to spawn symbol watcher setup: {}", e);
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            // Dropping the debouncer when the loop ends stops watching the files
+            let _debouncer_guard = debouncer;
+            loop {
+                let result = tokio::select! {
+                    _ = stop.cancelled() => break,
+                    result = events.next() => match result {
+                        Some(result) => result,
+                        None => break,
+                    },
+                };
+                let events = match result {
+                    Ok(events) => events,
+                    Err(errors) => {
+                        tracing::warn!("Symbol watcher error for {:?}: {:?}", root, errors);
+                        continue;
+                    }
+                };
+                let Some(repo) = repo.upgrade() else {
+                    break;
+                };
+
+                // Directories are only walked when they appear, e.g. after a rename
+                let mut changed: HashMap<PathBuf, bool> = HashMap::new();
+                for event in events {
+                    let appeared = event.event.kind.is_create()
+                        || matches!(event.event.kind, EventKind::Modify(ModifyKind::Name(_)));
+                    for path in event.event.paths {
+                        *changed.entry(path).or_default() |= appeared;
+                    }
+                }
+
+                let root = root.clone();
+                let update =
+                    tokio::task::spawn_blocking(move || reindex_changed(&root, changed)).await;
+                match update {
+                    Ok((removed, indexed)) => {
+                        let mut files = repo.files.write().unwrap();
+                        if !removed.is_empty() {
+                            files.retain(|path, _| {
+                                !removed.iter().any(|prefix| {
+                                    path == prefix
+                                        || path
+                                            .strip_prefix(prefix.as_str())
+                                            .is_some_and(|rest| rest.starts_with('/'))
+                                })
+                            });
+                        }
+                        files.extend(indexed);
+                    }
+                    Err(e) => tracing::error!("Failed to re-index changed files: {}", e),
+                }
+            }
+        });
+    }
+}
+
+impl Default for SymbolIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Definitions of the source files under `dir`, keyed by path relative to `root`
+fn index_files(root: &Path, dir: &Path) -> HashMap<String, Vec<SymbolMatch>> {
+    WalkBuilder::new(dir)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !ALWAYS_SKIP_DIRS.contains(&name.as_ref())
+        })
+        .build()
+        .flatten()
+        .filter_map(|entry| index_file(root, entry.path()))
+        .collect()
+}
+
+/// Relative path and definitions of a source file, or `None` if it isn't indexed
+fn index_file(root: &Path, path: &Path) -> Option<(String, Vec<SymbolMatch>)> {
+    let language = SymbolLanguage::from_path(path)?;
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let source = std::fs::read_to_string(path).ok()?;
+    let relative_path = path.strip_prefix(root).ok()?.to_string_lossy().to_string();
+    Some((relative_path, extract_symbols(language, &source)))
+}
+
+/// Removed paths and re-parsed files for changed paths and whether each just appeared
+fn reindex_changed(
+    root: &Path,
+    changed: HashMap<PathBuf, bool>,
+) -> (Vec<String>, HashMap<String, Vec<SymbolMatch>>) {
+    let mut removed = Vec::new();
+    let mut indexed = HashMap::new();
+    let mut seen = HashSet::new();
+
+    for (path, appeared) in changed {
+        let Ok(relative_path) = path.strip_prefix(root) else {
+            continue;
+        };
+        if !seen.insert(relative_path.to_path_buf()) {
+            continue;
+        }
+        if !path.exists() {
+            removed.push(relative_path.to_string_lossy().to_string());
+        } else if path.is_dir() {
+            if appeared {
+                indexed.extend(index_files(root, &path));
+            }
+        } else if let Some((relative_path, symbols)) = index_file(root, &path) {
+            indexed.insert(relative_path, symbols);
+        }
+    }
+
+    (removed, indexed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(symbols: &[SymbolMatch]) -> Vec<(String, SymbolKind, Option<String>)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.clone(), s.kind, s.container.clone()))
+            .collect()
+    }
+
+    fn symbol(
+        name: &str,
+        kind: SymbolKind,
+        container: Option<&str>,
+    ) -> (String, SymbolKind, Option<String>) {
+        (name.to_string(), kind, container.map(str::to_string))
+    }
+
+    #[test]
+    fn extracts_rust_definitions_with_their_impl_type() {
+        let source = r#"
+pub struct FileSearchCache<T> {
+    inner: T,
+}
+
+impl<T> FileSearchCache<T> {
+    pub fn search(&self) {
+        fn helper() {}
+    }
+}
+
+pub trait Forge {
+    fn update_pr_base(&self);
+}
+
+mod tests {
+    const LIMIT: usize = 10;
+}
+"#;
+
+        let symbols = extract_symbols(SymbolLanguage::Rust, source);
+
+        assert_eq!(
+            names(&symbols),
+            vec![
+                symbol("FileSearchCache", SymbolKind::Struct, None),
+                symbol("search", SymbolKind::Method, Some("FileSearchCache")),
+                symbol("Forge", SymbolKind::Trait, None),
+                symbol("update_pr_base", SymbolKind::Method, Some("Forge")),
+                symbol("tests", SymbolKind::Module, None),
+                symbol("LIMIT", SymbolKind::Constant, Some("tests")),
+            ]
+        );
+        assert_eq!(symbols[1].line, 7);
+    }
+
+    #[test]
+    fn extracts_typescript_components_classes_and_types() {
+        let source = r#"
+export interface Props { open: boolean }
+export type Mode = 'a' | 'b';
+export const GitOperations = ({ open }: Props) => {
+  const local = () => {};
+  return null;
+};
+export class Api {
+  merge(id: string) {}
+}
+const limit = 10;
+"#;
+
+        let symbols = extract_symbols(SymbolLanguage::Tsx, source);
+
+        assert_eq!(
+            names(&symbols),
+            vec![
+                symbol("Props", SymbolKind::Interface, None),
+                symbol("Mode", SymbolKind::Type, None),
+                symbol("GitOperations", SymbolKind::Function, None),
+                symbol("Api", SymbolKind::Class, None),
+                symbol("merge", SymbolKind::Method, Some("Api")),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_python_and_go_methods() {
+        let python = "class Runner:\n    def run(self):\n        pass\n\ndef main():\n    pass\n";
+        assert_eq!(
+            names(&extract_symbols(SymbolLanguage::Python, python)),
+            vec![
+                symbol("Runner", SymbolKind::Class, None),
+                symbol("run", SymbolKind::Method, Some("Runner")),
+                symbol("main", SymbolKind::Function, None),
+            ]
+        );
+
+        let go = "package main\n\ntype Server struct{}\n\nfunc (s *Server) Start() {}\n";
+        assert_eq!(
+            names(&extract_symbols(SymbolLanguage::Go, go)),
+            vec![
+                symbol("Server", SymbolKind::Struct, None),
+                symbol("Start", SymbolKind::Method, Some("Server")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn evicting_a_repo_stops_its_watcher() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "pub fn indexed() {}\n").unwrap();
+        let index = SymbolIndex::new();
+        let repo = index.index(dir.path()).await.unwrap();
+        assert!(!repo.stop_watching.is_cancelled());
+
+        index.repos.invalidate(dir.path()).await;
+        index.repos.run_pending_tasks().await;
+        assert!(repo.stop_watching.is_cancelled());
+    }
+
+    #[test]
+    fn query_ranks_exact_matches_first_and_filters_by_container() {
+        let search = SymbolMatch {
+            name: "search".to_string(),
+            kind: SymbolKind::Method,
+            line: 1,
+            container: Some("FileSearchCache".to_string()),
+        };
+        let search_files = SymbolMatch {
+            name: "search_files".to_string(),
+            kind: SymbolKind::Method,
+            line: 2,
+            container: Some("ProjectService".to_string()),
+        };
+
+        let query = SymbolQuery::parse("search");
+        assert_eq!(query.match_rank(&search), Some(0));
+        assert_eq!(query.match_rank(&search_files), Some(2));
+
+        let qualified = SymbolQuery::parse("services::FileSearchCache::search");
+        assert_eq!(qualified.match_rank(&search), Some(0));
+        assert_eq!(qualified.match_rank(&search_files), None);
+    }
+}
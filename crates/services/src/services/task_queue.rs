@@ -182,9 +182,13 @@ impl TaskQueueService {
         TaskQueueEntry::count_by_status(&self.db.pool, QueueEntryStatus::Processing).await
     }
 
-    /// Claim the next pending entry for processing
-    pub async fn claim_next(&self) -> Result<Option<TaskQueueEntry>, TaskQueueError> {
-        let entry = TaskQueueEntry::claim_next(&self.db.pool).await?;
+    /// Claim the next pending entry for processing, skipping entries of
+    /// `excluded_project_ids`
+    pub async fn claim_next(
+        &self,
+        excluded_project_ids: &[Uuid],
+    ) -> Result<Option<TaskQueueEntry>, TaskQueueError> {
+        let entry = TaskQueueEntry::claim_next(&self.db.pool, excluded_project_ids).await?;
         if let Some(ref e) = entry {
             tracing::info!(
                 "Queue entry claimed: entry_id={}, session_id={}, executor={}",
@@ -0,0 +1,344 @@
+//! Starts tasks from recurring `TaskSchedule` templates when they come due.
+//!
+//! Each run creates a task, creates a workspace for it and starts it the same
+//! way `create-and-start` does. When concurrency limits or budgets would block
+//! the start, the run is parked in the task queue if it is enabled and skipped
+//! otherwise. Runs missed while the app was closed are not caught up; the
+//! schedule fires once and moves on to its next slot.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        task::{CreateTask, Task},
+        task_schedule::TaskSchedule,
+        workspace_repo::CreateWorkspaceRepo,
+    },
+};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::{sync::RwLock, task::JoinHandle, time::interval};
+use ts_rs::TS;
+use utils::cron::CronSchedule;
+use uuid::Uuid;
+
+use super::{
+    concurrency::{ConcurrencyCheckResult, ConcurrencyService},
+    config::Config,
+    container::{ContainerError, ContainerService},
+};
+
+#[derive(Debug, Error)]
+pub enum SchedulerError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+}
+
+/// When a schedule fires
+#[derive(Debug, Clone)]
+pub enum ScheduleTiming {
+    Cron(CronSchedule),
+    Interval(chrono::Duration),
+}
+
+impl ScheduleTiming {
+    /// Exactly one of `cron_expression` and `interval_seconds` must be set
+    pub fn from_parts(
+        cron_expression: Option<&str>,
+        interval_seconds: Option<i64>,
+    ) -> Result<Self, SchedulerError> {
+        match (cron_expression, interval_seconds) {
+            (Some(expr), None) => CronSchedule::parse(expr)
+                .map(ScheduleTiming::Cron)
+                .map_err(|e| SchedulerError::InvalidSchedule(e.to_string())),
+            (None, Some(seconds)) if seconds > 0 => {
+                Ok(ScheduleTiming::Interval(chrono::Duration::seconds(seconds)))
+            }
+            (None, Some(_)) => Err(SchedulerError::InvalidSchedule(
+                "interval_seconds must be positive".to_string(),
+            )),
+            _ => Err(SchedulerError::InvalidSchedule(
+                "Set exactly one of cron_expression or interval_seconds".to_string(),
+            )),
+        }
+    }
+
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            ScheduleTiming::Cron(cron) => cron.next_after(after),
+            ScheduleTiming::Interval(every) => after.checked_add_signed(*every),
+        }
+    }
+}
+
+/// Next run of an enabled schedule after `after`; None when disabled
+pub fn next_run_at(
+    cron_expression: Option<&str>,
+    interval_seconds: Option<i64>,
+    enabled: bool,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, SchedulerError> {
+    let timing = ScheduleTiming::from_parts(cron_expression, interval_seconds)?;
+    if !enabled {
+        return Ok(None);
+    }
+    match timing.next_after(after) {
+        Some(next) => Ok(Some(next)),
+        None => Err(SchedulerError::InvalidSchedule(
+            "Cron expression never fires".to_string(),
+        )),
+    }
+}
+
+/// Fill `{date}` in a title or description template with the run's UTC date
+pub fn render_template(template: &str, now: DateTime<Utc>) -> String {
+    template.replace("{date}", &now.format("%Y-%m-%d").to_string())
+}
+
+/// What a single run of a schedule did
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[ts(tag = "status", rename_all = "snake_case")]
+pub enum ScheduleRunOutcome {
+    Started {
+        task_id: Uuid,
+    },
+    /// Limits were hit; the task waits in the task queue
+    Queued {
+        task_id: Uuid,
+    },
+    /// Limits were hit and the task queue is disabled
+    Skipped {
+        reason: String,
+    },
+}
+
+/// Run `schedule` now: create its task, start or queue it, and record the
+/// outcome together with the schedule's next run
+pub async fn run_schedule<C: ContainerService + Sync>(
+    container: &C,
+    concurrency: &ConcurrencyService,
+    config: &RwLock<Config>,
+    schedule: &TaskSchedule,
+) -> Result<ScheduleRunOutcome, SchedulerError> {
+    let pool = &container.db().pool;
+    let now = Utc::now();
+    let next_run = next_run_at(
+        schedule.cron_expression.as_deref(),
+        schedule.interval_seconds,
+        schedule.enabled,
+        now,
+    )
+    .unwrap_or_else(|e| {
+        tracing::warn!("Schedule {} has no next run: {}", schedule.id, e);
+        None
+    });
+
+    let result = start_scheduled_task(container, concurrency, config, schedule, now).await;
+    let (task_id, error) = match &result {
+        Ok(ScheduleRunOutcome::Started { task_id } | ScheduleRunOutcome::Queued { task_id }) => {
+            (Some(*task_id), None)
+        }
+        Ok(ScheduleRunOutcome::Skipped { reason }) => (None, Some(reason.clone())),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    TaskSchedule::record_run(pool, schedule.id, now, next_run, task_id, error).await?;
+
+    result
+}
+
+async fn start_scheduled_task<C: ContainerService + Sync>(
+    container: &C,
+    concurrency: &ConcurrencyService,
+    config: &RwLock<Config>,
+    schedule: &TaskSchedule,
+    now: DateTime<Utc>,
+) -> Result<ScheduleRunOutcome, SchedulerError> {
+    if schedule.repos.is_empty() {
+        return Err(SchedulerError::InvalidSchedule(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    let executor_profile_id = schedule.executor_profile_id.0.clone();
+
+    let (check, queue_enabled) = {
+        let config = config.read().await;
+        let mut check = concurrency
+            .check_can_start_execution(&config.concurrency, &executor_profile_id.executor)
+            .await?;
+        if check.is_allowed() {
+            check = concurrency
+                .check_budget(
+                    &config.concurrency,
+                    &config.pricing,
+                    Some(schedule.project_id),
+                )
+                .await?;
+        }
+        (check, config.concurrency.queue.enabled)
+    };
+    let can_start = matches!(check, ConcurrencyCheckResult::Allowed);
+    if !can_start
+        && !queue_enabled
+        && let Err(e) = check.into_result()
+    {
+        return Ok(ScheduleRunOutcome::Skipped {
+            reason: e.to_string(),
+        });
+    }
+
+    let pool = &container.db().pool;
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            schedule.project_id,
+            render_template(&schedule.task_title, now),
+            schedule
+                .task_description
+                .as_deref()
+                .map(|description| render_template(description, now)),
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    let repos: Vec<CreateWorkspaceRepo> = schedule
+        .repos
+        .iter()
+        .map(|repo| CreateWorkspaceRepo {
+            repo_id: repo.repo_id,
+            target_branch: repo.target_branch.clone(),
+        })
+        .collect();
+    let workspace = container.create_workspace_for_task(&task, &repos).await?;
+
+    if can_start {
+        container
+            .start_workspace(&workspace, executor_profile_id)
+            .await?;
+        tracing::info!(
+            "Schedule {} started task {} in workspace {}",
+            schedule.id,
+            task.id,
+            workspace.id
+        );
+        Ok(ScheduleRunOutcome::Started { task_id: task.id })
+    } else {
+        container
+            .queue_workspace_start(&workspace, executor_profile_id)
+            .await?;
+        tracing::info!(
+            "Schedule {} queued task {} until capacity is available",
+            schedule.id,
+            task.id
+        );
+        Ok(ScheduleRunOutcome::Queued { task_id: task.id })
+    }
+}
+
+/// Background service that runs due schedules
+pub struct TaskScheduler<C> {
+    db: DBService,
+    container: Arc<C>,
+    concurrency: ConcurrencyService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> TaskScheduler<C> {
+    pub fn spawn(db: DBService, container: Arc<C>, config: Arc<RwLock<Config>>) -> JoinHandle<()> {
+        let service = Self {
+            concurrency: ConcurrencyService::new(db.clone()),
+            db,
+            container,
+            config,
+            poll_interval: Duration::from_secs(30),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        tracing::info!(
+            "Starting task scheduler with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                tracing::error!("Error running due schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), SchedulerError> {
+        let due = TaskSchedule::find_due(&self.db.pool, Utc::now()).await?;
+        for schedule in due {
+            match run_schedule(
+                self.container.as_ref(),
+                &self.concurrency,
+                &self.config,
+                &schedule,
+            )
+            .await
+            {
+                Ok(ScheduleRunOutcome::Skipped { reason }) => {
+                    tracing::warn!("Schedule {} skipped: {}", schedule.id, reason);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Schedule {} failed to run: {}", schedule.id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_timing_requires_exactly_one_source() {
+        assert!(ScheduleTiming::from_parts(None, None).is_err());
+        assert!(ScheduleTiming::from_parts(Some("@daily"), Some(60)).is_err());
+        assert!(ScheduleTiming::from_parts(None, Some(0)).is_err());
+        assert!(ScheduleTiming::from_parts(Some("not cron"), None).is_err());
+    }
+
+    #[test]
+    fn test_next_run_at() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 4, 10, 0, 0).unwrap();
+        assert_eq!(
+            next_run_at(None, Some(3600), true, now).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 3, 4, 11, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_run_at(Some("0 9 * * *"), None, true, now).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 3, 5, 9, 0, 0).unwrap())
+        );
+        assert_eq!(next_run_at(None, Some(3600), false, now).unwrap(), None);
+        assert!(next_run_at(Some("0 0 31 2 *"), None, true, now).is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 4, 10, 0, 0).unwrap();
+        assert_eq!(
+            render_template("Bump dependencies ({date})", now),
+            "Bump dependencies (2025-03-04)"
+        );
+    }
+}
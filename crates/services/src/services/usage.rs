@@ -0,0 +1,220 @@
+//! Token cost accounting.
+//!
+//! Applies the configured per-model pricing to the token usage recorded for each
+//! execution process and rolls it up per task, project and executor.
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::execution_process_token_usage::{ExecutionProcessTokenUsage, TokenUsageBreakdown},
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::config::PricingConfig;
+
+/// Usage recorded for a single execution process, priced with the current config
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutionProcessUsage {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub usage: ExecutionProcessTokenUsage,
+    /// None when the model has no configured pricing
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PricedTokenUsageBreakdown {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub usage: TokenUsageBreakdown,
+    /// None when the model has no configured pricing
+    pub cost_usd: Option<f64>,
+}
+
+/// Token and cost totals for a task, a project or all projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct UsageSummary {
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_creation_input_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_input_tokens: i64,
+    /// Sum over priced models only; see `unpriced_models`
+    pub total_cost_usd: f64,
+    /// Models that appear in the usage but have no configured pricing
+    pub unpriced_models: Vec<String>,
+    /// Per executor/model totals
+    pub breakdown: Vec<PricedTokenUsageBreakdown>,
+}
+
+fn cost_for(
+    pricing: &PricingConfig,
+    model: Option<&str>,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_creation_input_tokens: i64,
+    cache_read_input_tokens: i64,
+) -> Option<f64> {
+    let model_pricing = pricing.pricing_for_model(model?)?;
+    Some(model_pricing.cost_usd(
+        input_tokens.max(0) as u64,
+        output_tokens.max(0) as u64,
+        cache_creation_input_tokens.max(0) as u64,
+        cache_read_input_tokens.max(0) as u64,
+    ))
+}
+
+impl ExecutionProcessUsage {
+    pub fn priced(usage: ExecutionProcessTokenUsage, pricing: &PricingConfig) -> Self {
+        let cost_usd = cost_for(
+            pricing,
+            usage.model.as_deref(),
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+        );
+        Self { usage, cost_usd }
+    }
+}
+
+impl UsageSummary {
+    pub fn from_breakdown(rows: Vec<TokenUsageBreakdown>, pricing: &PricingConfig) -> Self {
+        let mut summary = UsageSummary::default();
+
+        for row in rows {
+            summary.input_tokens += row.input_tokens;
+            summary.output_tokens += row.output_tokens;
+            summary.cache_creation_input_tokens += row.cache_creation_input_tokens;
+            summary.cache_read_input_tokens += row.cache_read_input_tokens;
+
+            let cost_usd = cost_for(
+                pricing,
+                row.model.as_deref(),
+                row.input_tokens,
+                row.output_tokens,
+                row.cache_creation_input_tokens,
+                row.cache_read_input_tokens,
+            );
+            match cost_usd {
+                Some(cost) => summary.total_cost_usd += cost,
+                None => {
+                    let model = row.model.clone().unwrap_or_else(|| "unknown".to_string());
+                    if !summary.unpriced_models.contains(&model) {
+                        summary.unpriced_models.push(model);
+                    }
+                }
+            }
+
+            summary.breakdown.push(PricedTokenUsageBreakdown {
+                usage: row,
+                cost_usd,
+            });
+        }
+
+        summary
+    }
+}
+
+/// Reads recorded usage and prices it
+#[derive(Clone)]
+pub struct UsageService {
+    db: DBService,
+}
+
+impl UsageService {
+    pub fn new(db: DBService) -> Self {
+        Self { db }
+    }
+
+    pub async fn execution_process_usage(
+        &self,
+        execution_process_id: Uuid,
+        pricing: &PricingConfig,
+    ) -> Result<Option<ExecutionProcessUsage>, sqlx::Error> {
+        let usage = ExecutionProcessTokenUsage::find_by_execution_process_id(
+            &self.db.pool,
+            execution_process_id,
+        )
+        .await?;
+        Ok(usage.map(|usage| ExecutionProcessUsage::priced(usage, pricing)))
+    }
+
+    pub async fn task_summary(
+        &self,
+        task_id: Uuid,
+        pricing: &PricingConfig,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        let rows =
+            ExecutionProcessTokenUsage::find_breakdown_for_task(&self.db.pool, task_id).await?;
+        Ok(UsageSummary::from_breakdown(rows, pricing))
+    }
+
+    pub async fn project_summary(
+        &self,
+        project_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        pricing: &PricingConfig,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        let rows = ExecutionProcessTokenUsage::find_breakdown_for_project(
+            &self.db.pool,
+            project_id,
+            since,
+        )
+        .await?;
+        Ok(UsageSummary::from_breakdown(rows, pricing))
+    }
+
+    pub async fn global_summary(
+        &self,
+        since: Option<DateTime<Utc>>,
+        pricing: &PricingConfig,
+    ) -> Result<UsageSummary, sqlx::Error> {
+        let rows = ExecutionProcessTokenUsage::find_breakdown(&self.db.pool, since).await?;
+        Ok(UsageSummary::from_breakdown(rows, pricing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(model: Option<&str>, input_tokens: i64, output_tokens: i64) -> TokenUsageBreakdown {
+        TokenUsageBreakdown {
+            executor: Some("CLAUDE_CODE".to_string()),
+            model: model.map(str::to_string),
+            execution_count: 1,
+            input_tokens,
+            output_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_prices_known_models_and_reports_unknown() {
+        let pricing = PricingConfig::default();
+        let summary = UsageSummary::from_breakdown(
+            vec![
+                row(Some("claude-sonnet-4-5-20250929"), 1_000_000, 0),
+                row(Some("mystery-model"), 10, 10),
+                row(None, 5, 5),
+            ],
+            &pricing,
+        );
+
+        assert_eq!(summary.input_tokens, 1_000_015);
+        assert_eq!(summary.output_tokens, 15);
+        assert!((summary.total_cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(
+            summary.unpriced_models,
+            vec!["mystery-model".to_string(), "unknown".to_string()]
+        );
+        assert!(summary.breakdown[1].cost_usd.is_none());
+    }
+}
@@ -0,0 +1,92 @@
+//! Helpers for project verification scripts.
+//!
+//! A failed verification keeps the task out of review. The tail of the
+//! script's output is stored with the run and, when automatic fixes are
+//! enabled, sent back to the coding agent in a follow-up prompt.
+
+use utils::log_msg::LogMsg;
+
+/// How much of a verification script's output is kept
+pub const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// The last `max_bytes` of stdout and stderr, interleaved in the order they
+/// were written
+pub fn output_tail(history: &[LogMsg], max_bytes: usize) -> String {
+    let output: String = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    if output.len() <= max_bytes {
+        return output;
+    }
+    let mut start = output.len() - max_bytes;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    output[start..].to_string()
+}
+
+/// Follow-up prompt asking the agent to fix a failed verification
+pub fn fix_prompt(repo_name: Option<&str>, exit_code: Option<i64>, output: &str) -> String {
+    let script = match repo_name {
+        Some(name) => format!("The verification script for `{name}`"),
+        None => "The verification script".to_string(),
+    };
+    let status = match exit_code {
+        Some(code) => format!("failed with exit code {code}"),
+        None => "failed".to_string(),
+    };
+    let output = output.trim();
+    if output.is_empty() {
+        return format!(
+            "{script} {status} and produced no output. Investigate and fix the problem so that it passes."
+        );
+    }
+    format!(
+        "{script} {status}. Fix the problems it reports so that it passes.\n\nOutput:\n```\n{output}\n```"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_tail_keeps_stdout_and_stderr_in_order() {
+        let history = vec![
+            LogMsg::Stdout("building\n".to_string()),
+            LogMsg::SessionId("ignored".to_string()),
+            LogMsg::Stderr("error: oops\n".to_string()),
+            LogMsg::Finished,
+        ];
+        assert_eq!(output_tail(&history, 1024), "building\nerror: oops\n");
+    }
+
+    #[test]
+    fn output_tail_truncates_from_the_front_on_char_boundary() {
+        let history = vec![LogMsg::Stdout("ab🔥cd".to_string())];
+        // 8 bytes total; the last 5 start inside the emoji
+        assert_eq!(output_tail(&history, 5), "cd");
+        assert_eq!(output_tail(&history, 6), "🔥cd");
+    }
+
+    #[test]
+    fn fix_prompt_includes_repo_exit_code_and_output() {
+        let prompt = fix_prompt(Some("api"), Some(101), "test failed\n");
+        assert!(prompt.starts_with("The verification script for `api` failed with exit code 101."));
+        assert!(prompt.ends_with("```\ntest failed\n```"));
+    }
+
+    #[test]
+    fn fix_prompt_without_output() {
+        let prompt = fix_prompt(None, None, "  \n");
+        assert_eq!(
+            prompt,
+            "The verification script failed and produced no output. Investigate and fix the problem so that it passes."
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Outbound webhooks for task and execution lifecycle events.
+//!
+//! Emitting an event writes one `webhook_deliveries` row per subscribed
+//! webhook; [`WebhookDispatcher`] then POSTs the stored body, signed with the
+//! webhook's secret. Failed attempts are retried with exponential backoff and
+//! the schedule lives in the database, so pending retries survive restarts.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        webhook::{Webhook, WebhookEventType},
+        webhook_delivery::WebhookDelivery,
+    },
+};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use tokio::{task::JoinHandle, time::interval};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `sha256=<hex>` HMAC of the raw request body, keyed by the webhook secret
+pub const SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+pub const EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
+pub const DELIVERY_HEADER: &str = "X-Vibe-Kanban-Delivery";
+
+/// Attempts before a delivery is marked failed
+pub const MAX_ATTEMPTS: i64 = 6;
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256={hex}")
+}
+
+/// How long to wait after `attempts` failed attempts: 30s, 1m, 2m, ... capped
+/// at an hour
+pub fn retry_delay(attempts: i64) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = BASE_RETRY_DELAY_SECS
+        .saturating_mul(2i64.saturating_pow(exponent))
+        .min(MAX_RETRY_DELAY_SECS);
+    chrono::Duration::seconds(seconds)
+}
+
+/// Queue `event` for one webhook. The stored body is what gets signed and
+/// sent on every attempt.
+pub async fn enqueue_for_webhook(
+    pool: &SqlitePool,
+    webhook: &Webhook,
+    event: WebhookEventType,
+    data: &Value,
+) -> Result<WebhookDelivery, sqlx::Error> {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+    let body = json!({
+        "id": id,
+        "event": event,
+        "created_at": now,
+        "data": data,
+    });
+    WebhookDelivery::create(
+        pool,
+        id,
+        webhook.id,
+        &event.to_string(),
+        &body.to_string(),
+        now,
+    )
+    .await
+}
+
+/// Queue `event` for every enabled webhook subscribed to it
+pub async fn enqueue_event(
+    pool: &SqlitePool,
+    event: WebhookEventType,
+    data: Value,
+) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    let mut deliveries = Vec::new();
+    for webhook in Webhook::find_enabled(pool).await? {
+        if webhook.subscribes_to(event) {
+            deliveries.push(enqueue_for_webhook(pool, &webhook, event, &data).await?);
+        }
+    }
+    Ok(deliveries)
+}
+
+/// Fire-and-forget variant of [`enqueue_event`] for call sites that must not
+/// fail because of webhooks
+pub fn emit(db: &DBService, event: WebhookEventType, data: Value) {
+    let pool = db.pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = enqueue_event(&pool, event, data).await {
+            tracing::error!("Failed to queue webhook event {}: {}", event, e);
+        }
+    });
+}
+
+/// Result of one HTTP attempt
+#[derive(Debug)]
+pub enum DeliveryAttempt {
+    Delivered { status: u16 },
+    Failed { status: Option<u16>, error: String },
+}
+
+/// POST a stored delivery body to `url` with the event, delivery id and
+/// signature headers
+pub async fn send_webhook(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    event_type: &str,
+    delivery_id: Uuid,
+    payload: &str,
+) -> DeliveryAttempt {
+    let response = client
+        .post(url)
+        .timeout(REQUEST_TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event_type)
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, payload.as_bytes()))
+        .body(payload.to_string())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => DeliveryAttempt::Delivered {
+            status: response.status().as_u16(),
+        },
+        Ok(response) => {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let snippet: String = body.chars().take(200).collect();
+            DeliveryAttempt::Failed {
+                status: Some(status.as_u16()),
+                error: format!("Endpoint responded with {status}: {snippet}"),
+            }
+        }
+        Err(e) => DeliveryAttempt::Failed {
+            status: None,
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Background service that sends due webhook deliveries
+pub struct WebhookDispatcher {
+    db: DBService,
+    client: reqwest::Client,
+    poll_interval: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: DBService) -> Self {
+        Self {
+            db,
+            client: reqwest::Client::new(),
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    pub fn spawn(db: DBService) -> JoinHandle<()> {
+        let service = Self::new(db);
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        tracing::info!(
+            "Starting webhook dispatcher with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.dispatch_due(Utc::now()).await {
+                tracing::error!("Error dispatching webhooks: {}", e);
+            }
+        }
+    }
+
+    /// Attempt every delivery due at `now`; returns how many were attempted
+    pub async fn dispatch_due(&self, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let pool = &self.db.pool;
+        let due = WebhookDelivery::find_due(pool, now, DISPATCH_BATCH_SIZE).await?;
+        let count = due.len();
+        for delivery in due {
+            self.attempt(&delivery).await?;
+        }
+        Ok(count)
+    }
+
+    async fn attempt(&self, delivery: &WebhookDelivery) -> Result<(), sqlx::Error> {
+        let pool = &self.db.pool;
+        let Some(webhook) = Webhook::find_by_id(pool, delivery.webhook_id).await? else {
+            return Ok(());
+        };
+        if !webhook.enabled {
+            return WebhookDelivery::mark_attempt_failed(
+                pool,
+                delivery.id,
+                None,
+                "Webhook is disabled",
+                None,
+            )
+            .await;
+        }
+
+        let attempt = send_webhook(
+            &self.client,
+            &webhook.url,
+            &webhook.secret,
+            &delivery.event_type,
+            delivery.id,
+            &delivery.payload,
+        )
+        .await;
+
+        match attempt {
+            DeliveryAttempt::Delivered { status } => {
+                WebhookDelivery::mark_delivered(pool, delivery.id, i64::from(status), Utc::now())
+                    .await
+            }
+            DeliveryAttempt::Failed { status, error } => {
+                let attempts = delivery.attempts + 1;
+                let retry_at =
+                    (attempts < MAX_ATTEMPTS).then(|| Utc::now() + retry_delay(attempts));
+                tracing::warn!(
+                    "Webhook delivery {} to {} failed (attempt {}/{}): {}",
+                    delivery.id,
+                    webhook.url,
+                    attempts,
+                    MAX_ATTEMPTS,
+                    error
+                );
+                WebhookDelivery::mark_attempt_failed(
+                    pool,
+                    delivery.id,
+                    status.map(i64::from),
+                    &error,
+                    retry_at,
+                )
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload_matches_rfc4231() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(60));
+        assert_eq!(retry_delay(5), chrono::Duration::seconds(480));
+        assert_eq!(retry_delay(20), chrono::Duration::seconds(3600));
+    }
+}
@@ -1406,3 +1406,65 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn sandboxed_worktree_refuses_tampered_git_config() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let workspace_dir = td.path().join("workspace");
+    let worktree_path = workspace_dir.join("repo");
+
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    write_file(&repo_path, "common.txt", "base\n");
+    commit_all(&repo, "initial commit");
+    create_branch_from_head(&repo, "feature");
+    service
+        .add_worktree(&repo_path, &worktree_path, "feature", false)
+        .expect("create worktree");
+
+    let git = GitCli::new();
+    git.mark_sandboxed(&workspace_dir, std::slice::from_ref(&repo_path));
+    write_file(&worktree_path, "feat.txt", "feat change\n");
+    git.add_all(&worktree_path)
+        .expect("untouched worktree works");
+
+    // Worktree config is writable from inside the sandbox
+    let dot_git = fs::read_to_string(worktree_path.join(".git")).unwrap();
+    let git_dir = PathBuf::from(dot_git.trim().strip_prefix("gitdir:").unwrap().trim());
+    git.git(&repo_path, ["config", "extensions.worktreeConfig", "true"])
+        .unwrap();
+    write_file(
+        &git_dir,
+        "config.worktree",
+        "[core]\n\tsshCommand = touch pwned\n",
+    );
+    assert!(matches!(
+        git.add_all(&worktree_path),
+        Err(GitCliError::UntrustedWorktree(_))
+    ));
+    fs::remove_file(git_dir.join("config.worktree")).unwrap();
+    git.add_all(&worktree_path).unwrap();
+
+    // So is anything a .git file could be pointed at
+    let planted = worktree_path.join("planted");
+    GitService::new()
+        .initialize_repo_with_main_branch(&planted)
+        .expect("init planted repo");
+    write_file(
+        &worktree_path,
+        ".git",
+        &format!("gitdir: {}\n", planted.join(".git").display()),
+    );
+    assert!(matches!(
+        git.add_all(&worktree_path),
+        Err(GitCliError::UntrustedWorktree(_))
+    ));
+
+    // The main repository isn't affected
+    git.git(&repo_path, ["status"]).unwrap();
+}
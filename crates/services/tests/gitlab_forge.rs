@@ -0,0 +1,482 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post, put},
+};
+use db::models::merge::{CiStatus, ForgeKind, MergeStatus};
+use serde_json::{Value, json};
+use services::services::{
+    forge::{ForgeError, ForgeRepoInfo},
+    github::{CreatePrRequest, UnifiedPrComment},
+    gitlab::{GitLabRepoInfo, GitLabService, GitLabServiceError},
+};
+
+#[derive(Clone, Default)]
+struct MockGitLab {
+    /// (project id as received, PRIVATE-TOKEN header, request body)
+    created: Arc<Mutex<Vec<(String, Option<String>, Value)>>>,
+    /// (discussion id, request body)
+    replies: Arc<Mutex<Vec<(String, Value)>>>,
+    /// (merge request iid, request body)
+    updated: Arc<Mutex<Vec<(i64, Value)>>>,
+}
+
+fn token_of(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("PRIVATE-TOKEN")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn diff_note(id: i64, body: &str, resolved: bool) -> Value {
+    json!({
+        "id": id,
+        "body": body,
+        "author": { "username": "bob" },
+        "created_at": format!("2025-01-01T00:0{id}:00Z"),
+        "system": false,
+        "resolvable": true,
+        "resolved": resolved,
+        "position": {
+            "new_path": "src/lib.rs",
+            "old_path": "src/lib.rs",
+            "new_line": 40 + id,
+            "old_line": null,
+        },
+    })
+}
+
+fn mr_json(base: &str, iid: i64, state: &str) -> Value {
+    json!({
+        "iid": iid,
+        "web_url": format!("{base}/group/project/-/merge_requests/{iid}"),
+        "state": state,
+        "merged_at": if state == "merged" { json!("2025-01-02T03:04:05Z") } else { Value::Null },
+        "merge_commit_sha": if state == "merged" { json!("abc123") } else { Value::Null },
+        "squash_commit_sha": Value::Null,
+        "sha": "head123",
+        "head_pipeline": { "id": 9 },
+    })
+}
+
+async fn spawn_mock() -> (String, MockGitLab) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let state = MockGitLab::default();
+
+    let list_base = base.clone();
+    let get_base = base.clone();
+    let put_base = base.clone();
+    let app = Router::new()
+        .route(
+            "/api/v4/projects/{id}/merge_requests",
+            get(move |Query(query): Query<Vec<(String, String)>>| {
+                let base = list_base.clone();
+                async move {
+                    assert!(query.contains(&("source_branch".into(), "feature".into())));
+                    assert!(query.contains(&("state".into(), "all".into())));
+                    Json(json!([
+                        mr_json(&base, 3, "closed"),
+                        mr_json(&base, 2, "merged"),
+                        mr_json(&base, 1, "opened"),
+                    ]))
+                }
+            })
+            .post(
+                |State(state): State<MockGitLab>,
+                 Path(id): Path<String>,
+                 headers: HeaderMap,
+                 Json(body): Json<Value>| async move {
+                    state
+                        .created
+                        .lock()
+                        .unwrap()
+                        .push((id, token_of(&headers), body));
+                    (
+                        StatusCode::CREATED,
+                        Json(json!({
+                            "iid": 7,
+                            "web_url": "https://gitlab.example.com/group/project/-/merge_requests/7",
+                            "state": "opened",
+                        })),
+                    )
+                },
+            ),
+        )
+        .route(
+            "/api/v4/projects/{id}/merge_requests/{iid}",
+            get(move |Path((_id, iid)): Path<(String, i64)>| {
+                let base = get_base.clone();
+                async move { Json(mr_json(&base, iid, "merged")) }
+            })
+            .put(
+                move |State(state): State<MockGitLab>,
+                      Path((_id, iid)): Path<(String, i64)>,
+                      Json(body): Json<Value>| {
+                    let base = put_base.clone();
+                    async move {
+                        state.updated.lock().unwrap().push((iid, body));
+                        Json(mr_json(&base, iid, "opened"))
+                    }
+                },
+            ),
+        )
+        .route(
+            "/api/v4/projects/{id}/merge_requests/{iid}/notes",
+            get(|| async {
+                Json(json!([
+                    {
+                        "id": 10,
+                        "body": "added 2 commits",
+                        "author": { "username": "bot" },
+                        "created_at": "2025-01-01T00:00:00Z",
+                        "system": true,
+                    },
+                    {
+                        "id": 11,
+                        "body": "Looks good overall",
+                        "author": { "username": "alice" },
+                        "created_at": "2025-01-01T00:01:00Z",
+                        "system": false,
+                    },
+                    {
+                        "id": 12,
+                        "body": "Rename this",
+                        "author": { "username": "bob" },
+                        "created_at": "2025-01-01T00:02:00Z",
+                        "system": false,
+                        "position": {
+                            "new_path": "src/lib.rs",
+                            "old_path": "src/lib.rs",
+                            "new_line": 42,
+                            "old_line": null,
+                        },
+                    },
+                ]))
+            }),
+        )
+        .route(
+            "/api/v4/projects/{id}/merge_requests/{iid}/discussions",
+            get(|| async {
+                Json(json!([
+                    {
+                        "id": "general",
+                        "notes": [{
+                            "id": 1,
+                            "body": "Looks good overall",
+                            "author": { "username": "alice" },
+                            "created_at": "2025-01-01T00:00:00Z",
+                            "system": false,
+                        }],
+                    },
+                    { "id": "resolved", "notes": [diff_note(2, "Typo", true)] },
+                    {
+                        "id": "open",
+                        "notes": [diff_note(3, "Rename this", false), diff_note(4, "Agreed", false)],
+                    },
+                ]))
+            }),
+        )
+        .route(
+            "/api/v4/projects/{id}/merge_requests/{iid}/discussions/{discussion}/notes",
+            post(
+                |State(state): State<MockGitLab>,
+                 Path((_id, _iid, discussion)): Path<(String, i64, String)>,
+                 Json(body): Json<Value>| async move {
+                    state.replies.lock().unwrap().push((discussion, body));
+                    (StatusCode::CREATED, Json(json!({ "id": 5 })))
+                },
+            ),
+        )
+        .route(
+            "/api/v4/projects/{id}/pipelines/{pipeline}/jobs",
+            get(|Path((_id, pipeline)): Path<(String, i64)>| async move {
+                assert_eq!(pipeline, 9);
+                Json(json!([
+                    { "id": 1, "name": "lint", "status": "success", "web_url": "https://ci/1" },
+                    { "id": 2, "name": "test", "status": "failed", "web_url": "https://ci/2" },
+                    {
+                        "id": 3,
+                        "name": "flaky",
+                        "status": "failed",
+                        "web_url": "https://ci/3",
+                        "allow_failure": true,
+                    },
+                    { "id": 4, "name": "deploy", "status": "manual", "web_url": "https://ci/4" },
+                ]))
+            }),
+        )
+        .route(
+            "/api/v4/projects/{id}/jobs/{job}/trace",
+            get(|Path((_id, job)): Path<(String, i64)>| async move {
+                format!("job {job}\nerror: assertion failed\n")
+            }),
+        )
+        .route(
+            "/api/v4/user",
+            get(|headers: HeaderMap| async move {
+                if token_of(&headers).as_deref() == Some("good-token") {
+                    (StatusCode::OK, Json(json!({ "username": "alice" })))
+                } else {
+                    (
+                        StatusCode::UNAUTHORIZED,
+                        Json(json!({ "message": "401 Unauthorized" })),
+                    )
+                }
+            }),
+        )
+        .with_state(state.clone());
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (base, state)
+}
+
+fn repo_info(base: &str) -> GitLabRepoInfo {
+    GitLabRepoInfo::from_remote_url(&format!("{base}/group/project.git")).unwrap()
+}
+
+#[test]
+fn parses_gitlab_remote_urls() {
+    let ssh = GitLabRepoInfo::from_remote_url("git@gitlab.com:group/sub/project.git").unwrap();
+    assert_eq!(ssh.base_url, "https://gitlab.com");
+    assert_eq!(ssh.host, "gitlab.com");
+    assert_eq!(ssh.project_path, "group/sub/project");
+
+    let ssh_scheme =
+        GitLabRepoInfo::from_remote_url("ssh://git@gitlab.example.com/team/project.git").unwrap();
+    assert_eq!(ssh_scheme.base_url, "https://gitlab.example.com");
+    assert_eq!(ssh_scheme.project_path, "team/project");
+
+    let https = GitLabRepoInfo::from_remote_url("https://gitlab.com/group/project").unwrap();
+    assert_eq!(https.project_path, "group/project");
+
+    let mr_url =
+        GitLabRepoInfo::from_remote_url("https://gitlab.com/group/sub/project/-/merge_requests/12")
+            .unwrap();
+    assert_eq!(mr_url.project_path, "group/sub/project");
+
+    let with_port =
+        GitLabRepoInfo::from_remote_url("http://localhost:8080/group/project.git").unwrap();
+    assert_eq!(with_port.base_url, "http://localhost:8080");
+
+    assert!(GitLabRepoInfo::from_remote_url("https://gitlab.com/project").is_err());
+}
+
+#[test]
+fn detects_forge_from_remote() {
+    let no_hosts: Vec<String> = vec![];
+
+    let github =
+        ForgeRepoInfo::from_remote_url("https://github.com/owner/repo.git", &no_hosts).unwrap();
+    assert_eq!(github.kind(), ForgeKind::GitHub);
+
+    let gitlab =
+        ForgeRepoInfo::from_remote_url("git@gitlab.com:group/project.git", &no_hosts).unwrap();
+    assert_eq!(gitlab.kind(), ForgeKind::GitLab);
+
+    let by_prefix =
+        ForgeRepoInfo::from_remote_url("https://gitlab.corp.example/group/project", &no_hosts)
+            .unwrap();
+    assert_eq!(by_prefix.kind(), ForgeKind::GitLab);
+
+    let custom_hosts = vec!["code.example.com".to_string()];
+    let custom =
+        ForgeRepoInfo::from_remote_url("https://code.example.com/group/project", &custom_hosts)
+            .unwrap();
+    assert_eq!(custom.kind(), ForgeKind::GitLab);
+
+    assert!(matches!(
+        ForgeRepoInfo::from_remote_url("https://code.example.com/group/project", &no_hosts),
+        Err(ForgeError::UnsupportedRemote(_))
+    ));
+}
+
+#[test]
+fn missing_token_is_reported() {
+    assert!(matches!(
+        GitLabService::new(None),
+        Err(GitLabServiceError::TokenMissing)
+    ));
+    assert!(matches!(
+        GitLabService::new(Some(String::new())),
+        Err(GitLabServiceError::TokenMissing)
+    ));
+}
+
+#[tokio::test]
+async fn creates_draft_merge_request() {
+    let (base, mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+
+    let pr = service
+        .create_pr(
+            &repo_info(&base),
+            &CreatePrRequest {
+                title: "Add feature".to_string(),
+                body: Some("Details".to_string()),
+                head_branch: "feature".to_string(),
+                base_branch: "main".to_string(),
+                draft: Some(true),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(pr.number, 7);
+    assert!(matches!(pr.status, MergeStatus::Open));
+
+    let created = mock.created.lock().unwrap();
+    let (project_id, token, body) = &created[0];
+    assert_eq!(project_id, "group/project");
+    assert_eq!(token.as_deref(), Some("good-token"));
+    assert_eq!(body["title"], "Draft: Add feature");
+    assert_eq!(body["source_branch"], "feature");
+    assert_eq!(body["target_branch"], "main");
+    assert_eq!(body["description"], "Details");
+}
+
+#[tokio::test]
+async fn maps_merged_status_and_lists_open_first() {
+    let (base, _mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+    let info = repo_info(&base);
+
+    let pr = service.update_pr_status(&info, 5).await.unwrap();
+    assert!(matches!(pr.status, MergeStatus::Merged));
+    assert_eq!(pr.merge_commit_sha.as_deref(), Some("abc123"));
+    assert!(pr.merged_at.is_some());
+
+    let prs = service
+        .list_all_prs_for_branch(&info, "feature")
+        .await
+        .unwrap();
+    assert_eq!(prs.len(), 3);
+    assert!(matches!(prs[0].status, MergeStatus::Open));
+    assert_eq!(prs[0].number, 1);
+}
+
+#[tokio::test]
+async fn fetches_comments_without_system_notes() {
+    let (base, _mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+
+    let comments = service.get_pr_comments(&repo_info(&base), 4).await.unwrap();
+    assert_eq!(comments.len(), 2);
+
+    match &comments[0] {
+        UnifiedPrComment::General {
+            id, author, url, ..
+        } => {
+            assert_eq!(id, "11");
+            assert_eq!(author, "alice");
+            assert!(url.ends_with("/merge_requests/4#note_11"));
+        }
+        other => panic!("expected general comment, got {other:?}"),
+    }
+    match &comments[1] {
+        UnifiedPrComment::Review { id, path, line, .. } => {
+            assert_eq!(*id, 12);
+            assert_eq!(path, "src/lib.rs");
+            assert_eq!(*line, Some(42));
+        }
+        other => panic!("expected review comment, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn fetches_unresolved_review_comments() {
+    let (base, _mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+
+    let comments = service
+        .get_unresolved_review_comments(&repo_info(&base), 4)
+        .await
+        .unwrap();
+
+    let ids: Vec<i64> = comments
+        .iter()
+        .map(|comment| match comment {
+            UnifiedPrComment::Review { id, .. } => *id,
+            other => panic!("expected review comment, got {other:?}"),
+        })
+        .collect();
+    assert_eq!(ids, vec![3, 4]);
+}
+
+#[tokio::test]
+async fn replies_in_the_discussion_of_a_note() {
+    let (base, mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+
+    service
+        .reply_to_review_comment(&repo_info(&base), 4, 4, "Done")
+        .await
+        .unwrap();
+
+    let replies = mock.replies.lock().unwrap();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].0, "open");
+    assert_eq!(replies[0].1["body"], "Done");
+}
+
+#[tokio::test]
+async fn fetches_ci_status_of_the_head_pipeline() {
+    let (base, _mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+    let info = repo_info(&base);
+
+    let ci = service.get_ci_status(&info, 4).await.unwrap().unwrap();
+    assert_eq!(ci.head_sha, "head123");
+    assert_eq!(ci.status, CiStatus::Failure);
+    let statuses: Vec<(&str, CiStatus)> = ci
+        .checks
+        .iter()
+        .map(|check| (check.name.as_str(), check.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("lint", CiStatus::Success),
+            ("test", CiStatus::Failure),
+            ("flaky", CiStatus::Success),
+            ("deploy", CiStatus::Success),
+        ]
+    );
+
+    let log = service.get_ci_job_log(&info, 2).await.unwrap();
+    assert_eq!(log, "job 2\nerror: assertion failed\n");
+}
+
+#[tokio::test]
+async fn retargets_a_merge_request() {
+    let (base, mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+
+    service
+        .update_pr_base(&repo_info(&base), 5, "main")
+        .await
+        .unwrap();
+
+    let updated = mock.updated.lock().unwrap();
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].0, 5);
+    assert_eq!(updated[0].1, json!({ "target_branch": "main" }));
+}
+
+#[tokio::test]
+async fn rejected_token_is_auth_failure() {
+    let (base, _mock) = spawn_mock().await;
+    let service = GitLabService::new(Some("bad-token".to_string())).unwrap();
+
+    let result = service.check_token(&repo_info(&base)).await;
+    assert!(matches!(result, Err(GitLabServiceError::AuthFailed(_))));
+
+    let service = GitLabService::new(Some("good-token".to_string())).unwrap();
+    service.check_token(&repo_info(&base)).await.unwrap();
+}
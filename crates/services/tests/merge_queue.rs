@@ -0,0 +1,191 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use git2::{Repository, build::CheckoutBuilder};
+use services::services::{
+    git::{GitService, GitServiceError},
+    merge_queue::{QueuedBranch, is_branch_merged},
+};
+use tempfile::TempDir;
+
+fn write_file<P: AsRef<Path>>(base: P, rel: &str, content: &str) {
+    let path = base.as_ref().join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let mut f = fs::File::create(&path).unwrap();
+    f.write_all(content.as_bytes()).unwrap();
+}
+
+fn configure_user(repo: &Repository) {
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("user.name", "Test User").unwrap();
+    cfg.set_str("user.email", "test@example.com").unwrap();
+}
+
+fn checkout_branch(repo: &Repository, name: &str) {
+    repo.set_head(&format!("refs/heads/{name}")).unwrap();
+    let mut co = CheckoutBuilder::new();
+    co.force();
+    repo.checkout_head(Some(&mut co)).unwrap();
+}
+
+fn create_branch_from_head(repo: &Repository, name: &str) {
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let _ = repo.branch(name, &head, true).unwrap();
+}
+
+/// A repo on `main` with one shared file
+fn init_repo(root: &TempDir) -> PathBuf {
+    let repo_path = root.path().join("repo");
+    let s = GitService::new();
+    s.initialize_repo_with_main_branch(&repo_path).unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "shared.txt", "base\n");
+    s.commit(&repo_path, "add shared").unwrap();
+    repo_path
+}
+
+/// Creates an attempt's branch off main in its own worktree, with one commit
+/// writing `content` to `file`
+fn start_attempt(root: &TempDir, repo_path: &Path, branch: &str, file: &str, content: &str) {
+    let s = GitService::new();
+    let repo = Repository::open(repo_path).unwrap();
+    create_branch_from_head(&repo, branch);
+    let worktree_path = root.path().join(branch);
+    s.add_worktree(repo_path, &worktree_path, branch, false)
+        .unwrap();
+    write_file(&worktree_path, file, content);
+    s.commit(&worktree_path, &format!("{branch} change"))
+        .unwrap();
+}
+
+fn queued<'a>(
+    git: &'a GitService,
+    repo_path: &'a Path,
+    worktree_path: &'a Path,
+    branch: &'a str,
+) -> QueuedBranch<'a> {
+    QueuedBranch {
+        git,
+        repo_path,
+        worktree_path,
+        branch,
+        target_branch: "main",
+    }
+}
+
+#[test]
+fn queued_attempts_merge_one_after_another() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo(&td);
+    start_attempt(&td, &repo_path, "first", "first.txt", "first\n");
+    start_attempt(&td, &repo_path, "second", "second.txt", "second\n");
+    let s = GitService::new();
+    let (first_wt, second_wt) = (td.path().join("first"), td.path().join("second"));
+    let first = queued(&s, &repo_path, &first_wt, "first");
+    let second = queued(&s, &repo_path, &second_wt, "second");
+
+    // Queued -> rebasing -> merged
+    first.rebase().unwrap();
+    assert!(!first.target_moved().unwrap());
+    let merge_sha = first.merge("first").unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), merge_sha);
+    assert!(is_branch_merged(&s, &repo_path, "first", "main").unwrap());
+    assert!(!is_branch_merged(&s, &repo_path, "second", "main").unwrap());
+
+    // The next entry starts on top of what was merged before it
+    assert!(second.target_moved().unwrap());
+    second.rebase().unwrap();
+    assert!(!second.target_moved().unwrap());
+    second.merge("second").unwrap();
+    assert!(is_branch_merged(&s, &repo_path, "second", "main").unwrap());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("first.txt")).unwrap(),
+        "first\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("second.txt")).unwrap(),
+        "second\n"
+    );
+}
+
+#[test]
+fn target_moving_during_verification_requeues_the_entry() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo(&td);
+    start_attempt(&td, &repo_path, "queued", "queued.txt", "queued\n");
+    start_attempt(&td, &repo_path, "manual", "manual.txt", "manual\n");
+    let s = GitService::new();
+    let (queued_wt, manual_wt) = (td.path().join("queued"), td.path().join("manual"));
+    let entry = queued(&s, &repo_path, &queued_wt, "queued");
+
+    // Rebased and verifying while another attempt is merged by hand
+    entry.rebase().unwrap();
+    s.merge_changes(&repo_path, &manual_wt, "manual", "main", "manual")
+        .unwrap();
+
+    // What was verified is stale, so it goes back to the queue rather than merging
+    assert!(entry.target_moved().unwrap());
+    assert!(matches!(
+        entry.merge("queued"),
+        Err(GitServiceError::BranchesDiverged(_))
+    ));
+
+    // Rebased and verified again, it merges
+    entry.rebase().unwrap();
+    assert!(!entry.target_moved().unwrap());
+    entry.merge("queued").unwrap();
+    assert!(is_branch_merged(&s, &repo_path, "queued", "main").unwrap());
+}
+
+#[test]
+fn conflicting_rebase_fails_the_entry_and_stays_in_progress() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo(&td);
+    start_attempt(&td, &repo_path, "merged", "shared.txt", "merged\n");
+    start_attempt(
+        &td,
+        &repo_path,
+        "conflicting",
+        "shared.txt",
+        "conflicting\n",
+    );
+    let s = GitService::new();
+    let (merged_wt, conflicting_wt) = (td.path().join("merged"), td.path().join("conflicting"));
+    queued(&s, &repo_path, &merged_wt, "merged")
+        .merge("merged")
+        .unwrap();
+
+    let entry = queued(&s, &repo_path, &conflicting_wt, "conflicting");
+    assert!(matches!(
+        entry.rebase(),
+        Err(GitServiceError::MergeConflicts(_))
+    ));
+    // Left for the user or the agent to resolve, nothing merged
+    assert!(s.is_rebase_in_progress(&conflicting_wt).unwrap());
+    assert!(!is_branch_merged(&s, &repo_path, "conflicting", "main").unwrap());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("shared.txt")).unwrap(),
+        "merged\n"
+    );
+}
+
+#[test]
+fn repos_without_changes_count_as_merged() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    create_branch_from_head(&repo, "untouched");
+    start_attempt(&td, &repo_path, "changed", "changed.txt", "changed\n");
+
+    // A multi-repo attempt is done once its changed repos are merged
+    let s = GitService::new();
+    assert!(is_branch_merged(&s, &repo_path, "untouched", "main").unwrap());
+    assert!(!is_branch_merged(&s, &repo_path, "changed", "main").unwrap());
+}
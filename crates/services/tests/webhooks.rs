@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use hmac::{Hmac, Mac};
+use services::services::webhooks::{
+    DELIVERY_HEADER, DeliveryAttempt, EVENT_HEADER, SIGNATURE_HEADER, send_webhook,
+};
+use sha2::Sha256;
+use uuid::Uuid;
+
+#[derive(Clone, Default)]
+struct Listener {
+    /// (headers, raw body) of every request received
+    received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+}
+
+async fn spawn_listener(status: StatusCode) -> (String, Listener) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let state = Listener::default();
+
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(
+                move |State(state): State<Listener>, headers: HeaderMap, body: String| async move {
+                    state.received.lock().unwrap().push((headers, body));
+                    (status, "ok")
+                },
+            ),
+        )
+        .with_state(state.clone());
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    (url, state)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers.get(name).unwrap().to_str().unwrap()
+}
+
+#[tokio::test]
+async fn delivers_signed_payload() {
+    let (url, listener) = spawn_listener(StatusCode::OK).await;
+    let client = reqwest::Client::new();
+    let delivery_id = Uuid::new_v4();
+    let payload = r#"{"event":"task.status_changed","data":{"status":"done"}}"#;
+
+    let attempt = send_webhook(
+        &client,
+        &url,
+        "shh",
+        "task.status_changed",
+        delivery_id,
+        payload,
+    )
+    .await;
+    assert!(matches!(
+        attempt,
+        DeliveryAttempt::Delivered { status: 200 }
+    ));
+
+    let received = listener.received.lock().unwrap();
+    let (headers, body) = &received[0];
+    assert_eq!(body, payload);
+    assert_eq!(header(headers, EVENT_HEADER), "task.status_changed");
+    assert_eq!(header(headers, DELIVERY_HEADER), delivery_id.to_string());
+
+    // A receiver can verify the body with nothing but the shared secret
+    let signature = header(headers, SIGNATURE_HEADER)
+        .strip_prefix("sha256=")
+        .unwrap();
+    let signature: Vec<u8> = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&signature[i..i + 2], 16).unwrap())
+        .collect();
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"shh").unwrap();
+    mac.update(body.as_bytes());
+    mac.verify_slice(&signature).unwrap();
+}
+
+#[tokio::test]
+async fn error_status_is_a_failed_attempt() {
+    let (url, listener) = spawn_listener(StatusCode::INTERNAL_SERVER_ERROR).await;
+    let client = reqwest::Client::new();
+
+    let attempt = send_webhook(&client, &url, "shh", "ping", Uuid::new_v4(), "{}").await;
+    match attempt {
+        DeliveryAttempt::Failed { status, error } => {
+            assert_eq!(status, Some(500));
+            assert!(error.contains("500"));
+        }
+        other => panic!("expected failed attempt, got {other:?}"),
+    }
+    assert_eq!(listener.received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn unreachable_endpoint_has_no_status() {
+    // Bind and drop to get a port nothing is listening on
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    drop(listener);
+
+    let attempt = send_webhook(
+        &reqwest::Client::new(),
+        &url,
+        "shh",
+        "ping",
+        Uuid::new_v4(),
+        "{}",
+    )
+    .await;
+    assert!(matches!(
+        attempt,
+        DeliveryAttempt::Failed { status: None, .. }
+    ));
+}
@@ -0,0 +1,358 @@
+//! Minimal five-field cron expressions, evaluated in UTC.
+//!
+//! Supports `*`, lists (`1,15`), ranges (`1-5`), steps (`*/15`, `0-30/10`),
+//! month and weekday names (`JAN`, `MON`) and the `@hourly`, `@daily`,
+//! `@weekly`, `@monthly` and `@yearly` shorthands. As in standard cron, when
+//! both day-of-month and day-of-week are restricted a day matches if either
+//! does.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use thiserror::Error;
+
+/// Give up searching for the next run after this many days (covers leap years)
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CronParseError {
+    #[error("Cron expression must have 5 fields (minute hour day month weekday), got {0}")]
+    FieldCount(usize),
+    #[error("Invalid {field} value '{value}'")]
+    InvalidValue { field: &'static str, value: String },
+    #[error("{field} value {value} is out of range {min}-{max}")]
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY_OF_MONTH: FieldSpec = FieldSpec {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &MONTH_NAMES,
+};
+// 7 is accepted as an alias for Sunday and folded into 0 after parsing
+const DAY_OF_WEEK: FieldSpec = FieldSpec {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &WEEKDAY_NAMES,
+};
+
+/// Set of allowed values for one field, as a bitmask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    bits: u64,
+    restricted: bool,
+}
+
+impl Field {
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+
+    fn parse(expr: &str, spec: FieldSpec) -> Result<Self, CronParseError> {
+        let invalid = || CronParseError::InvalidValue {
+            field: spec.name,
+            value: expr.to_string(),
+        };
+
+        let mut bits = 0u64;
+        for part in expr.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step.parse().map_err(|_| invalid())?;
+                    if step == 0 {
+                        return Err(invalid());
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (spec.min, spec.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (
+                    Self::parse_value(start, spec)?,
+                    Self::parse_value(end, spec)?,
+                )
+            } else {
+                let start = Self::parse_value(range, spec)?;
+                // `5/15` means every 15 starting at 5
+                let end = if part.contains('/') { spec.max } else { start };
+                (start, end)
+            };
+            if start > end {
+                return Err(invalid());
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        // Like Vixie cron, `*/n` still counts as unrestricted for the
+        // day-of-month/day-of-week rule
+        Ok(Self {
+            bits,
+            restricted: !expr.starts_with('*'),
+        })
+    }
+
+    fn parse_value(value: &str, spec: FieldSpec) -> Result<u32, CronParseError> {
+        if let Some(index) = spec
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            return Ok(index as u32 + spec.min);
+        }
+
+        let parsed: u32 = value.parse().map_err(|_| CronParseError::InvalidValue {
+            field: spec.name,
+            value: value.to_string(),
+        })?;
+        if parsed < spec.min || parsed > spec.max {
+            return Err(CronParseError::OutOfRange {
+                field: spec.name,
+                value: parsed,
+                min: spec.min,
+                max: spec.max,
+            });
+        }
+        Ok(parsed)
+    }
+}
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    source: String,
+    minute: Field,
+    hour: Field,
+    day_of_month: Field,
+    month: Field,
+    day_of_week: Field,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, CronParseError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(CronParseError::FieldCount(fields.len()));
+        }
+
+        let mut day_of_week = Field::parse(fields[4], DAY_OF_WEEK)?;
+        if day_of_week.contains(7) {
+            day_of_week.bits = (day_of_week.bits & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            source: expr.trim().to_string(),
+            minute: Field::parse(fields[0], MINUTE)?,
+            hour: Field::parse(fields[1], HOUR)?,
+            day_of_month: Field::parse(fields[2], DAY_OF_MONTH)?,
+            month: Field::parse(fields[3], MONTH)?,
+            day_of_week,
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.month.contains(date.month()) {
+            return false;
+        }
+        let dom = self.day_of_month.contains(date.day());
+        let dow = self
+            .day_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        match (self.day_of_month.restricted, self.day_of_week.restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// The first matching minute strictly after `after`, or None when the
+    /// expression never fires (e.g. `0 0 30 2 *`)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+
+        let mut date = start.date_naive();
+        let mut first_day = true;
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_day(date) {
+                let (from_hour, from_minute) = if first_day {
+                    (start.hour(), start.minute())
+                } else {
+                    (0, 0)
+                };
+                for hour in from_hour..24 {
+                    if !self.hour.contains(hour) {
+                        continue;
+                    }
+                    let minute_start = if hour == from_hour { from_minute } else { 0 };
+                    if let Some(minute) = (minute_start..60).find(|m| self.minute.contains(*m)) {
+                        let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+                        return Some(date.and_time(time).and_utc());
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            first_day = false;
+        }
+        None
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_every_fifteen_minutes() {
+        let cron = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 1, 1, 10, 7)),
+            Some(at(2025, 1, 1, 10, 15))
+        );
+        assert_eq!(
+            cron.next_after(at(2025, 1, 1, 10, 45)),
+            Some(at(2025, 1, 1, 11, 0))
+        );
+    }
+
+    #[test]
+    fn test_weekly_on_named_weekday() {
+        // 2025-01-01 is a Wednesday; the next Monday is the 6th
+        let cron = CronSchedule::parse("30 9 * * MON").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 1, 1, 12, 0)),
+            Some(at(2025, 1, 6, 9, 30))
+        );
+        // Exactly at the scheduled minute moves on to the following week
+        assert_eq!(
+            cron.next_after(at(2025, 1, 6, 9, 30)),
+            Some(at(2025, 1, 13, 9, 30))
+        );
+    }
+
+    #[test]
+    fn test_shorthands_and_sunday_alias() {
+        let monthly = CronSchedule::parse("@monthly").unwrap();
+        assert_eq!(
+            monthly.next_after(at(2025, 1, 15, 0, 0)),
+            Some(at(2025, 2, 1, 0, 0))
+        );
+
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(
+            sunday.next_after(at(2025, 1, 1, 0, 0)),
+            Some(at(2025, 1, 5, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // Fires on the 10th and on every Friday
+        let cron = CronSchedule::parse("0 8 10 * FRI").unwrap();
+        assert_eq!(
+            cron.next_after(at(2025, 1, 1, 0, 0)),
+            Some(at(2025, 1, 3, 8, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2025, 1, 8, 0, 0)),
+            Some(at(2025, 1, 10, 8, 0))
+        );
+    }
+
+    #[test]
+    fn test_impossible_date_never_fires() {
+        let cron = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(cron.next_after(at(2025, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert_eq!(
+            CronSchedule::parse("* * * *"),
+            Err(CronParseError::FieldCount(4))
+        );
+        assert!(matches!(
+            CronSchedule::parse("60 * * * *"),
+            Err(CronParseError::OutOfRange {
+                field: "minute",
+                ..
+            })
+        ));
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 * * FUNDAY").is_err());
+    }
+}
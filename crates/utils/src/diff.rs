@@ -30,6 +30,21 @@ pub struct Diff {
     pub deletions: Option<usize>,
 }
 
+impl Diff {
+    /// Added and deleted line counts, using the precomputed stats when the
+    /// contents were omitted
+    pub fn line_counts(&self) -> (usize, usize) {
+        match (self.additions, self.deletions) {
+            (Some(add), Some(del)) => (add, del),
+            _ => {
+                let old = self.old_content.as_deref().unwrap_or("");
+                let new = self.new_content.as_deref().unwrap_or("");
+                compute_line_change_counts(old, new)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 #[serde(rename_all = "camelCase")]
@@ -1,19 +1,37 @@
 use axum::{extract::ws::Message, response::sse::Event};
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 pub const EV_STDOUT: &str = "stdout";
 pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
+pub const EV_TOKEN_USAGE: &str = "token_usage";
 pub const EV_FINISHED: &str = "finished";
 
+/// Cumulative token counts reported by a coding agent for one execution.
+/// `input_tokens` excludes tokens served from or written to the prompt cache.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct TokenUsage {
+    pub model: Option<String>,
+    #[ts(type = "number")]
+    pub input_tokens: u64,
+    #[ts(type = "number")]
+    pub output_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_creation_input_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_read_input_tokens: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
     Stdout(String),
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
+    TokenUsage(TokenUsage),
     Finished,
 }
 
@@ -24,6 +42,7 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
+            LogMsg::TokenUsage(_) => EV_TOKEN_USAGE,
             LogMsg::Finished => EV_FINISHED,
         }
     }
@@ -37,6 +56,10 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
+            LogMsg::TokenUsage(usage) => {
+                let data = serde_json::to_string(usage).unwrap_or_else(|_| "{}".to_string());
+                Event::default().event(EV_TOKEN_USAGE).data(data)
+            }
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
         }
     }
@@ -73,6 +96,10 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
+            LogMsg::TokenUsage(usage) => {
+                let model_len = usage.model.as_deref().map(str::len).unwrap_or(0);
+                EV_TOKEN_USAGE.len() + model_len + 4 * size_of::<u64>() + OVERHEAD
+            }
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
         }
     }
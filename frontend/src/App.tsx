@@ -6,6 +6,7 @@ import { Projects } from '@/pages/Projects';
 import { ProjectTasks } from '@/pages/ProjectTasks';
 import { Dashboard } from '@/pages/Dashboard';
 import { FullAttemptLogsPage } from '@/pages/FullAttemptLogs';
+import { ImportedAttempts } from '@/pages/ImportedAttempts';
 import { NormalLayout } from '@/components/layout/NormalLayout';
 import { useAuth } from '@/hooks';
 import { usePreviousPath } from '@/hooks/usePreviousPath';
@@ -15,6 +16,7 @@ import {
   GeneralSettings,
   McpSettings,
   OrganizationSettings,
+  PlaybookSettings,
   ProjectSettings,
   SettingsLayout,
 } from '@/pages/settings/';
@@ -131,7 +133,16 @@ function AppContent() {
                   />
                   <Route path="agents" element={<AgentSettings />} />
                   <Route path="mcp" element={<McpSettings />} />
+                  <Route path="playbooks" element={<PlaybookSettings />} />
                 </Route>
+                <Route
+                  path="/imported-attempts"
+                  element={<ImportedAttempts />}
+                />
+                <Route
+                  path="/imported-attempts/:importId"
+                  element={<ImportedAttempts />}
+                />
                 <Route
                   path="/mcp-servers"
                   element={<Navigate to="/settings/mcp" replace />}
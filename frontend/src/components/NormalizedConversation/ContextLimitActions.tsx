@@ -0,0 +1,69 @@
+import { useTranslation } from 'react-i18next';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { Loader2, Shrink } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { sessionsApi } from '@/lib/api';
+import { useExecutionProcessesContext } from '@/contexts/ExecutionProcessesContext';
+import { taskAttemptKeys } from '@/hooks/useTaskAttempts';
+import type { WorkspaceWithSession } from '@/types/attempt';
+
+/**
+ * Offers to continue in a fresh session after the agent ran out of context
+ * window. Only shown for the latest session while nothing is running.
+ */
+export function ContextLimitActions({
+  executionProcessId,
+  taskAttempt,
+}: {
+  executionProcessId?: string;
+  taskAttempt?: WorkspaceWithSession;
+}) {
+  const { t } = useTranslation('tasks');
+  const queryClient = useQueryClient();
+  const { executionProcessesByIdAll, isAttemptRunningAll } =
+    useExecutionProcessesContext();
+
+  const session = taskAttempt?.session;
+  const process = executionProcessId
+    ? executionProcessesByIdAll[executionProcessId]
+    : undefined;
+
+  const compactMutation = useMutation({
+    mutationFn: (sessionId: string) => sessionsApi.compact(sessionId),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: ['taskAttemptWithSession', taskAttempt?.id],
+      });
+      queryClient.invalidateQueries({
+        queryKey: taskAttemptKeys.byTaskWithSessions(taskAttempt?.task_id),
+      });
+    },
+  });
+
+  if (!session || process?.session_id !== session.id || isAttemptRunningAll) {
+    return null;
+  }
+
+  return (
+    <div className="flex items-center gap-2 pt-2">
+      <Button
+        size="sm"
+        variant="outline"
+        onClick={() => compactMutation.mutate(session.id)}
+        disabled={compactMutation.isPending}
+      >
+        {compactMutation.isPending ? (
+          <Loader2 className="h-3 w-3 mr-1 animate-spin" />
+        ) : (
+          <Shrink className="h-3 w-3 mr-1" />
+        )}
+        {t('contextCompaction.continueInNewSession')}
+      </Button>
+      {compactMutation.error && (
+        <span className="text-xs text-destructive">
+          {compactMutation.error.message}
+        </span>
+      )}
+    </div>
+  );
+}
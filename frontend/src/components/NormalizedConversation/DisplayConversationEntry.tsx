@@ -32,6 +32,7 @@ import RawLogText from '../common/RawLogText';
 import UserMessage from './UserMessage';
 import PendingApprovalEntry from './PendingApprovalEntry';
 import { NextActionCard } from './NextActionCard';
+import { ContextLimitActions } from './ContextLimitActions';
 import { cn } from '@/lib/utils';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 
@@ -753,6 +754,16 @@ function DisplayConversationEntry({
         className={`px-4 py-2 text-sm space-y-3 ${greyed ? 'opacity-50 pointer-events-none' : ''}`}
       >
         {body}
+        {status.status === 'auto_approved' && (
+          <div
+            className="text-xs opacity-70"
+            title={status.reason ?? undefined}
+          >
+            {status.rule
+              ? t('conversation.autoApprovedByRule', { rule: status.rule })
+              : t('conversation.autoApprovedByDefault')}
+          </div>
+        )}
       </div>
     );
 
@@ -787,6 +798,14 @@ function DisplayConversationEntry({
           contentClassName={getContentClassName(entryType)}
           taskAttemptId={taskAttempt?.id}
         />
+        {taskAttempt &&
+          entryType.type === 'error_message' &&
+          entryType.error_type.type === 'context_limit_exceeded' && (
+            <ContextLimitActions
+              executionProcessId={executionProcessId}
+              taskAttempt={taskAttempt}
+            />
+          )}
       </div>
     );
   }
@@ -0,0 +1,148 @@
+import { useCallback, useMemo, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { useTranslation } from 'react-i18next';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import WYSIWYGEditor from '@/components/ui/wysiwyg';
+import { useProject } from '@/contexts/ProjectContext';
+import { cn } from '@/lib/utils';
+import { paths } from '@/lib/paths';
+import { VariantSelector } from '@/components/tasks/VariantSelector';
+import { Button } from '@/components/ui/button';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { AlertCircle, GitFork, Loader2, X } from 'lucide-react';
+import { sessionsApi } from '@/lib/api';
+import type { WorkspaceWithSession } from '@/types/attempt';
+import { useAttemptExecution } from '@/hooks/useAttemptExecution';
+import { useUserSystem } from '@/components/ConfigProvider';
+import { useVariant } from '@/hooks/useVariant';
+import { taskAttemptKeys } from '@/hooks/useTaskAttempts';
+import { BaseAgentCapability } from 'shared/types';
+
+/**
+ * Prompt for a new attempt that continues the conversation from the end of
+ * `executionProcessId`, leaving this attempt untouched.
+ */
+export function ForkEditorInline({
+  attempt,
+  executionProcessId,
+  onCancelled,
+}: {
+  attempt: WorkspaceWithSession;
+  executionProcessId: string;
+  onCancelled?: () => void;
+}) {
+  const { t } = useTranslation(['tasks', 'common']);
+  const navigate = useNavigate();
+  const queryClient = useQueryClient();
+  const { attemptData } = useAttemptExecution(attempt.id);
+  const { profiles, capabilities } = useUserSystem();
+  const { projectId } = useProject();
+
+  const [message, setMessage] = useState('');
+
+  const sessionId = attempt.session?.id;
+  const executor = attempt.session?.executor ?? '';
+  const resumesSession =
+    capabilities?.[executor]?.includes(BaseAgentCapability.SESSION_FORK) ??
+    false;
+
+  const processVariant = useMemo<string | null>(() => {
+    const process = attemptData.processes?.find(
+      (p) => p.id === executionProcessId
+    );
+    const typ = process?.executor_action?.typ;
+    if (
+      typ?.type === 'CodingAgentInitialRequest' ||
+      typ?.type === 'CodingAgentFollowUpRequest'
+    ) {
+      return typ.executor_profile_id.variant;
+    }
+    return null;
+  }, [attemptData.processes, executionProcessId]);
+
+  const { selectedVariant, setSelectedVariant } = useVariant({
+    processVariant,
+    scratchVariant: undefined,
+  });
+
+  const forkMutation = useMutation({
+    mutationFn: () =>
+      sessionsApi.fork(sessionId ?? '', {
+        execution_process_id: executionProcessId,
+        prompt: message,
+        variant: selectedVariant,
+      }),
+    onSuccess: ({ workspace }) => {
+      queryClient.invalidateQueries({
+        queryKey: taskAttemptKeys.byTask(attempt.task_id),
+      });
+      queryClient.invalidateQueries({
+        queryKey: taskAttemptKeys.byTaskWithSessions(attempt.task_id),
+      });
+      onCancelled?.();
+      if (projectId) {
+        navigate(paths.attempt(projectId, attempt.task_id, workspace.id));
+      }
+    },
+  });
+
+  const isSending = forkMutation.isPending;
+  const canSend = !!message.trim() && !!sessionId;
+
+  const onSend = useCallback(() => {
+    if (!canSend || isSending) return;
+    forkMutation.mutate();
+  }, [canSend, isSending, forkMutation]);
+
+  return (
+    <div className="space-y-2">
+      <p className="text-xs text-muted-foreground">
+        {resumesSession
+          ? t('forkConversation.resumesSession')
+          : t('forkConversation.replaysConversation')}
+      </p>
+      <div className="relative">
+        <WYSIWYGEditor
+          placeholder={t('forkConversation.placeholder')}
+          value={message}
+          onChange={setMessage}
+          disabled={isSending}
+          onCmdEnter={onSend}
+          className={cn('min-h-[40px]', 'bg-background')}
+          projectId={projectId}
+          taskAttemptId={attempt.id}
+          autoFocus
+        />
+        {isSending && (
+          <div className="pointer-events-none absolute inset-0 z-20 flex items-center justify-center bg-background/60">
+            <Loader2 className="h-4 w-4 animate-spin" />
+          </div>
+        )}
+      </div>
+
+      <div className="flex items-center gap-2">
+        <VariantSelector
+          selectedVariant={selectedVariant}
+          onChange={setSelectedVariant}
+          currentProfile={profiles?.[executor] ?? null}
+        />
+        <div className="ml-auto flex items-center gap-2">
+          <Button variant="outline" onClick={onCancelled} disabled={isSending}>
+            <X className="h-3 w-3 mr-1" />{' '}
+            {t('buttons.cancel', { ns: 'common' })}
+          </Button>
+          <Button onClick={onSend} disabled={!canSend || isSending}>
+            <GitFork className="h-3 w-3 mr-1" /> {t('forkConversation.fork')}
+          </Button>
+        </div>
+      </div>
+
+      {forkMutation.error && (
+        <Alert variant="destructive">
+          <AlertCircle className="h-4 w-4" />
+          <AlertDescription>{forkMutation.error.message}</AlertDescription>
+        </Alert>
+      )}
+    </div>
+  );
+}
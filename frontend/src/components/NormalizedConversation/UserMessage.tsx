@@ -6,6 +6,7 @@ import { useUserSystem } from '@/components/ConfigProvider';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useAttemptExecution } from '@/hooks/useAttemptExecution';
 import { RetryEditorInline } from './RetryEditorInline';
+import { ForkEditorInline } from './ForkEditorInline';
 
 const UserMessage = ({
   content,
@@ -17,10 +18,13 @@ const UserMessage = ({
   taskAttempt?: WorkspaceWithSession;
 }) => {
   const [isEditing, setIsEditing] = useState(false);
+  const [isForking, setIsForking] = useState(false);
   const { capabilities } = useUserSystem();
   const { activeRetryProcessId, setActiveRetryProcessId, isProcessGreyed } =
     useRetryUi();
-  const { isAttemptRunning } = useAttemptExecution(taskAttempt?.id);
+  const { isAttemptRunning, attemptData } = useAttemptExecution(
+    taskAttempt?.id
+  );
 
   const canFork = !!(
     taskAttempt?.session?.executor &&
@@ -52,11 +56,27 @@ const UserMessage = ({
   // Only show retry button when allowed (has process, can fork, not running)
   const canRetry = executionProcessId && canFork && !isAttemptRunning;
 
+  // Any finished turn can be forked into a new attempt, whatever the agent
+  const processStatus = attemptData.processes?.find(
+    (p) => p.id === executionProcessId
+  )?.status;
+  const canForkTurn =
+    !!executionProcessId &&
+    !!taskAttempt?.session &&
+    !!processStatus &&
+    processStatus !== 'running';
+
   return (
     <div className={`py-2 ${greyed ? 'opacity-50 pointer-events-none' : ''}`}>
       <div className="bg-background px-4 py-2 text-sm">
         <div className="py-3">
-          {showRetryEditor && taskAttempt ? (
+          {isForking && executionProcessId && taskAttempt ? (
+            <ForkEditorInline
+              attempt={taskAttempt}
+              executionProcessId={executionProcessId}
+              onCancelled={() => setIsForking(false)}
+            />
+          ) : showRetryEditor && taskAttempt ? (
             <RetryEditorInline
               attempt={taskAttempt}
               executionProcessId={executionProcessId}
@@ -70,6 +90,7 @@ const UserMessage = ({
               className="whitespace-pre-wrap break-words flex flex-col gap-1 font-light"
               taskAttemptId={taskAttempt?.id}
               onEdit={canRetry ? startRetry : undefined}
+              onFork={canForkTurn ? () => setIsForking(true) : undefined}
             />
           )}
         </div>
@@ -58,6 +58,11 @@ export {
   type ChangeTargetBranchDialogProps,
   type ChangeTargetBranchDialogResult,
 } from './tasks/ChangeTargetBranchDialog';
+export {
+  HandoffDialog,
+  type HandoffDialogProps,
+  type HandoffDialogResult,
+} from './tasks/HandoffDialog';
 export {
   RebaseDialog,
   type RebaseDialogProps,
@@ -198,6 +198,12 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           setError(result.message || t(gitCliErrorKey));
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'gitlab_not_authenticated') {
+          setError(
+            result.message || t('createPrDialog.errors.gitlabNotAuthenticated')
+          );
+          setGhCliHelp(null);
+          return;
         } else if (result.error.type === 'target_branch_not_found') {
           setError(
             t('createPrDialog.errors.targetBranchNotFound', {
@@ -225,6 +225,9 @@ function getErrorMessage(error: unknown): string {
     if (errorData?.type === 'github_cli_not_logged_in') {
       return 'GitHub CLI is not logged in. Please run "gh auth login" to authenticate.';
     }
+    if (errorData?.type === 'gitlab_not_authenticated') {
+      return 'GitLab is not authenticated. Set a GitLab access token in settings or the GITLAB_TOKEN environment variable.';
+    }
   }
   return 'Failed to load PR comments. Please try again.';
 }
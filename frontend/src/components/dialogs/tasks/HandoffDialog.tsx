@@ -0,0 +1,169 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { ExecutorProfileSelector } from '@/components/settings';
+import { useUserSystem } from '@/components/ConfigProvider';
+import { taskAttemptKeys } from '@/hooks/useTaskAttempts';
+import { sessionsApi } from '@/lib/api';
+import type { BaseCodingAgent, ExecutorProfileId, Session } from 'shared/types';
+import NiceModal, { useModal } from '@ebay/nice-modal-react';
+import { defineModal } from '@/lib/modals';
+
+export interface HandoffDialogProps {
+  session: Session;
+  taskId: string;
+  initialPrompt?: string;
+}
+
+export type HandoffDialogResult = {
+  action: 'handed_off' | 'canceled';
+};
+
+/**
+ * Hands the workspace over to a different coding agent. The new agent starts
+ * a fresh session in the same worktree, primed with what the previous agent
+ * did.
+ */
+const HandoffDialogImpl = NiceModal.create<HandoffDialogProps>(
+  ({ session, taskId, initialPrompt = '' }) => {
+    const modal = useModal();
+    const { t } = useTranslation(['tasks', 'common']);
+    const queryClient = useQueryClient();
+    const { profiles, config } = useUserSystem();
+
+    // Default to the user's preferred agent unless that is the one handing off
+    const [profile, setProfile] = useState<ExecutorProfileId | null>(() => {
+      const preferred = config?.executor_profile ?? null;
+      if (preferred && preferred.executor !== session.executor) {
+        return preferred;
+      }
+      const other = Object.keys(profiles ?? {}).find(
+        (executor) => executor !== session.executor
+      );
+      return other
+        ? { executor: other as BaseCodingAgent, variant: null }
+        : preferred;
+    });
+    const [prompt, setPrompt] = useState(initialPrompt);
+
+    const handoffMutation = useMutation({
+      mutationFn: (executorProfileId: ExecutorProfileId) =>
+        sessionsApi.handoff(session.id, {
+          executor_profile_id: executorProfileId,
+          prompt,
+        }),
+      onSuccess: () => {
+        queryClient.invalidateQueries({
+          queryKey: ['taskAttemptWithSession', session.workspace_id],
+        });
+        queryClient.invalidateQueries({
+          queryKey: taskAttemptKeys.byTaskWithSessions(taskId),
+        });
+        modal.resolve({ action: 'handed_off' } as HandoffDialogResult);
+        modal.hide();
+      },
+    });
+
+    const handleConfirm = () => {
+      if (!profile || !prompt.trim()) return;
+      handoffMutation.mutate(profile);
+    };
+
+    const handleCancel = () => {
+      modal.resolve({ action: 'canceled' } as HandoffDialogResult);
+      modal.hide();
+    };
+
+    const handleOpenChange = (open: boolean) => {
+      if (!open) {
+        handleCancel();
+      }
+    };
+
+    const isSending = handoffMutation.isPending;
+
+    return (
+      <Dialog open={modal.visible} onOpenChange={handleOpenChange}>
+        <DialogContent className="sm:max-w-[500px]">
+          <DialogHeader>
+            <DialogTitle>{t('handoff.dialog.title')}</DialogTitle>
+            <DialogDescription>
+              {t('handoff.dialog.description', {
+                executor: session.executor,
+              })}
+            </DialogDescription>
+          </DialogHeader>
+
+          <div className="space-y-4">
+            {profiles && (
+              <ExecutorProfileSelector
+                profiles={profiles}
+                selectedProfile={profile}
+                onProfileSelect={setProfile}
+                disabled={isSending}
+                showLabel={true}
+              />
+            )}
+
+            <div className="space-y-2">
+              <Label htmlFor="handoff-prompt">
+                {t('handoff.dialog.promptLabel')}
+              </Label>
+              <Textarea
+                id="handoff-prompt"
+                value={prompt}
+                onChange={(e) => setPrompt(e.target.value)}
+                placeholder={t('handoff.dialog.promptPlaceholder')}
+                disabled={isSending}
+                autoFocus
+              />
+            </div>
+
+            {handoffMutation.error && (
+              <Alert variant="destructive">
+                <AlertDescription>
+                  {handoffMutation.error.message}
+                </AlertDescription>
+              </Alert>
+            )}
+          </div>
+
+          <DialogFooter>
+            <Button
+              variant="outline"
+              onClick={handleCancel}
+              disabled={isSending}
+            >
+              {t('common:buttons.cancel')}
+            </Button>
+            <Button
+              onClick={handleConfirm}
+              disabled={isSending || !profile || !prompt.trim()}
+            >
+              {isSending
+                ? t('handoff.dialog.inProgress')
+                : t('handoff.dialog.action')}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    );
+  }
+);
+
+export const HandoffDialog = defineModal<
+  HandoffDialogProps,
+  HandoffDialogResult
+>(HandoffDialogImpl);
@@ -10,6 +10,7 @@ import {
 } from '@/components/ui/dropdown-menu';
 import {
   FolderOpen,
+  Archive,
   Settings,
   BookOpen,
   MessageCircleQuestion,
@@ -39,7 +40,10 @@ import { OAuthDialog } from '@/components/dialogs/global/OAuthDialog';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { oauthApi } from '@/lib/api';
 
-const INTERNAL_NAV = [{ label: 'Projects', icon: FolderOpen, to: '/projects' }];
+const INTERNAL_NAV = [
+  { label: 'Projects', icon: FolderOpen, to: '/projects' },
+  { label: 'Imported attempts', icon: Archive, to: '/imported-attempts' },
+];
 
 const EXTERNAL_LINKS = [
   {
@@ -76,6 +76,10 @@ vi.mock('@/components/tasks/TodoPanel', () => ({
   default: () => <div data-testid="todo-panel" />,
 }));
 
+vi.mock('@/components/tasks/TaskPlaybookSelector', () => ({
+  TaskPlaybookSelector: () => <div data-testid="task-playbook-selector" />,
+}));
+
 vi.mock('@/components/ui/table', () => ({
   DataTable: ({
     headerContent,
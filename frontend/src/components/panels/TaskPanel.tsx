@@ -12,6 +12,7 @@ import { PlusIcon } from 'lucide-react';
 import { CreateAttemptDialog } from '@/components/dialogs/tasks/CreateAttemptDialog';
 import WYSIWYGEditor from '@/components/ui/wysiwyg';
 import { DataTable, type ColumnDef } from '@/components/ui/table';
+import { TaskPlaybookSelector } from '@/components/tasks/TaskPlaybookSelector';
 
 interface TaskPanelProps {
   task: TaskWithAttemptStatus | null;
@@ -109,6 +110,8 @@ const TaskPanel = ({ task }: TaskPanelProps) => {
           </div>
 
           <div className="mt-6 flex-shrink-0 space-y-4">
+            <TaskPlaybookSelector taskId={task.id} />
+
             {task.parent_workspace_id && (
               <DataTable
                 data={parentAttempt ? [parentAttempt] : []}
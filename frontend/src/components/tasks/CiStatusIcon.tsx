@@ -0,0 +1,31 @@
+import { CheckCircle, Clock, XCircle } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import type { CiStatus } from 'shared/types';
+import { cn } from '@/lib/utils';
+
+const ICONS = {
+  pending: { Icon: Clock, color: 'text-amber-500' },
+  success: { Icon: CheckCircle, color: 'text-emerald-500' },
+  failure: { Icon: XCircle, color: 'text-destructive' },
+} as const;
+
+/**
+ * Icon for the CI result of a PR
+ */
+export function CiStatusIcon({
+  status,
+  className,
+}: {
+  status: CiStatus;
+  className?: string;
+}) {
+  const { t } = useTranslation('tasks');
+  const { Icon, color } = ICONS[status];
+  const label = t(`git.ci.status.${status}`);
+
+  return (
+    <span className="inline-flex" title={label} aria-label={label}>
+      <Icon className={cn('h-4 w-4 shrink-0', color, className)} />
+    </span>
+  );
+}
@@ -0,0 +1,101 @@
+import { useTranslation } from 'react-i18next';
+import {
+  Check,
+  ChevronUp,
+  Circle,
+  CircleDot,
+  CircleSlash,
+  X,
+} from 'lucide-react';
+import { Card } from '../ui/card';
+import { usePlaybookRuns } from '@/hooks/usePlaybooks';
+import type {
+  PlaybookStep,
+  PlaybookStepRun,
+  PlaybookStepRunStatus,
+} from 'shared/types';
+
+function getStatusIcon(status?: PlaybookStepRunStatus) {
+  if (status === 'passed')
+    return <Check aria-hidden className="h-4 w-4 text-success" />;
+  if (status === 'running')
+    return <CircleDot aria-hidden className="h-4 w-4 text-blue-500" />;
+  if (status === 'failed')
+    return <X aria-hidden className="h-4 w-4 text-destructive" />;
+  if (status === 'cancelled')
+    return <CircleSlash aria-hidden className="h-4 w-4 text-gray-400" />;
+  return <Circle aria-hidden className="h-4 w-4 text-muted-foreground" />;
+}
+
+function stepSummary(step: PlaybookStep) {
+  return step.type === 'prompt' ? step.prompt : step.script;
+}
+
+/**
+ * Steps of the attempt's latest playbook run, including the ones not reached
+ * yet
+ */
+function PlaybookRunPanel({ attemptId }: { attemptId: string }) {
+  const { t } = useTranslation('tasks');
+  const { data: runs } = usePlaybookRuns(attemptId);
+
+  const run = runs?.[0];
+  if (!run) return null;
+
+  const stepRuns = new Map<number, PlaybookStepRun>(
+    run.step_runs.map((stepRun) => [stepRun.step_index, stepRun])
+  );
+
+  return (
+    <details className="group" open={run.status === 'running'}>
+      <summary className="list-none cursor-pointer">
+        <Card className="bg-muted p-3 text-sm flex items-center justify-between">
+          <span>
+            {t('playbooks.runTitle', {
+              name: run.name,
+              status: t(`playbooks.runStatus.${run.status}`),
+            })}
+          </span>
+          <ChevronUp
+            aria-hidden
+            className="h-4 w-4 text-muted-foreground transition-transform group-open:rotate-180"
+          />
+        </Card>
+      </summary>
+      <div className="px-3 pb-2">
+        <ol className="space-y-2">
+          {run.steps.map((step, index) => {
+            const stepRun = stepRuns.get(index);
+            return (
+              <li key={index} className="flex items-start gap-2">
+                <span className="mt-0.5 h-4 w-4 flex items-center justify-center shrink-0">
+                  {getStatusIcon(stepRun?.status)}
+                </span>
+                <div className="min-w-0 text-sm leading-5">
+                  <div className="break-words">
+                    <span className="text-muted-foreground">
+                      {t(`playbooks.stepType.${step.type}`)}:
+                    </span>{' '}
+                    <span className="font-mono">{stepSummary(step)}</span>
+                  </div>
+                  {stepRun && stepRun.retries > 0 && (
+                    <div className="text-xs text-muted-foreground">
+                      {t('playbooks.retries', { count: stepRun.retries })}
+                    </div>
+                  )}
+                  {stepRun?.output && (
+                    <pre className="mt-1 max-h-40 overflow-auto whitespace-pre-wrap text-xs text-muted-foreground">
+                      {stepRun.output}
+                    </pre>
+                  )}
+                </div>
+              </li>
+            );
+          })}
+        </ol>
+      </div>
+    </details>
+  );
+}
+
+export default PlaybookRunPanel;
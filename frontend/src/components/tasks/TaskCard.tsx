@@ -6,6 +6,7 @@ import { ActionsDropdown } from '@/components/ui/actions-dropdown';
 import { Button } from '@/components/ui/button';
 import { useNavigateWithSearch } from '@/hooks';
 import { paths } from '@/lib/paths';
+import { CiStatusIcon } from '@/components/tasks/CiStatusIcon';
 import { attemptsApi } from '@/lib/api';
 import type { SharedTaskRecord } from '@/hooks/useProjectTasks';
 import { TaskCardHeader } from './TaskCardHeader';
@@ -116,6 +117,7 @@ export function TaskCard({
               {task.last_attempt_failed && (
                 <XCircle className="h-4 w-4 text-destructive" />
               )}
+              {task.ci_status && <CiStatusIcon status={task.ci_status} />}
               {task.parent_workspace_id && (
                 <Button
                   variant="icon"
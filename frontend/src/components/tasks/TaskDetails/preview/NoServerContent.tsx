@@ -87,6 +87,10 @@ export function NoServerContent({
           dev_script: script,
           dev_script_working_dir: project.dev_script_working_dir ?? null,
           default_agent_working_dir: project.default_agent_working_dir ?? null,
+          container_image: project.container_image ?? null,
+          auto_address_review_comments: null,
+          auto_fix_ci: null,
+          max_ci_fix_attempts: null,
         },
       },
       {
@@ -8,6 +8,7 @@ import {
   Paperclip,
   Terminal,
   MessageSquare,
+  ArrowRightLeft,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
@@ -59,6 +60,7 @@ import { useDebouncedCallback } from '@/hooks/useDebouncedCallback';
 import { useQueueStatus } from '@/hooks/useQueueStatus';
 import { imagesApi, attemptsApi } from '@/lib/api';
 import { GitHubCommentsDialog } from '@/components/dialogs/tasks/GitHubCommentsDialog';
+import { HandoffDialog } from '@/components/dialogs/tasks/HandoffDialog';
 import type { NormalizedComment } from '@/components/ui/wysiwyg/nodes/github-comment-node';
 import type { Session } from 'shared/types';
 
@@ -580,6 +582,20 @@ export function TaskFollowUpSection({
     }
   }, [workspaceId, getSelectedRepoId]);
 
+  // Hand the workspace to another agent, passing along the typed message
+  const handleHandoffClick = useCallback(async () => {
+    if (!session) return;
+    const result = await HandoffDialog.show({
+      session,
+      taskId: task.id,
+      initialPrompt: localMessage,
+    });
+    if (result.action === 'handed_off') {
+      cancelDebouncedSave();
+      setLocalMessage('');
+    }
+  }, [session, task.id, localMessage, cancelDebouncedSave]);
+
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
@@ -792,6 +808,18 @@ export function TaskFollowUpSection({
             <MessageSquare className="h-4 w-4" />
           </Button>
 
+          {/* Hand off to another coding agent */}
+          <Button
+            onClick={handleHandoffClick}
+            disabled={!isEditable || isAttemptRunning || !session}
+            size="sm"
+            variant="outline"
+            title={t('handoff.button')}
+            aria-label={t('handoff.button')}
+          >
+            <ArrowRightLeft className="h-4 w-4" />
+          </Button>
+
           {/* Scripts dropdown - only show if project has any scripts */}
           {hasAnyScript && (
             <DropdownMenu>
@@ -0,0 +1,57 @@
+import { useTranslation } from 'react-i18next';
+import { ListChecks } from 'lucide-react';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import {
+  usePlaybooks,
+  useSetTaskPlaybook,
+  useTaskPlaybook,
+} from '@/hooks/usePlaybooks';
+
+const NO_PLAYBOOK = 'none';
+
+/**
+ * Picks the playbook that new attempts of a task run after the agent's first
+ * turn. Hidden until at least one playbook exists.
+ */
+export function TaskPlaybookSelector({ taskId }: { taskId: string }) {
+  const { t } = useTranslation('tasks');
+  const { data: playbooks = [] } = usePlaybooks();
+  const { data: playbook, isLoading } = useTaskPlaybook(taskId);
+  const setPlaybook = useSetTaskPlaybook(taskId);
+
+  if (playbooks.length === 0 && !playbook) return null;
+
+  return (
+    <div className="flex items-center gap-2 text-sm">
+      <ListChecks className="h-4 w-4 text-muted-foreground shrink-0" />
+      <span className="text-muted-foreground shrink-0">
+        {t('playbooks.label')}
+      </span>
+      <Select
+        value={playbook?.id ?? NO_PLAYBOOK}
+        onValueChange={(value) =>
+          setPlaybook.mutate(value === NO_PLAYBOOK ? null : value)
+        }
+        disabled={isLoading || setPlaybook.isPending}
+      >
+        <SelectTrigger className="h-8 w-64">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value={NO_PLAYBOOK}>{t('playbooks.none')}</SelectItem>
+          {playbooks.map((p) => (
+            <SelectItem key={p.id} value={p.id}>
+              {p.name}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+    </div>
+  );
+}
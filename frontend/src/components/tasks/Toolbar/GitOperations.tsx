@@ -7,6 +7,8 @@ import {
   AlertTriangle,
   CheckCircle,
   ExternalLink,
+  ListOrdered,
+  XCircle,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button.tsx';
 import {
@@ -30,6 +32,7 @@ import { useTranslation } from 'react-i18next';
 import { useAttemptRepo } from '@/hooks/useAttemptRepo';
 import { useGitOperations } from '@/hooks/useGitOperations';
 import { useRepoBranches } from '@/hooks';
+import { CiStatusIcon } from '@/components/tasks/CiStatusIcon';
 
 interface GitOperationsProps {
   selectedAttempt: Workspace;
@@ -109,6 +112,13 @@ function GitOperations({
   const hasConflictsCalculated =
     (selectedRepoStatus?.conflicted_files?.length ?? 0) > 0;
 
+  const queueEntry = selectedRepoStatus?.merge_queue_entry ?? null;
+  const isInMergeQueue =
+    queueEntry?.status === 'queued' ||
+    queueEntry?.status === 'rebasing' ||
+    queueEntry?.status === 'verifying';
+  const queueMerging = git.states.queueMergePending;
+
   // Memoize merge status information to avoid repeated calculations
   const mergeInfo = useMemo(() => {
     const selectedRepoStatus = getSelectedRepoStatus();
@@ -173,6 +183,21 @@ function GitOperations({
     await performMerge();
   };
 
+  const handleQueueMergeClick = async () => {
+    const repoId = getSelectedRepoId();
+    if (!repoId) return;
+    if (queueEntry?.status === 'queued') {
+      await git.actions.cancelQueuedMerge({ repoId });
+    } else {
+      await git.actions.queueMerge({ repoId });
+    }
+  };
+
+  const queueButtonLabel = useMemo(() => {
+    if (queueEntry?.status === 'queued') return t('git.mergeQueue.leave');
+    return t('git.mergeQueue.queue');
+  }, [queueEntry?.status, t]);
+
   const handlePushClick = async () => {
     try {
       setPushing(true);
@@ -272,8 +297,61 @@ function GitOperations({
     ? 'flex flex-wrap items-center gap-2'
     : 'shrink-0 flex flex-wrap items-center gap-2 overflow-y-hidden overflow-x-visible max-h-8';
 
+  const mergeQueueChip = (() => {
+    if (!queueEntry) return null;
+
+    if (isInMergeQueue) {
+      const ahead = selectedRepoStatus?.merge_queue_ahead ?? 0;
+      return (
+        <span className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-muted">
+          {queueEntry.status === 'queued' ? (
+            <ListOrdered className="h-3.5 w-3.5" />
+          ) : (
+            <RefreshCw className="h-3.5 w-3.5 animate-spin" />
+          )}
+          {t(`git.mergeQueue.status.${queueEntry.status}`)}
+          {queueEntry.status === 'queued' && ahead > 0 && (
+            <span className="text-muted-foreground">
+              {t('git.mergeQueue.ahead', { count: ahead })}
+            </span>
+          )}
+        </span>
+      );
+    }
+
+    // A failure stays visible until the attempt is queued again or merged
+    if (
+      queueEntry.status === 'failed' &&
+      (selectedRepoStatus?.commits_ahead ?? 0) > 0
+    ) {
+      return (
+        <TooltipProvider>
+          <Tooltip>
+            <TooltipTrigger asChild>
+              <span className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-destructive/10 text-destructive">
+                <XCircle className="h-3.5 w-3.5" />
+                {t('git.mergeQueue.status.failed')}
+              </span>
+            </TooltipTrigger>
+            {queueEntry.error_message && (
+              <TooltipContent
+                side="bottom"
+                className="max-w-md max-h-64 overflow-auto whitespace-pre-wrap font-mono text-xs"
+              >
+                {queueEntry.error_message}
+              </TooltipContent>
+            )}
+          </Tooltip>
+        </TooltipProvider>
+      );
+    }
+
+    return null;
+  })();
+
   const statusChips = (
     <div className="flex items-center gap-2 text-xs min-w-0 overflow-hidden whitespace-nowrap">
+      {mergeQueueChip}
       {(() => {
         const commitsAhead = selectedRepoStatus?.commits_ahead ?? 0;
         const commitsBehind = selectedRepoStatus?.commits_behind ?? 0;
@@ -307,20 +385,59 @@ function GitOperations({
 
         if (mergeInfo.hasOpenPR && mergeInfo.openPR?.type === 'pr') {
           const prMerge = mergeInfo.openPR;
+          const ci = prMerge.ci;
           return (
-            <button
-              onClick={() => window.open(prMerge.pr_info.url, '_blank')}
-              className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-sky-100/60 dark:bg-sky-900/30 text-sky-700 dark:text-sky-300 hover:underline truncate max-w-[180px] sm:max-w-none"
-              aria-label={t('git.pr.open', {
-                number: Number(prMerge.pr_info.number),
-              })}
-            >
-              <GitPullRequest className="h-3.5 w-3.5" />
-              {t('git.pr.number', {
-                number: Number(prMerge.pr_info.number),
-              })}
-              <ExternalLink className="h-3.5 w-3.5" />
-            </button>
+            <>
+              <button
+                onClick={() => window.open(prMerge.pr_info.url, '_blank')}
+                className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-sky-100/60 dark:bg-sky-900/30 text-sky-700 dark:text-sky-300 hover:underline truncate max-w-[180px] sm:max-w-none"
+                aria-label={t('git.pr.open', {
+                  number: Number(prMerge.pr_info.number),
+                })}
+              >
+                <GitPullRequest className="h-3.5 w-3.5" />
+                {t('git.pr.number', {
+                  number: Number(prMerge.pr_info.number),
+                })}
+                <ExternalLink className="h-3.5 w-3.5" />
+              </button>
+              {ci && (
+                <TooltipProvider>
+                  <Tooltip>
+                    <TooltipTrigger asChild>
+                      <span className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-muted">
+                        <CiStatusIcon
+                          status={ci.status}
+                          className="h-3.5 w-3.5"
+                        />
+                        {t(`git.ci.status.${ci.status}`)}
+                      </span>
+                    </TooltipTrigger>
+                    <TooltipContent side="bottom" className="space-y-1">
+                      {ci.checks.map((check) => (
+                        <div
+                          key={`${check.name}-${check.job_id}`}
+                          className="flex items-center gap-1.5"
+                        >
+                          <CiStatusIcon
+                            status={check.status}
+                            className="h-3.5 w-3.5"
+                          />
+                          <span className="truncate">{check.name}</span>
+                        </div>
+                      ))}
+                      {prMerge.ci_fix_attempts > 0 && (
+                        <div className="pt-1 text-muted-foreground">
+                          {t('git.ci.fixAttempts', {
+                            count: prMerge.ci_fix_attempts,
+                          })}
+                        </div>
+                      )}
+                    </TooltipContent>
+                  </Tooltip>
+                </TooltipProvider>
+              )}
+            </>
           );
         }
 
@@ -471,6 +588,7 @@ function GitOperations({
                 mergeInfo.hasMergedPR ||
                 mergeInfo.hasOpenPR ||
                 merging ||
+                isInMergeQueue ||
                 hasConflictsCalculated ||
                 isAttemptRunning ||
                 ((selectedRepoStatus?.commits_ahead ?? 0) === 0 &&
@@ -486,6 +604,40 @@ function GitOperations({
               <span className="truncate max-w-[10ch]">{mergeButtonLabel}</span>
             </Button>
 
+            <TooltipProvider>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <span className="inline-flex shrink-0">
+                    <Button
+                      onClick={handleQueueMergeClick}
+                      disabled={
+                        queueMerging ||
+                        (isInMergeQueue && queueEntry?.status !== 'queued') ||
+                        (!isInMergeQueue &&
+                          (mergeInfo.hasMergedPR ||
+                            mergeInfo.hasOpenPR ||
+                            merging ||
+                            hasConflictsCalculated ||
+                            (selectedRepoStatus?.commits_ahead ?? 0) === 0))
+                      }
+                      variant="outline"
+                      size="xs"
+                      className="border-success text-success hover:bg-success gap-1 shrink-0"
+                      aria-label={queueButtonLabel}
+                    >
+                      <ListOrdered className="h-3.5 w-3.5" />
+                      <span className="truncate max-w-[12ch]">
+                        {queueButtonLabel}
+                      </span>
+                    </Button>
+                  </span>
+                </TooltipTrigger>
+                <TooltipContent side="bottom" className="max-w-xs">
+                  {t('git.mergeQueue.description')}
+                </TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+
             <Button
               onClick={handlePRButtonClick}
               disabled={
@@ -23,6 +23,7 @@ import { ReassignDialog } from '@/components/dialogs/tasks/ReassignDialog';
 import { StopShareTaskDialog } from '@/components/dialogs/tasks/StopShareTaskDialog';
 import { useProject } from '@/contexts/ProjectContext';
 import { openTaskForm } from '@/lib/openTaskForm';
+import { attemptsApi } from '@/lib/api';
 
 import { useNavigate } from 'react-router-dom';
 import type { SharedTaskRecord } from '@/hooks/useProjectTasks';
@@ -141,6 +142,22 @@ export function ActionsDropdown({
       currentBranchName: attempt.branch,
     });
   };
+  const handleExport = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (!attempt?.id) return;
+    try {
+      const archive = await attemptsApi.exportArchive(attempt.id);
+      const url = URL.createObjectURL(archive);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `${attempt.branch.replace(/[/\\]/g, '-')}.tar.gz`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err) {
+      console.error('Failed to export attempt:', err);
+    }
+  };
+
   const handleShare = (e: React.MouseEvent) => {
     e.stopPropagation();
     if (!task || isShared) return;
@@ -223,6 +240,9 @@ export function ActionsDropdown({
               >
                 {t('actionsMenu.editBranchName')}
               </DropdownMenuItem>
+              <DropdownMenuItem disabled={!attempt?.id} onClick={handleExport}>
+                {t('actionsMenu.exportAttempt')}
+              </DropdownMenuItem>
               <DropdownMenuSeparator />
             </>
           )}
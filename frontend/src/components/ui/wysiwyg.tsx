@@ -37,7 +37,7 @@ import { LinkNode } from '@lexical/link';
 import { EditorState } from 'lexical';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
-import { Check, Clipboard, Pencil, Trash2 } from 'lucide-react';
+import { Check, Clipboard, GitFork, Pencil, Trash2 } from 'lucide-react';
 import { writeClipboardViaBridge } from '@/vscode/bridge';
 
 /** Markdown string representing the editor content */
@@ -65,6 +65,8 @@ type WysiwygProps = {
   onEdit?: () => void;
   /** Optional delete callback - shows delete button in read-only mode when provided */
   onDelete?: () => void;
+  /** Optional fork callback - shows fork button in read-only mode when provided */
+  onFork?: () => void;
   /** Auto-focus the editor on mount */
   autoFocus?: boolean;
 };
@@ -85,6 +87,7 @@ function WYSIWYGEditor({
   localImages,
   onEdit,
   onDelete,
+  onFork,
   autoFocus = false,
 }: WysiwygProps) {
   // Copy button state
@@ -288,6 +291,20 @@ function WYSIWYGEditor({
                 <Pencil className="w-4 h-4 text-muted-foreground" />
               </Button>
             )}
+            {/* Fork button - only if onFork provided */}
+            {onFork && (
+              <Button
+                type="button"
+                aria-label="Fork"
+                title="Fork"
+                variant="icon"
+                size="icon"
+                onClick={onFork}
+                className="pointer-events-auto p-2 bg-muted h-8 w-8"
+              >
+                <GitFork className="w-4 h-4 text-muted-foreground" />
+              </Button>
+            )}
             {/* Delete button - only if onDelete provided */}
             {onDelete && (
               <Button
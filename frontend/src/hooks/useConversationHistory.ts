@@ -11,6 +11,8 @@ import {
 } from 'shared/types';
 import { useExecutionProcessesContext } from '@/contexts/ExecutionProcessesContext';
 import { useCallback, useEffect, useMemo, useRef } from 'react';
+import { useQuery } from '@tanstack/react-query';
+import { sessionsApi } from '@/lib/api';
 import { streamJsonPatchEntries } from '@/utils/streamJsonPatchEntries';
 
 export type PatchTypeWithKey = PatchType & {
@@ -49,6 +51,8 @@ interface UseConversationHistoryResult {}
 
 const MIN_INITIAL_ENTRIES = 10;
 const REMAINING_BATCH_SIZE = 50;
+const CONTEXT_COMPACTED_MESSAGE =
+  'The agent ran out of context window. It continues in a new session, starting from a summary of the conversation and the changes so far.';
 
 const makeLoadingPatch = (executionProcessId: string): PatchTypeWithKey => ({
   type: 'NORMALIZED_ENTRY',
@@ -101,6 +105,8 @@ export const useConversationHistory = ({
   const loadedInitialEntries = useRef(false);
   const streamingProcessIdsRef = useRef<Set<string>>(new Set());
   const onEntriesUpdatedRef = useRef<OnEntriesUpdated | null>(null);
+  // Sessions that replaced one that ran out of context window
+  const compactedSessionIds = useRef<Set<string>>(new Set());
 
   const mergeIntoDisplayed = (
     mutator: (state: ExecutionProcessStateStore) => void
@@ -229,14 +235,29 @@ export const useConversationHistory = ({
             p.executionProcess.executor_action.typ.type ===
               'CodingAgentFollowUpRequest'
           ) {
-            // New user message
-            const userNormalizedEntry: NormalizedEntry = {
-              entry_type: {
-                type: 'user_message',
-              },
-              content: p.executionProcess.executor_action.typ.prompt,
-              timestamp: null,
-            };
+            // New user message, or a note in place of the generated summary
+            // that starts a compacted session
+            const sessionId = getLiveExecutionProcess(
+              p.executionProcess.id
+            )?.session_id;
+            const isCompaction =
+              p.executionProcess.executor_action.typ.type ===
+                'CodingAgentInitialRequest' &&
+              !!sessionId &&
+              compactedSessionIds.current.has(sessionId);
+            const userNormalizedEntry: NormalizedEntry = isCompaction
+              ? {
+                  entry_type: { type: 'system_message' },
+                  content: CONTEXT_COMPACTED_MESSAGE,
+                  timestamp: null,
+                }
+              : {
+                  entry_type: {
+                    type: 'user_message',
+                  },
+                  content: p.executionProcess.executor_action.typ.prompt,
+                  timestamp: null,
+                };
             const userPatch: PatchType = {
               type: 'NORMALIZED_ENTRY',
               content: userNormalizedEntry,
@@ -327,6 +348,12 @@ export const useConversationHistory = ({
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
+              case 'Verification':
+                toolName = 'Verification Script';
+                break;
+              case 'Playbook':
+                toolName = 'Playbook Script';
+                break;
               default:
                 return [];
             }
@@ -581,6 +608,31 @@ export const useConversationHistory = ({
     [executionProcessesRaw]
   );
 
+  const sessionIdsKey = useMemo(
+    () =>
+      Array.from(new Set(executionProcessesRaw?.map((p) => p.session_id))).join(
+        ','
+      ),
+    [executionProcessesRaw]
+  );
+
+  // Refetched whenever a process of a new session shows up
+  const { data: sessions } = useQuery({
+    queryKey: ['workspaceSessions', attempt.id, sessionIdsKey],
+    queryFn: () => sessionsApi.getByWorkspace(attempt.id),
+  });
+
+  useEffect(() => {
+    compactedSessionIds.current = new Set(
+      sessions
+        ?.filter((session) => session.compacted_from_session_id)
+        .map((session) => session.id)
+    );
+    if (loadedInitialEntries.current) {
+      emitEntries(displayedExecutionProcesses.current, 'historic', false);
+    }
+  }, [sessions, emitEntries]);
+
   // Initial load when attempt changes
   useEffect(() => {
     let cancelled = false;
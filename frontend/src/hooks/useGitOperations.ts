@@ -1,5 +1,6 @@
 import { useRebase } from './useRebase';
 import { useMerge } from './useMerge';
+import { useMergeQueue } from './useMergeQueue';
 import { usePush } from './usePush';
 import { useForcePush } from './useForcePush';
 import { useChangeTargetBranch } from './useChangeTargetBranch';
@@ -43,6 +44,18 @@ export function useGitOperations(
     }
   );
 
+  const mergeQueue = useMergeQueue(
+    attemptId,
+    () => setError(null),
+    (err: unknown) => {
+      const message =
+        err && typeof err === 'object' && 'message' in err
+          ? String(err.message)
+          : 'Failed to update merge queue';
+      setError(message);
+    }
+  );
+
   const forcePush = useForcePush(
     attemptId,
     () => setError(null),
@@ -92,6 +105,8 @@ export function useGitOperations(
   const isAnyLoading =
     rebase.isPending ||
     merge.isPending ||
+    mergeQueue.enqueue.isPending ||
+    mergeQueue.cancel.isPending ||
     push.isPending ||
     forcePush.isPending ||
     changeTargetBranch.isPending;
@@ -100,6 +115,8 @@ export function useGitOperations(
     actions: {
       rebase: rebase.mutateAsync,
       merge: merge.mutateAsync,
+      queueMerge: mergeQueue.enqueue.mutateAsync,
+      cancelQueuedMerge: mergeQueue.cancel.mutateAsync,
       push: push.mutateAsync,
       forcePush: forcePush.mutateAsync,
       changeTargetBranch: changeTargetBranch.mutateAsync,
@@ -108,6 +125,8 @@ export function useGitOperations(
     states: {
       rebasePending: rebase.isPending,
       mergePending: merge.isPending,
+      queueMergePending:
+        mergeQueue.enqueue.isPending || mergeQueue.cancel.isPending,
       pushPending: push.isPending,
       forcePushPending: forcePush.isPending,
       changeTargetBranchPending: changeTargetBranch.isPending,
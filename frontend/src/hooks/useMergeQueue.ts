@@ -0,0 +1,57 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import type { MergeQueueEntry } from 'shared/types';
+
+type MergeQueueParams = {
+  repoId: string;
+};
+
+export function useMergeQueue(
+  attemptId?: string,
+  onSuccess?: () => void,
+  onError?: (err: unknown) => void
+) {
+  const queryClient = useQueryClient();
+
+  const invalidateBranchStatus = () => {
+    queryClient.invalidateQueries({ queryKey: ['branchStatus', attemptId] });
+  };
+
+  const enqueue = useMutation<
+    MergeQueueEntry | undefined,
+    unknown,
+    MergeQueueParams
+  >({
+    mutationFn: (params: MergeQueueParams) => {
+      if (!attemptId) return Promise.resolve(undefined);
+      return attemptsApi.enqueueMerge(attemptId, { repo_id: params.repoId });
+    },
+    onSuccess: () => {
+      invalidateBranchStatus();
+      onSuccess?.();
+    },
+    onError: (err) => {
+      console.error('Failed to queue merge:', err);
+      onError?.(err);
+    },
+  });
+
+  const cancel = useMutation<void, unknown, MergeQueueParams>({
+    mutationFn: (params: MergeQueueParams) => {
+      if (!attemptId) return Promise.resolve();
+      return attemptsApi.cancelQueuedMerge(attemptId, {
+        repo_id: params.repoId,
+      });
+    },
+    onSuccess: () => {
+      invalidateBranchStatus();
+      onSuccess?.();
+    },
+    onError: (err) => {
+      console.error('Failed to cancel queued merge:', err);
+      onError?.(err);
+    },
+  });
+
+  return { enqueue, cancel };
+}
@@ -0,0 +1,50 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { playbooksApi } from '@/lib/api';
+import type { Playbook, PlaybookRunWithSteps } from 'shared/types';
+
+export const playbookKeys = {
+  all: ['playbooks'] as const,
+  forTask: (taskId: string | undefined) =>
+    ['playbooks', 'task', taskId] as const,
+  runs: (attemptId: string | undefined) =>
+    ['playbooks', 'runs', attemptId] as const,
+};
+
+export function usePlaybooks() {
+  return useQuery<Playbook[]>({
+    queryKey: playbookKeys.all,
+    queryFn: () => playbooksApi.list(),
+  });
+}
+
+export function useTaskPlaybook(taskId?: string) {
+  return useQuery<Playbook | null>({
+    queryKey: playbookKeys.forTask(taskId),
+    queryFn: () => playbooksApi.getForTask(taskId!),
+    enabled: !!taskId,
+  });
+}
+
+export function useSetTaskPlaybook(taskId: string) {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (playbookId: string | null) =>
+      playbooksApi.setForTask(taskId, playbookId),
+    onSuccess: (playbook) => {
+      queryClient.setQueryData(playbookKeys.forTask(taskId), playbook);
+    },
+  });
+}
+
+/**
+ * Playbook runs of an attempt, polled while one of them is still running
+ */
+export function usePlaybookRuns(attemptId?: string) {
+  return useQuery<PlaybookRunWithSteps[]>({
+    queryKey: playbookKeys.runs(attemptId),
+    queryFn: () => playbooksApi.getRuns(attemptId!),
+    enabled: !!attemptId,
+    refetchInterval: (query) =>
+      query.state.data?.some((run) => run.status === 'running') ? 3000 : 15000,
+  });
+}
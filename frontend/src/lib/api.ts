@@ -1,8 +1,11 @@
 // Import all necessary types from shared types
 
 import {
+  Approval,
   ApprovalStatus,
   ApiResponse,
+  AttemptGroupReport,
+  AttemptGroupWithMembers,
   Config,
   CreateFollowUpAttempt,
   EditorType,
@@ -10,11 +13,17 @@ import {
   CreateTask,
   CreateAndStartTaskRequest,
   CreateTaskAttemptBody,
+  FanOutTaskAttemptsBody,
   CreateTag,
   DirectoryListResponse,
   DirectoryEntry,
   ExecutionProcess,
   ExecutionProcessRepoState,
+  ForkSessionRequest,
+  ForkSessionResponse,
+  HandoffSessionRequest,
+  HandoffSessionResponse,
+  CompactSessionResponse,
   GitBranch,
   Project,
   ProjectRepo,
@@ -26,6 +35,8 @@ import {
   SearchResult,
   ShareTaskResponse,
   Task,
+  TaskDependencies,
+  TaskDependency,
   TaskRelationships,
   Tag,
   TagSearchParams,
@@ -84,13 +95,35 @@ import {
   SharedTaskDetails,
   QueueStatus,
   PrCommentsResponse,
+  PromoteAttemptRequest,
   MergeTaskAttemptRequest,
+  MergeQueueEntry,
+  MergeQueueRequest,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
   Session,
   Workspace,
   SessionQueueStatus,
+  ExecutionProcessUsage,
+  UsageSummary,
+  TaskSchedule,
+  CreateTaskSchedule,
+  UpdateTaskSchedule,
+  ScheduleRunOutcome,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  WebhookDelivery,
+  VerificationRun,
+  Playbook,
+  CreatePlaybook,
+  UpdatePlaybook,
+  PlaybookRunWithSteps,
+  ImportedAttempt,
+  AttemptArchiveManifest,
+  PatchType,
+  ProjectLogStorage,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -432,6 +465,33 @@ export const tasksApi = {
     return handleApiResponse<void>(response);
   },
 
+  getDependencies: async (taskId: string): Promise<TaskDependencies> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`);
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  addDependency: async (
+    taskId: string,
+    dependsOnTaskId: string
+  ): Promise<TaskDependency> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`, {
+      method: 'POST',
+      body: JSON.stringify({ depends_on_task_id: dependsOnTaskId }),
+    });
+    return handleApiResponse<TaskDependency>(response);
+  },
+
+  removeDependency: async (
+    taskId: string,
+    dependsOnTaskId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/dependencies/${dependsOnTaskId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   share: async (taskId: string): Promise<ShareTaskResponse> => {
     const response = await makeRequest(`/api/tasks/${taskId}/share`, {
       method: 'POST',
@@ -509,6 +569,38 @@ export const sessionsApi = {
     });
     return handleApiResponse<ExecutionProcess>(response);
   },
+
+  fork: async (
+    sessionId: string,
+    data: ForkSessionRequest
+  ): Promise<ForkSessionResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/fork`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ForkSessionResponse>(response);
+  },
+
+  /**
+   * Continue the workspace with another coding agent in a new session
+   */
+  handoff: async (
+    sessionId: string,
+    data: HandoffSessionRequest
+  ): Promise<HandoffSessionResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/handoff`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<HandoffSessionResponse>(response);
+  },
+
+  compact: async (sessionId: string): Promise<CompactSessionResponse> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/compact`, {
+      method: 'POST',
+    });
+    return handleApiResponse<CompactSessionResponse>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -608,6 +700,34 @@ export const attemptsApi = {
     return handleApiResponse<void, GitOperationError>(response);
   },
 
+  enqueueMerge: async (
+    attemptId: string,
+    data: MergeQueueRequest
+  ): Promise<MergeQueueEntry> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<MergeQueueEntry>(response);
+  },
+
+  cancelQueuedMerge: async (
+    attemptId: string,
+    data: MergeQueueRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge-queue/cancel`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   push: async (
     attemptId: string,
     data: PushTaskAttemptRequest
@@ -760,6 +880,63 @@ export const attemptsApi = {
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  /**
+   * Download the attempt as an archive that can be imported elsewhere
+   */
+  exportArchive: async (attemptId: string): Promise<Blob> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/export`
+    );
+    if (!response.ok) {
+      await handleApiResponse<void>(response);
+    }
+    return response.blob();
+  },
+};
+
+// Best-of-N attempt groups
+export const attemptGroupsApi = {
+  fanOut: async (
+    data: FanOutTaskAttemptsBody
+  ): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest(`/api/attempt-groups`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
+
+  getAll: async (taskId: string): Promise<AttemptGroupWithMembers[]> => {
+    const response = await makeRequest(`/api/attempt-groups?task_id=${taskId}`);
+    return handleApiResponse<AttemptGroupWithMembers[]>(response);
+  },
+
+  get: async (groupId: string): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest(`/api/attempt-groups/${groupId}`);
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
+
+  getReport: async (groupId: string): Promise<AttemptGroupReport> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${groupId}/report`
+    );
+    return handleApiResponse<AttemptGroupReport>(response);
+  },
+
+  promote: async (
+    groupId: string,
+    data: PromoteAttemptRequest
+  ): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${groupId}/promote`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
 };
 
 // Execution Process APIs
@@ -1066,6 +1243,35 @@ export const approvalsApi = {
 
     return handleApiResponse<ApprovalStatus>(res);
   },
+
+  getForTask: async (taskId: string): Promise<Approval[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/approvals`);
+    return handleApiResponse<Approval[]>(response);
+  },
+
+  getForAttempt: async (attemptId: string): Promise<Approval[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/approvals`
+    );
+    return handleApiResponse<Approval[]>(response);
+  },
+};
+
+// Verification API
+export const verificationApi = {
+  getForTask: async (taskId: string): Promise<VerificationRun[]> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/verification-runs`
+    );
+    return handleApiResponse<VerificationRun[]>(response);
+  },
+
+  getForAttempt: async (attemptId: string): Promise<VerificationRun[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/verification-runs`
+    );
+    return handleApiResponse<VerificationRun[]>(response);
+  },
 };
 
 // OAuth API
@@ -1322,3 +1528,275 @@ export const taskQueueApi = {
     return handleApiResponse<SessionQueueStatus>(response);
   },
 };
+
+export const usageApi = {
+  /**
+   * Token usage and cost for a single execution process (null if none recorded)
+   */
+  getForExecutionProcess: async (
+    executionProcessId: string
+  ): Promise<ExecutionProcessUsage | null> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${executionProcessId}/usage`
+    );
+    return handleApiResponse<ExecutionProcessUsage | null>(response);
+  },
+
+  getForTask: async (taskId: string): Promise<UsageSummary> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/usage`);
+    return handleApiResponse<UsageSummary>(response);
+  },
+
+  getForProject: async (
+    projectId: string,
+    since?: string
+  ): Promise<UsageSummary> => {
+    const query = since ? `?since=${encodeURIComponent(since)}` : '';
+    const response = await makeRequest(
+      `/api/projects/${projectId}/usage${query}`
+    );
+    return handleApiResponse<UsageSummary>(response);
+  },
+
+  getGlobal: async (since?: string): Promise<UsageSummary> => {
+    const query = since ? `?since=${encodeURIComponent(since)}` : '';
+    const response = await makeRequest(`/api/usage${query}`);
+    return handleApiResponse<UsageSummary>(response);
+  },
+};
+
+// Task Schedules APIs
+export const taskSchedulesApi = {
+  list: async (projectId?: string): Promise<TaskSchedule[]> => {
+    const query = projectId
+      ? `?project_id=${encodeURIComponent(projectId)}`
+      : '';
+    const response = await makeRequest(`/api/task-schedules${query}`);
+    return handleApiResponse<TaskSchedule[]>(response);
+  },
+
+  getById: async (scheduleId: string): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/task-schedules/${scheduleId}`);
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  create: async (data: CreateTaskSchedule): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/task-schedules`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  update: async (
+    scheduleId: string,
+    data: UpdateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/task-schedules/${scheduleId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  delete: async (scheduleId: string): Promise<void> => {
+    const response = await makeRequest(`/api/task-schedules/${scheduleId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  /**
+   * Run a schedule now instead of waiting for its next slot
+   */
+  run: async (scheduleId: string): Promise<ScheduleRunOutcome> => {
+    const response = await makeRequest(
+      `/api/task-schedules/${scheduleId}/run`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<ScheduleRunOutcome>(response);
+  },
+};
+
+// Webhooks APIs
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  create: async (data: CreateWebhook): Promise<Webhook> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  update: async (webhookId: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  delete: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getDeliveries: async (
+    webhookId: string,
+    limit?: number
+  ): Promise<WebhookDelivery[]> => {
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/webhooks/${webhookId}/deliveries${query}`
+    );
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+
+  /**
+   * Queue a ping delivery to check the endpoint and its signature handling
+   */
+  test: async (webhookId: string): Promise<WebhookDelivery> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}/test`, {
+      method: 'POST',
+    });
+    return handleApiResponse<WebhookDelivery>(response);
+  },
+
+  retryDelivery: async (deliveryId: string): Promise<WebhookDelivery> => {
+    const response = await makeRequest(
+      `/api/webhook-deliveries/${deliveryId}/retry`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<WebhookDelivery>(response);
+  },
+};
+
+// Playbooks APIs
+export const playbooksApi = {
+  list: async (): Promise<Playbook[]> => {
+    const response = await makeRequest('/api/playbooks');
+    return handleApiResponse<Playbook[]>(response);
+  },
+
+  create: async (data: CreatePlaybook): Promise<Playbook> => {
+    const response = await makeRequest('/api/playbooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Playbook>(response);
+  },
+
+  update: async (
+    playbookId: string,
+    data: UpdatePlaybook
+  ): Promise<Playbook> => {
+    const response = await makeRequest(`/api/playbooks/${playbookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Playbook>(response);
+  },
+
+  delete: async (playbookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/playbooks/${playbookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getForTask: async (taskId: string): Promise<Playbook | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/playbook`);
+    return handleApiResponse<Playbook | null>(response);
+  },
+
+  /**
+   * Attach a playbook to a task, or detach it with null
+   */
+  setForTask: async (
+    taskId: string,
+    playbookId: string | null
+  ): Promise<Playbook | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/playbook`, {
+      method: 'PUT',
+      body: JSON.stringify({ playbook_id: playbookId }),
+    });
+    return handleApiResponse<Playbook | null>(response);
+  },
+
+  getRuns: async (attemptId: string): Promise<PlaybookRunWithSteps[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/playbook-runs`
+    );
+    return handleApiResponse<PlaybookRunWithSteps[]>(response);
+  },
+};
+
+// Attempts imported from exported archives, browsed read-only
+export const importedAttemptsApi = {
+  list: async (): Promise<ImportedAttempt[]> => {
+    const response = await makeRequest('/api/imported-attempts');
+    return handleApiResponse<ImportedAttempt[]>(response);
+  },
+
+  import: async (file: File): Promise<ImportedAttempt> => {
+    const formData = new FormData();
+    formData.append('archive', file);
+
+    const response = await fetch('/api/imported-attempts', {
+      method: 'POST',
+      body: formData,
+      credentials: 'include',
+    });
+    return handleApiResponse<ImportedAttempt>(response);
+  },
+
+  get: async (importId: string): Promise<AttemptArchiveManifest> => {
+    const response = await makeRequest(`/api/imported-attempts/${importId}`);
+    return handleApiResponse<AttemptArchiveManifest>(response);
+  },
+
+  getEntries: async (
+    importId: string,
+    processId: string
+  ): Promise<PatchType[]> => {
+    const response = await makeRequest(
+      `/api/imported-attempts/${importId}/processes/${processId}/entries`
+    );
+    return handleApiResponse<PatchType[]>(response);
+  },
+
+  getRawLogs: async (
+    importId: string,
+    processId: string
+  ): Promise<PatchType[]> => {
+    const response = await makeRequest(
+      `/api/imported-attempts/${importId}/processes/${processId}/raw-logs`
+    );
+    return handleApiResponse<PatchType[]>(response);
+  },
+
+  imageUrl: (importId: string, imageId: string): string =>
+    `/api/imported-attempts/${importId}/images/${imageId}`,
+
+  delete: async (importId: string): Promise<void> => {
+    const response = await makeRequest(`/api/imported-attempts/${importId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Space taken by execution process logs
+export const logStorageApi = {
+  list: async (): Promise<ProjectLogStorage[]> => {
+    const response = await makeRequest('/api/log-storage');
+    return handleApiResponse<ProjectLogStorage[]>(response);
+  },
+};
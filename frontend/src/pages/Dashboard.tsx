@@ -34,6 +34,7 @@ import TaskAttemptPanel from '@/components/panels/TaskAttemptPanel';
 import { PreviewPanel } from '@/components/panels/PreviewPanel';
 import { DiffsPanel } from '@/components/panels/DiffsPanel';
 import TodoPanel from '@/components/tasks/TodoPanel';
+import PlaybookRunPanel from '@/components/tasks/PlaybookRunPanel';
 import {
   Breadcrumb,
   BreadcrumbItem,
@@ -634,6 +635,9 @@ export function Dashboard() {
                 <div className="shrink-0 border-t">
                   <div className="mx-auto w-full max-w-[50rem]">
                     <TodoPanel />
+                    {attempt && (
+                      <PlaybookRunPanel attemptId={attempt.id} />
+                    )}
                   </div>
                 </div>
 
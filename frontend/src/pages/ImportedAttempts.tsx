@@ -0,0 +1,377 @@
+import { useRef, useState } from 'react';
+import { useNavigate, useParams } from 'react-router-dom';
+import { useTranslation } from 'react-i18next';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  AlertCircle,
+  ArrowLeft,
+  ChevronDown,
+  ChevronRight,
+  Loader2,
+  Trash2,
+  Upload,
+} from 'lucide-react';
+
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { Card, CardContent } from '@/components/ui/card';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import DisplayConversationEntry from '@/components/NormalizedConversation/DisplayConversationEntry';
+import RawLogText from '@/components/common/RawLogText';
+import DiffCard from '@/components/DiffCard';
+import { ReviewProvider } from '@/contexts/ReviewProvider';
+import { importedAttemptsApi } from '@/lib/api';
+import type { ArchivedExecutionProcess } from 'shared/types';
+
+export function ImportedAttempts() {
+  const { importId } = useParams<{ importId: string }>();
+  if (importId) {
+    return <ImportedAttemptDetail importId={importId} />;
+  }
+  return <ImportedAttemptList />;
+}
+
+function ImportedAttemptList() {
+  const { t } = useTranslation('tasks');
+  const navigate = useNavigate();
+  const queryClient = useQueryClient();
+  const fileInput = useRef<HTMLInputElement>(null);
+
+  const {
+    data: imported = [],
+    isLoading,
+    error: listError,
+  } = useQuery({
+    queryKey: ['importedAttempts'],
+    queryFn: importedAttemptsApi.list,
+  });
+
+  const importMutation = useMutation({
+    mutationFn: importedAttemptsApi.import,
+    onSuccess: (attempt) => {
+      queryClient.invalidateQueries({ queryKey: ['importedAttempts'] });
+      navigate(`/imported-attempts/${attempt.id}`);
+    },
+  });
+
+  const deleteMutation = useMutation({
+    mutationFn: importedAttemptsApi.delete,
+    onSuccess: () =>
+      queryClient.invalidateQueries({ queryKey: ['importedAttempts'] }),
+  });
+
+  const handleFile = (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    e.target.value = '';
+    if (file) importMutation.mutate(file);
+  };
+
+  const error = importMutation.error || deleteMutation.error || listError;
+
+  return (
+    <div className="space-y-6 p-8 pb-16 md:pb-8 h-full overflow-auto">
+      <div className="flex justify-between items-center">
+        <div>
+          <h1 className="text-3xl font-bold tracking-tight">
+            {t('importedAttempts.title')}
+          </h1>
+          <p className="text-muted-foreground">
+            {t('importedAttempts.subtitle')}
+          </p>
+        </div>
+        <input
+          ref={fileInput}
+          type="file"
+          accept=".tar.gz,.tgz,application/gzip"
+          className="hidden"
+          onChange={handleFile}
+        />
+        <Button
+          onClick={() => fileInput.current?.click()}
+          disabled={importMutation.isPending}
+        >
+          {importMutation.isPending ? (
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          ) : (
+            <Upload className="mr-2 h-4 w-4" />
+          )}
+          {t('importedAttempts.import')}
+        </Button>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertCircle className="h-4 w-4" />
+          <AlertDescription>{error.message}</AlertDescription>
+        </Alert>
+      )}
+
+      {isLoading ? (
+        <div className="flex items-center justify-center py-12">
+          <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          {t('importedAttempts.loading')}
+        </div>
+      ) : imported.length === 0 ? (
+        <Card>
+          <CardContent className="py-12 text-center text-muted-foreground">
+            {t('importedAttempts.empty')}
+          </CardContent>
+        </Card>
+      ) : (
+        <div className="space-y-2">
+          {imported.map((attempt) => (
+            <Card
+              key={attempt.id}
+              className="cursor-pointer hover:bg-accent/50"
+              onClick={() => navigate(`/imported-attempts/${attempt.id}`)}
+            >
+              <CardContent className="flex items-center justify-between py-4">
+                <div className="min-w-0">
+                  <div className="font-medium truncate">
+                    {attempt.task_title}
+                  </div>
+                  <div className="text-sm text-muted-foreground truncate">
+                    {attempt.branch} ·{' '}
+                    {t('importedAttempts.exportedAt', {
+                      date: new Date(attempt.exported_at).toLocaleString(),
+                    })}
+                  </div>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  aria-label={t('importedAttempts.delete')}
+                  disabled={deleteMutation.isPending}
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    deleteMutation.mutate(attempt.id);
+                  }}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </CardContent>
+            </Card>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function ImportedAttemptDetail({ importId }: { importId: string }) {
+  const { t } = useTranslation('tasks');
+  const navigate = useNavigate();
+  const [expandedDiffs, setExpandedDiffs] = useState<Record<string, boolean>>(
+    {}
+  );
+
+  const {
+    data: manifest,
+    isLoading,
+    error,
+  } = useQuery({
+    queryKey: ['importedAttempt', importId],
+    queryFn: () => importedAttemptsApi.get(importId),
+  });
+
+  return (
+    <div className="space-y-6 p-8 pb-16 md:pb-8 h-full overflow-auto">
+      <Button variant="ghost" onClick={() => navigate('/imported-attempts')}>
+        <ArrowLeft className="mr-2 h-4 w-4" />
+        {t('importedAttempts.back')}
+      </Button>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertCircle className="h-4 w-4" />
+          <AlertDescription>{error.message}</AlertDescription>
+        </Alert>
+      )}
+
+      {isLoading || !manifest ? (
+        !error && (
+          <div className="flex items-center justify-center py-12">
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+            {t('importedAttempts.loading')}
+          </div>
+        )
+      ) : (
+        <>
+          <div className="space-y-2">
+            <div className="flex items-center gap-2">
+              <h1 className="text-2xl font-bold tracking-tight">
+                {manifest.task.title}
+              </h1>
+              <Badge variant="secondary">
+                {t('importedAttempts.readOnly')}
+              </Badge>
+            </div>
+            <p className="text-sm text-muted-foreground">
+              {manifest.workspace.branch} ·{' '}
+              {t('importedAttempts.exportedFrom', {
+                date: new Date(manifest.exported_at).toLocaleString(),
+                version: manifest.app_version,
+              })}
+            </p>
+            {manifest.task.description && (
+              <p className="whitespace-pre-wrap text-sm">
+                {manifest.task.description}
+              </p>
+            )}
+          </div>
+
+          {manifest.images.length > 0 && (
+            <div className="flex flex-wrap gap-2">
+              {manifest.images.map((image) => (
+                <img
+                  key={image.id}
+                  src={importedAttemptsApi.imageUrl(importId, image.id)}
+                  alt={image.original_name}
+                  className="h-24 rounded border object-cover"
+                />
+              ))}
+            </div>
+          )}
+
+          <section className="space-y-2">
+            <h2 className="text-lg font-semibold">
+              {t('importedAttempts.processes')}
+            </h2>
+            {manifest.processes
+              .filter((process) => !process.dropped)
+              .map((process) => (
+                <ImportedProcess
+                  key={process.id}
+                  importId={importId}
+                  process={process}
+                />
+              ))}
+          </section>
+
+          <section className="space-y-2">
+            <h2 className="text-lg font-semibold">
+              {t('importedAttempts.changes')}
+            </h2>
+            {manifest.diffs.length === 0 ? (
+              <p className="text-sm text-muted-foreground">
+                {t('importedAttempts.noChanges')}
+              </p>
+            ) : (
+              <ReviewProvider attemptId={importId}>
+                {manifest.diffs.map((diff, index) => {
+                  const key = diff.newPath || diff.oldPath || String(index);
+                  return (
+                    <DiffCard
+                      key={key}
+                      diff={diff}
+                      expanded={expandedDiffs[key] ?? false}
+                      onToggle={() =>
+                        setExpandedDiffs((prev) => ({
+                          ...prev,
+                          [key]: !prev[key],
+                        }))
+                      }
+                      selectedAttempt={null}
+                    />
+                  );
+                })}
+              </ReviewProvider>
+            )}
+          </section>
+        </>
+      )}
+    </div>
+  );
+}
+
+function ImportedProcess({
+  importId,
+  process,
+}: {
+  importId: string;
+  process: ArchivedExecutionProcess;
+}) {
+  const { t } = useTranslation('tasks');
+  const [open, setOpen] = useState(false);
+  const isAgent = process.run_reason === 'codingagent';
+
+  const { data: entries = [], isLoading } = useQuery({
+    queryKey: ['importedAttemptLogs', importId, process.id],
+    queryFn: () =>
+      isAgent
+        ? importedAttemptsApi.getEntries(importId, process.id)
+        : importedAttemptsApi.getRawLogs(importId, process.id),
+    enabled: open,
+  });
+
+  return (
+    <Card>
+      <button
+        type="button"
+        className="flex w-full items-center gap-2 px-4 py-3 text-left text-sm"
+        onClick={() => setOpen((prev) => !prev)}
+      >
+        {open ? (
+          <ChevronDown className="h-4 w-4" />
+        ) : (
+          <ChevronRight className="h-4 w-4" />
+        )}
+        <span className="font-medium">
+          {t(`importedAttempts.runReason.${process.run_reason}`)}
+        </span>
+        <Badge variant="outline">{process.status}</Badge>
+        {process.exit_code !== null && (
+          <span className="text-muted-foreground">
+            {t('importedAttempts.exitCode', {
+              code: String(process.exit_code),
+            })}
+          </span>
+        )}
+        <span className="ml-auto text-muted-foreground">
+          {new Date(process.started_at).toLocaleString()}
+        </span>
+      </button>
+      {open && (
+        <CardContent className="border-t pt-4">
+          {process.agent_turn?.prompt && (
+            <p className="mb-4 whitespace-pre-wrap text-sm">
+              {process.agent_turn.prompt}
+            </p>
+          )}
+          {isLoading ? (
+            <Loader2 className="h-4 w-4 animate-spin" />
+          ) : entries.length === 0 ? (
+            <p className="text-sm text-muted-foreground">
+              {t('importedAttempts.noLogs')}
+            </p>
+          ) : (
+            entries.map((entry, index) => {
+              switch (entry.type) {
+                case 'NORMALIZED_ENTRY':
+                  return (
+                    <DisplayConversationEntry
+                      key={index}
+                      entry={entry.content}
+                      expansionKey={`imported:${process.id}:${index}`}
+                      executionProcessId={process.id}
+                    />
+                  );
+                case 'STDOUT':
+                case 'STDERR':
+                  return (
+                    <RawLogText
+                      key={index}
+                      content={entry.content}
+                      channel={entry.type === 'STDERR' ? 'stderr' : 'stdout'}
+                    />
+                  );
+                default:
+                  return null;
+              }
+            })
+          )}
+        </CardContent>
+      )}
+    </Card>
+  );
+}
@@ -57,6 +57,7 @@ import TaskAttemptPanel from '@/components/panels/TaskAttemptPanel';
 import TaskPanel from '@/components/panels/TaskPanel';
 import SharedTaskPanel from '@/components/panels/SharedTaskPanel';
 import TodoPanel from '@/components/tasks/TodoPanel';
+import PlaybookRunPanel from '@/components/tasks/PlaybookRunPanel';
 import { useAuth } from '@/hooks';
 import { NewCard, NewCardHeader } from '@/components/ui/new-card';
 import {
@@ -942,6 +943,9 @@ export function ProjectTasks() {
                 <div className="shrink-0 border-t">
                   <div className="mx-auto w-full max-w-[50rem]">
                     <TodoPanel />
+                    {attempt && (
+                      <PlaybookRunPanel attemptId={attempt.id} />
+                    )}
                   </div>
                 </div>
 
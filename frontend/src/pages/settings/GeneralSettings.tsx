@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { cloneDeep, merge, isEqual } from 'lodash';
+import { useQuery } from '@tanstack/react-query';
 import {
   Card,
   CardContent,
@@ -29,7 +30,7 @@ import {
   UiLanguage,
   ConcurrencyLimit,
 } from 'shared/types';
-import type { BaseCodingAgent } from 'shared/types';
+import type { BaseCodingAgent, OciRuntime } from 'shared/types';
 import { getLanguageOptions } from '@/i18n/languages';
 
 import { toPrettyCase } from '@/utils/string';
@@ -38,6 +39,12 @@ import { EditorAvailabilityIndicator } from '@/components/EditorAvailabilityIndi
 import { useTheme } from '@/components/ThemeProvider';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { TagManager } from '@/components/TagManager';
+import { logStorageApi } from '@/lib/api';
+import { formatFileSize } from '@/lib/utils';
+
+const OCI_RUNTIMES: OciRuntime[] = ['docker', 'podman'];
+
+const formatBytes = (bytes: number) => formatFileSize(BigInt(bytes)) || '0 B';
 
 export function GeneralSettings() {
   const { t } = useTranslation(['settings', 'common']);
@@ -65,6 +72,10 @@ export function GeneralSettings() {
     null
   );
   const { setTheme } = useTheme();
+  const { data: logStorage = [] } = useQuery({
+    queryKey: ['logStorage'],
+    queryFn: logStorageApi.list,
+  });
 
   // Check editor availability when draft editor changes
   const editorAvailability = useEditorAvailability(draft?.editor.editor_type);
@@ -892,6 +903,7 @@ export function GeneralSettings() {
                     'QWEN_CODE',
                     'COPILOT',
                     'DROID',
+                    'CUSTOM_ACP',
                   ]
                     .filter(
                       (agent) =>
@@ -991,6 +1003,242 @@ export function GeneralSettings() {
         </CardContent>
       </Card>
 
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.containers.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.containers.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="container-runtime">
+              {t('settings.general.containers.runtime.label')}
+            </Label>
+            <Select
+              value={draft?.containers.runtime}
+              onValueChange={(value: OciRuntime) =>
+                updateDraft({
+                  containers: { ...draft!.containers, runtime: value },
+                })
+              }
+            >
+              <SelectTrigger id="container-runtime">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {OCI_RUNTIMES.map((runtime) => (
+                  <SelectItem key={runtime} value={runtime}>
+                    {toPrettyCase(runtime)}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.containers.runtime.helper')}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="container-mounts">
+              {t('settings.general.containers.mounts.label')}
+            </Label>
+            <textarea
+              id="container-mounts"
+              className="flex min-h-[80px] w-full rounded-md border border-input bg-background px-3 py-2 text-sm font-mono ring-offset-background placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2"
+              placeholder={'~/.claude\n~/.claude.json'}
+              value={draft?.containers.mounts.join('\n') ?? ''}
+              onChange={(e) =>
+                updateDraft({
+                  containers: {
+                    ...draft!.containers,
+                    mounts: e.target.value ? e.target.value.split('\n') : [],
+                  },
+                })
+              }
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.containers.mounts.helper')}
+            </p>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.logRetention.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.logRetention.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="log-retention-compact-after-days">
+              {t('settings.general.logRetention.compactAfterDays.label')}
+            </Label>
+            <Input
+              id="log-retention-compact-after-days"
+              type="number"
+              min={1}
+              className="w-32"
+              placeholder={t('settings.general.logRetention.never')}
+              value={draft?.log_retention.compact_after_days ?? ''}
+              onChange={(e) => {
+                const value = parseInt(e.target.value, 10);
+                updateDraft({
+                  log_retention: {
+                    ...draft!.log_retention,
+                    compact_after_days: !isNaN(value) && value > 0 ? value : null,
+                  },
+                });
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.logRetention.compactAfterDays.helper')}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="log-retention-prune-raw-after-days">
+              {t('settings.general.logRetention.pruneRawAfterDays.label')}
+            </Label>
+            <Input
+              id="log-retention-prune-raw-after-days"
+              type="number"
+              min={1}
+              className="w-32"
+              placeholder={t('settings.general.logRetention.never')}
+              value={draft?.log_retention.prune_raw_after_days ?? ''}
+              onChange={(e) => {
+                const value = parseInt(e.target.value, 10);
+                updateDraft({
+                  log_retention: {
+                    ...draft!.log_retention,
+                    prune_raw_after_days: !isNaN(value) && value > 0 ? value : null,
+                  },
+                });
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.logRetention.pruneRawAfterDays.helper')}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="log-retention-max-storage-mb">
+              {t('settings.general.logRetention.maxStorageMb.label')}
+            </Label>
+            <Input
+              id="log-retention-max-storage-mb"
+              type="number"
+              min={1}
+              className="w-32"
+              placeholder={t('settings.general.logRetention.never')}
+              value={draft?.log_retention.max_storage_mb ?? ''}
+              onChange={(e) => {
+                const value = parseInt(e.target.value, 10);
+                updateDraft({
+                  log_retention: {
+                    ...draft!.log_retention,
+                    max_storage_mb: !isNaN(value) && value > 0 ? value : null,
+                  },
+                });
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.logRetention.maxStorageMb.helper')}
+            </p>
+          </div>
+          <div className="space-y-2">
+            <Label>{t('settings.general.logRetention.usage.label')}</Label>
+            {logStorage.length === 0 ? (
+              <p className="text-sm text-muted-foreground">
+                {t('settings.general.logRetention.usage.empty')}
+              </p>
+            ) : (
+              <div className="space-y-1 text-sm">
+                {logStorage.map((project) => (
+                  <div
+                    key={project.project_id}
+                    className="flex items-center justify-between gap-4"
+                  >
+                    <span className="truncate">{project.project_name}</span>
+                    <span className="text-muted-foreground whitespace-nowrap">
+                      {t('settings.general.logRetention.usage.row', {
+                        stored: formatBytes(
+                          project.live_bytes + project.compacted_stored_bytes
+                        ),
+                        compacted: formatBytes(project.compacted_bytes),
+                      })}
+                    </span>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.contextCompaction.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.contextCompaction.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id="context-compaction-auto"
+              checked={draft?.context_compaction.auto_compact ?? false}
+              onCheckedChange={(checked: boolean) =>
+                updateDraft({
+                  context_compaction: {
+                    ...draft!.context_compaction,
+                    auto_compact: checked,
+                  },
+                })
+              }
+            />
+            <div className="space-y-0.5">
+              <Label
+                htmlFor="context-compaction-auto"
+                className="cursor-pointer"
+              >
+                {t('settings.general.contextCompaction.autoCompact.label')}
+              </Label>
+              <p className="text-sm text-muted-foreground">
+                {t('settings.general.contextCompaction.autoCompact.helper')}
+              </p>
+            </div>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="context-compaction-max">
+              {t('settings.general.contextCompaction.maxCompactions.label')}
+            </Label>
+            <Input
+              id="context-compaction-max"
+              type="number"
+              min={1}
+              className="w-32"
+              value={draft?.context_compaction.max_compactions ?? ''}
+              disabled={!draft?.context_compaction.auto_compact}
+              onChange={(e) => {
+                const value = parseInt(e.target.value, 10);
+                if (!isNaN(value) && value > 0) {
+                  updateDraft({
+                    context_compaction: {
+                      ...draft!.context_compaction,
+                      max_compactions: value,
+                    },
+                  });
+                }
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.contextCompaction.maxCompactions.helper')}
+            </p>
+          </div>
+        </CardContent>
+      </Card>
+
       <Card>
         <CardHeader>
           <CardTitle>{t('settings.general.taskTemplates.title')}</CardTitle>
@@ -0,0 +1,249 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { JSONEditor } from '@/components/ui/json-editor';
+import { Loader2, Trash2 } from 'lucide-react';
+import { playbooksApi } from '@/lib/api';
+import { playbookKeys, usePlaybooks } from '@/hooks/usePlaybooks';
+import type { Playbook, PlaybookStep } from 'shared/types';
+
+const NEW_PLAYBOOK = 'new';
+
+const EXAMPLE_STEPS: PlaybookStep[] = [
+  { type: 'prompt', prompt: 'Add tests for the code you changed.' },
+  {
+    type: 'check',
+    script: 'npm run lint',
+    working_dir: null,
+    on_failure: 'Fix the lint errors.',
+    max_retries: 2,
+  },
+  { type: 'script', script: 'npm run format', working_dir: null },
+];
+
+function stepsToJson(steps: PlaybookStep[]) {
+  return JSON.stringify(steps, null, 2);
+}
+
+export function PlaybookSettings() {
+  const { t } = useTranslation('settings');
+  const queryClient = useQueryClient();
+  const { data: playbooks, isLoading } = usePlaybooks();
+
+  const [selectedId, setSelectedId] = useState(NEW_PLAYBOOK);
+  const [name, setName] = useState('');
+  const [description, setDescription] = useState('');
+  const [steps, setSteps] = useState(stepsToJson(EXAMPLE_STEPS));
+  const [error, setError] = useState<string | null>(null);
+  const [success, setSuccess] = useState(false);
+
+  const selected = playbooks?.find((p) => p.id === selectedId) ?? null;
+
+  useEffect(() => {
+    setError(null);
+    setSuccess(false);
+    if (selected) {
+      setName(selected.name);
+      setDescription(selected.description ?? '');
+      setSteps(stepsToJson(selected.steps));
+    } else {
+      setName('');
+      setDescription('');
+      setSteps(stepsToJson(EXAMPLE_STEPS));
+    }
+  }, [selected]);
+
+  const onSaved = (playbook: Playbook) => {
+    queryClient.invalidateQueries({ queryKey: playbookKeys.all });
+    setSelectedId(playbook.id);
+    setSuccess(true);
+    setTimeout(() => setSuccess(false), 3000);
+  };
+
+  const saveMutation = useMutation({
+    mutationFn: (parsedSteps: PlaybookStep[]) => {
+      const data = {
+        name: name.trim(),
+        description: description.trim() || null,
+        steps: parsedSteps,
+      };
+      return selected
+        ? playbooksApi.update(selected.id, data)
+        : playbooksApi.create(data);
+    },
+    onSuccess: onSaved,
+    onError: (err: Error) => setError(err.message),
+  });
+
+  const deleteMutation = useMutation({
+    mutationFn: (playbookId: string) => playbooksApi.delete(playbookId),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: playbookKeys.all });
+      setSelectedId(NEW_PLAYBOOK);
+    },
+    onError: (err: Error) => setError(err.message),
+  });
+
+  const handleSave = () => {
+    setError(null);
+    let parsedSteps: PlaybookStep[];
+    try {
+      parsedSteps = JSON.parse(steps);
+    } catch {
+      setError(t('settings.playbooks.errors.invalidSteps'));
+      return;
+    }
+    if (!Array.isArray(parsedSteps)) {
+      setError(t('settings.playbooks.errors.invalidSteps'));
+      return;
+    }
+    saveMutation.mutate(parsedSteps);
+  };
+
+  const handleDelete = () => {
+    if (!selected) return;
+    if (
+      !window.confirm(
+        t('settings.playbooks.delete.confirm', { name: selected.name })
+      )
+    ) {
+      return;
+    }
+    deleteMutation.mutate(selected.id);
+  };
+
+  if (isLoading) {
+    return (
+      <div className="flex items-center justify-center py-8">
+        <Loader2 className="h-8 w-8 animate-spin" />
+        <span className="ml-2">{t('settings.playbooks.loading')}</span>
+      </div>
+    );
+  }
+
+  const isBusy = saveMutation.isPending || deleteMutation.isPending;
+
+  return (
+    <div className="space-y-6">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      {success && (
+        <Alert variant="success">
+          <AlertDescription className="font-medium">
+            {t('settings.playbooks.save.success')}
+          </AlertDescription>
+        </Alert>
+      )}
+
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.playbooks.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.playbooks.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <Label htmlFor="playbook-selector">
+              {t('settings.playbooks.selector.label')}
+            </Label>
+            <Select value={selectedId} onValueChange={setSelectedId}>
+              <SelectTrigger id="playbook-selector">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={NEW_PLAYBOOK}>
+                  {t('settings.playbooks.selector.new')}
+                </SelectItem>
+                {playbooks?.map((playbook) => (
+                  <SelectItem key={playbook.id} value={playbook.id}>
+                    {playbook.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="playbook-name">
+              {t('settings.playbooks.fields.name')}
+            </Label>
+            <Input
+              id="playbook-name"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              disabled={isBusy}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="playbook-description">
+              {t('settings.playbooks.fields.description')}
+            </Label>
+            <Input
+              id="playbook-description"
+              value={description}
+              onChange={(e) => setDescription(e.target.value)}
+              disabled={isBusy}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor="playbook-steps">
+              {t('settings.playbooks.fields.steps')}
+            </Label>
+            <JSONEditor
+              id="playbook-steps"
+              value={steps}
+              onChange={setSteps}
+              disabled={isBusy}
+              minHeight={240}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.playbooks.fields.stepsHelper')}
+            </p>
+          </div>
+
+          <div className="flex justify-between">
+            <Button
+              variant="destructive"
+              onClick={handleDelete}
+              disabled={!selected || isBusy}
+            >
+              <Trash2 className="mr-2 h-4 w-4" />
+              {t('settings.playbooks.delete.button')}
+            </Button>
+            <Button onClick={handleSave} disabled={!name.trim() || isBusy}>
+              {saveMutation.isPending && (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              )}
+              {t('settings.playbooks.save.button')}
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
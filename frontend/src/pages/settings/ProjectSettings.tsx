@@ -38,12 +38,17 @@ interface ProjectFormState {
   dev_script: string;
   dev_script_working_dir: string;
   default_agent_working_dir: string;
+  container_image: string;
+  auto_address_review_comments: boolean;
+  auto_fix_ci: boolean;
+  max_ci_fix_attempts: number;
 }
 
 interface RepoScriptsFormState {
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
+  verification_script: string;
   copy_files: string;
 }
 
@@ -53,6 +58,10 @@ function projectToFormState(project: Project): ProjectFormState {
     dev_script: project.dev_script ?? '',
     dev_script_working_dir: project.dev_script_working_dir ?? '',
     default_agent_working_dir: project.default_agent_working_dir ?? '',
+    container_image: project.container_image ?? '',
+    auto_address_review_comments: project.auto_address_review_comments,
+    auto_fix_ci: project.auto_fix_ci,
+    max_ci_fix_attempts: project.max_ci_fix_attempts,
   };
 }
 
@@ -63,6 +72,7 @@ function projectRepoToScriptsFormState(
     setup_script: projectRepo?.setup_script ?? '',
     parallel_setup_script: projectRepo?.parallel_setup_script ?? false,
     cleanup_script: projectRepo?.cleanup_script ?? '',
+    verification_script: projectRepo?.verification_script ?? '',
     copy_files: projectRepo?.copy_files ?? '',
   };
 }
@@ -397,6 +407,10 @@ export function ProjectSettings() {
         dev_script_working_dir: draft.dev_script_working_dir.trim() || null,
         default_agent_working_dir:
           draft.default_agent_working_dir.trim() || null,
+        container_image: draft.container_image.trim() || null,
+        auto_address_review_comments: draft.auto_address_review_comments,
+        auto_fix_ci: draft.auto_fix_ci,
+        max_ci_fix_attempts: draft.max_ci_fix_attempts,
       };
 
       updateProject.mutate({
@@ -424,6 +438,7 @@ export function ProjectSettings() {
         {
           setup_script: scriptsDraft.setup_script.trim() || null,
           cleanup_script: scriptsDraft.cleanup_script.trim() || null,
+          verification_script: scriptsDraft.verification_script.trim() || null,
           copy_files: scriptsDraft.copy_files.trim() || null,
           parallel_setup_script: scriptsDraft.parallel_setup_script,
         }
@@ -630,6 +645,97 @@ export function ProjectSettings() {
                 </p>
               </div>
 
+              <div className="space-y-2">
+                <Label htmlFor="container-image">
+                  {t('settings.projects.general.containerImage.label')}
+                </Label>
+                <Input
+                  id="container-image"
+                  value={draft.container_image}
+                  onChange={(e) =>
+                    updateDraft({ container_image: e.target.value })
+                  }
+                  placeholder={t(
+                    'settings.projects.general.containerImage.placeholder'
+                  )}
+                  className="font-mono"
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.containerImage.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="auto-address-review-comments"
+                    checked={draft.auto_address_review_comments}
+                    onCheckedChange={(checked) =>
+                      updateDraft({
+                        auto_address_review_comments: checked === true,
+                      })
+                    }
+                  />
+                  <Label
+                    htmlFor="auto-address-review-comments"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t(
+                      'settings.projects.general.autoAddressReviewComments.label'
+                    )}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t(
+                    'settings.projects.general.autoAddressReviewComments.helper'
+                  )}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="auto-fix-ci"
+                    checked={draft.auto_fix_ci}
+                    onCheckedChange={(checked) =>
+                      updateDraft({ auto_fix_ci: checked === true })
+                    }
+                  />
+                  <Label
+                    htmlFor="auto-fix-ci"
+                    className="text-sm font-normal cursor-pointer"
+                  >
+                    {t('settings.projects.general.autoFixCi.label')}
+                  </Label>
+                </div>
+                <p className="text-sm text-muted-foreground pl-6">
+                  {t('settings.projects.general.autoFixCi.helper')}
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="max-ci-fix-attempts">
+                  {t('settings.projects.general.maxCiFixAttempts.label')}
+                </Label>
+                <Input
+                  id="max-ci-fix-attempts"
+                  type="number"
+                  min={1}
+                  className="w-32"
+                  value={draft.max_ci_fix_attempts}
+                  disabled={!draft.auto_fix_ci}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value, 10);
+                    if (!isNaN(value) && value > 0) {
+                      updateDraft({ max_ci_fix_attempts: value });
+                    }
+                  }}
+                />
+                <p className="text-sm text-muted-foreground">
+                  {t('settings.projects.general.maxCiFixAttempts.helper')}
+                </p>
+              </div>
+
               {/* Save Button */}
               <div className="flex items-center justify-between pt-4 border-t">
                 {hasUnsavedProjectChanges ? (
@@ -875,6 +981,27 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="verification-script">
+                          {t('settings.projects.scripts.verification.label')}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="verification-script"
+                          value={scriptsDraft.verification_script}
+                          onChange={(e) =>
+                            updateScriptsDraft({
+                              verification_script: e.target.value,
+                            })
+                          }
+                          placeholder={placeholders.verification}
+                          maxRows={12}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.verification.helper')}
+                        </p>
+                      </div>
+
                       <div className="space-y-2">
                         <Label>
                           {t('settings.projects.scripts.copyFiles.label')}
@@ -1,6 +1,14 @@
 import { NavLink, Outlet } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
-import { Settings, Cpu, Server, X, FolderOpen, Building2 } from 'lucide-react';
+import {
+  Settings,
+  Cpu,
+  Server,
+  X,
+  FolderOpen,
+  Building2,
+  ListChecks,
+} from 'lucide-react';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
 import { useEffect } from 'react';
@@ -30,6 +38,10 @@ const settingsNavigation = [
     path: 'mcp',
     icon: Server,
   },
+  {
+    path: 'playbooks',
+    icon: ListChecks,
+  },
 ];
 
 export function SettingsLayout() {
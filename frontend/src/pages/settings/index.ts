@@ -4,3 +4,4 @@ export { ProjectSettings } from './ProjectSettings';
 export { OrganizationSettings } from './OrganizationSettings';
 export { AgentSettings } from './AgentSettings';
 export { McpSettings } from './McpSettings';
+export { PlaybookSettings } from './PlaybookSettings';
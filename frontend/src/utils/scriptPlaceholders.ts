@@ -2,6 +2,7 @@ interface ScriptPlaceholders {
   setup: string;
   dev: string;
   cleanup: string;
+  verification: string;
 }
 
 interface ScriptPlaceholderStrategy {
@@ -20,6 +21,9 @@ REM Add dev server start command here...`,
       cleanup: `@echo off
 REM Add cleanup commands here...
 REM This runs after coding agent execution - only if changes were made`,
+      verification: `@echo off
+npm test
+REM The task only moves to review if this exits successfully`,
     };
   }
 }
@@ -36,6 +40,9 @@ npm run dev
       cleanup: `#!/bin/bash
 # Add cleanup commands here...
 # This runs after coding agent execution - only if changes were made`,
+      verification: `#!/bin/bash
+npm test
+# The task only moves to review if this exits successfully`,
     };
   }
 }
@@ -12,23 +12,62 @@ export type SharedTask = { id: string, organization_id: string, project_id: stri
 
 export type UserData = { user_id: string, first_name: string | null, last_name: string | null, username: string | null, };
 
-export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, 
+/**
+ * Image to run the project's workspaces in, instead of on the host
+ */
+container_image: string | null, 
+/**
+ * Send new PR review comments to the workspace's coding agent
+ */
+auto_address_review_comments: boolean, 
+/**
+ * Send failing CI on the workspace's PRs to its coding agent
+ */
+auto_fix_ci: boolean, 
+/**
+ * Follow-ups started per PR to fix failing CI before giving up
+ */
+max_ci_fix_attempts: number, remote_project_id: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, };
+export type UpdateProject = { name: string | null, dev_script: string | null, dev_script_working_dir: string | null, default_agent_working_dir: string | null, container_image: string | null, auto_address_review_comments: boolean | null, auto_fix_ci: boolean | null, max_ci_fix_attempts: number | null, };
 
-export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
+export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
+/**
+ * Definition found in the file, for symbol matches
+ */
+symbol: SymbolMatch | null, };
 
-export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
+export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath" | "Symbol";
+
+/**
+ * Function, type or module defined in a repository file
+ */
+export type SymbolMatch = { name: string, kind: SymbolKind, 
+/**
+ * 1-based line the definition starts on
+ */
+line: number, 
+/**
+ * Type, trait or module the symbol is defined in, e.g. the `impl` type of a method
+ */
+container: string | null, };
+
+export type SymbolKind = "function" | "method" | "struct" | "class" | "enum" | "interface" | "trait" | "type" | "module" | "constant" | "macro";
 
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
-export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
+export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, 
+/**
+ * Runs after the cleanup script; the task only moves to review if it passes
+ */
+verification_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
+export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, verification_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
 
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
@@ -46,9 +85,17 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, 
+/**
+ * CI result of the task's latest open PR
+ */
+ci_status: CiStatus | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatusAndProject = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, project_name: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatusAndProject = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, 
+/**
+ * CI result of the task's latest open PR
+ */
+ci_status: CiStatus | null, project_name: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
@@ -56,6 +103,64 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
+export type TaskDependency = { id: string, task_id: string, depends_on_task_id: string, created_at: string, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+/**
+ * A prerequisite task together with whether it currently blocks its dependent
+ */
+export type TaskDependencyStatus = { task_id: string, title: string, status: TaskStatus, is_merged: boolean, is_satisfied: boolean, };
+
+export type TaskDependencies = { 
+/**
+ * Tasks this task waits on
+ */
+blocked_by: Array<TaskDependencyStatus>, 
+/**
+ * Tasks waiting on this task
+ */
+blocking: Array<Task>, 
+/**
+ * True when at least one prerequisite is not yet done or merged
+ */
+is_blocked: boolean, };
+
+/**
+ * Repository and base branch a scheduled run starts its workspace from
+ */
+export type ScheduleRepo = { repo_id: string, target_branch: string, };
+
+export type TaskSchedule = { id: string, project_id: string, name: string, 
+/**
+ * Title of created tasks; `{date}` is replaced with the run's UTC date
+ */
+task_title: string, 
+/**
+ * Description of created tasks; `{date}` is replaced as in the title
+ */
+task_description: string | null, executor_profile_id: ExecutorProfileId, repos: Array<ScheduleRepo>, 
+/**
+ * Five-field cron expression in UTC; exclusive with `interval_seconds`
+ */
+cron_expression: string | null, interval_seconds: number | null, enabled: boolean, next_run_at: string | null, last_run_at: string | null, 
+/**
+ * Task created by the most recent successful run
+ */
+last_task_id: string | null, 
+/**
+ * Why the most recent run did not start a task, if it didn't
+ */
+last_error: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, name: string, task_title: string, task_description: string | null, executor_profile_id: ExecutorProfileId, repos: Array<ScheduleRepo>, cron_expression: string | null, interval_seconds: number | null, enabled: boolean | null, };
+
+/**
+ * Fields left out keep their current value. Setting either `cron_expression`
+ * or `interval_seconds` replaces the schedule's timing entirely.
+ */
+export type UpdateTaskSchedule = { name: string | null, task_title: string | null, task_description: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<ScheduleRepo> | null, cron_expression: string | null, interval_seconds: number | null, enabled: boolean | null, };
+
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
 export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData };
@@ -74,7 +179,16 @@ export type CreateImage = { file_path: string, original_name: string, mime_type:
 
 export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, };
 
-export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
+export type Session = { id: string, workspace_id: string, executor: string | null, 
+/**
+ * Coding agent turn this session was forked from
+ */
+forked_from_process_id: string | null, 
+/**
+ * Session whose conversation outgrew the context window and was
+ * summarised into this one
+ */
+compacted_from_session_id: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
 /**
@@ -90,16 +204,55 @@ export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codin
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type CodingAgentTurn = { id: string, execution_process_id: string, agent_session_id: string | null, prompt: string | null, summary: string | null, created_at: string, updated_at: string, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
 
-export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+export type PrMerge = { id: string, workspace_id: string, repo_id: string, created_at: string, target_branch_name: string, forge: ForgeKind, pr_info: PullRequestInfo, 
+/**
+ * Latest CI result for the PR head, unset while the forge reports no checks
+ */
+ci: PrCiState | null, 
+/**
+ * Follow-ups started so far to fix failing CI on this PR
+ */
+ci_fix_attempts: number, 
+/**
+ * Head commit whose failing CI the last fix follow-up was started for
+ */
+ci_fix_head_sha: string | null, 
+/**
+ * Latest fix follow-up, until its changes have been pushed
+ */
+ci_fix_execution_process_id: string | null, };
+
+export type ForgeKind = "github" | "gitlab";
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+/**
+ * Result of a CI check, or of all checks on a commit combined
+ */
+export type CiStatus = "pending" | "success" | "failure";
+
+/**
+ * One CI job or commit status reported for a PR's head commit
+ */
+export type CiCheck = { name: string, status: CiStatus, url: string | null, 
+/**
+ * Forge id of the job, set when its log can be fetched
+ */
+job_id: number | null, };
+
+/**
+ * CI checks on the head commit of a PR
+ */
+export type PrCiState = { head_sha: string, status: CiStatus, checks: Array<CiCheck>, checked_at: string, };
+
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
 export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue, tool_call_id: string, };
@@ -212,12 +365,32 @@ export type CurrentUserResponse = { user_id: string, };
 
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type ForkSessionRequest = { 
+/**
+ * Coding agent turn of this session to fork from
+ */
+execution_process_id: string, prompt: string, variant: string | null, };
+
+export type ForkSessionResponse = { workspace: Workspace, session: Session, execution_process: ExecutionProcess, };
+
+export type HandoffSessionRequest = { 
+/**
+ * Coding agent that takes over the workspace
+ */
+executor_profile_id: ExecutorProfileId, prompt: string, };
+
+export type HandoffSessionResponse = { session: Session, execution_process: ExecutionProcess, };
+
+export type CompactSessionResponse = { session: Session, execution_process: ExecutionProcess, };
+
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
 
 export type MergeTaskAttemptRequest = { repo_id: string, auto_rebase: boolean | null, };
 
+export type MergeQueueRequest = { repo_id: string, };
+
 export type PushTaskAttemptRequest = { repo_id: string, };
 
 export type RenameBranchRequest = { new_branch_name: string, };
@@ -232,7 +405,11 @@ export type AssignSharedTaskRequest = { new_assignee_user_id: string | null, };
 
 export type ShareTaskResponse = { shared_task_id: string, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Tasks that must be done or merged before this one starts
+ */
+depends_on_task_ids?: Array<string>, };
 
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
@@ -244,6 +421,14 @@ export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: Exec
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
+export type FanOutTaskAttemptsBody = { task_id: string, 
+/**
+ * One attempt is started per profile; profiles must be distinct
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<WorkspaceRepoInput>, };
+
+export type PromoteAttemptRequest = { workspace_id: string, };
+
 export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
 
 export type RunAgentSetupResponse = Record<string, never>;
@@ -258,7 +443,7 @@ export type GitOperationError = { "type": "merge_conflicts", message: string, op
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "gitlab_not_authenticated" } | { "type": "target_branch_not_found", branch: string, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -272,7 +457,15 @@ conflict_op: ConflictOp | null,
 /**
  * List of files currently in conflicted (unmerged) state
  */
-conflicted_files: Array<string>, };
+conflicted_files: Array<string>, 
+/**
+ * Latest merge queue entry of the attempt for this repo
+ */
+merge_queue_entry: MergeQueueEntry | null, 
+/**
+ * Entries of the same repo that are merged before the queued one
+ */
+merge_queue_ahead: number | null, };
 
 export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
 
@@ -282,7 +475,7 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "gitlab_not_authenticated" };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
@@ -300,7 +493,15 @@ conflict_op: ConflictOp | null,
 /**
  * List of files currently in conflicted (unmerged) state
  */
-conflicted_files: Array<string>, };
+conflicted_files: Array<string>, 
+/**
+ * Latest merge queue entry of the attempt for this repo
+ */
+merge_queue_entry: MergeQueueEntry | null, 
+/**
+ * Entries of the same repo that are merged before the queued one
+ */
+merge_queue_ahead: number | null, };
 
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
@@ -310,7 +511,35 @@ export type Config = { config_version: string, theme: ThemeMode, executor_profil
 /**
  * Concurrency settings for task execution
  */
-concurrency: ConcurrencyConfig, };
+concurrency: ConcurrencyConfig, 
+/**
+ * Model pricing for token cost accounting
+ */
+pricing: PricingConfig, 
+/**
+ * GitLab merge request settings
+ */
+gitlab: GitLabConfig, 
+/**
+ * Rules that approve, deny or escalate agent tool calls
+ */
+approval_policy: ApprovalPolicyConfig, 
+/**
+ * Automatic follow-ups when a verification script fails
+ */
+verification: VerificationConfig, 
+/**
+ * Settings for projects that run their workspaces in containers
+ */
+containers: ContainerConfig, 
+/**
+ * Compaction and pruning of execution process logs
+ */
+log_retention: LogRetentionConfig, 
+/**
+ * Fresh sessions for agents that run out of context window
+ */
+context_compaction: ContextCompactionConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -324,6 +553,114 @@ export type EditorOpenError = { "type": "executable_not_found", executable: stri
 
 export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, };
 
+/**
+ * GitLab merge request settings
+ */
+export type GitLabConfig = { 
+/**
+ * Personal access token with the `api` scope. Falls back to the
+ * GITLAB_TOKEN environment variable when unset.
+ */
+token: string | null, 
+/**
+ * Extra hostnames to treat as GitLab, for self-hosted instances.
+ * gitlab.com and hosts starting with "gitlab." are always recognised.
+ */
+hosts: Array<string>, };
+
+/**
+ * What an approval policy rule does with a matching tool call
+ */
+export type ApprovalRuleAction = "approve" | "deny" | "escalate";
+
+/**
+ * Which tool calls an approval policy rule applies to
+ */
+export type ApprovalMatcher = { "type": "command_run", pattern: string, } | { "type": "file_edit", glob: string, } | { "type": "file_read", glob: string, } | { "type": "web_fetch", domain: string, } | { "type": "tool", name: string, } | { "type": "any" };
+
+export type ApprovalRule = { 
+/**
+ * Shown in the conversation log whenever the rule decides a tool call
+ */
+name: string, 
+/**
+ * Only apply the rule to this project
+ */
+project_id: string | null, 
+/**
+ * Only apply the rule to this executor profile. A profile without a
+ * variant matches every variant of its executor.
+ */
+executor_profile: ExecutorProfileId | null, matcher: ApprovalMatcher, action: ApprovalRuleAction, 
+/**
+ * Explanation recorded with the decision and, for denials, passed back
+ * to the agent
+ */
+reason: string | null, };
+
+/**
+ * Rules checked before a tool call is sent to a human for approval. The
+ * first matching rule decides; calls no rule matches get `default_action`.
+ */
+export type ApprovalPolicyConfig = { rules: Array<ApprovalRule>, default_action: ApprovalRuleAction, };
+
+/**
+ * What happens when a project's verification script fails
+ */
+export type VerificationConfig = { 
+/**
+ * Send the failure output back to the coding agent as a follow-up
+ */
+auto_fix: boolean, 
+/**
+ * Follow-ups sent for one attempt before leaving the failure to the user
+ */
+max_auto_fix_attempts: number, };
+
+export type ContainerConfig = { 
+/**
+ * Container engine used to run project images
+ */
+runtime: OciRuntime, 
+/**
+ * Host paths mounted into every container at the same path, e.g. agent
+ * credentials like `~/.claude`. Paths that don't exist are skipped.
+ */
+mounts: Array<string>, };
+
+/**
+ * When the logs of finished execution processes are compacted or dropped
+ */
+export type LogRetentionConfig = { 
+/**
+ * Compress the logs of processes that finished this many days ago
+ */
+compact_after_days: number | null, 
+/**
+ * Drop the raw logs of processes that finished this many days ago. The
+ * conversation of coding agents is kept.
+ */
+prune_raw_after_days: number | null, 
+/**
+ * Once logs take more than this many megabytes, compact and then prune
+ * the oldest finished processes until they fit
+ */
+max_storage_mb: number | null, };
+
+/**
+ * What happens when a coding agent runs out of context window
+ */
+export type ContextCompactionConfig = { 
+/**
+ * Continue in a new session, seeded with a summary of the conversation
+ * and the changes so far
+ */
+auto_compact: boolean, 
+/**
+ * Compactions of one attempt before leaving the failure to the user
+ */
+max_compactions: number, };
+
 export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
 export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
@@ -343,7 +680,11 @@ agent_limits: Record<string, number | null>,
 /**
  * Queue behavior configuration
  */
-queue: QueueConfig, };
+queue: QueueConfig, 
+/**
+ * Token spend caps
+ */
+budget: BudgetConfig, };
 
 export type ConcurrencyLimit = number | null;
 
@@ -362,6 +703,39 @@ resume_on_restart: boolean,
  */
 resume_prompt: string, };
 
+/**
+ * Spend caps in USD, priced with the `pricing` table. Days and months are UTC.
+ */
+export type BudgetConfig = { 
+/**
+ * Maximum spend per project per day (null = unlimited)
+ */
+project_daily_limit_usd: number | null, 
+/**
+ * Maximum spend across all projects per month (null = unlimited)
+ */
+global_monthly_limit_usd: number | null, 
+/**
+ * Whether crossing a cap also interrupts running coding agents,
+ * rather than only holding back new executions
+ */
+hard_limit: boolean, };
+
+/**
+ * Per-model pricing used to turn recorded token usage into cost
+ */
+export type PricingConfig = { 
+/**
+ * Model name (or model name prefix) -> pricing. The longest matching prefix wins,
+ * so "claude-sonnet-4" also prices "claude-sonnet-4-20250514".
+ */
+models: Record<string, ModelPricing>, };
+
+/**
+ * Price of one model in USD per million tokens
+ */
+export type ModelPricing = { input_per_mtok: number, output_per_mtok: number, cache_write_per_mtok: number, cache_read_per_mtok: number, };
+
 export type ConcurrencyStats = { 
 /**
  * Total number of running coding agent processes
@@ -428,6 +802,295 @@ export type QueueStats = { total_pending: number, total_processing: number, by_e
 
 export type ExecutorQueueStats = { pending: number, processing: number, limit: number | null, };
 
+/**
+ * Cumulative token counts reported by a coding agent for one execution.
+ * `input_tokens` excludes tokens served from or written to the prompt cache.
+ */
+export type TokenUsage = { model: string | null, input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, };
+
+export type ExecutionProcessTokenUsage = { id: string, execution_process_id: string, model: string | null, input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, created_at: string, updated_at: string, };
+
+/**
+ * Token totals for one executor/model pair within a task, project or globally
+ */
+export type TokenUsageBreakdown = { executor: string | null, model: string | null, execution_count: number, input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, };
+
+/**
+ * Usage recorded for a single execution process, priced with the current config
+ */
+export type ExecutionProcessUsage = { 
+/**
+ * None when the model has no configured pricing
+ */
+cost_usd: number | null, id: string, execution_process_id: string, model: string | null, input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, created_at: string, updated_at: string, };
+
+export type PricedTokenUsageBreakdown = { 
+/**
+ * None when the model has no configured pricing
+ */
+cost_usd: number | null, executor: string | null, model: string | null, execution_count: number, input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, };
+
+/**
+ * Token and cost totals for a task, a project or all projects
+ */
+export type UsageSummary = { input_tokens: number, output_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, 
+/**
+ * Sum over priced models only; see `unpriced_models`
+ */
+total_cost_usd: number, 
+/**
+ * Models that appear in the usage but have no configured pricing
+ */
+unpriced_models: Array<string>, 
+/**
+ * Per executor/model totals
+ */
+breakdown: Array<PricedTokenUsageBreakdown>, };
+
+/**
+ * What a single run of a schedule did
+ */
+export type ScheduleRunOutcome = { "status": "started", task_id: string, } | { "status": "queued", task_id: string, } | { "status": "skipped", reason: string, };
+
+/**
+ * Lifecycle events a webhook can subscribe to
+ */
+export type WebhookEventType = "task.status_changed" | "execution.completed" | "execution.failed" | "approval.requested" | "pr.merged" | "ping";
+
+export type Webhook = { id: string, name: string, url: string, 
+/**
+ * Key for the `X-Vibe-Kanban-Signature` HMAC-SHA256 header. It can only
+ * be set; responses carry a masked `secret_hint` instead.
+ */
+secret_hint: string, 
+/**
+ * Events to deliver; empty subscribes to all of them
+ */
+events: Array<WebhookEventType>, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateWebhook = { name: string, url: string, secret: string, events: Array<WebhookEventType>, enabled: boolean | null, };
+
+export type UpdateWebhook = { name: string | null, url: string | null, secret: string | null, events: Array<WebhookEventType> | null, enabled: boolean | null, };
+
+export type WebhookDeliveryStatus = "pending" | "delivered" | "failed";
+
+export type WebhookDelivery = { id: string, webhook_id: string, event_type: string, 
+/**
+ * JSON body exactly as signed and sent
+ */
+payload: string, status: WebhookDeliveryStatus, attempts: number, 
+/**
+ * When the next attempt is due while the delivery is pending
+ */
+next_attempt_at: string | null, 
+/**
+ * HTTP status of the most recent attempt, if the endpoint answered
+ */
+response_status: number | null, last_error: string | null, delivered_at: string | null, created_at: string, updated_at: string, };
+
+export type ApprovalOutcome = "pending" | "approved" | "denied" | "timed_out";
+
+/**
+ * Who resolved an approval request
+ */
+export type ApprovalDecider = "user" | "policy" | "system";
+
+export type Approval = { id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, status: ApprovalOutcome, decided_by: ApprovalDecider | null, 
+/**
+ * Approval policy rule that decided the request, if any
+ */
+policy_rule: string | null, reason: string | null, requested_at: string, timeout_at: string, decided_at: string | null, 
+/**
+ * Milliseconds between the request and its decision
+ */
+latency_ms: number | null, };
+
+export type AttemptGroupStatus = "running" | "completed" | "promoted";
+
+export type AttemptGroup = { id: string, task_id: string, status: AttemptGroupStatus, promoted_workspace_id: string | null, report: AttemptGroupReport | null, completed_at: string | null, created_at: string, updated_at: string, };
+
+export type AttemptGroupMember = { group_id: string, workspace_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Set when another attempt in the group was promoted
+ */
+archived_at: string | null, created_at: string, };
+
+export type AttemptGroupWithMembers = { members: Array<AttemptGroupMember>, id: string, task_id: string, status: AttemptGroupStatus, promoted_workspace_id: string | null, report: AttemptGroupReport | null, completed_at: string | null, created_at: string, updated_at: string, };
+
+/**
+ * Side-by-side comparison of the attempts in a group
+ */
+export type AttemptGroupReport = { generated_at: string, attempts: Array<AttemptComparison>, };
+
+export type AttemptComparison = { workspace_id: string, executor_profile_id: ExecutorProfileId, branch: string, 
+/**
+ * Outcome of the latest coding agent run
+ */
+agent_status: ExecutionProcessStatus | null, agent_exit_code: number | null, files_changed: number, additions: number, deletions: number, 
+/**
+ * Why the diff stats are missing, e.g. the worktree was already removed
+ */
+diff_error: string | null, 
+/**
+ * Latest setup and cleanup script runs
+ */
+scripts: Array<ScriptOutcome>, input_tokens: number, output_tokens: number, 
+/**
+ * None when any model used has no configured pricing
+ */
+cost_usd: number | null, };
+
+export type ScriptOutcome = { run_reason: ExecutionProcessRunReason, status: ExecutionProcessStatus, exit_code: number | null, };
+
+export type VerificationRunStatus = "passed" | "failed";
+
+export type VerificationRun = { id: string, workspace_id: string, execution_process_id: string, status: VerificationRunStatus, exit_code: number | null, 
+/**
+ * Last part of the script's stdout and stderr
+ */
+output: string, 
+/**
+ * Number of automatic fix attempts that preceded this run
+ */
+retry: number, 
+/**
+ * Agent follow-up started to fix this failure, if any
+ */
+follow_up_execution_process_id: string | null, created_at: string, };
+
+export type MergeQueueStatus = "queued" | "rebasing" | "verifying" | "merged" | "failed" | "cancelled";
+
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, status: MergeQueueStatus, 
+/**
+ * Verification or setup script run after the rebase
+ */
+execution_process_id: string | null, 
+/**
+ * Conflicts or script output when the merge failed
+ */
+error_message: string | null, created_at: string, updated_at: string, };
+
+export type PlaybookStep = { "type": "prompt", prompt: string, } | { "type": "script", script: string, 
+/**
+ * Directory to run in, relative to the workspace (usually a repo name)
+ */
+working_dir: string | null, } | { "type": "check", script: string, working_dir: string | null, on_failure: string | null, max_retries: number, };
+
+export type Playbook = { id: string, name: string, description: string | null, steps: Array<PlaybookStep>, created_at: string, updated_at: string, };
+
+export type CreatePlaybook = { name: string, description: string | null, steps: Array<PlaybookStep>, };
+
+/**
+ * Fields left out keep their current value
+ */
+export type UpdatePlaybook = { name: string | null, description: string | null, steps: Array<PlaybookStep> | null, };
+
+export type PlaybookRunStatus = "running" | "completed" | "failed" | "cancelled";
+
+export type PlaybookStepRunStatus = "running" | "passed" | "failed" | "cancelled";
+
+export type PlaybookRun = { id: string, 
+/**
+ * None once the playbook was deleted
+ */
+playbook_id: string | null, workspace_id: string, name: string, 
+/**
+ * Steps of the playbook when the run started
+ */
+steps: Array<PlaybookStep>, status: PlaybookRunStatus, created_at: string, updated_at: string, };
+
+export type PlaybookStepRun = { id: string, playbook_run_id: string, step_index: number, status: PlaybookStepRunStatus, 
+/**
+ * Latest process started for the step
+ */
+execution_process_id: string | null, 
+/**
+ * Follow-ups sent to the agent after the step's check failed
+ */
+retries: number, 
+/**
+ * Last part of the output of a failed script or check
+ */
+output: string | null, created_at: string, completed_at: string | null, };
+
+export type PlaybookRunWithSteps = { 
+/**
+ * Steps reached so far, in order
+ */
+step_runs: Array<PlaybookStepRun>, id: string, 
+/**
+ * None once the playbook was deleted
+ */
+playbook_id: string | null, workspace_id: string, name: string, 
+/**
+ * Steps of the playbook when the run started
+ */
+steps: Array<PlaybookStep>, status: PlaybookRunStatus, created_at: string, updated_at: string, };
+
+export type SetTaskPlaybook = { 
+/**
+ * None detaches the task's playbook
+ */
+playbook_id: string | null, };
+
+export type LogSearchKind = "user_message" | "assistant_message" | "tool_use" | "error";
+
+export type LogSearchHit = { project_id: string, task_id: string, task_title: string, workspace_id: string, workspace_branch: string, execution_process_id: string, executor: string | null, entry_index: number, kind: LogSearchKind, 
+/**
+ * Text around the match, with matched words wrapped in `**`
+ */
+snippet: string, 
+/**
+ * When the execution process started
+ */
+created_at: string, };
+
+export type ImportedAttempt = { id: string, task_title: string, branch: string, format_version: number, 
+/**
+ * Version of vibe-kanban that exported the attempt
+ */
+app_version: string, exported_at: string, size_bytes: number, created_at: string, };
+
+export type AttemptArchiveManifest = { format_version: number, 
+/**
+ * Version of vibe-kanban that wrote the archive
+ */
+app_version: string, exported_at: string, task: Task, workspace: Workspace, repos: Array<RepoWithTargetBranch>, sessions: Array<Session>, 
+/**
+ * Every process of the attempt, including dropped ones, oldest first
+ */
+processes: Array<ArchivedExecutionProcess>, images: Array<Image>, 
+/**
+ * Changes against each repo's target branch when the attempt was exported,
+ * with paths prefixed by the repo name
+ */
+diffs: Array<Diff>, };
+
+export type ArchivedExecutionProcess = { repo_states: Array<ExecutionProcessRepoState>, agent_turn: CodingAgentTurn | null, id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * dropped: true if this process is excluded from the current
+ * history view (due to restore/trimming). Hidden from logs/timeline;
+ * still listed in the Processes tab.
+ */
+dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+
+/**
+ * How much space the logs of one project's execution processes take
+ */
+export type ProjectLogStorage = { project_id: string, project_name: string, process_count: number, 
+/**
+ * Logs not compacted yet
+ */
+live_bytes: number, 
+/**
+ * Uncompressed size of the compacted logs
+ */
+compacted_bytes: number, 
+/**
+ * What the compacted logs actually take on disk
+ */
+compacted_stored_bytes: number, pruned_process_count: number, };
+
 export type FollowUpResponse = { "status": "started", execution_process: ExecutionProcess, } | { "status": "queued", queue_entry: TaskQueueEntry, position: QueuePosition | null, };
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
@@ -458,7 +1121,7 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "Verification" | "Playbook";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -469,9 +1132,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_ACP = "CUSTOM_ACP" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_ACP": CustomAcp };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
@@ -485,6 +1148,28 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type SandboxConfig = { 
+/**
+ * Run the agent and the attempt's scripts inside the sandbox
+ */
+enabled: boolean, 
+/**
+ * Whether sandboxed processes can reach the network
+ */
+network: SandboxNetwork, 
+/**
+ * Extra host paths mounted read-only, e.g. a toolchain in your home directory
+ */
+read_only_paths?: Array<string>, 
+/**
+ * Extra host paths the process may write to, e.g. the agent's config and cache directories
+ */
+writable_paths?: Array<string>, };
+
+export type SandboxNetwork = "allow" | "deny";
+
+export type OciRuntime = "docker" | "podman";
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -495,19 +1180,19 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_ACP": CustomAcp } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -519,24 +1204,47 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, mode?: string | null, 
 /**
  * Auto-approve agent actions
  */
-auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+/**
+ * Any agent that speaks the Agent Client Protocol over stdio. Everything
+ * needed to launch it comes from the executor profile.
+ */
+export type CustomAcp = { append_prompt: AppendPrompt, 
+/**
+ * Command that starts the agent in ACP mode, e.g. `npx -y my-agent --acp`
+ */
+command: string, 
+/**
+ * Extra arguments appended to the command
+ */
+args?: Array<string>, 
+/**
+ * Directory under ~/.vibe-kanban where session history is kept. Give each
+ * agent its own namespace.
+ */
+session_namespace?: string | null, model?: string | null, mode?: string | null, 
+/**
+ * Auto-approve agent actions
+ */
+auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxConfig | null, };
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -583,7 +1291,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "sandbox_blocked" } | { "type": "context_limit_exceeded" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**
@@ -593,7 +1301,11 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" } | { "status": "auto_approved", 
+/**
+ * Rule that approved the call, or `None` for the policy's default
+ */
+rule: string | null, reason: string | null, };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 